@@ -0,0 +1,56 @@
+//! Benchmarks for serial vs parallel Rust code generation.
+//!
+//! Run with `cargo bench --features parallel --bench codegen_bench`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use metadol::ast::{Declaration, Gene, QualifiedName, Span, Statement, Visibility};
+use metadol::codegen::{Codegen, CodegenOptions, RustCodegen};
+
+/// Builds `count` independent gene declarations with a handful of fields each.
+fn make_genes(count: usize) -> Vec<Declaration> {
+    (0..count)
+        .map(|i| {
+            let statements = (0..8)
+                .map(|f| Statement::Has {
+                    subject: format!("entity{i}"),
+                    property: format!("field{f}"),
+                    span: Span::default(),
+                })
+                .collect();
+            Declaration::Gene(Gene {
+                name: QualifiedName::new(&format!("bench.entity{i}")),
+                extends: None,
+                statements,
+                exegesis: format!("Benchmark entity number {i}.").into(),
+                visibility: Visibility::Private,
+                attributes: vec![],
+                span: Span::default(),
+            })
+        })
+        .collect()
+}
+
+fn bench_codegen(c: &mut Criterion) {
+    let decls = make_genes(2_000);
+    let opts = CodegenOptions::default();
+
+    let mut group = c.benchmark_group("rust_codegen_2000_genes");
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            black_box(
+                decls
+                    .iter()
+                    .map(|d| <RustCodegen as Codegen>::generate_with(d, &opts))
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
+            )
+        })
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| black_box(RustCodegen::generate_all_parallel(&decls, &opts)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_codegen);
+criterion_main!(benches);
@@ -38,7 +38,7 @@ fn main() {
             op: BinaryOp::Add,
             right: Box::new(Expr::Identifier("b".to_string())),
         }))],
-        exegesis: "Adds two numbers".to_string(),
+        exegesis: "Adds two numbers".into(),
         span: Span::default(),
     };
 
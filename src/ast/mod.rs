@@ -20,10 +20,10 @@
 //! # Example
 //!
 //! ```rust
-//! use metadol::ast::{Declaration, Gene, Statement, Span};
+//! use metadol::ast::{Declaration, Gene, QualifiedName, Span, Statement, Visibility};
 //!
 //! let gene = Gene {
-//!     name: "container.exists".to_string(),
+//!     name: QualifiedName::new("container.exists"),
 //!     extends: None,
 //!     statements: vec![
 //!         Statement::Has {
@@ -32,16 +32,28 @@
 //!             span: Span::default(),
 //!         },
 //!     ],
-//!     exegesis: "A container is the fundamental unit.".to_string(),
+//!     exegesis: "A container is the fundamental unit.".into(),
+//!     visibility: Visibility::Private,
+//!     attributes: vec![],
 //!     span: Span::default(),
 //! };
 //!
 //! let decl = Declaration::Gene(gene);
 //! ```
+//!
+//! # Serialization
+//!
+//! With the `serde` feature, every AST type implements `Serialize` and
+//! `Deserialize`. Enums that carry data are adjacently tagged, so a node
+//! serializes as `{"kind": "Binary", "value": {...}}`; enums of plain names
+//! such as [`BinaryOp`] serialize as strings.
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+mod to_dol;
+pub mod walk;
+
 /// Source location information for error reporting and tooling.
 ///
 /// Spans track the byte offsets and line/column positions of AST nodes
@@ -58,7 +70,7 @@ use serde::{Deserialize, Serialize};
 /// assert_eq!(span.line, 1);
 /// assert_eq!(span.column, 1);
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Span {
     /// Starting byte offset (inclusive)
@@ -116,6 +128,109 @@ impl Span {
     }
 }
 
+/// The prose of an `exegesis { ... }` block.
+///
+/// `text` is trimmed of surrounding whitespace and `span` covers exactly
+/// that text in the source, so tools can map the prose back to the file.
+/// Declarations without an exegesis block carry empty text and a default
+/// span.
+///
+/// # Example
+///
+/// ```rust
+/// use metadol::ast::{Declaration, Exegesis};
+/// use metadol::parser::Parser;
+///
+/// let source = "gene counter.state { counter has value }\nexegesis { A counter. }";
+/// let decl = Parser::new(source).parse().unwrap();
+///
+/// if let Declaration::Gene(gene) = decl {
+///     assert_eq!(gene.exegesis.text(), "A counter.");
+///     let span = gene.exegesis.span();
+///     assert_eq!(&source[span.start..span.end], "A counter.");
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Exegesis {
+    /// The trimmed exegesis text
+    pub text: String,
+    /// Location of `text` in the source
+    pub span: Span,
+}
+
+impl Exegesis {
+    /// Creates an exegesis from its text and source location.
+    pub fn new(text: impl Into<String>, span: Span) -> Self {
+        Self {
+            text: text.into(),
+            span,
+        }
+    }
+
+    /// Returns the exegesis text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the location of the text in the source.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns true if this exegesis came from an `exegesis { ... }` block.
+    ///
+    /// Parsed blocks, even empty ones, have a 1-indexed line, while a
+    /// declaration without a block carries the default span and no text.
+    pub fn is_present(&self) -> bool {
+        !self.text.is_empty() || self.span.line != 0
+    }
+}
+
+impl std::ops::Deref for Exegesis {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl std::fmt::Display for Exegesis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl From<String> for Exegesis {
+    fn from(text: String) -> Self {
+        Self::new(text, Span::default())
+    }
+}
+
+impl From<&str> for Exegesis {
+    fn from(text: &str) -> Self {
+        Self::new(text, Span::default())
+    }
+}
+
+impl PartialEq<str> for Exegesis {
+    fn eq(&self, other: &str) -> bool {
+        self.text == other
+    }
+}
+
+impl PartialEq<&str> for Exegesis {
+    fn eq(&self, other: &&str) -> bool {
+        self.text == *other
+    }
+}
+
+impl PartialEq<String> for Exegesis {
+    fn eq(&self, other: &String) -> bool {
+        &self.text == other
+    }
+}
+
 /// Visibility modifier for declarations.
 ///
 /// Controls the accessibility of declarations across module boundaries.
@@ -133,11 +248,90 @@ pub enum Visibility {
     PubParent,
 }
 
+/// An attribute on a declaration.
+///
+/// Represents a `#[name(args)]` annotation. The compiler reads
+/// `#[deprecated]`, `#[test]` and `#[derive(...)]` itself; other attributes
+/// name macros for [`MacroExpander`](crate::macros::MacroExpander) to expand.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MacroAttribute {
+    /// Attribute name (without `#`)
+    pub name: String,
+    /// Arguments as identifiers or key-value pairs
+    pub args: Vec<AttributeArg>,
+    /// Source location
+    pub span: Span,
+}
+
+impl MacroAttribute {
+    /// Creates a new attribute.
+    pub fn new(name: impl Into<String>, args: Vec<AttributeArg>, span: Span) -> Self {
+        Self {
+            name: name.into(),
+            args,
+            span,
+        }
+    }
+
+    /// Creates an attribute with no arguments.
+    pub fn simple(name: impl Into<String>, span: Span) -> Self {
+        Self::new(name, Vec::new(), span)
+    }
+
+    /// Creates an attribute with identifier arguments.
+    pub fn with_idents(name: impl Into<String>, idents: Vec<String>, span: Span) -> Self {
+        let args = idents.into_iter().map(AttributeArg::Ident).collect();
+        Self::new(name, args, span)
+    }
+}
+
+/// Argument to an attribute.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
+pub enum AttributeArg {
+    /// Simple identifier: `Debug`
+    Ident(String),
+    /// Key-value pair: `name = "value"`
+    KeyValue {
+        /// The key name
+        key: String,
+        /// The value expression
+        value: Expr,
+    },
+    /// Nested attribute: `serde(rename = "foo")`
+    Nested {
+        /// The nested attribute name
+        name: String,
+        /// The nested arguments
+        args: Vec<AttributeArg>,
+    },
+}
+
+/// Returns `Some(note)` if marked `#[deprecated]` (`Some("")` without a note),
+/// `None` otherwise.
+fn deprecation_note(attributes: &[MacroAttribute]) -> Option<&str> {
+    let attr = attributes.iter().find(|attr| attr.name == "deprecated")?;
+    let note = attr.args.iter().find_map(|arg| match arg {
+        AttributeArg::KeyValue { key, value } if key == "note" => match value.unspanned() {
+            Expr::Literal(Literal::String(note)) => Some(note.as_str()),
+            _ => None,
+        },
+        _ => None,
+    });
+    Some(note.unwrap_or(""))
+}
+
 /// Semantic version number.
 ///
 /// Supports standard semver (major.minor.patch) plus optional suffix
 /// for pre-release versions or geological time scales (e.g., "Gya").
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Versions are ordered by semver precedence: numeric components first,
+/// then a version with a suffix sorts before the same version without
+/// one, so `1.2.0-alpha < 1.2.0-beta < 1.2.0-rc.1 < 1.2.0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Version {
     /// Major version number
@@ -150,6 +344,328 @@ pub struct Version {
     pub suffix: Option<String>,
 }
 
+impl Version {
+    /// Parses a strict `major.minor.patch[-suffix]` version.
+    ///
+    /// Every numeric component must be present and must parse; `1.x.0`
+    /// and `1.2` are rejected.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (numbers, suffix) = Self::split_suffix(text);
+        let parts: Vec<&str> = numbers.split('.').collect();
+        if parts.len() != 3 {
+            return Err(format!("version '{text}' must have three parts"));
+        }
+        Self::from_parts(text, &parts, suffix)
+    }
+
+    /// Parses a version that may omit trailing components.
+    ///
+    /// Accepts `1`, `1.2` and `1.2.3` (each with an optional `-suffix`),
+    /// filling missing components with zero. Non-numeric components are
+    /// still rejected.
+    pub fn parse_lenient(text: &str) -> Result<Self, String> {
+        let (numbers, suffix) = Self::split_suffix(text);
+        let mut parts: Vec<&str> = numbers.split('.').collect();
+        if parts.len() > 3 {
+            return Err(format!("version '{text}' has more than three parts"));
+        }
+        parts.resize(3, "0");
+        Self::from_parts(text, &parts, suffix)
+    }
+
+    fn split_suffix(text: &str) -> (&str, Option<String>) {
+        match text.split_once('-') {
+            Some((numbers, suffix)) => (numbers, Some(suffix.to_string())),
+            None => (text, None),
+        }
+    }
+
+    fn from_parts(text: &str, parts: &[&str], suffix: Option<String>) -> Result<Self, String> {
+        let component = |part: &str| {
+            part.parse::<u32>()
+                .map_err(|_| format!("version '{text}' has non-numeric component '{part}'"))
+        };
+        Ok(Version {
+            major: component(parts[0])?,
+            minor: component(parts[1])?,
+            patch: component(parts[2])?,
+            suffix,
+        })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+
+        let numbers = (self.major, self.minor, self.patch);
+        numbers
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.suffix, &other.suffix) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_prerelease(a, b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares pre-release suffixes identifier by identifier.
+///
+/// Dot-separated identifiers that are all digits compare numerically and
+/// sort before alphanumeric ones, which compare lexically. When one suffix
+/// is a prefix of the other, the shorter one sorts first.
+fn compare_prerelease(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let numeric = |id: &str| -> Option<u64> {
+        if id.bytes().all(|b| b.is_ascii_digit()) {
+            id.parse().ok()
+        } else {
+            None
+        }
+    };
+    let mut left = a.split('.');
+    let mut right = b.split('.');
+    loop {
+        let ordering = match (left.next(), right.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match (numeric(x), numeric(y)) {
+                // Tie-break on the text so `01` and `1` stay distinct
+                (Some(m), Some(n)) => m.cmp(&n).then_with(|| x.cmp(y)),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => x.cmp(y),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(suffix) = &self.suffix {
+            write!(f, "-{suffix}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Normalizes a possibly abbreviated version to `major.minor.patch` form.
+///
+/// `"1.2"` becomes `"1.2.0"`; malformed input is an error rather than
+/// silently becoming zero.
+pub fn normalize_version(text: &str) -> Result<String, String> {
+    Version::parse_lenient(text).map(|v| v.to_string())
+}
+
+/// A dotted declaration name split into its segments.
+///
+/// `container.exists` has the segments `["container", "exists"]`: the
+/// domain `container` and the property `exists`. The empty name (no
+/// segments) is the default and names no declaration. The span records
+/// where the name came from and does not take part in comparisons.
+///
+/// The parser stores the name of every gene, trait, constraint and system
+/// as a `QualifiedName`, keeping each segment as written so the validator
+/// can report malformed names such as `a..b`.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "QualifiedNameParts", into = "QualifiedNameParts")
+)]
+pub struct QualifiedName {
+    /// Name segments, outermost first
+    segments: Vec<String>,
+    /// The segments joined with `.`
+    dotted: String,
+    /// Source location
+    pub span: Span,
+}
+
+impl QualifiedName {
+    /// Splits a dotted name into a qualified name.
+    ///
+    /// Any string is accepted. Empty segments, as in `a..b` or `.a`, are
+    /// dropped, so `a..b` and `a.b` give equal names; use
+    /// [`QualifiedName::parse`] to reject them. Segments are not checked
+    /// to be identifiers.
+    pub fn new(name: &str) -> Self {
+        Self::from_segments(
+            name.split('.')
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+
+    /// Splits a dotted name, rejecting empty segments.
+    ///
+    /// Returns `None` for the empty string, a leading or trailing dot, or
+    /// consecutive dots. Like [`QualifiedName::new`], segments are not
+    /// checked to be identifiers.
+    ///
+    /// ```rust
+    /// use metadol::ast::QualifiedName;
+    ///
+    /// assert_eq!(QualifiedName::parse("container.exists").unwrap().segments().len(), 2);
+    /// assert!(QualifiedName::parse("container..exists").is_none());
+    /// assert!(QualifiedName::parse("").is_none());
+    /// ```
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = Self::verbatim(name);
+        if name.is_empty() || name.segments.iter().any(String::is_empty) {
+            return None;
+        }
+        Some(name)
+    }
+
+    /// Splits a dotted name, keeping every segment as written.
+    ///
+    /// Unlike [`QualifiedName::new`], empty segments are kept, so the name
+    /// displays exactly as `name`. The empty string has no segments.
+    pub(crate) fn verbatim(name: &str) -> Self {
+        if name.is_empty() {
+            return Self::default();
+        }
+        Self::from_segments(name.split('.').map(str::to_string).collect())
+    }
+
+    fn from_segments(segments: Vec<String>) -> Self {
+        Self {
+            dotted: segments.join("."),
+            segments,
+            span: Span::default(),
+        }
+    }
+
+    /// Sets the source location of the name.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Returns the name's segments, outermost first.
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    /// Returns the name in dot notation, as in `container.exists`.
+    pub fn as_str(&self) -> &str {
+        &self.dotted
+    }
+
+    /// Returns true if the name has no segments.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Returns the name without its last segment.
+    ///
+    /// The domain of `container.lifecycle.exists` is `container.lifecycle`;
+    /// a single-segment name has an empty domain.
+    ///
+    /// ```rust
+    /// use metadol::ast::QualifiedName;
+    ///
+    /// let name = QualifiedName::new("container.exists");
+    /// assert_eq!(name.domain().to_string(), "container");
+    /// assert_eq!(name.property(), Some("exists"));
+    /// assert!(QualifiedName::new("Container").domain().is_empty());
+    /// ```
+    pub fn domain(&self) -> QualifiedName {
+        let len = self.segments.len().saturating_sub(1);
+        Self::from_segments(self.segments[..len].to_vec()).with_span(self.span)
+    }
+
+    /// Returns the last segment of the name, if any.
+    pub fn property(&self) -> Option<&str> {
+        self.segments.last().map(String::as_str)
+    }
+}
+
+/// The serialized form of a [`QualifiedName`], without the cached dotted
+/// text.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct QualifiedNameParts {
+    segments: Vec<String>,
+    span: Span,
+}
+
+#[cfg(feature = "serde")]
+impl From<QualifiedNameParts> for QualifiedName {
+    fn from(parts: QualifiedNameParts) -> Self {
+        Self::from_segments(parts.segments).with_span(parts.span)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<QualifiedName> for QualifiedNameParts {
+    fn from(name: QualifiedName) -> Self {
+        Self {
+            segments: name.segments,
+            span: name.span,
+        }
+    }
+}
+
+impl PartialEq for QualifiedName {
+    fn eq(&self, other: &Self) -> bool {
+        self.segments == other.segments
+    }
+}
+
+impl Eq for QualifiedName {}
+
+impl std::hash::Hash for QualifiedName {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.segments.hash(state);
+    }
+}
+
+impl PartialOrd for QualifiedName {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QualifiedName {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.segments.cmp(&other.segments)
+    }
+}
+
+impl PartialEq<str> for QualifiedName {
+    fn eq(&self, other: &str) -> bool {
+        self.dotted == other
+    }
+}
+
+impl PartialEq<&str> for QualifiedName {
+    fn eq(&self, other: &&str) -> bool {
+        self.dotted == *other
+    }
+}
+
+impl std::fmt::Display for QualifiedName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.dotted)
+    }
+}
+
 /// Module declaration: `module name.path @ version`.
 ///
 /// Defines a module with a hierarchical path and optional version.
@@ -183,6 +699,7 @@ pub struct UseDecl {
 /// What items are imported in a use declaration.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
 pub enum UseItems {
     /// Import all items: `use module.*`
     All,
@@ -212,6 +729,9 @@ pub struct DolFile {
     pub module: Option<ModuleDecl>,
     /// Use/import declarations
     pub uses: Vec<UseDecl>,
+    /// Paths named by top-level `#include("path")` directives, in source
+    /// order; see [`MacroExpander::expand_includes`](crate::macros::MacroExpander::expand_includes)
+    pub includes: Vec<String>,
     /// All declarations in the file
     pub declarations: Vec<Declaration>,
 }
@@ -256,6 +776,7 @@ pub enum Mutability {
 /// - [`Evolution`]: Lineage records of ontology changes
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
 pub enum Declaration {
     /// A gene declaration - the atomic unit of DOL.
     Gene(Gene),
@@ -280,20 +801,131 @@ pub enum Declaration {
 
     /// A SEX mutable variable declaration.
     SexVar(VarDecl),
+
+    /// A `sex extern` block of foreign function signatures.
+    Extern(ExternBlock),
+}
+
+/// Discriminant for the kinds of top-level [`Declaration`].
+///
+/// Useful when callers need to dispatch on the kind of a declaration
+/// without matching on (and borrowing) its payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DeclarationKind {
+    /// A gene declaration
+    Gene,
+    /// A trait declaration
+    Trait,
+    /// A constraint declaration
+    Constraint,
+    /// A system declaration
+    System,
+    /// An evolution declaration
+    Evolution,
+    /// A top-level function declaration
+    Function,
+    /// A constant declaration
+    Const,
+    /// A SEX mutable variable declaration
+    SexVar,
+    /// A `sex extern` block
+    Extern,
+}
+
+impl DeclarationKind {
+    /// Returns the DOL keyword that introduces this kind of declaration.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeclarationKind::Gene => "gene",
+            DeclarationKind::Trait => "trait",
+            DeclarationKind::Constraint => "constraint",
+            DeclarationKind::System => "system",
+            DeclarationKind::Evolution => "evolves",
+            DeclarationKind::Function => "fun",
+            DeclarationKind::Const => "const",
+            DeclarationKind::SexVar => "sex var",
+            DeclarationKind::Extern => "sex extern",
+        }
+    }
+}
+
+impl std::fmt::Display for DeclarationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
 }
 
 impl Declaration {
+    /// Returns the kind of the declaration.
+    pub fn kind(&self) -> DeclarationKind {
+        match self {
+            Declaration::Gene(_) => DeclarationKind::Gene,
+            Declaration::Trait(_) => DeclarationKind::Trait,
+            Declaration::Constraint(_) => DeclarationKind::Constraint,
+            Declaration::System(_) => DeclarationKind::System,
+            Declaration::Evolution(_) => DeclarationKind::Evolution,
+            Declaration::Function(_) => DeclarationKind::Function,
+            Declaration::Const(_) => DeclarationKind::Const,
+            Declaration::SexVar(_) => DeclarationKind::SexVar,
+            Declaration::Extern(_) => DeclarationKind::Extern,
+        }
+    }
+
+    /// Returns the deprecation note if the declaration is marked `#[deprecated]`.
+    pub fn deprecated(&self) -> Option<&str> {
+        match self {
+            Declaration::Gene(g) => g.deprecated(),
+            Declaration::Trait(t) => t.deprecated(),
+            _ => None,
+        }
+    }
+
     /// Returns the name of the declaration.
     pub fn name(&self) -> &str {
         match self {
-            Declaration::Gene(g) => &g.name,
-            Declaration::Trait(t) => &t.name,
-            Declaration::Constraint(c) => &c.name,
-            Declaration::System(s) => &s.name,
+            Declaration::Gene(g) => g.name.as_str(),
+            Declaration::Trait(t) => t.name.as_str(),
+            Declaration::Constraint(c) => c.name.as_str(),
+            Declaration::System(s) => s.name.as_str(),
             Declaration::Evolution(e) => &e.name,
             Declaration::Function(f) => &f.name,
             Declaration::Const(c) => &c.name,
             Declaration::SexVar(v) => &v.name,
+            Declaration::Extern(e) => e.name(),
+        }
+    }
+
+    /// Returns the declaration's name split into its dotted segments.
+    ///
+    /// Genes, traits, constraints and systems return the name as parsed,
+    /// located at the name. Other declarations store a plain string, so
+    /// their name is split here and located at the whole declaration.
+    pub fn qualified_name(&self) -> QualifiedName {
+        match self {
+            Declaration::Gene(g) => g.name.clone(),
+            Declaration::Trait(t) => t.name.clone(),
+            Declaration::Constraint(c) => c.name.clone(),
+            Declaration::System(s) => s.name.clone(),
+            _ => QualifiedName::verbatim(self.name()).with_span(self.span()),
+        }
+    }
+
+    /// Returns the visibility of the declaration.
+    ///
+    /// Evolutions, constants, SexVars and extern blocks carry no
+    /// visibility and are always private.
+    pub fn visibility(&self) -> Visibility {
+        match self {
+            Declaration::Gene(g) => g.visibility,
+            Declaration::Trait(t) => t.visibility,
+            Declaration::Constraint(c) => c.visibility,
+            Declaration::System(s) => s.visibility,
+            Declaration::Function(f) => f.visibility,
+            Declaration::Evolution(_)
+            | Declaration::Const(_)
+            | Declaration::SexVar(_)
+            | Declaration::Extern(_) => Visibility::Private,
         }
     }
 
@@ -306,10 +938,53 @@ impl Declaration {
             Declaration::System(s) => &s.exegesis,
             Declaration::Evolution(e) => &e.exegesis,
             Declaration::Function(f) => &f.exegesis,
-            Declaration::Const(_) | Declaration::SexVar(_) => "", // Constants and SexVars don't have exegesis
+            // Constants, SexVars and extern blocks don't have exegesis
+            Declaration::Const(_) | Declaration::SexVar(_) | Declaration::Extern(_) => "",
         }
     }
 
+    /// Returns the exegesis block, or `None` if the declaration has none.
+    pub fn exegesis_block(&self) -> Option<&Exegesis> {
+        let exegesis = match self {
+            Declaration::Gene(g) => &g.exegesis,
+            Declaration::Trait(t) => &t.exegesis,
+            Declaration::Constraint(c) => &c.exegesis,
+            Declaration::System(s) => &s.exegesis,
+            Declaration::Evolution(e) => &e.exegesis,
+            Declaration::Function(f) => &f.exegesis,
+            Declaration::Const(_) | Declaration::SexVar(_) | Declaration::Extern(_) => return None,
+        };
+        Some(exegesis).filter(|e| e.is_present())
+    }
+
+    /// Returns the declaration with its exegesis replaced.
+    ///
+    /// Constants, SexVars and extern blocks carry no exegesis and are
+    /// returned unchanged.
+    pub fn with_exegesis(mut self, text: String) -> Self {
+        self.replace_exegesis(text);
+        self
+    }
+
+    /// Replaces the exegesis text, returning the previous text.
+    ///
+    /// Constants, SexVars and extern blocks carry no exegesis; for them this
+    /// is a no-op that returns an empty string.
+    pub fn replace_exegesis(&mut self, text: String) -> String {
+        let slot = match self {
+            Declaration::Gene(g) => &mut g.exegesis,
+            Declaration::Trait(t) => &mut t.exegesis,
+            Declaration::Constraint(c) => &mut c.exegesis,
+            Declaration::System(s) => &mut s.exegesis,
+            Declaration::Evolution(e) => &mut e.exegesis,
+            Declaration::Function(f) => &mut f.exegesis,
+            Declaration::Const(_) | Declaration::SexVar(_) | Declaration::Extern(_) => {
+                return String::new()
+            }
+        };
+        std::mem::replace(slot, text.into()).text
+    }
+
     /// Returns the span of the declaration.
     pub fn span(&self) -> Span {
         match self {
@@ -321,6 +996,7 @@ impl Declaration {
             Declaration::Function(f) => f.span,
             Declaration::Const(c) => c.span,
             Declaration::SexVar(v) => v.span,
+            Declaration::Extern(e) => e.span,
         }
     }
 
@@ -336,7 +1012,8 @@ impl Declaration {
             Declaration::Evolution(_)
             | Declaration::Function(_)
             | Declaration::Const(_)
-            | Declaration::SexVar(_) => return ids,
+            | Declaration::SexVar(_)
+            | Declaration::Extern(_) => return ids,
         };
 
         for stmt in statements {
@@ -344,7 +1021,9 @@ impl Declaration {
                 Statement::Has {
                     subject, property, ..
                 } => {
-                    ids.push(subject.clone());
+                    if !is_implicit_subject(subject) {
+                        ids.push(subject.clone());
+                    }
                     ids.push(property.clone());
                 }
                 Statement::HasField(field) => {
@@ -412,7 +1091,7 @@ impl Declaration {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Gene {
     /// The fully qualified name using dot notation
-    pub name: String,
+    pub name: QualifiedName,
 
     /// Optional parent type this gene extends (v0.3.0)
     pub extends: Option<String>,
@@ -421,12 +1100,46 @@ pub struct Gene {
     pub statements: Vec<Statement>,
 
     /// The mandatory exegesis explaining intent and context
-    pub exegesis: String,
+    pub exegesis: Exegesis,
+
+    /// Visibility from a `pub` modifier
+    pub visibility: Visibility,
+
+    /// Attributes such as `#[deprecated]`, `#[test]` and `#[derive(Debug)]`
+    pub attributes: Vec<MacroAttribute>,
 
     /// Source location for error reporting
     pub span: Span,
 }
 
+impl Gene {
+    /// Returns `Some(note)` if marked `#[deprecated]` (`Some("")` without a note),
+    /// `None` otherwise.
+    pub fn deprecated(&self) -> Option<&str> {
+        deprecation_note(&self.attributes)
+    }
+
+    /// Returns whether a `#[test]` attribute requests a generated test scaffold.
+    pub fn is_test(&self) -> bool {
+        self.attributes.iter().any(|attr| attr.name == "test")
+    }
+
+    /// Returns the traits requested by `#[derive(...)]`, without duplicates.
+    pub fn derives(&self) -> Vec<&str> {
+        let mut derives = Vec::new();
+        for attr in self.attributes.iter().filter(|attr| attr.name == "derive") {
+            for arg in &attr.args {
+                if let AttributeArg::Ident(name) = arg {
+                    if !derives.contains(&name.as_str()) {
+                        derives.push(name.as_str());
+                    }
+                }
+            }
+        }
+        derives
+    }
+}
+
 /// A trait declaration for composable behaviors.
 ///
 /// Traits build on genes using `uses` statements and declare
@@ -459,18 +1172,35 @@ pub struct Gene {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Trait {
     /// The fully qualified name using dot notation
-    pub name: String,
+    pub name: QualifiedName,
 
     /// The statements including uses and behavior declarations
     pub statements: Vec<Statement>,
 
+    /// Laws declared with `law name(params) { ... }`
+    pub laws: Vec<LawDecl>,
+
     /// The mandatory exegesis
-    pub exegesis: String,
+    pub exegesis: Exegesis,
+
+    /// Visibility from a `pub` modifier
+    pub visibility: Visibility,
+
+    /// Attributes such as `#[deprecated]` and `#[cache]`
+    pub attributes: Vec<MacroAttribute>,
 
     /// Source location
     pub span: Span,
 }
 
+impl Trait {
+    /// Returns `Some(note)` if marked `#[deprecated]` (`Some("")` without a note),
+    /// `None` otherwise.
+    pub fn deprecated(&self) -> Option<&str> {
+        deprecation_note(&self.attributes)
+    }
+}
+
 /// A constraint declaration for system invariants.
 ///
 /// Constraints define rules that must always hold true in the system.
@@ -497,13 +1227,16 @@ pub struct Trait {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Constraint {
     /// The fully qualified name
-    pub name: String,
+    pub name: QualifiedName,
 
     /// The constraint statements (matches, never, etc.)
     pub statements: Vec<Statement>,
 
     /// The mandatory exegesis
-    pub exegesis: String,
+    pub exegesis: Exegesis,
+
+    /// Visibility from a `pub` modifier
+    pub visibility: Visibility,
 
     /// Source location
     pub span: Span,
@@ -537,7 +1270,7 @@ pub struct Constraint {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct System {
     /// The fully qualified name
-    pub name: String,
+    pub name: QualifiedName,
 
     /// The system version (semver)
     pub version: String,
@@ -545,11 +1278,20 @@ pub struct System {
     /// Version requirements for dependencies
     pub requirements: Vec<Requirement>,
 
+    /// Capability requirements (`requires network and storage`)
+    pub capabilities: Vec<CapabilityRequirement>,
+
     /// System-level statements
     pub statements: Vec<Statement>,
 
+    /// State variables declared with `state name: Type [= default]`
+    pub states: Vec<StateDecl>,
+
     /// The mandatory exegesis
-    pub exegesis: String,
+    pub exegesis: Exegesis,
+
+    /// Visibility from a `pub` modifier
+    pub visibility: Visibility,
 
     /// Source location
     pub span: Span,
@@ -562,7 +1304,8 @@ pub struct Requirement {
     /// The referenced declaration name
     pub name: String,
 
-    /// The version constraint operator (>=, >, =)
+    /// The version constraint operator (>=, >, =); a bare version is
+    /// stored as `=`
     pub constraint: String,
 
     /// The required version
@@ -572,6 +1315,64 @@ pub struct Requirement {
     pub span: Span,
 }
 
+/// A capability requirement of a system.
+///
+/// # DOL Syntax
+///
+/// ```dol
+/// system univrs.node @ 0.1.0 {
+///   requires (network and storage) or local_disk
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CapabilityRequirement {
+    /// The required combination of capabilities
+    pub expr: CapabilityExpr,
+
+    /// Source location
+    pub span: Span,
+}
+
+/// A boolean combination of capability names.
+///
+/// `and` binds tighter than `or`; parentheses group explicitly.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
+pub enum CapabilityExpr {
+    /// A single named capability
+    Capability(String),
+    /// Both capabilities are required
+    And(Box<CapabilityExpr>, Box<CapabilityExpr>),
+    /// Either capability satisfies the requirement
+    Or(Box<CapabilityExpr>, Box<CapabilityExpr>),
+}
+
+impl CapabilityExpr {
+    /// Returns the capability names at the leaves, left to right.
+    pub fn capabilities(&self) -> Vec<&str> {
+        match self {
+            CapabilityExpr::Capability(name) => vec![name.as_str()],
+            CapabilityExpr::And(left, right) | CapabilityExpr::Or(left, right) => {
+                let mut names = left.capabilities();
+                names.extend(right.capabilities());
+                names
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for CapabilityExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CapabilityExpr::Capability(name) => write!(f, "{}", name),
+            CapabilityExpr::And(left, right) => write!(f, "({} and {})", left, right),
+            CapabilityExpr::Or(left, right) => write!(f, "({} or {})", left, right),
+        }
+    }
+}
+
 /// An evolution declaration tracking version changes.
 ///
 /// Evolutions record how declarations change over time, maintaining
@@ -615,13 +1416,29 @@ pub struct Evolution {
     /// Rationale for the evolution (from `because`)
     pub rationale: Option<String>,
 
+    /// Statements from a `migrate { ... }` block, run to upgrade data
+    /// from the parent version, which they see as `old`
+    pub migrate: Option<Vec<Stmt>>,
+
     /// The mandatory exegesis
-    pub exegesis: String,
+    pub exegesis: Exegesis,
 
     /// Source location
     pub span: Span,
 }
 
+/// Subject recorded for statements written without one.
+///
+/// DOL 2.0 bodies allow `has name` and `requires law` with the subject left
+/// implicit; the parser fills in this sentinel. It names the enclosing
+/// declaration, never a property, so consumers must not emit it as a field.
+pub const IMPLICIT_SUBJECT: &str = "self";
+
+/// Returns true if `subject` is the [`IMPLICIT_SUBJECT`] sentinel.
+pub fn is_implicit_subject(subject: &str) -> bool {
+    subject == IMPLICIT_SUBJECT
+}
+
 /// A statement within a DOL declaration.
 ///
 /// Statements use simple predicates to declare relationships and properties.
@@ -641,10 +1458,12 @@ pub struct Evolution {
 /// | Never | `subject never action` | `identity never changes` |
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
 pub enum Statement {
     /// Property possession: `subject has property`
     Has {
-        /// The entity that has the property
+        /// The entity that has the property, or [`IMPLICIT_SUBJECT`] for a
+        /// bare `has property`
         subject: String,
         /// The property being possessed
         property: String,
@@ -725,7 +1544,7 @@ pub enum Statement {
 
     /// Quantified statement: `each|all subject predicate`
     Quantified {
-        /// The quantifier (each, all)
+        /// The quantifier (each, all, forall)
         quantifier: Quantifier,
         /// The rest of the statement
         phrase: String,
@@ -735,6 +1554,99 @@ pub enum Statement {
 
     /// Function declaration inside a gene or trait: `fun name(...) -> Type { ... }`
     Function(Box<FunctionDecl>),
+
+    /// Inline constraint inside a gene: `constraint name { expr }`, or a
+    /// bodiless `constraint name` referencing a top-level constraint
+    Constraint(Box<InlineConstraint>),
+}
+
+impl Statement {
+    /// Returns the source span of the statement.
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Has { span, .. }
+            | Statement::Is { span, .. }
+            | Statement::DerivesFrom { span, .. }
+            | Statement::Requires { span, .. }
+            | Statement::Uses { span, .. }
+            | Statement::Emits { span, .. }
+            | Statement::Matches { span, .. }
+            | Statement::Never { span, .. }
+            | Statement::Quantified { span, .. } => *span,
+            Statement::HasField(field) => field.span,
+            Statement::Function(func) => func.span,
+            Statement::Constraint(constraint) => constraint.span,
+        }
+    }
+
+    /// Returns the words of the statement's predicate phrase.
+    ///
+    /// The phrase is the object of the predicate (e.g. the requirement in
+    /// `container requires stable network connectivity`). Typed fields and
+    /// functions have no phrase and return an empty list.
+    pub fn phrase_parts(&self) -> Vec<&str> {
+        let phrase = match self {
+            Statement::Has { property, .. } => property,
+            Statement::Is { state, .. } => state,
+            Statement::DerivesFrom { origin, .. } => origin,
+            Statement::Requires { requirement, .. } => requirement,
+            Statement::Uses { reference, .. } => reference,
+            Statement::Emits { event, .. } => event,
+            Statement::Matches { target, .. } => target,
+            Statement::Never { action, .. } => action,
+            Statement::Quantified { phrase, .. } => phrase,
+            Statement::HasField(_) | Statement::Function(_) | Statement::Constraint(_) => {
+                return Vec::new()
+            }
+        };
+        phrase.split_whitespace().collect()
+    }
+}
+
+/// Grammatical roles identified in a predicate phrase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhraseRoles<'a> {
+    /// The head noun the phrase is about
+    pub head: &'a str,
+    /// Words qualifying the head, in source order
+    pub modifiers: Vec<&'a str>,
+}
+
+/// Prepositions that end a noun phrase and start its complement.
+const PHRASE_PREPOSITIONS: &[&str] = &["of", "from", "for", "with", "in", "on", "to", "by"];
+
+/// Classifies a phrase into its head noun and modifiers.
+///
+/// This is a heuristic for English noun phrases: the head is the last word
+/// before the first preposition (or the last word overall), and every other
+/// word is a modifier. Returns `None` for an empty phrase.
+///
+/// # Example
+///
+/// ```rust
+/// use metadol::ast::classify_phrase;
+///
+/// let roles = classify_phrase("stable network connectivity").unwrap();
+/// assert_eq!(roles.head, "connectivity");
+/// assert_eq!(roles.modifiers, vec!["stable", "network"]);
+/// ```
+pub fn classify_phrase(phrase: &str) -> Option<PhraseRoles<'_>> {
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    let head_index = match words
+        .iter()
+        .position(|w| PHRASE_PREPOSITIONS.contains(&w.to_lowercase().as_str()))
+    {
+        Some(0) | None => words.len().checked_sub(1)?,
+        Some(i) => i - 1,
+    };
+    let head = words[head_index];
+    let modifiers = words
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != head_index)
+        .map(|(_, w)| *w)
+        .collect();
+    Some(PhraseRoles { head, modifiers })
 }
 
 /// Quantifier for statements.
@@ -745,6 +1657,15 @@ pub enum Quantifier {
     Each,
     /// "all" - applies universally
     All,
+    /// "forall" - the canonical universal quantifier
+    Forall,
+}
+
+impl Quantifier {
+    /// Returns true for the deprecated spellings `each` and `all`.
+    pub fn is_deprecated(&self) -> bool {
+        matches!(self, Quantifier::Each | Quantifier::All)
+    }
 }
 
 impl std::fmt::Display for Quantifier {
@@ -752,6 +1673,7 @@ impl std::fmt::Display for Quantifier {
         match self {
             Quantifier::Each => write!(f, "each"),
             Quantifier::All => write!(f, "all"),
+            Quantifier::Forall => write!(f, "forall"),
         }
     }
 }
@@ -800,8 +1722,6 @@ pub enum BinaryOp {
     Apply,
     /// Bind `:=`
     Bind,
-    /// Member access `.`
-    Member,
     /// Functor map `<$>` - maps a function over a functor
     Map,
     /// Applicative apply `<*>` - applies a wrapped function to a wrapped value
@@ -836,6 +1756,7 @@ pub enum UnaryOp {
 /// and variable declarations.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
 pub enum TypeExpr {
     /// Named type (e.g., `Int32`, `String`)
     Named(String),
@@ -862,6 +1783,8 @@ pub enum TypeExpr {
         /// Enum variants with optional fields
         variants: Vec<EnumVariant>,
     },
+    /// Inline anonymous record type (e.g., `{ cpu: Int64, memory: Int64 }`)
+    Record(Vec<(String, TypeExpr)>),
 }
 
 /// An enum variant with optional associated fields.
@@ -917,6 +1840,9 @@ pub struct ForallExpr {
     pub var: String,
     /// Type of the bound variable
     pub type_: TypeExpr,
+    /// Collection iterated by `forall x in iter { ... }`, whose type is
+    /// written `_`; `None` for `forall x: T. expr`
+    pub iter: Option<Box<Expr>>,
     /// Body expression (the proposition)
     pub body: Box<Expr>,
     /// Source location
@@ -944,6 +1870,7 @@ pub struct ExistsExpr {
 /// Represents computational expressions that can be evaluated to produce values.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
 pub enum Expr {
     /// Literal value
     Literal(Literal),
@@ -953,6 +1880,8 @@ pub enum Expr {
     List(Vec<Expr>),
     /// Tuple literal: (expr, expr, ...)
     Tuple(Vec<Expr>),
+    /// Map literal: { key: value, ... }
+    Map(Vec<(Expr, Expr)>),
     /// Binary operation
     Binary {
         /// Left operand
@@ -1025,6 +1954,10 @@ pub enum Expr {
         arms: Vec<MatchArm>,
     },
     /// Block expression
+    ///
+    /// Statements run in order and the block evaluates to `final_expr`. A
+    /// block whose last item is a binding or ends in `;` has no final
+    /// expression and evaluates to unit.
     Block {
         /// Statements in the block
         statements: Vec<Stmt>,
@@ -1082,6 +2015,35 @@ pub enum Expr {
     /// Try expression (error propagation)
     /// Syntax: `expr?`
     Try(Box<Expr>),
+    /// Expression annotated with its source location
+    ///
+    /// Only produced by parsers created with
+    /// [`Parser::with_expr_spans`](crate::parser::Parser::with_expr_spans);
+    /// consumers treat it as the wrapped expression.
+    Spanned {
+        /// The wrapped expression
+        expr: Box<Expr>,
+        /// Source location of the expression
+        span: Span,
+    },
+}
+
+impl Expr {
+    /// Returns the source span if this expression carries one.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Expr::Spanned { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
+
+    /// Returns the expression with any span annotations peeled off.
+    pub fn unspanned(&self) -> &Expr {
+        match self {
+            Expr::Spanned { expr, .. } => expr.unspanned(),
+            other => other,
+        }
+    }
 }
 
 /// Literal value.
@@ -1089,6 +2051,7 @@ pub enum Expr {
 /// Represents constant values in expressions.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
 pub enum Literal {
     /// Integer literal
     Int(i64),
@@ -1119,6 +2082,7 @@ pub enum Literal {
 /// ```
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
 pub enum QuotedExpr {
     /// Literal value
     Literal(Literal),
@@ -1231,6 +2195,7 @@ impl QuotedExpr {
                 // QuasiQuote is treated similar to Quote but allows unquotes
                 QuotedExpr::Quote(Box::new(QuotedExpr::from_expr(inner)))
             }
+            Expr::Spanned { expr, .. } => QuotedExpr::from_expr(expr),
             Expr::Forall(_) | Expr::Exists(_) | Expr::Implies { .. } | Expr::SexBlock { .. } => {
                 // For logical expressions and sex blocks, convert to identifier (simplified)
                 QuotedExpr::Ident(format!("{:?}", expr))
@@ -1318,6 +2283,7 @@ pub struct MatchArm {
 /// Represents patterns used in match expressions and destructuring.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
 pub enum Pattern {
     /// Wildcard pattern `_` (matches anything)
     Wildcard,
@@ -1343,6 +2309,7 @@ pub enum Pattern {
 /// Represents imperative statements that perform actions or control flow.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
 pub enum Stmt {
     /// Let binding (variable declaration)
     Let {
@@ -1353,6 +2320,13 @@ pub enum Stmt {
         /// Initial value
         value: Expr,
     },
+    /// Destructuring binding (`val (a, b) = pair`, `val Point { x, y } = p`)
+    LetPattern {
+        /// Pattern the value is destructured into
+        pattern: Pattern,
+        /// Value being destructured
+        value: Expr,
+    },
     /// Assignment to existing variable
     Assign {
         /// Target of assignment
@@ -1463,6 +2437,29 @@ pub struct ExternDecl {
     pub span: Span,
 }
 
+/// A `sex extern` block: one or more foreign function signatures.
+///
+/// `sex extern fun now() -> Int64` yields a block with a single function;
+/// `sex extern "C" { fun read(fd: Int32) -> Int64 }` may declare several.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExternBlock {
+    /// The declared foreign functions, in source order
+    pub functions: Vec<ExternDecl>,
+    /// Source location
+    pub span: Span,
+}
+
+impl ExternBlock {
+    /// Returns the name the block is known by: its first function's name,
+    /// or `extern` for an empty block.
+    pub fn name(&self) -> &str {
+        self.functions
+            .first()
+            .map_or("extern", |function| function.name.as_str())
+    }
+}
+
 /// Function declaration for DOL 2.0 (`fun` keyword).
 ///
 /// Represents a function definition inside a gene or trait body.
@@ -1499,7 +2496,7 @@ pub struct FunctionDecl {
     /// Function body (statements)
     pub body: Vec<Stmt>,
     /// Documentation for the function
-    pub exegesis: String,
+    pub exegesis: Exegesis,
     /// Source location
     pub span: Span,
 }
@@ -1554,10 +2551,35 @@ pub struct HasField {
     pub default: Option<Expr>,
     /// Optional constraint on the field
     pub constraint: Option<Expr>,
+    /// Version the field was introduced in, from `#[since("1.2.0")]`
+    pub since: Option<String>,
+    /// Source location
+    pub span: Span,
+}
+
+/// Named constraint declared inside a gene.
+///
+/// A constraint with a body is a sub-constraint owned by the gene whose
+/// predicate is the body expression. A constraint without a body refers to
+/// a top-level `constraint` declaration of the same name.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InlineConstraint {
+    /// Constraint name
+    pub name: String,
+    /// Predicate, or `None` for a reference
+    pub body: Option<Expr>,
     /// Source location
     pub span: Span,
 }
 
+impl InlineConstraint {
+    /// Returns true if this constraint refers to a top-level declaration.
+    pub fn is_reference(&self) -> bool {
+        self.body.is_none()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1579,13 +2601,58 @@ mod tests {
         assert_eq!(merged.end, 20);
     }
 
+    #[test]
+    fn test_version_parse_strict() {
+        let v = Version::parse("1.2.3-alpha").unwrap();
+        assert_eq!((v.major, v.minor, v.patch), (1, 2, 3));
+        assert_eq!(v.suffix.as_deref(), Some("alpha"));
+        assert!(Version::parse("1.x.0").is_err());
+        assert!(Version::parse("1.2").is_err());
+    }
+
+    #[test]
+    fn test_version_parse_lenient() {
+        assert_eq!(Version::parse_lenient("1.2").unwrap().to_string(), "1.2.0");
+        assert_eq!(Version::parse_lenient("1").unwrap().to_string(), "1.0.0");
+        assert!(Version::parse_lenient("1.x").is_err());
+        assert!(Version::parse_lenient("1.2.3.4").is_err());
+        assert_eq!(normalize_version("2.1").unwrap(), "2.1.0");
+    }
+
+    #[test]
+    fn test_version_prerelease_ordering() {
+        let v = |text: &str| Version::parse(text).unwrap();
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1-alpha",
+            "1.2.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+        assert!(v("1.2.0-alpha") < v("1.2.0"));
+        assert_eq!(
+            v("1.2.0-rc.1").cmp(&v("1.2.0-rc.1")),
+            std::cmp::Ordering::Equal
+        );
+    }
+
     #[test]
     fn test_declaration_name() {
         let gene = Gene {
-            name: "container.exists".to_string(),
+            name: QualifiedName::new("container.exists"),
             extends: None,
             statements: vec![],
-            exegesis: "Test".to_string(),
+            exegesis: "Test".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
         let decl = Declaration::Gene(gene);
@@ -1593,10 +2660,35 @@ mod tests {
         assert_eq!(decl.name(), "container.exists");
     }
 
+    #[test]
+    fn test_qualified_name() {
+        let span = Span::new(0, 40, 1, 1);
+        let name_span = Span::new(5, 31, 1, 6);
+        let gene = Gene {
+            name: QualifiedName::new("container.lifecycle.exists").with_span(name_span),
+            extends: None,
+            statements: vec![],
+            exegesis: "Test".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
+            span,
+        };
+        let name = Declaration::Gene(gene).qualified_name();
+
+        assert_eq!(name.segments(), ["container", "lifecycle", "exists"]);
+        assert_eq!(name.span, name_span);
+        assert_eq!(name.domain().to_string(), "container.lifecycle");
+        assert_eq!(name.property(), Some("exists"));
+        assert_eq!(name.to_string(), "container.lifecycle.exists");
+        // Spans do not affect identity
+        assert_eq!(name, QualifiedName::new("container.lifecycle.exists"));
+        assert_eq!(QualifiedName::default().property(), None);
+    }
+
     #[test]
     fn test_collect_dependencies() {
         let trait_decl = Trait {
-            name: "test.trait".to_string(),
+            name: QualifiedName::new("test.trait"),
             statements: vec![
                 Statement::Uses {
                     reference: "dep.one".to_string(),
@@ -1612,7 +2704,10 @@ mod tests {
                     span: Span::default(),
                 },
             ],
-            exegesis: "Test".to_string(),
+            laws: vec![],
+            exegesis: "Test".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -0,0 +1,1288 @@
+//! Rendering AST nodes back to DOL source.
+//!
+//! Each node has a `to_dol()` method producing source text that parses back
+//! to an equivalent tree, so declarations can be built or edited in code and
+//! written out again. Output uses a fixed layout; comments and the original
+//! formatting are not kept. Lines, indentation and exegesis blocks go
+//! through the same printer as [`format_source`](crate::format::format_source).
+//! To change one part of an existing file while keeping the rest intact,
+//! see [`unparse`](crate::unparse) instead.
+//!
+//! # Example
+//!
+//! ```rust
+//! use metadol::parser::Parser;
+//!
+//! let source = "gene container.exists {\n  container has identity\n}\n\nexegesis {\n  A container.\n}";
+//! let decl = Parser::new(source).parse().unwrap();
+//!
+//! let rendered = decl.to_dol();
+//! assert_eq!(rendered, source);
+//! assert_eq!(Parser::new(&rendered).parse().unwrap().name(), "container.exists");
+//! ```
+
+use super::{
+    is_implicit_subject, AttributeArg, BinaryOp, CapabilityRequirement, ConstDecl, Constraint,
+    Declaration, Evolution, Exegesis, Expr, ExternBlock, ExternDecl, ForallExpr, FunctionDecl,
+    Gene, HasField, InlineConstraint, LawDecl, Literal, MacroAttribute, MatchArm, Pattern, Purity,
+    Requirement, StateDecl, Statement, Stmt, System, Trait, TypeExpr, TypeParams, UnaryOp, VarDecl,
+    Visibility,
+};
+use crate::format;
+
+impl Declaration {
+    /// Renders the declaration, with its exegesis, as DOL source.
+    pub fn to_dol(&self) -> String {
+        render(|p| p.declaration(self))
+    }
+}
+
+impl Gene {
+    /// Renders the gene, with its attributes and exegesis, as DOL source.
+    pub fn to_dol(&self) -> String {
+        render(|p| p.gene(self))
+    }
+}
+
+impl Trait {
+    /// Renders the trait, with its attributes and exegesis, as DOL source.
+    pub fn to_dol(&self) -> String {
+        render(|p| p.trait_decl(self))
+    }
+}
+
+impl Constraint {
+    /// Renders the constraint and its exegesis as DOL source.
+    pub fn to_dol(&self) -> String {
+        render(|p| p.constraint(self))
+    }
+}
+
+impl System {
+    /// Renders the system and its exegesis as DOL source.
+    pub fn to_dol(&self) -> String {
+        render(|p| p.system(self))
+    }
+}
+
+impl Evolution {
+    /// Renders the evolution and its exegesis as DOL source.
+    pub fn to_dol(&self) -> String {
+        render(|p| p.evolution(self))
+    }
+}
+
+impl Statement {
+    /// Renders a single declaration body statement.
+    pub fn to_dol(&self) -> String {
+        render(|p| p.statement(self))
+    }
+}
+
+impl FunctionDecl {
+    /// Renders the function as a `fun` declaration.
+    pub fn to_dol(&self) -> String {
+        render(|p| p.function(self))
+    }
+}
+
+impl Stmt {
+    /// Renders a function body statement, including its terminating `;`.
+    pub fn to_dol(&self) -> String {
+        render(|p| p.stmt(self))
+    }
+}
+
+impl Expr {
+    /// Renders the expression, parenthesizing only where precedence requires.
+    pub fn to_dol(&self) -> String {
+        render(|p| p.expr(self))
+    }
+}
+
+impl Pattern {
+    /// Renders the pattern as it appears in a `match` arm.
+    pub fn to_dol(&self) -> String {
+        render(|p| p.pattern(self))
+    }
+}
+
+impl TypeExpr {
+    /// Renders the type as it appears in an annotation.
+    pub fn to_dol(&self) -> String {
+        render(|p| p.type_expr(self))
+    }
+}
+
+fn render(f: impl FnOnce(&mut Printer)) -> String {
+    let mut printer = Printer {
+        lines: format::Printer::new(2),
+    };
+    f(&mut printer);
+    printer.lines.into_string()
+}
+
+/// Marker statement the parser leaves where an exegesis block ends a body.
+fn is_skip_marker(stmt: &Statement) -> bool {
+    matches!(stmt, Statement::Is { subject, state, .. } if subject == "_skip" && state == "_noop")
+}
+
+/// Returns the leading phrase of a statement written `subject predicate ...`.
+fn leading_subject(stmt: &Statement) -> Option<&str> {
+    match stmt {
+        Statement::Has { subject, .. } | Statement::Requires { subject, .. }
+            if is_implicit_subject(subject) =>
+        {
+            None
+        }
+        Statement::Has { subject, .. }
+        | Statement::Is { subject, .. }
+        | Statement::DerivesFrom { subject, .. }
+        | Statement::Requires { subject, .. }
+        | Statement::Matches { subject, .. }
+        | Statement::Never { subject, .. } => Some(subject),
+        Statement::Emits { action, .. } => Some(action),
+        _ => None,
+    }
+}
+
+/// Returns true if `prev` needs a `;` so that it does not absorb `next`.
+///
+/// Quantified statements run to the next brace, and multi-word objects
+/// (`requires`, `matches`, `derives from`) continue until a word followed
+/// by a predicate, which a multi-word subject does not supply.
+fn needs_separator(prev: &Statement, next: &Statement) -> bool {
+    match prev {
+        Statement::Quantified { .. } => true,
+        Statement::Requires { .. } | Statement::Matches { .. } | Statement::DerivesFrom { .. } => {
+            leading_subject(next).is_some_and(|subject| subject.contains(' '))
+        }
+        _ => false,
+    }
+}
+
+fn binary_op_symbol(op: BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Add => "+",
+        BinaryOp::Sub => "-",
+        BinaryOp::Mul => "*",
+        BinaryOp::Div => "/",
+        BinaryOp::Mod => "%",
+        BinaryOp::Pow => "^",
+        BinaryOp::Eq => "==",
+        BinaryOp::Ne => "!=",
+        BinaryOp::Lt => "<",
+        BinaryOp::Le => "<=",
+        BinaryOp::Gt => ">",
+        BinaryOp::Ge => ">=",
+        BinaryOp::And => "&&",
+        BinaryOp::Or => "||",
+        BinaryOp::Pipe => "|>",
+        BinaryOp::Compose => ">>",
+        BinaryOp::Apply => "@",
+        BinaryOp::Bind => ":=",
+        BinaryOp::Map => "<$>",
+        BinaryOp::Ap => "<*>",
+        BinaryOp::Implies => "implies",
+        BinaryOp::Range => "..",
+    }
+}
+
+/// Left and right binding powers, mirroring [`crate::pratt`].
+fn binary_op_power(op: BinaryOp) -> (u8, u8) {
+    match op {
+        BinaryOp::Implies => (3, 2),
+        BinaryOp::Bind => (10, 9),
+        BinaryOp::Pipe => (21, 20),
+        BinaryOp::Apply | BinaryOp::Map | BinaryOp::Ap => (31, 30),
+        BinaryOp::Compose => (40, 41),
+        BinaryOp::Range => (55, 55),
+        BinaryOp::Or => (61, 60),
+        BinaryOp::And => (71, 70),
+        BinaryOp::Eq | BinaryOp::Ne => (80, 80),
+        BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => (90, 90),
+        BinaryOp::Add | BinaryOp::Sub => (101, 100),
+        BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => (111, 110),
+        BinaryOp::Pow => (120, 121),
+    }
+}
+
+/// How an expression binds when it appears as an operand.
+enum Shape {
+    /// Self-delimiting: literals, calls, brackets, blocks
+    Atom,
+    /// A prefix operator whose operand is parsed at this binding power
+    Prefix(u8),
+    /// An infix operator with these binding powers
+    Infix(u8, u8),
+    /// Ends in a type or an unbounded expression, so it must be parenthesized
+    Open,
+}
+
+fn shape(expr: &Expr) -> Shape {
+    match expr.unspanned() {
+        Expr::Binary { op, .. } => {
+            let (left, right) = binary_op_power(*op);
+            Shape::Infix(left, right)
+        }
+        Expr::Implies { .. } => Shape::Infix(3, 2),
+        Expr::Unary { op, .. } => match op {
+            UnaryOp::Neg | UnaryOp::Not | UnaryOp::Deref => Shape::Prefix(130),
+            UnaryOp::Quote | UnaryOp::Reflect => Shape::Prefix(135),
+        },
+        Expr::Quote(_) | Expr::QuasiQuote(_) | Expr::Unquote(_) => Shape::Prefix(135),
+        Expr::Literal(Literal::Int(n)) if *n < 0 => Shape::Prefix(130),
+        Expr::Literal(Literal::Float(n)) if n.is_sign_negative() => Shape::Prefix(130),
+        Expr::Forall(forall) if iterator_forall(forall).is_some() => Shape::Atom,
+        Expr::Lambda { .. }
+        | Expr::Forall(_)
+        | Expr::Exists(_)
+        | Expr::Cast { .. }
+        | Expr::Reflect(_) => Shape::Open,
+        _ => Shape::Atom,
+    }
+}
+
+/// Returns the collection and block of an iterator-style
+/// `forall x in iter { ... }`.
+fn iterator_forall(forall: &ForallExpr) -> Option<(&Expr, &Expr)> {
+    match (&forall.iter, forall.body.unspanned()) {
+        (Some(iter), Expr::Block { .. }) => Some((iter, &forall.body)),
+        _ => None,
+    }
+}
+
+fn escape_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            _ => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn escape_char(ch: char) -> String {
+    match ch {
+        '\'' => "'\\''".to_string(),
+        '\\' => "'\\\\'".to_string(),
+        '\n' => "'\\n'".to_string(),
+        '\t' => "'\\t'".to_string(),
+        '\r' => "'\\r'".to_string(),
+        '\0' => "'\\0'".to_string(),
+        _ => format!("'{}'", ch),
+    }
+}
+
+/// Renders AST nodes onto the shared line printer.
+struct Printer {
+    lines: format::Printer,
+}
+
+impl Printer {
+    fn push(&mut self, text: &str) {
+        self.lines.push(text);
+    }
+
+    fn newline(&mut self) {
+        self.lines.newline();
+    }
+
+    /// Writes `{`, one indented line per item, and `}`; `{}` if empty.
+    fn body<T>(&mut self, items: &[T], mut item: impl FnMut(&mut Self, usize, &T)) {
+        if items.is_empty() {
+            self.push("{}");
+            return;
+        }
+        self.push("{");
+        self.lines.indent_more();
+        for (i, it) in items.iter().enumerate() {
+            self.newline();
+            item(self, i, it);
+        }
+        self.lines.indent_less();
+        self.newline();
+        self.push("}");
+    }
+
+    fn separated<T>(&mut self, items: &[T], sep: &str, mut item: impl FnMut(&mut Self, &T)) {
+        for (i, it) in items.iter().enumerate() {
+            if i > 0 {
+                self.push(sep);
+            }
+            item(self, it);
+        }
+    }
+
+    // === Declarations ===
+
+    fn declaration(&mut self, decl: &Declaration) {
+        match decl {
+            Declaration::Gene(gene) => self.gene(gene),
+            Declaration::Trait(trait_decl) => self.trait_decl(trait_decl),
+            Declaration::Constraint(constraint) => self.constraint(constraint),
+            Declaration::System(system) => self.system(system),
+            Declaration::Evolution(evolution) => self.evolution(evolution),
+            Declaration::Function(func) => self.function(func),
+            Declaration::Const(constant) => self.const_decl(constant),
+            Declaration::SexVar(var) => self.sex_var(var),
+            Declaration::Extern(block) => self.extern_block(block),
+        }
+    }
+
+    fn attributes(&mut self, attributes: &[MacroAttribute]) {
+        for attr in attributes {
+            self.push("#[");
+            self.push(&attr.name);
+            if !attr.args.is_empty() {
+                self.push("(");
+                self.attribute_args(&attr.args);
+                self.push(")");
+            }
+            self.push("]");
+            self.newline();
+        }
+    }
+
+    fn attribute_args(&mut self, args: &[AttributeArg]) {
+        self.separated(args, ", ", |p, arg| match arg {
+            AttributeArg::Ident(name) => p.push(name),
+            AttributeArg::KeyValue { key, value } => {
+                p.push(key);
+                p.push(" = ");
+                p.expr(value);
+            }
+            AttributeArg::Nested { name, args } => {
+                p.push(name);
+                p.push("(");
+                p.attribute_args(args);
+                p.push(")");
+            }
+        });
+    }
+
+    fn visibility(&mut self, visibility: Visibility) {
+        self.push(match visibility {
+            Visibility::Private => "",
+            Visibility::Public => "pub ",
+            Visibility::PubSpirit => "pub(spirit) ",
+            Visibility::PubParent => "pub(parent) ",
+        });
+    }
+
+    fn exegesis(&mut self, exegesis: &Exegesis) {
+        if !exegesis.is_empty() {
+            self.newline();
+            self.newline();
+            self.lines.exegesis(exegesis.text(), None);
+        }
+    }
+
+    fn gene(&mut self, gene: &Gene) {
+        self.attributes(&gene.attributes);
+        self.visibility(gene.visibility);
+        self.push("gene ");
+        self.push(gene.name.as_str());
+        if let Some(parent) = &gene.extends {
+            self.push(" extends ");
+            self.push(parent);
+        }
+        self.push(" ");
+        self.statements(&gene.statements);
+        self.exegesis(&gene.exegesis);
+    }
+
+    fn trait_decl(&mut self, trait_decl: &Trait) {
+        self.attributes(&trait_decl.attributes);
+        self.visibility(trait_decl.visibility);
+        enum Item<'a> {
+            Statement(&'a Statement, Option<&'a Statement>),
+            Law(&'a LawDecl),
+        }
+
+        let statements: Vec<&Statement> = trait_decl
+            .statements
+            .iter()
+            .filter(|s| !is_skip_marker(s))
+            .collect();
+        let items: Vec<Item> = statements
+            .iter()
+            .enumerate()
+            .map(|(i, s)| Item::Statement(s, statements.get(i + 1).copied()))
+            .chain(trait_decl.laws.iter().map(Item::Law))
+            .collect();
+
+        self.push("trait ");
+        self.push(trait_decl.name.as_str());
+        self.push(" ");
+        self.body(&items, |p, _, item| match item {
+            Item::Statement(stmt, next) => {
+                p.statement(stmt);
+                if next.is_some_and(|next| needs_separator(stmt, next)) {
+                    p.push(";");
+                }
+            }
+            Item::Law(law) => p.law(law),
+        });
+        self.exegesis(&trait_decl.exegesis);
+    }
+
+    fn law(&mut self, law: &LawDecl) {
+        self.push(&format!("law {}(", law.name));
+        self.separated(&law.params, ", ", |p, param| {
+            p.push(&format!("{}: ", param.name));
+            p.type_expr(&param.type_ann);
+        });
+        self.push(") { ");
+        self.expr(&law.body);
+        self.push(" }");
+        if let Some(exegesis) = &law.exegesis {
+            self.push(&format!(" exegesis {{ {} }}", exegesis.trim()));
+        }
+    }
+
+    fn constraint(&mut self, constraint: &Constraint) {
+        self.visibility(constraint.visibility);
+        self.push("constraint ");
+        self.push(constraint.name.as_str());
+        self.push(" ");
+        self.statements(&constraint.statements);
+        self.exegesis(&constraint.exegesis);
+    }
+
+    fn system(&mut self, system: &System) {
+        enum Item<'a> {
+            Requirement(&'a Requirement),
+            Capability(&'a CapabilityRequirement),
+            State(&'a StateDecl),
+            Statement(&'a Statement, Option<&'a Statement>),
+        }
+
+        let statements: Vec<&Statement> = system
+            .statements
+            .iter()
+            .filter(|s| !is_skip_marker(s))
+            .collect();
+        let items: Vec<Item> = system
+            .requirements
+            .iter()
+            .map(Item::Requirement)
+            .chain(system.capabilities.iter().map(Item::Capability))
+            .chain(system.states.iter().map(Item::State))
+            .chain(
+                statements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, s)| Item::Statement(s, statements.get(i + 1).copied())),
+            )
+            .collect();
+
+        self.visibility(system.visibility);
+        self.push(&format!("system {} @ {} ", system.name, system.version));
+        self.body(&items, |p, _, item| match item {
+            Item::Requirement(req) => p.push(&format!(
+                "requires {} {} {}",
+                req.name, req.constraint, req.version
+            )),
+            Item::Capability(cap) => p.push(&format!("requires {}", cap.expr)),
+            Item::State(state) => {
+                p.push(&format!("state {}: ", state.name));
+                p.type_expr(&state.type_);
+                if let Some(default) = &state.default {
+                    p.push(" = ");
+                    p.expr(default);
+                }
+            }
+            Item::Statement(stmt, next) => {
+                p.statement(stmt);
+                if next.is_some_and(|next| needs_separator(stmt, next)) {
+                    p.push(";");
+                }
+            }
+        });
+        self.exegesis(&system.exegesis);
+    }
+
+    fn evolution(&mut self, evolution: &Evolution) {
+        enum Item<'a> {
+            Adds(&'a Statement),
+            Deprecates(&'a Statement),
+            Removes(&'a str),
+            Because(&'a str),
+            Migrate(&'a [Stmt]),
+        }
+
+        let items: Vec<Item> = evolution
+            .additions
+            .iter()
+            .map(Item::Adds)
+            .chain(evolution.deprecations.iter().map(Item::Deprecates))
+            .chain(evolution.removals.iter().map(|r| Item::Removes(r)))
+            .chain(evolution.rationale.as_deref().map(Item::Because))
+            .chain(evolution.migrate.as_deref().map(Item::Migrate))
+            .collect();
+
+        self.push(&format!(
+            "evolves {} from {} to {} ",
+            evolution.name, evolution.parent_version, evolution.version
+        ));
+        self.body(&items, |p, _, item| match item {
+            Item::Adds(stmt) => {
+                p.push("adds ");
+                p.statement(stmt);
+            }
+            Item::Deprecates(stmt) => {
+                p.push("deprecates ");
+                p.statement(stmt);
+            }
+            Item::Removes(name) => p.push(&format!("removes {}", name)),
+            Item::Because(reason) => p.push(&format!("because {}", escape_string(reason))),
+            Item::Migrate(body) => {
+                p.push("migrate ");
+                p.body(body, |p, _, stmt| p.stmt(stmt));
+            }
+        });
+        self.exegesis(&evolution.exegesis);
+    }
+
+    fn const_decl(&mut self, constant: &ConstDecl) {
+        self.push("const ");
+        self.push(&constant.name);
+        self.type_annotation(&constant.type_ann);
+        self.push(" = ");
+        self.expr(&constant.value);
+    }
+
+    fn sex_var(&mut self, var: &VarDecl) {
+        self.push("sex var ");
+        self.push(&var.name);
+        self.type_annotation(&var.type_ann);
+        if let Some(value) = &var.value {
+            self.push(" = ");
+            self.expr(value);
+        }
+    }
+
+    fn extern_block(&mut self, block: &ExternBlock) {
+        self.push("sex extern ");
+        if let [function] = block.functions.as_slice() {
+            self.extern_fun(function);
+            return;
+        }
+        self.body(&block.functions, |p, _, function| p.extern_fun(function));
+    }
+
+    fn extern_fun(&mut self, function: &ExternDecl) {
+        if let Some(abi) = &function.abi {
+            self.push(&escape_string(abi));
+            self.push(" ");
+        }
+        self.push("fun ");
+        self.push(&function.name);
+        self.push("(");
+        self.separated(&function.params, ", ", |p, param| {
+            p.push(&format!("{}: ", param.name));
+            p.type_expr(&param.type_ann);
+        });
+        self.push(")");
+        if let Some(ret) = &function.return_type {
+            self.push(" -> ");
+            self.type_expr(ret);
+        }
+    }
+
+    fn type_annotation(&mut self, type_ann: &Option<TypeExpr>) {
+        if let Some(ty) = type_ann {
+            self.push(": ");
+            self.type_expr(ty);
+        }
+    }
+
+    // === Declaration bodies ===
+
+    fn statements(&mut self, statements: &[Statement]) {
+        let statements: Vec<&Statement> =
+            statements.iter().filter(|s| !is_skip_marker(s)).collect();
+        self.body(&statements, |p, i, stmt| {
+            p.statement(stmt);
+            if statements
+                .get(i + 1)
+                .is_some_and(|next| needs_separator(stmt, next))
+            {
+                p.push(";");
+            }
+        });
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Has {
+                subject, property, ..
+            } => {
+                if is_implicit_subject(subject) {
+                    self.push(&format!("has {}", property));
+                } else {
+                    self.push(&format!("{} has {}", subject, property));
+                }
+            }
+            Statement::HasField(field) => self.has_field(field),
+            Statement::Is { subject, state, .. } => {
+                self.push(&format!("{} is {}", subject, state));
+            }
+            Statement::DerivesFrom {
+                subject, origin, ..
+            } => self.push(&format!("{} derives from {}", subject, origin)),
+            Statement::Requires {
+                subject,
+                requirement,
+                ..
+            } => {
+                self.push(&format!("{} requires {}", subject, requirement));
+            }
+            Statement::Uses { reference, .. } => self.push(&format!("uses {}", reference)),
+            Statement::Emits { action, event, .. } => {
+                self.push(&format!("{} emits {}", action, event));
+            }
+            Statement::Matches {
+                subject, target, ..
+            } => self.push(&format!("{} matches {}", subject, target)),
+            Statement::Never {
+                subject, action, ..
+            } => self.push(&format!("{} never {}", subject, action)),
+            Statement::Quantified {
+                quantifier, phrase, ..
+            } => self.push(&format!("{} {}", quantifier, phrase)),
+            Statement::Function(func) => self.function(func),
+            Statement::Constraint(constraint) => self.inline_constraint(constraint),
+        }
+    }
+
+    fn has_field(&mut self, field: &HasField) {
+        if let Some(since) = &field.since {
+            self.push(&format!("#[since({})] ", escape_string(since)));
+        }
+        self.push(&format!("has {}: ", field.name));
+        self.type_expr(&field.type_);
+        if let Some(default) = &field.default {
+            self.push(" = ");
+            self.expr(default);
+        }
+        if let Some(constraint) = &field.constraint {
+            self.push(" where ");
+            self.expr(constraint);
+        }
+    }
+
+    fn inline_constraint(&mut self, constraint: &InlineConstraint) {
+        self.push("constraint ");
+        self.push(&constraint.name);
+        if let Some(body) = &constraint.body {
+            self.push(" {");
+            self.lines.indent_more();
+            self.newline();
+            self.expr(body);
+            self.lines.indent_less();
+            self.newline();
+            self.push("}");
+        }
+    }
+
+    // === Functions and statements ===
+
+    fn function(&mut self, func: &FunctionDecl) {
+        self.visibility(func.visibility);
+        if func.purity == Purity::Sex {
+            self.push("sex ");
+        }
+        self.push("fun ");
+        self.push(&func.name);
+        if let Some(type_params) = &func.type_params {
+            self.type_params(type_params);
+        }
+        self.push("(");
+        self.separated(&func.params, ", ", |p, param| {
+            p.push(&format!("{}: ", param.name));
+            p.type_expr(&param.type_ann);
+        });
+        self.push(")");
+        if let Some(ret) = &func.return_type {
+            self.push(" -> ");
+            self.type_expr(ret);
+        }
+        self.push(" ");
+        self.body(&func.body, |p, _, stmt| p.stmt(stmt));
+    }
+
+    fn type_params(&mut self, type_params: &TypeParams) {
+        self.push("<");
+        self.separated(&type_params.params, ", ", |p, param| {
+            p.push(&param.name);
+            if !param.bounds.is_empty() {
+                p.push(": ");
+                p.separated(&param.bounds, " + ", |p, bound| p.type_expr(bound));
+            }
+            if let Some(default) = &param.default {
+                p.push(" = ");
+                p.type_expr(default);
+            }
+        });
+        self.push(">");
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Let {
+                name,
+                type_ann,
+                value,
+            } => {
+                self.push("let ");
+                self.push(name);
+                self.type_annotation(type_ann);
+                let uninitialized = type_ann.is_some()
+                    && matches!(value.unspanned(), Expr::Identifier(v) if v == "__uninitialized__");
+                if !uninitialized {
+                    self.push(" = ");
+                    self.expr(value);
+                }
+                self.push(";");
+            }
+            Stmt::LetPattern { pattern, value } => {
+                self.push("let ");
+                self.pattern(pattern);
+                self.push(" = ");
+                self.expr(value);
+                self.push(";");
+            }
+            Stmt::Assign { target, value } => {
+                self.expr(target);
+                self.push(" = ");
+                self.expr(value);
+                self.push(";");
+            }
+            Stmt::For {
+                binding,
+                iterable,
+                body,
+            } => {
+                self.push(&format!("for {} in ", binding));
+                self.expr(iterable);
+                self.push(" ");
+                self.body(body, |p, _, stmt| p.stmt(stmt));
+            }
+            Stmt::While { condition, body } => {
+                self.push("while ");
+                self.expr(condition);
+                self.push(" ");
+                self.body(body, |p, _, stmt| p.stmt(stmt));
+            }
+            Stmt::Loop { body } => {
+                self.push("loop ");
+                self.body(body, |p, _, stmt| p.stmt(stmt));
+            }
+            Stmt::Break => self.push("break;"),
+            Stmt::Continue => self.push("continue;"),
+            Stmt::Return(value) => {
+                self.push("return");
+                if let Some(value) = value {
+                    self.push(" ");
+                    self.expr(value);
+                }
+                self.push(";");
+            }
+            Stmt::Expr(expr) => {
+                self.expr(expr);
+                self.push(";");
+            }
+        }
+    }
+
+    /// Writes a block from its statements and optional trailing value.
+    fn block(&mut self, statements: &[Stmt], final_expr: Option<&Expr>) {
+        enum Item<'a> {
+            Stmt(&'a Stmt),
+            Final(&'a Expr),
+        }
+
+        let items: Vec<Item> = statements
+            .iter()
+            .map(Item::Stmt)
+            .chain(final_expr.map(Item::Final))
+            .collect();
+        self.body(&items, |p, _, item| match item {
+            Item::Stmt(stmt) => p.stmt(stmt),
+            Item::Final(expr) => p.expr(expr),
+        });
+    }
+
+    /// Writes an `if` branch or similar braced body from an expression.
+    fn braced(&mut self, expr: &Expr) {
+        match expr.unspanned() {
+            Expr::Block {
+                statements,
+                final_expr,
+            } => self.block(statements, final_expr.as_deref()),
+            other => self.block(&[], Some(other)),
+        }
+    }
+
+    // === Expressions ===
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Spanned { expr, .. } => self.expr(expr),
+            Expr::Literal(literal) => self.literal(literal),
+            Expr::Identifier(name) => self.push(name),
+            Expr::List(items) => {
+                self.push("[");
+                self.separated(items, ", ", |p, item| p.expr(item));
+                self.push("]");
+            }
+            Expr::Tuple(items) => {
+                self.push("(");
+                self.separated(items, ", ", |p, item| p.expr(item));
+                if items.len() == 1 {
+                    self.push(",");
+                }
+                self.push(")");
+            }
+            Expr::Map(entries) => {
+                if entries.is_empty() {
+                    self.push("{}");
+                    return;
+                }
+                self.push("{ ");
+                self.separated(entries, ", ", |p, (key, value)| {
+                    p.expr(key);
+                    p.push(": ");
+                    p.expr(value);
+                });
+                self.push(" }");
+            }
+            Expr::Binary { left, op, right } => self.binary(left, *op, right),
+            Expr::Implies { left, right, .. } => self.binary(left, BinaryOp::Implies, right),
+            Expr::Unary { op, operand } => {
+                self.push(match op {
+                    UnaryOp::Neg => "-",
+                    UnaryOp::Not => "!",
+                    UnaryOp::Quote => "'",
+                    UnaryOp::Reflect => "?",
+                    UnaryOp::Deref => "*",
+                });
+                self.tight_operand(operand);
+            }
+            Expr::Call { callee, args } => {
+                self.tight_operand(callee);
+                self.push("(");
+                self.separated(args, ", ", |p, arg| p.expr(arg));
+                self.push(")");
+            }
+            Expr::StructLiteral { type_name, fields } => {
+                self.push(type_name);
+                if fields.is_empty() {
+                    self.push(" {}");
+                    return;
+                }
+                self.push(" { ");
+                self.separated(fields, ", ", |p, (name, value)| {
+                    p.push(&format!("{}: ", name));
+                    p.expr(value);
+                });
+                self.push(" }");
+            }
+            Expr::Member { object, field } => {
+                self.tight_operand(object);
+                self.push(".");
+                self.push(field);
+            }
+            Expr::Lambda {
+                params,
+                return_type,
+                body,
+            } => {
+                self.push("|");
+                self.separated(params, ", ", |p, (name, ty)| {
+                    p.push(name);
+                    p.type_annotation(ty);
+                });
+                self.push("|");
+                if let Some(ret) = return_type {
+                    self.push(" -> ");
+                    self.type_expr(ret);
+                }
+                self.push(" ");
+                self.expr(body);
+            }
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.push("if ");
+                self.expr(condition);
+                self.push(" ");
+                self.braced(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.push(" else ");
+                    match else_branch.unspanned() {
+                        chained @ Expr::If { .. } => self.expr(chained),
+                        other => self.braced(other),
+                    }
+                }
+            }
+            Expr::Match { scrutinee, arms } => {
+                self.push("match ");
+                self.expr(scrutinee);
+                self.push(" ");
+                self.body(arms, |p, _, arm| p.match_arm(arm));
+            }
+            Expr::Block {
+                statements,
+                final_expr,
+            } => self.block(statements, final_expr.as_deref()),
+            Expr::Quote(inner) => {
+                self.push("'");
+                self.tight_operand(inner);
+            }
+            Expr::QuasiQuote(inner) => {
+                self.push("''");
+                self.tight_operand(inner);
+            }
+            Expr::Unquote(inner) => {
+                self.push(",");
+                self.tight_operand(inner);
+            }
+            Expr::Eval(inner) => {
+                self.push("!{");
+                self.expr(inner);
+                self.push("}");
+            }
+            Expr::Reflect(ty) => {
+                self.push("?");
+                self.type_expr(ty);
+            }
+            Expr::IdiomBracket { func, args } => {
+                self.push("[| ");
+                self.tight_operand(func);
+                for arg in args {
+                    self.push(" ");
+                    self.tight_operand(arg);
+                }
+                self.push(" |]");
+            }
+            Expr::Forall(forall) => {
+                if let Some((iter, body)) = iterator_forall(forall) {
+                    self.push(&format!("forall {} in ", forall.var));
+                    self.expr(iter);
+                    self.push(" ");
+                    self.braced(body);
+                } else {
+                    self.push(&format!("forall {}: ", forall.var));
+                    self.type_expr(&forall.type_);
+                    self.push(". ");
+                    self.expr(&forall.body);
+                }
+            }
+            Expr::Exists(exists) => {
+                self.push(&format!("exists {}: ", exists.var));
+                self.type_expr(&exists.type_);
+                self.push(". ");
+                self.expr(&exists.body);
+            }
+            Expr::SexBlock {
+                statements,
+                final_expr,
+            } => {
+                self.push("sex ");
+                self.block(statements, final_expr.as_deref());
+            }
+            Expr::Cast { expr, target_type } => {
+                self.tight_operand(expr);
+                self.push(" as ");
+                self.type_expr(target_type);
+            }
+            Expr::Try(inner) => {
+                self.tight_operand(inner);
+                self.push("?");
+            }
+        }
+    }
+
+    fn binary(&mut self, left: &Expr, op: BinaryOp, right: &Expr) {
+        let (left_bp, right_bp) = binary_op_power(op);
+
+        // A left operand keeps its operator only if it binds tighter than
+        // ours; a right operand's operator must bind at least as tightly.
+        let left_parens = match shape(left) {
+            Shape::Atom => false,
+            Shape::Prefix(bp) | Shape::Infix(_, bp) => left_bp >= bp,
+            Shape::Open => true,
+        };
+        let right_parens = match shape(right) {
+            Shape::Atom | Shape::Prefix(_) => false,
+            Shape::Infix(bp, _) => bp < right_bp,
+            Shape::Open => true,
+        };
+
+        self.operand(left, left_parens);
+        self.push(&format!(" {} ", binary_op_symbol(op)));
+        self.operand(right, right_parens);
+    }
+
+    /// Writes the operand of a prefix or postfix form, which binds tighter
+    /// than any infix operator.
+    fn tight_operand(&mut self, expr: &Expr) {
+        let parens = !matches!(shape(expr), Shape::Atom);
+        self.operand(expr, parens);
+    }
+
+    fn operand(&mut self, expr: &Expr, parens: bool) {
+        if parens {
+            self.push("(");
+            self.expr(expr);
+            self.push(")");
+        } else {
+            self.expr(expr);
+        }
+    }
+
+    fn match_arm(&mut self, arm: &MatchArm) {
+        self.pattern(&arm.pattern);
+        if let Some(guard) = &arm.guard {
+            self.push(" if ");
+            self.expr(guard);
+        }
+        self.push(" => ");
+        self.expr(&arm.body);
+        self.push(",");
+    }
+
+    fn literal(&mut self, literal: &Literal) {
+        match literal {
+            Literal::Int(n) => self.push(&n.to_string()),
+            Literal::Float(n) => self.push(&format!("{:?}", n)),
+            Literal::String(s) => self.push(&escape_string(s)),
+            Literal::Char(c) => self.push(&escape_char(*c)),
+            Literal::Bool(b) => self.push(if *b { "true" } else { "false" }),
+            Literal::Null => self.push("null"),
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Wildcard => self.push("_"),
+            Pattern::Identifier(name) => self.push(name),
+            Pattern::Literal(literal) => self.literal(literal),
+            Pattern::Constructor { name, fields } => {
+                self.push(name);
+                self.push("(");
+                self.separated(fields, ", ", |p, field| p.pattern(field));
+                self.push(")");
+            }
+            Pattern::Tuple(items) => {
+                self.push("(");
+                self.separated(items, ", ", |p, item| p.pattern(item));
+                self.push(")");
+            }
+            Pattern::Or(alternatives) => {
+                self.separated(alternatives, " | ", |p, alt| p.pattern(alt));
+            }
+        }
+    }
+
+    // === Types ===
+
+    fn type_expr(&mut self, ty: &TypeExpr) {
+        match ty {
+            TypeExpr::Named(name) => self.push(name),
+            TypeExpr::Generic { name, args } => {
+                self.push(name);
+                self.push("<");
+                self.separated(args, ", ", |p, arg| p.type_expr(arg));
+                self.push(">");
+            }
+            TypeExpr::Function {
+                params,
+                return_type,
+            } => {
+                self.push("(");
+                self.separated(params, ", ", |p, param| p.type_expr(param));
+                self.push(") -> ");
+                self.type_expr(return_type);
+            }
+            TypeExpr::Tuple(items) => {
+                self.push("(");
+                self.separated(items, ", ", |p, item| p.type_expr(item));
+                self.push(")");
+            }
+            TypeExpr::Never => self.push("!"),
+            TypeExpr::Enum { variants } => {
+                self.push("enum { ");
+                self.separated(variants, ", ", |p, variant| {
+                    p.push(&variant.name);
+                    if !variant.tuple_types.is_empty() {
+                        p.push("(");
+                        p.separated(&variant.tuple_types, ", ", |p, ty| p.type_expr(ty));
+                        p.push(")");
+                    } else if !variant.fields.is_empty() {
+                        p.push(" ");
+                        p.record_fields(&variant.fields);
+                    }
+                    if let Some(discriminant) = variant.discriminant {
+                        p.push(&format!(" = {}", discriminant));
+                    }
+                });
+                self.push(" }");
+            }
+            TypeExpr::Record(fields) => self.record_fields(fields),
+        }
+    }
+
+    fn record_fields(&mut self, fields: &[(String, TypeExpr)]) {
+        self.push("{ ");
+        self.separated(fields, ", ", |p, (name, ty)| {
+            p.push(&format!("{}: ", name));
+            p.type_expr(ty);
+        });
+        self.push(" }");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::Parser;
+
+    /// Debug output with source locations removed, for comparing trees
+    /// parsed from differently laid out source.
+    fn without_spans<T: std::fmt::Debug>(node: &T) -> String {
+        let debug = format!("{:?}", node);
+        let mut out = String::with_capacity(debug.len());
+        let mut rest = debug.as_str();
+        while let Some(start) = rest.find("Span {") {
+            out.push_str(&rest[..start]);
+            let end = rest[start..].find('}').expect("unterminated span");
+            rest = &rest[start + end + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    fn assert_roundtrip(source: &str) -> String {
+        let original = Parser::new(source).parse_all().unwrap();
+        let rendered: Vec<String> = original.iter().map(|d| d.to_dol()).collect();
+        let rendered = rendered.join("\n\n");
+        let reparsed = Parser::new(&rendered)
+            .parse_all()
+            .unwrap_or_else(|e| panic!("rendered source does not parse: {e}\n{rendered}"));
+        assert_eq!(
+            without_spans(&original),
+            without_spans(&reparsed),
+            "\n{rendered}"
+        );
+        rendered
+    }
+
+    #[test]
+    fn test_gene_roundtrip() {
+        let rendered = assert_roundtrip(
+            "gene container.exists {\n  container has identity\n  container is created\n  \
+             identity derives from ed25519 keypair\n  identity requires no authority\n}\n\n\
+             exegesis {\n  A container.\n}",
+        );
+        assert!(rendered.contains("  identity derives from ed25519 keypair\n"));
+    }
+
+    #[test]
+    fn test_attributes_and_fields_roundtrip() {
+        assert_roundtrip(
+            "#[deprecated(\"use \\\"v2\\\"\")]\npub gene Point extends Shape {\n  \
+             has x: Float64 = 0.0\n  #[since(\"1.2.0\")] has tags: List<String>\n  \
+             has label: String? = null\n  law symmetric\n  has origin\n}",
+        );
+        assert_roundtrip("#[test]\ngene counter {\n  has count: Int64 = 0\n}");
+        assert_roundtrip(
+            "#[derive(Debug, Clone)]\n#[serde(rename(name = \"pt\"), skip)]\ngene point {\n  \
+             has x: Int64\n}",
+        );
+    }
+
+    #[test]
+    fn test_trait_quantified_roundtrip() {
+        let rendered = assert_roundtrip(
+            "trait container.lifecycle {\n  uses container.exists\n  \
+             each transition emits event;\n  container is started\n  \
+             law idempotent(c: Container) { start(start(c)) == start(c) } exegesis { Once. }\n}\n\n\
+             exegesis {\n  Lifecycle.\n}",
+        );
+        assert!(rendered.contains("each transition emits event;\n"));
+        assert!(rendered.contains(
+            "  law idempotent(c: Container) { start(start(c)) == start(c) } exegesis { Once. }\n"
+        ));
+    }
+
+    #[test]
+    fn test_constraint_roundtrip() {
+        assert_roundtrip(
+            "constraint container.integrity {\n  runtime matches declared config;\n  \
+             container identity never changes\n  constraint bounded { count <= 10 }\n}",
+        );
+    }
+
+    #[test]
+    fn test_system_roundtrip() {
+        assert_roundtrip(
+            "system univrs.orchestrator @ 0.1.0 {\n  requires container.lifecycle >= 0.0.2\n  \
+             requires (network and storage) or local_disk\n  state peers: List<String>\n  \
+             state ticks: Int64 = 0\n  node peers is discovered\n  nodes requires gossip\n}",
+        );
+    }
+
+    #[test]
+    fn test_evolution_roundtrip() {
+        let rendered = assert_roundtrip(
+            "evolves container.lifecycle from 0.0.1 to 0.0.2 {\n  adds container is paused\n  \
+             deprecates container is frozen\n  removes legacy\n  \
+             because \"migration needs \\\"pause\\\"\"\n  \
+             migrate {\n    let paused = false\n  }\n}\n\nexegesis {\n  Adds pausing.\n}",
+        );
+        assert!(rendered.starts_with("evolves container.lifecycle from 0.0.1 to 0.0.2 {\n"));
+        assert!(rendered.contains("  removes legacy\n"));
+    }
+
+    #[test]
+    fn test_function_roundtrip() {
+        assert_roundtrip(
+            "pub fun classify(x: Int64, f: (Int64) -> Bool) -> String {\n  \
+             let y: Int64 = -x * (2 + 3) - 1\n  let pair = (y,)\n  \
+             val z = if x > 0 && !f(x) { \"pos\" } else if x == 0 { 'z' } else { \"n\\ty\" }\n  \
+             for i in 0..10 { y = y + i }\n  while y > 0 { break }\n  \
+             return match pair { (a) if a > 1 => \"big\", Some(_) | None => { return \"other\" }, _ => \"x\" }\n}",
+        );
+    }
+
+    #[test]
+    fn test_expression_roundtrip() {
+        assert_roundtrip(
+            "sex fun effects() {\n  let xs = [1, 2.5, point.x]\n  \
+             let m = { \"a\": 1, \"b\": 2 }\n  let p = Point { x: 1, y: (2 - 3) - 4 }\n  \
+             let f = |a: Int64| -> Int64 a + 1\n  let g = (|a| a) >> f\n  \
+             let c = (x as Float64) * 2.0\n  let r = read(path)?\n  \
+             let q = ''(1 + ,x)\n  let e = !{ q }\n  let t = ?Int32\n  \
+             let k = [| add a b |]\n  let every = forall n: Int64. n >= 0\n  \
+             sex { write(x); }\n  forall v in items { check(v) }\n  \
+             let s = 2 ^ 3 ^ 4 |> show\n}",
+        );
+    }
+
+    #[test]
+    fn test_const_and_sex_var_roundtrip() {
+        assert_roundtrip("const MAX_HOPS: Int32 = 100\n\nsex var counter: Int64 = 0");
+    }
+}
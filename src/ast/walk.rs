@@ -0,0 +1,253 @@
+//! Closure-based AST traversal.
+//!
+//! The [`Visitor`](crate::transform::visitor::Visitor) trait suits analyses
+//! that carry state across many node kinds. For one-off walks, the helpers
+//! here take a closure instead of a struct.
+//!
+//! # Example
+//!
+//! ```rust
+//! use metadol::ast::{walk, Expr};
+//! use metadol::parser::Parser;
+//!
+//! let source = "fun f() -> Int { return 1 + 2 }\nexegesis { Adds. }";
+//! let file = Parser::new(source).parse_file().unwrap();
+//!
+//! let mut literals = 0;
+//! walk::for_each_expr(&file.declarations[0], &mut |e| {
+//!     if matches!(e, Expr::Literal(_)) {
+//!         literals += 1;
+//!     }
+//! });
+//! assert_eq!(literals, 2);
+//! ```
+
+use super::{Declaration, Expr, Statement, Stmt};
+
+/// A borrowed AST node, as handed to [`pre_order`] callbacks.
+#[derive(Debug, Clone, Copy)]
+pub enum Node<'a> {
+    /// A top-level declaration
+    Declaration(&'a Declaration),
+    /// A DOL 1.x declaration body statement
+    Statement(&'a Statement),
+    /// A DOL 2.0 statement
+    Stmt(&'a Stmt),
+    /// An expression
+    Expr(&'a Expr),
+}
+
+/// Calls `f` on `decl` and every node beneath it, parents before children.
+pub fn pre_order<'a, F>(decl: &'a Declaration, f: &mut F)
+where
+    F: FnMut(Node<'a>),
+{
+    f(Node::Declaration(decl));
+    match decl {
+        Declaration::Gene(gene) => statements(&gene.statements, f),
+        Declaration::Trait(tr) => {
+            statements(&tr.statements, f);
+            for law in &tr.laws {
+                expr(&law.body, f);
+            }
+        }
+        Declaration::Constraint(c) => statements(&c.statements, f),
+        Declaration::System(sys) => {
+            statements(&sys.statements, f);
+            for state in &sys.states {
+                if let Some(default) = &state.default {
+                    expr(default, f);
+                }
+            }
+        }
+        Declaration::Evolution(evo) => {
+            statements(&evo.additions, f);
+            statements(&evo.deprecations, f);
+            if let Some(migrate) = &evo.migrate {
+                stmts(migrate, f);
+            }
+        }
+        Declaration::Function(func) => stmts(&func.body, f),
+        Declaration::Const(c) => expr(&c.value, f),
+        Declaration::SexVar(var) => {
+            if let Some(value) = &var.value {
+                expr(value, f);
+            }
+        }
+        Declaration::Extern(_) => {}
+    }
+}
+
+/// Calls `f` on every expression in `decl`, outer expressions first.
+pub fn for_each_expr<'a, F>(decl: &'a Declaration, f: &mut F)
+where
+    F: FnMut(&'a Expr),
+{
+    pre_order(decl, &mut |node| {
+        if let Node::Expr(e) = node {
+            f(e);
+        }
+    });
+}
+
+fn statements<'a, F: FnMut(Node<'a>)>(list: &'a [Statement], f: &mut F) {
+    for stmt in list {
+        f(Node::Statement(stmt));
+        match stmt {
+            Statement::HasField(field) => {
+                if let Some(default) = &field.default {
+                    expr(default, f);
+                }
+                if let Some(constraint) = &field.constraint {
+                    expr(constraint, f);
+                }
+            }
+            Statement::Function(func) => stmts(&func.body, f),
+            _ => {}
+        }
+    }
+}
+
+fn stmts<'a, F: FnMut(Node<'a>)>(list: &'a [Stmt], f: &mut F) {
+    for stmt in list {
+        f(Node::Stmt(stmt));
+        match stmt {
+            Stmt::Let { value, .. } | Stmt::LetPattern { value, .. } => expr(value, f),
+            Stmt::Assign { target, value } => {
+                expr(target, f);
+                expr(value, f);
+            }
+            Stmt::For { iterable, body, .. } => {
+                expr(iterable, f);
+                stmts(body, f);
+            }
+            Stmt::While { condition, body } => {
+                expr(condition, f);
+                stmts(body, f);
+            }
+            Stmt::Loop { body } => stmts(body, f),
+            Stmt::Return(Some(value)) | Stmt::Expr(value) => expr(value, f),
+            Stmt::Return(None) | Stmt::Break | Stmt::Continue => {}
+        }
+    }
+}
+
+fn expr<'a, F: FnMut(Node<'a>)>(e: &'a Expr, f: &mut F) {
+    f(Node::Expr(e));
+    match e {
+        Expr::Literal(_) | Expr::Identifier(_) | Expr::Reflect(_) => {}
+        Expr::List(items) | Expr::Tuple(items) => {
+            for item in items {
+                expr(item, f);
+            }
+        }
+        Expr::Map(entries) => {
+            for (key, value) in entries {
+                expr(key, f);
+                expr(value, f);
+            }
+        }
+        Expr::Binary { left, right, .. } | Expr::Implies { left, right, .. } => {
+            expr(left, f);
+            expr(right, f);
+        }
+        Expr::Call { callee: func, args } | Expr::IdiomBracket { func, args } => {
+            expr(func, f);
+            for arg in args {
+                expr(arg, f);
+            }
+        }
+        Expr::StructLiteral { fields, .. } => {
+            for (_, value) in fields {
+                expr(value, f);
+            }
+        }
+        Expr::Unary { operand: inner, .. }
+        | Expr::Member { object: inner, .. }
+        | Expr::Lambda { body: inner, .. }
+        | Expr::Quote(inner)
+        | Expr::Unquote(inner)
+        | Expr::QuasiQuote(inner)
+        | Expr::Eval(inner)
+        | Expr::Cast { expr: inner, .. }
+        | Expr::Try(inner)
+        | Expr::Spanned { expr: inner, .. } => expr(inner, f),
+        Expr::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            expr(condition, f);
+            expr(then_branch, f);
+            if let Some(else_branch) = else_branch {
+                expr(else_branch, f);
+            }
+        }
+        Expr::Match { scrutinee, arms } => {
+            expr(scrutinee, f);
+            for arm in arms {
+                if let Some(guard) = &arm.guard {
+                    expr(guard, f);
+                }
+                expr(&arm.body, f);
+            }
+        }
+        Expr::Block {
+            statements,
+            final_expr,
+        }
+        | Expr::SexBlock {
+            statements,
+            final_expr,
+        } => {
+            stmts(statements, f);
+            if let Some(final_expr) = final_expr {
+                expr(final_expr, f);
+            }
+        }
+        Expr::Forall(q) => {
+            if let Some(iter) = &q.iter {
+                expr(iter, f);
+            }
+            expr(&q.body, f);
+        }
+        Expr::Exists(q) => expr(&q.body, f),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_count_literals() {
+        let source = r#"
+fun pick(flag: Bool) -> Int {
+    let base = 10
+    if flag {
+        return base + 1
+    }
+    return match base { 10 => 2, _ => 3 }
+}
+exegesis { Picks a number. }
+"#;
+        let file = Parser::new(source).parse_file().unwrap();
+
+        let mut literals = 0;
+        for_each_expr(&file.declarations[0], &mut |e| {
+            if matches!(e, Expr::Literal(_)) {
+                literals += 1;
+            }
+        });
+        assert_eq!(literals, 4);
+
+        let mut lets = 0;
+        pre_order(&file.declarations[0], &mut |node| {
+            if let Node::Stmt(Stmt::Let { .. }) = node {
+                lets += 1;
+            }
+        });
+        assert_eq!(lets, 1);
+    }
+}
@@ -20,15 +20,20 @@
 //!
 //! # Enable DOL 2.0 type checking
 //! dol-check --typecheck examples/
+//!
+//! # Lint side effects (SEX)
+//! dol-check --lint-sex examples/
 //! ```
 
 use clap::{Parser, ValueEnum};
 use colored::Colorize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
-use metadol::validator::{validate_with_options, ValidationOptions};
-use metadol::{parse_file, Declaration};
+use metadol::repository::Repository;
+use metadol::sex::{file_sex_context, lint::SexLinter};
+use metadol::validator::{validate_with_options, ValidationOptions, DEFAULT_MIN_EXEGESIS_LENGTH};
+use metadol::Declaration;
 
 /// Validate DOL files and check coverage
 #[derive(Parser, Debug)]
@@ -43,8 +48,8 @@ struct Args {
     #[arg(long)]
     require_exegesis: bool,
 
-    /// Minimum exegesis length (characters)
-    #[arg(long, default_value = "20")]
+    /// Minimum exegesis length (characters) before it is flagged as short
+    #[arg(long, default_value_t = DEFAULT_MIN_EXEGESIS_LENGTH)]
     min_exegesis_length: usize,
 
     /// Source directory to check coverage against
@@ -59,6 +64,10 @@ struct Args {
     #[arg(long)]
     typecheck: bool,
 
+    /// Lint side effects (SEX) across all declarations in each file
+    #[arg(long)]
+    lint_sex: bool,
+
     /// CI mode: exit code only, minimal output
     #[arg(long)]
     ci: bool,
@@ -105,6 +114,7 @@ struct CheckError {
     path: PathBuf,
     message: String,
     line: Option<usize>,
+    code: Option<&'static str>,
 }
 
 #[derive(Debug)]
@@ -210,6 +220,53 @@ fn collect_dol_files_recursive(dir: &PathBuf, files: &mut Vec<PathBuf>) {
     }
 }
 
+/// Creates the parser used for checked files.
+///
+/// Expressions carry spans so diagnostics point at the failing
+/// subexpression rather than the enclosing declaration.
+fn parser_for(source: &str) -> metadol::parser::Parser<'_> {
+    metadol::parser::Parser::new(source).with_expr_spans()
+}
+
+/// Runs the SEX linter over every declaration in `source`, in the context
+/// implied by the file's path.
+fn lint_sex(
+    path: &Path,
+    source: &str,
+    errors: &mut Vec<CheckError>,
+    warnings: &mut Vec<CheckWarning>,
+) {
+    let decls = match parser_for(source).parse_all() {
+        Ok(decls) => decls,
+        Err(e) => {
+            errors.push(CheckError {
+                path: path.to_path_buf(),
+                message: format!("Parse error: {}", e),
+                line: Some(e.span().line),
+                code: Some(e.code()),
+            });
+            return;
+        }
+    };
+
+    let result = SexLinter::new(file_sex_context(path)).lint_repository(&Repository::new(&decls));
+    for error in &result.errors {
+        errors.push(CheckError {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+            line: Some(error.span().line),
+            code: Some(error.code()),
+        });
+    }
+    for warning in &result.warnings {
+        warnings.push(CheckWarning {
+            path: path.to_path_buf(),
+            message: warning.to_string(),
+            line: Some(warning.span().line),
+        });
+    }
+}
+
 fn check_file(
     path: &PathBuf,
     args: &Args,
@@ -225,27 +282,40 @@ fn check_file(
                 path: path.clone(),
                 message: format!("Failed to read file: {}", e),
                 line: None,
+                code: None,
             });
             return Err(errors);
         }
     };
 
     // Parse file
-    let decl = match parse_file(&source) {
+    let mut parser = parser_for(&source);
+    let decl = match parser.parse() {
         Ok(d) => d,
         Err(e) => {
             errors.push(CheckError {
                 path: path.clone(),
                 message: format!("Parse error: {}", e),
                 line: Some(e.span().line),
+                code: Some(e.code()),
             });
             return Err(errors);
         }
     };
 
+    // Report non-fatal parser diagnostics, such as deprecated syntax
+    for warning in parser.warnings() {
+        warnings.push(CheckWarning {
+            path: path.clone(),
+            message: warning.message.clone(),
+            line: warning.span.map(|span| span.line),
+        });
+    }
+
     // Validate with optional type checking
     let validation_options = ValidationOptions {
         typecheck: args.typecheck,
+        min_exegesis_length: args.min_exegesis_length,
     };
     let validation = validate_with_options(&decl, &validation_options);
 
@@ -254,7 +324,8 @@ fn check_file(
         errors.push(CheckError {
             path: path.clone(),
             message: error.to_string(),
-            line: None,
+            line: error.span().map(|span| span.line),
+            code: Some(error.code()),
         });
     }
 
@@ -263,31 +334,24 @@ fn check_file(
         warnings.push(CheckWarning {
             path: path.clone(),
             message: warning.to_string(),
-            line: None,
+            line: warning.span().map(|span| span.line),
         });
     }
 
-    // Check exegesis requirements
+    if args.lint_sex {
+        lint_sex(path, &source, &mut errors, &mut warnings);
+    }
+
+    // Check exegesis requirements; validation already flags short exegesis
     let exegesis = decl.exegesis();
     let exegesis_length = exegesis.trim().len();
-
-    if args.require_exegesis {
-        if exegesis.trim().is_empty() {
-            errors.push(CheckError {
-                path: path.clone(),
-                message: "Missing or empty exegesis block".to_string(),
-                line: None,
-            });
-        } else if exegesis_length < args.min_exegesis_length {
-            warnings.push(CheckWarning {
-                path: path.clone(),
-                message: format!(
-                    "Exegesis too short ({} chars, minimum {})",
-                    exegesis_length, args.min_exegesis_length
-                ),
-                line: None,
-            });
-        }
+    if args.require_exegesis && exegesis.trim().is_empty() {
+        errors.push(CheckError {
+            path: path.clone(),
+            message: "Missing or empty exegesis block".to_string(),
+            line: None,
+            code: None,
+        });
     }
 
     // ═══════════════════════════════════════════════════════════════════
@@ -315,6 +379,7 @@ fn check_file(
         Declaration::Function(_) => "function",
         Declaration::Const(_) => "const",
         Declaration::SexVar(_) => "sex_var",
+        Declaration::Extern(_) => "extern",
     };
 
     Ok((
@@ -433,7 +498,10 @@ fn output_pretty(results: &CheckResults, args: &Args) {
             print!(":{}", line);
         }
         println!();
-        println!("  {}", error.message.red());
+        match error.code {
+            Some(code) => println!("  {}", format!("error[{}]: {}", code, error.message).red()),
+            None => println!("  {}", error.message.red()),
+        }
     }
 
     // Print warnings (unless quiet)
@@ -506,6 +574,7 @@ fn output_json(results: &CheckResults) {
         path: String,
         message: String,
         line: Option<usize>,
+        code: Option<&'static str>,
     }
 
     #[derive(serde::Serialize)]
@@ -545,6 +614,7 @@ fn output_json(results: &CheckResults) {
                 path: e.path.display().to_string(),
                 message: e.message.clone(),
                 line: e.line,
+                code: e.code,
             })
             .collect(),
         warnings: results
@@ -608,4 +678,48 @@ mod tests {
         let files = collect_dol_files(&[]);
         assert!(files.is_empty());
     }
+
+    #[test]
+    fn test_assert_failure_points_at_comparison() {
+        use metadol::eval::{Interpreter, Value};
+
+        let source = "fun check(x: Int64) -> Bool {\n    #assert(x > 0)\n    return true\n}";
+        let decl = parser_for(source).parse().unwrap();
+
+        let mut interp = Interpreter::new();
+        interp.load(std::slice::from_ref(&decl));
+        let err = interp
+            .call_function("check", vec![Value::Int(0)])
+            .unwrap_err();
+        let span = err.span.expect("assertion should carry a span");
+        assert_eq!(&source[span.start..span.end], "x > 0");
+        assert_eq!((span.line, span.column), (2, 13));
+    }
+
+    #[test]
+    fn test_lint_sex_points_at_effectful_call() {
+        let source = "sex extern fun c_write(fd: Int64) -> Int64\n\n\
+                      fun flush(fd: Int64) -> Int64 {\n    return c_write(fd)\n}";
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+        lint_sex(
+            &PathBuf::from("io.sex.dol"),
+            source,
+            &mut errors,
+            &mut warnings,
+        );
+        assert!(errors.is_empty());
+
+        lint_sex(
+            &PathBuf::from("flush.dol"),
+            source,
+            &mut errors,
+            &mut warnings,
+        );
+        let impure = errors
+            .iter()
+            .find(|e| e.code == Some("E005"))
+            .expect("flush should be reported");
+        assert_eq!(impure.line, Some(4));
+    }
 }
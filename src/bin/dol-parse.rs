@@ -20,6 +20,9 @@
 //!
 //! # CI mode (exit code only)
 //! dol-parse --ci --recursive .
+//!
+//! # Parse from stdin
+//! cat container.exists.dol | dol-parse -
 //! ```
 
 use clap::{Parser, ValueEnum};
@@ -27,14 +30,14 @@ use colored::Colorize;
 use std::path::PathBuf;
 use std::process::ExitCode;
 
-use metadol::{parse_file, validate, Declaration, ValidationResult};
+use metadol::{parse_file, validate, Declaration, ValidationResult, STDIN_PATH};
 
 /// Parse and validate Metal DOL files
 #[derive(Parser, Debug)]
 #[command(name = "dol-parse")]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Files or directories to parse
+    /// Files or directories to parse (`-` reads from stdin)
     #[arg(required = true)]
     paths: Vec<PathBuf>,
 
@@ -173,7 +176,9 @@ fn collect_dol_files(paths: &[PathBuf], recursive: bool) -> Vec<PathBuf> {
     let mut files = Vec::new();
 
     for path in paths {
-        if path.is_file() {
+        if path.as_os_str() == "-" {
+            files.push(PathBuf::from(STDIN_PATH));
+        } else if path.is_file() {
             if path.extension().is_some_and(|ext| ext == "dol") {
                 files.push(path.clone());
             }
@@ -212,15 +217,23 @@ fn collect_dol_files_recursive(dir: &PathBuf, files: &mut Vec<PathBuf>) {
 }
 
 fn process_file(path: &PathBuf) -> Result<(Declaration, ValidationResult), String> {
-    let source =
-        std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let source = if path.as_os_str() == STDIN_PATH {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(path)
+    }
+    .map_err(|e| format!("Failed to read file: {}", e))?;
 
-    let decl = parse_file(&source).map_err(|e| format!("Parse error: {}", e))?;
+    let decl = parse_file(&source).map_err(|e| e.located(&path.display().to_string()))?;
 
     let validation = validate(&decl);
 
     if !validation.is_valid() {
-        let errors: Vec<String> = validation.errors.iter().map(|e| e.to_string()).collect();
+        let errors: Vec<String> = validation
+            .errors
+            .iter()
+            .map(|e| format!("error[{}]: {}", e.code(), e))
+            .collect();
         return Err(format!("Validation errors:\n  {}", errors.join("\n  ")));
     }
 
@@ -276,6 +289,7 @@ fn output_json(results: &[ParseResult], _args: &Args) {
                 Declaration::Function(_) => "function".to_string(),
                 Declaration::Const(_) => "const".to_string(),
                 Declaration::SexVar(_) => "sex_var".to_string(),
+                Declaration::Extern(_) => "extern".to_string(),
             }),
             name: r.declaration.as_ref().map(|d| d.name().to_string()),
             error: r.error.clone(),
@@ -345,6 +359,7 @@ fn output_compact(results: &[ParseResult], _args: &Args) {
                 Declaration::Function(_) => "function",
                 Declaration::Const(_) => "const",
                 Declaration::SexVar(_) => "sex_var",
+                Declaration::Extern(_) => "extern",
             };
             println!(
                 "OK\t{}\t{}\t{}",
@@ -380,7 +395,7 @@ fn print_declaration_summary(decl: &Declaration) {
         Declaration::Gene(g) => {
             println!(
                 "    {} gene with {} statements",
-                g.name.dimmed(),
+                g.name.as_str().dimmed(),
                 g.statements.len()
             );
         }
@@ -392,7 +407,7 @@ fn print_declaration_summary(decl: &Declaration) {
                 .count();
             println!(
                 "    {} trait using {} dependencies, {} behaviors",
-                t.name.dimmed(),
+                t.name.as_str().dimmed(),
                 uses_count,
                 t.statements.len() - uses_count
             );
@@ -400,14 +415,14 @@ fn print_declaration_summary(decl: &Declaration) {
         Declaration::Constraint(c) => {
             println!(
                 "    {} constraint with {} rules",
-                c.name.dimmed(),
+                c.name.as_str().dimmed(),
                 c.statements.len()
             );
         }
         Declaration::System(s) => {
             println!(
                 "    {} system @ {} with {} requirements",
-                s.name.dimmed(),
+                s.name.as_str().dimmed(),
                 s.version,
                 s.requirements.len()
             );
@@ -434,6 +449,13 @@ fn print_declaration_summary(decl: &Declaration) {
         Declaration::SexVar(v) => {
             println!("    {} sex var (mutable global)", v.name.dimmed());
         }
+        Declaration::Extern(e) => {
+            println!(
+                "    {} extern block with {} functions",
+                e.name().dimmed(),
+                e.functions.len()
+            );
+        }
     }
 }
 
@@ -407,6 +407,7 @@ mod tests {
         let file = DolFile {
             module: None,
             uses: Vec::new(),
+            includes: Vec::new(),
             declarations: Vec::new(),
         };
         assert!(file.module.is_none());
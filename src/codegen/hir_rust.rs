@@ -34,6 +34,12 @@ impl HirRustCodegen {
 
     /// Generate Rust code from a HIR module
     pub fn generate(&mut self, module: &HirModule) -> String {
+        self.generate_mapped(module).0
+    }
+
+    /// Generate Rust code from a HIR module, along with the byte offset in
+    /// the output at which each top-level declaration starts
+    pub fn generate_mapped(&mut self, module: &HirModule) -> (String, Vec<usize>) {
         self.output.clear();
 
         // Module header
@@ -41,12 +47,14 @@ impl HirRustCodegen {
         self.emit_line("");
 
         // Generate declarations
+        let mut offsets = Vec::with_capacity(module.decls.len());
         for decl in &module.decls {
+            offsets.push(self.output.len());
             self.gen_decl(decl);
             self.emit_line("");
         }
 
-        std::mem::take(&mut self.output)
+        (std::mem::take(&mut self.output), offsets)
     }
 
     fn emit(&mut self, s: &str) {
@@ -134,11 +142,13 @@ impl HirRustCodegen {
                     match &stmt.kind {
                         HirStatementKind::Has { subject, property } => {
                             // subject has property -> field: property
-                            self.emit_line(&format!(
-                                "/// {} has {}",
-                                self.sym(*subject),
-                                self.sym(*property)
-                            ));
+                            let subject = self.sym(*subject);
+                            let doc = if crate::ast::is_implicit_subject(subject) {
+                                format!("/// has {}", self.sym(*property))
+                            } else {
+                                format!("/// {} has {}", subject, self.sym(*property))
+                            };
+                            self.emit_line(&doc);
                             self.emit_line(&format!(
                                 "pub {}: String, // TODO: infer type",
                                 self.sym(*property)
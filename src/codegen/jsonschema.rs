@@ -25,8 +25,9 @@
 //!
 //! let source = r#"
 //! gene container.exists {
-//!   container has id
-//!   container has image
+//!   has id: String
+//!   has port: Int32
+//!   has label: Option<String>
 //! }
 //!
 //! exegesis {
@@ -36,10 +37,14 @@
 //!
 //! let decl = parse_file(source).unwrap();
 //! let schema = JsonSchemaCodegen::generate(&decl);
-//! println!("{}", schema);
+//! assert!(schema.contains(r#""port": { "type": "integer" }"#));
+//! assert!(schema.contains(r#""required": ["id", "port"]"#));
 //! ```
 
-use crate::ast::{Constraint, Declaration, Evolution, Gene, Statement, System, Trait, TypeExpr};
+use crate::ast::{
+    is_implicit_subject, Constraint, Declaration, Evolution, Gene, Statement, System, Trait,
+    TypeExpr,
+};
 use crate::typechecker::Type;
 
 use super::{to_pascal_case, CodegenOptions, TypeMapper};
@@ -79,14 +84,15 @@ impl JsonSchemaCodegen {
 
         for decl in decls {
             let name = match decl {
-                Declaration::Gene(g) => to_pascal_case(&g.name),
-                Declaration::Trait(t) => to_pascal_case(&t.name),
-                Declaration::Constraint(c) => to_pascal_case(&c.name),
-                Declaration::System(s) => to_pascal_case(&s.name),
+                Declaration::Gene(g) => to_pascal_case(g.name.as_str()),
+                Declaration::Trait(t) => to_pascal_case(t.name.as_str()),
+                Declaration::Constraint(c) => to_pascal_case(c.name.as_str()),
+                Declaration::System(s) => to_pascal_case(s.name.as_str()),
                 Declaration::Evolution(e) => to_pascal_case(&e.name),
                 Declaration::Function(f) => to_pascal_case(&f.name),
                 Declaration::Const(c) => to_pascal_case(&c.name),
                 Declaration::SexVar(v) => to_pascal_case(&v.name),
+                Declaration::Extern(e) => to_pascal_case(e.name()),
             };
             let schema = generator.generate_declaration_inner(decl);
             defs.push(format!("    \"{}\": {}", name, schema));
@@ -106,14 +112,15 @@ impl JsonSchemaCodegen {
     /// Generate schema for a single declaration.
     fn generate_declaration(&self, decl: &Declaration) -> String {
         let title = match decl {
-            Declaration::Gene(g) => to_pascal_case(&g.name),
-            Declaration::Trait(t) => to_pascal_case(&t.name),
-            Declaration::Constraint(c) => to_pascal_case(&c.name),
-            Declaration::System(s) => to_pascal_case(&s.name),
+            Declaration::Gene(g) => to_pascal_case(g.name.as_str()),
+            Declaration::Trait(t) => to_pascal_case(t.name.as_str()),
+            Declaration::Constraint(c) => to_pascal_case(c.name.as_str()),
+            Declaration::System(s) => to_pascal_case(s.name.as_str()),
             Declaration::Evolution(e) => to_pascal_case(&e.name),
             Declaration::Function(f) => to_pascal_case(&f.name),
             Declaration::Const(c) => to_pascal_case(&c.name),
             Declaration::SexVar(v) => to_pascal_case(&v.name),
+            Declaration::Extern(e) => to_pascal_case(e.name()),
         };
 
         let inner = self.generate_declaration_inner(decl);
@@ -121,9 +128,11 @@ impl JsonSchemaCodegen {
         format!(
             r#"{{
   "$schema": "http://json-schema.org/draft-07/schema#",
+  "$id": "{}",
   "title": "{}",
   {}
 }}"#,
+            escape_json_string(decl.name()),
             title,
             // Remove leading '{' and trailing '}' from inner to merge
             &inner[1..inner.len() - 1].trim()
@@ -141,6 +150,7 @@ impl JsonSchemaCodegen {
             Declaration::Function(func) => self.generate_function(func),
             Declaration::Const(c) => self.generate_const(c),
             Declaration::SexVar(v) => self.generate_sex_var(v),
+            Declaration::Extern(e) => self.generate_extern(e),
         }
     }
 
@@ -168,6 +178,19 @@ impl JsonSchemaCodegen {
         )
     }
 
+    /// Generate schema for an extern block (placeholder, like functions).
+    fn generate_extern(&self, block: &crate::ast::ExternBlock) -> String {
+        let names: Vec<&str> = block.functions.iter().map(|f| f.name.as_str()).collect();
+        format!(
+            r#"{{
+    "type": "object",
+    "description": "Extern functions {}",
+    "properties": {{}}
+}}"#,
+            names.join(", ")
+        )
+    }
+
     /// Generate schema for a function (placeholder - functions don't map well to JSON Schema).
     fn generate_function(&self, func: &crate::ast::FunctionDecl) -> String {
         format!(
@@ -414,6 +437,9 @@ impl JsonSchemaCodegen {
             .additions
             .iter()
             .filter_map(|stmt| match stmt {
+                Statement::Has {
+                    subject, property, ..
+                } if is_implicit_subject(subject) => Some(format!("\"has:{}\"", property)),
                 Statement::Has {
                     subject, property, ..
                 } => Some(format!("\"{}:has:{}\"", subject, property)),
@@ -441,27 +467,32 @@ impl JsonSchemaCodegen {
     fn extract_properties(&self, statements: &[Statement]) -> Vec<(String, String)> {
         statements
             .iter()
-            .filter_map(|stmt| {
-                if let Statement::Has { property, .. } = stmt {
-                    // Since AST doesn't have type annotations, use generic schema
+            .filter_map(|stmt| match stmt {
+                // Untyped properties carry no annotation, so use a string schema
+                Statement::Has { property, .. } => {
                     Some((property.clone(), "{ \"type\": \"string\" }".to_string()))
-                } else {
-                    None
                 }
+                Statement::HasField(field) => {
+                    Some((field.name.clone(), Self::map_type_expr(&field.type_)))
+                }
+                _ => None,
             })
             .collect()
     }
 
     /// Extract required field names from "has" statements.
+    ///
+    /// Fields typed `Option<T>` may be absent and are not required.
     fn extract_required(&self, statements: &[Statement]) -> Vec<String> {
         statements
             .iter()
-            .filter_map(|stmt| {
-                if let Statement::Has { property, .. } = stmt {
-                    Some(property.clone())
-                } else {
-                    None
-                }
+            .filter_map(|stmt| match stmt {
+                Statement::Has { property, .. } => Some(property.clone()),
+                Statement::HasField(field) => match &field.type_ {
+                    TypeExpr::Generic { name, .. } if name == "Option" => None,
+                    _ => Some(field.name.clone()),
+                },
+                _ => None,
             })
             .collect()
     }
@@ -493,6 +524,25 @@ impl TypeMapper for JsonSchemaCodegen {
                     types.len()
                 )
             }
+            Type::Record(fields) => {
+                let properties: Vec<_> = fields
+                    .iter()
+                    .map(|(name, ty)| format!(r#""{}": {}"#, name, Self::map_type(ty)))
+                    .collect();
+                let required: Vec<_> = fields
+                    .iter()
+                    .map(|(name, _)| format!(r#""{}""#, name))
+                    .collect();
+                format!(
+                    r#"{{ "type": "object", "properties": {{ {} }}, "required": [{}] }}"#,
+                    properties.join(", "),
+                    required.join(", ")
+                )
+            }
+            Type::Option(inner) => format!(
+                r#"{{ "oneOf": [{}, {{ "type": "null" }}] }}"#,
+                Self::map_type(inner)
+            ),
             Type::Generic { name, args } => {
                 let mapped_args: Vec<_> = args.iter().map(Self::map_type).collect();
                 match name.as_str() {
@@ -624,6 +674,21 @@ impl TypeMapper for JsonSchemaCodegen {
                     .collect();
                 format!(r#"{{ "enum": [{}] }}"#, enum_values.join(", "))
             }
+            TypeExpr::Record(fields) => {
+                let properties: Vec<_> = fields
+                    .iter()
+                    .map(|(name, ty)| format!(r#""{}": {}"#, name, Self::map_type_expr(ty)))
+                    .collect();
+                let required: Vec<_> = fields
+                    .iter()
+                    .map(|(name, _)| format!(r#""{}""#, name))
+                    .collect();
+                format!(
+                    r#"{{ "type": "object", "properties": {{ {} }}, "required": [{}] }}"#,
+                    properties.join(", "),
+                    required.join(", ")
+                )
+            }
         }
     }
 }
@@ -640,12 +705,12 @@ fn escape_json_string(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ast::Span;
+    use crate::ast::{QualifiedName, Span, Visibility};
 
     #[test]
     fn test_generate_gene_schema() {
         let gene = Gene {
-            name: "container.exists".to_string(),
+            name: QualifiedName::new("container.exists"),
             extends: None,
             statements: vec![
                 Statement::Has {
@@ -659,7 +724,9 @@ mod tests {
                     span: Span::default(),
                 },
             ],
-            exegesis: "A container is the fundamental unit.".to_string(),
+            exegesis: "A container is the fundamental unit.".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -676,12 +743,15 @@ mod tests {
     #[test]
     fn test_generate_trait_with_refs() {
         let trait_decl = Trait {
-            name: "container.lifecycle".to_string(),
+            name: QualifiedName::new("container.lifecycle"),
             statements: vec![Statement::Uses {
                 reference: "container.exists".to_string(),
                 span: Span::default(),
             }],
-            exegesis: "Container lifecycle management.".to_string(),
+            laws: vec![],
+            exegesis: "Container lifecycle management.".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -694,13 +764,14 @@ mod tests {
     #[test]
     fn test_generate_constraint_schema() {
         let constraint = Constraint {
-            name: "container.integrity".to_string(),
+            name: QualifiedName::new("container.integrity"),
             statements: vec![Statement::Matches {
                 subject: "state".to_string(),
                 target: "declared_state".to_string(),
                 span: Span::default(),
             }],
-            exegesis: "Container integrity constraints.".to_string(),
+            exegesis: "Container integrity constraints.".into(),
+            visibility: Visibility::Private,
             span: Span::default(),
         };
 
@@ -714,11 +785,14 @@ mod tests {
     #[test]
     fn test_generate_system_schema() {
         let system = System {
-            name: "container.runtime".to_string(),
+            name: QualifiedName::new("container.runtime"),
             version: "1.0.0".to_string(),
             requirements: vec![],
             statements: vec![],
-            exegesis: "Container runtime system.".to_string(),
+            states: vec![],
+            exegesis: "Container runtime system.".into(),
+            capabilities: vec![],
+            visibility: Visibility::Private,
             span: Span::default(),
         };
 
@@ -769,4 +843,47 @@ mod tests {
         assert_eq!(escape_json_string("hello\nworld"), "hello\\nworld");
         assert_eq!(escape_json_string("say \"hi\""), "say \\\"hi\\\"");
     }
+
+    fn parse_json(schema: &str) -> serde_json::Value {
+        serde_json::from_str(schema).unwrap_or_else(|e| panic!("{e}\n{schema}"))
+    }
+
+    #[test]
+    fn test_generate_gene_typed_fields_schema() {
+        let source = "gene server.config {\n  has id: String\n  has port: Int32\n  \
+                      has tags: List<String>\n  has label: Option<String>\n}\n\n\
+                      exegesis {\n  Server \"config\".\n}";
+        let decl = crate::parse_file(source).unwrap();
+        let schema = parse_json(&JsonSchemaCodegen::generate(&decl));
+
+        assert_eq!(schema["$id"], "server.config");
+        assert_eq!(schema["title"], "ServerConfig");
+        assert_eq!(schema["description"], "Server \"config\".");
+        let properties = &schema["properties"];
+        assert_eq!(properties["id"]["type"], "string");
+        assert_eq!(properties["port"]["type"], "integer");
+        assert_eq!(properties["tags"]["items"]["type"], "string");
+        assert_eq!(properties["label"]["oneOf"][1]["type"], "null");
+        assert_eq!(
+            schema["required"],
+            serde_json::json!(["id", "port", "tags"])
+        );
+    }
+
+    #[test]
+    fn test_generate_all_is_valid_json() {
+        let source = "gene container.exists {\n  has id: UInt64\n  container is running\n}\n\n\
+                      exegesis {\n  A container.\n}\n\n\
+                      trait container.lifecycle {\n  uses container.exists\n}\n\n\
+                      exegesis {\n  Lifecycle.\n}";
+        let decls = crate::parse_file_all(source).unwrap();
+        let schema = parse_json(&JsonSchemaCodegen::generate_all(&decls));
+
+        let defs = &schema["$defs"];
+        assert_eq!(defs["ContainerExists"]["properties"]["id"]["minimum"], 0);
+        assert_eq!(
+            defs["ContainerLifecycle"]["allOf"][0]["$ref"],
+            "#/$defs/ContainerExists"
+        );
+    }
 }
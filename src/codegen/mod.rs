@@ -7,7 +7,10 @@
 //!
 //! - **Rust**: Generate structs, traits, and type aliases
 //! - **TypeScript**: Generate interfaces and type definitions
-//! - **JSON Schema**: Generate JSON Schema from types (planned)
+//! - **JSON Schema**: Generate JSON Schema from genes and their typed fields
+//!
+//! Tools that take the target as a string dispatch through a
+//! [`CodegenRegistry`], which further backends can be registered with.
 //!
 //! # Example
 //!
@@ -33,16 +36,20 @@
 mod crate_gen;
 pub mod hir_rust;
 mod jsonschema;
+mod registry;
 mod rust;
 mod typescript;
 
 pub use crate_gen::{CrateCodegen, CrateConfig, ModuleInfo};
 pub use hir_rust::HirRustCodegen;
 pub use jsonschema::JsonSchemaCodegen;
+pub use registry::{CodegenRegistry, GeneratorFn};
 pub use rust::RustCodegen;
 pub use typescript::TypeScriptCodegen;
 
-use crate::ast::{Declaration, TypeExpr};
+pub use crate::naming::{to_pascal_case, to_snake_case};
+
+use crate::ast::{Declaration, QualifiedName, TypeExpr};
 use crate::lower::{lower_file, LowerDiagnostic};
 use crate::typechecker::Type;
 
@@ -53,6 +60,14 @@ pub trait Codegen {
     /// Generate code from a DOL declaration.
     fn generate(decl: &Declaration) -> String;
 
+    /// Generate code from a DOL declaration using the given options.
+    ///
+    /// Backends that don't support options fall back to [`Codegen::generate`].
+    fn generate_with(decl: &Declaration, opts: &CodegenOptions) -> String {
+        let _ = opts;
+        Self::generate(decl)
+    }
+
     /// Generate code from multiple declarations.
     fn generate_all(decls: &[Declaration]) -> String {
         decls
@@ -64,7 +79,10 @@ pub trait Codegen {
 }
 
 /// Configuration options for code generation.
-#[derive(Debug, Clone, Default)]
+///
+/// The defaults include doc comments, emit public items and add no derives
+/// beyond each backend's own.
+#[derive(Debug, Clone)]
 pub struct CodegenOptions {
     /// Include documentation comments in output
     pub include_docs: bool,
@@ -77,6 +95,23 @@ pub struct CodegenOptions {
 
     /// Generate builder pattern methods
     pub generate_builders: bool,
+
+    /// Nest generated items in modules (Rust) or namespaces (TypeScript)
+    /// following the segments of their qualified names, so that
+    /// `univrs.container.exists` lands in `univrs::container`.
+    pub module_from_namespace: bool,
+}
+
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        Self {
+            include_docs: true,
+            derive_macros: Vec::new(),
+            visibility: Visibility::default(),
+            generate_builders: false,
+            module_from_namespace: false,
+        }
+    }
 }
 
 /// Visibility level for generated code.
@@ -100,35 +135,66 @@ pub trait TypeMapper {
     fn map_type_expr(ty: &TypeExpr) -> String;
 }
 
-/// Convert a DOL identifier to a valid identifier in the target language.
-pub fn to_pascal_case(s: &str) -> String {
-    s.split('.')
-        .flat_map(|part| part.split('_'))
-        .map(|word| {
-            let mut chars = word.chars();
-            match chars.next() {
-                None => String::new(),
-                Some(first) => first.to_uppercase().chain(chars).collect(),
-            }
-        })
-        .collect()
+/// Generated items grouped by the namespace of their qualified DOL names.
+///
+/// The namespace of `univrs.container.exists` is `univrs.container`: every
+/// segment but the last.
+#[derive(Debug, Default)]
+pub(crate) struct NamespaceTree {
+    /// Items declared directly in this namespace
+    items: Vec<String>,
+    /// Nested namespaces, by segment
+    children: std::collections::BTreeMap<String, NamespaceTree>,
 }
 
-/// Convert a DOL identifier to snake_case.
-pub fn to_snake_case(s: &str) -> String {
-    s.split('.')
-        .collect::<Vec<_>>()
-        .join("_")
-        .chars()
-        .enumerate()
-        .flat_map(|(i, c)| {
-            if c.is_uppercase() && i > 0 {
-                vec!['_', c.to_lowercase().next().unwrap()]
-            } else {
-                vec![c.to_lowercase().next().unwrap()]
+impl NamespaceTree {
+    /// Adds generated code under the domain of the qualified `name`.
+    pub(crate) fn insert(&mut self, name: &QualifiedName, code: String) {
+        let mut node = self;
+        for segment in name.domain().segments() {
+            node = node.children.entry(segment.clone()).or_default();
+        }
+        node.items.push(code);
+    }
+
+    /// Renders the items and nested blocks, separated by blank lines.
+    ///
+    /// `open` produces the header line of a namespace block, `prelude` is
+    /// emitted first inside every block, and nested content is indented by
+    /// `indent`.
+    pub(crate) fn render(
+        &self,
+        open: &dyn Fn(&str) -> String,
+        prelude: &str,
+        indent: &str,
+    ) -> String {
+        let mut parts = self.items.clone();
+        for (segment, child) in &self.children {
+            let mut body = String::new();
+            if !prelude.is_empty() {
+                body.push_str(prelude);
+                body.push_str("\n\n");
             }
-        })
-        .collect()
+            body.push_str(&child.render(open, prelude, indent));
+            let body: Vec<String> = body
+                .trim_end()
+                .lines()
+                .map(|line| {
+                    if line.is_empty() {
+                        String::new()
+                    } else {
+                        format!("{indent}{line}")
+                    }
+                })
+                .collect();
+            parts.push(format!("{} {{\n{}\n}}", open(segment), body.join("\n")));
+        }
+        parts
+            .iter()
+            .map(|part| part.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
 }
 
 /// Rust reserved keywords that need r# escaping when used as identifiers.
@@ -242,29 +308,6 @@ pub fn compile_with_diagnostics(
     Ok((code, ctx.take_diagnostics()))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_to_pascal_case() {
-        assert_eq!(to_pascal_case("container.exists"), "ContainerExists");
-        assert_eq!(
-            to_pascal_case("identity.cryptographic"),
-            "IdentityCryptographic"
-        );
-        assert_eq!(to_pascal_case("simple"), "Simple");
-        assert_eq!(to_pascal_case("snake_case"), "SnakeCase");
-    }
-
-    #[test]
-    fn test_to_snake_case() {
-        assert_eq!(to_snake_case("container.exists"), "container_exists");
-        assert_eq!(to_snake_case("ContainerExists"), "container_exists");
-        assert_eq!(to_snake_case("simple"), "simple");
-    }
-}
-
 /// Tests for the HIR-based compilation pipeline (v0.3.0+)
 #[cfg(test)]
 mod hir_pipeline_tests {
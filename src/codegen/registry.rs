@@ -0,0 +1,135 @@
+//! Name-based dispatch to code generation backends.
+//!
+//! A [`CodegenRegistry`] maps target names such as `"rust"` to generators,
+//! so that tools taking the target as a string (the CLI, the MCP server)
+//! need no per-backend code. The built-in backends are registered by
+//! [`CodegenRegistry::with_builtins`]; further backends can be added with
+//! [`CodegenRegistry::register`] or [`CodegenRegistry::register_fn`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use metadol::codegen::{CodegenOptions, CodegenRegistry};
+//! use metadol::parse_file;
+//!
+//! let decl = parse_file("gene counter.state { counter has value }").unwrap();
+//! let mut registry = CodegenRegistry::with_builtins();
+//! registry.register_fn("names", |decl, _| decl.name().to_string());
+//!
+//! let opts = CodegenOptions::default();
+//! assert_eq!(registry.generate("names", &decl, &opts).unwrap(), "counter.state");
+//! assert!(registry.generate("python", &decl, &opts).is_err());
+//! ```
+
+use std::collections::BTreeMap;
+
+use super::{Codegen, CodegenOptions, JsonSchemaCodegen, RustCodegen, TypeScriptCodegen};
+use crate::ast::Declaration;
+use crate::error::UnknownTargetError;
+
+/// A code generator for one target, as stored in a [`CodegenRegistry`].
+pub type GeneratorFn = Box<dyn Fn(&Declaration, &CodegenOptions) -> String + Send + Sync>;
+
+/// Code generation backends, by target name.
+pub struct CodegenRegistry {
+    generators: BTreeMap<String, GeneratorFn>,
+}
+
+impl CodegenRegistry {
+    /// Creates a registry with no backends.
+    pub fn new() -> Self {
+        Self {
+            generators: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a registry with the built-in backends: `rust`, `typescript`
+    /// and `jsonschema`.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register::<RustCodegen>("rust");
+        registry.register::<TypeScriptCodegen>("typescript");
+        registry.register_fn("jsonschema", |decl, _| JsonSchemaCodegen::generate(decl));
+        registry
+    }
+
+    /// Registers a [`Codegen`] backend under `name`, replacing any backend
+    /// already registered under it.
+    pub fn register<C: Codegen + 'static>(&mut self, name: impl Into<String>) {
+        self.register_fn(name, C::generate_with);
+    }
+
+    /// Registers a generator function under `name`, replacing any backend
+    /// already registered under it.
+    pub fn register_fn<F>(&mut self, name: impl Into<String>, generator: F)
+    where
+        F: Fn(&Declaration, &CodegenOptions) -> String + Send + Sync + 'static,
+    {
+        self.generators.insert(name.into(), Box::new(generator));
+    }
+
+    /// Returns true if a backend is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.generators.contains_key(name)
+    }
+
+    /// Returns the registered target names, sorted.
+    pub fn targets(&self) -> impl Iterator<Item = &str> {
+        self.generators.keys().map(String::as_str)
+    }
+
+    /// Generates code for `decl` with the backend registered under `target`.
+    pub fn generate(
+        &self,
+        target: &str,
+        decl: &Declaration,
+        opts: &CodegenOptions,
+    ) -> Result<String, UnknownTargetError> {
+        let generator = self
+            .generators
+            .get(target)
+            .ok_or_else(|| UnknownTargetError {
+                target: target.to_string(),
+                available: self.targets().map(str::to_string).collect(),
+            })?;
+        Ok(generator(decl, opts))
+    }
+}
+
+impl Default for CodegenRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+impl std::fmt::Debug for CodegenRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CodegenRegistry")
+            .field("targets", &self.generators.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_file;
+
+    #[test]
+    fn test_dispatch_by_target_name() {
+        let decl = parse_file("gene counter.state { counter has value }").unwrap();
+        let registry = CodegenRegistry::with_builtins();
+        let opts = CodegenOptions::default();
+
+        let code = registry.generate("rust", &decl, &opts).unwrap();
+        assert!(code.contains("pub struct CounterState"));
+
+        let err = registry.generate("python", &decl, &opts).unwrap_err();
+        assert_eq!(err.target, "python");
+        assert_eq!(err.available, ["jsonschema", "rust", "typescript"]);
+        assert_eq!(
+            err.to_string(),
+            "unknown codegen target 'python' (available: jsonschema, rust, typescript)"
+        );
+    }
+}
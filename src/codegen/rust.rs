@@ -22,6 +22,51 @@
 //! | `Result<T, E>` | `Result<T, E>` |
 //! | `List<T>` | `Vec<T>` |
 //! | `Map<K, V>` | `std::collections::HashMap<K, V>` |
+//!
+//! # Builders
+//!
+//! With [`CodegenOptions::generate_builders`] set, each gene struct gets a
+//! `{Struct}Builder` with a chainable `with_*` setter per field. Fields with
+//! a declared default or an `Option` type may be left unset; `build` fails
+//! with a `BuildError` naming any other field that was not set.
+//!
+//! ```rust
+//! use metadol::codegen::{CodegenOptions, RustCodegen};
+//!
+//! let source = r#"
+//! gene server.config {
+//!   has host: String
+//!   has port: Int32 = 8080
+//! }
+//!
+//! exegesis {
+//!   Server settings.
+//! }
+//! "#;
+//!
+//! let decls = metadol::parse_file_all(source).unwrap();
+//! let options = CodegenOptions {
+//!     generate_builders: true,
+//!     ..Default::default()
+//! };
+//! let code = RustCodegen::with_options(options).gen_file(&decls);
+//!
+//! assert!(code.contains("pub struct BuildError"));
+//! assert!(code.contains("pub fn with_host(mut self, host: String) -> Self"));
+//! assert!(code.contains("pub fn build(self) -> Result<ServerConfig, BuildError>"));
+//! ```
+//!
+//! The generated builder is used like this:
+//!
+//! ```rust,ignore
+//! let config = ServerConfig::builder()
+//!     .with_host("localhost".to_string())
+//!     .build()?;
+//! assert_eq!(config.port, 8080);
+//!
+//! let err = ServerConfig::builder().build().unwrap_err();
+//! assert_eq!(err.to_string(), "missing required field `host`");
+//! ```
 
 use crate::ast::{
     Constraint, Declaration, EnumVariant, Evolution, Expr, ExternDecl, FunctionDecl, FunctionParam,
@@ -30,9 +75,17 @@ use crate::ast::{
 use crate::typechecker::Type;
 
 use super::{
-    to_pascal_case, to_rust_ident, to_snake_case, Codegen, CodegenOptions, TypeMapper, Visibility,
+    to_pascal_case, to_rust_ident, to_snake_case, Codegen, CodegenOptions, NamespaceTree,
+    TypeMapper, Visibility,
 };
 
+/// Type emitted for an inline record outside a gene field.
+///
+/// Rust has no anonymous structs, so the generated code fails to compile
+/// with this message instead of silently changing the record's shape.
+const INLINE_RECORD_UNSUPPORTED: &str =
+    "compile_error!(\"inline record types are only supported as gene field types\")";
+
 /// Rust code generator.
 ///
 /// Transforms DOL declarations into Rust source code.
@@ -57,6 +110,35 @@ impl RustCodegen {
         Self::new().generate_declaration(decl)
     }
 
+    /// Generate Rust code from a declaration using the given options.
+    ///
+    /// Generated items take `opts.visibility`, derive `opts.derive_macros`
+    /// alongside `Debug, Clone, PartialEq`, and carry the exegesis as a doc
+    /// comment when `opts.include_docs` is set.
+    ///
+    /// ```rust
+    /// use metadol::codegen::{CodegenOptions, RustCodegen, Visibility};
+    ///
+    /// let decl = metadol::parse_file("gene point { has x: Int64 }").unwrap();
+    /// let opts = CodegenOptions {
+    ///     visibility: Visibility::Crate,
+    ///     derive_macros: vec!["Serialize".to_string()],
+    ///     include_docs: false,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let code = RustCodegen::generate_with(&decl, &opts);
+    /// assert!(code.starts_with("#[derive(Debug, Clone, PartialEq, Serialize)]\npub(crate) struct Point {"));
+    /// ```
+    ///
+    /// With `opts.generate_builders` set, a gene's output starts with the
+    /// `BuildError` its builder returns.
+    pub fn generate_with(decl: &Declaration, opts: &CodegenOptions) -> String {
+        let generator = Self::with_options(opts.clone());
+        let prelude = generator.build_error_prelude(std::slice::from_ref(decl));
+        prelude + &generator.generate_declaration(decl)
+    }
+
     /// Generate Rust code from multiple declarations.
     /// This handles self-functions by grouping them into impl blocks.
     pub fn generate_all(decls: &[Declaration]) -> String {
@@ -64,6 +146,25 @@ impl RustCodegen {
         generator.gen_file(decls)
     }
 
+    /// Generate Rust code for many declarations on a thread pool.
+    ///
+    /// Each declaration is generated independently and the results are
+    /// joined in declaration order, so the output is identical to
+    /// generating them one by one with [`Codegen::generate_with`], except
+    /// that the `BuildError` used by builders is emitted once, up front.
+    #[cfg(feature = "parallel")]
+    pub fn generate_all_parallel(decls: &[Declaration], opts: &CodegenOptions) -> String {
+        use rayon::prelude::*;
+
+        let generator = Self::with_options(opts.clone());
+        let body = decls
+            .par_iter()
+            .map(|d| generator.generate_declaration(d))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        generator.build_error_prelude(decls) + &body
+    }
+
     /// Generate a complete Rust file from multiple declarations.
     ///
     /// This creates a complete .rs file with:
@@ -110,11 +211,26 @@ impl RustCodegen {
             }
         }
 
+        output.push_str(&self.build_error_prelude(decls));
+
         // Generate non-function declarations (genes, traits, etc.)
-        for decl in decls {
-            if !matches!(decl, Declaration::Function(_)) {
-                output.push_str(&self.generate_declaration(decl));
-                output.push_str("\n\n");
+        if self.options.module_from_namespace {
+            let mut tree = NamespaceTree::default();
+            for decl in decls {
+                if !matches!(decl, Declaration::Function(_)) {
+                    tree.insert(&decl.qualified_name(), self.generate_declaration(decl));
+                }
+            }
+            let visibility = self.visibility_str();
+            let open = |segment: &str| format!("{visibility}mod {}", to_rust_ident(segment));
+            output.push_str(&tree.render(&open, "use super::*;", "    "));
+            output.push_str("\n\n");
+        } else {
+            for decl in decls {
+                if !matches!(decl, Declaration::Function(_)) {
+                    output.push_str(&self.generate_declaration(decl));
+                    output.push_str("\n\n");
+                }
             }
         }
 
@@ -155,7 +271,7 @@ impl RustCodegen {
         let mut output = String::new();
 
         // Add exegesis as doc comment
-        if !func.exegesis.is_empty() {
+        if self.options.include_docs && !func.exegesis.is_empty() {
             for line in func.exegesis.lines() {
                 output.push_str(&format!("    /// {}\n", line.trim()));
             }
@@ -234,9 +350,19 @@ impl RustCodegen {
             Declaration::Function(func) => self.generate_toplevel_function(func),
             Declaration::Const(c) => self.generate_const(c),
             Declaration::SexVar(v) => self.generate_sex_var(v),
+            Declaration::Extern(e) => self.generate_extern(e),
         }
     }
 
+    /// Generate `extern` blocks, one per run of functions sharing an ABI.
+    fn generate_extern(&self, block: &crate::ast::ExternBlock) -> String {
+        block
+            .functions
+            .chunk_by(|a, b| a.abi == b.abi)
+            .map(|run| self.gen_extern_block(run[0].abi.as_deref(), run))
+            .collect()
+    }
+
     /// Generate a Rust constant declaration.
     fn generate_const(&self, c: &crate::ast::ConstDecl) -> String {
         let visibility = self.visibility_str();
@@ -276,7 +402,7 @@ impl RustCodegen {
         let mut output = String::new();
 
         // Add exegesis as doc comment
-        if !func.exegesis.is_empty() {
+        if self.options.include_docs && !func.exegesis.is_empty() {
             for line in func.exegesis.lines() {
                 output.push_str(&format!("/// {}\n", line.trim()));
             }
@@ -322,7 +448,7 @@ impl RustCodegen {
             return self.gen_enum_from_gene(gene, variants, &extra_fields);
         }
 
-        let struct_name = to_pascal_case(&gene.name);
+        let struct_name = to_pascal_case(gene.name.as_str());
         let visibility = self.visibility_str();
 
         let mut output = String::new();
@@ -340,9 +466,16 @@ impl RustCodegen {
 
         // Doc comment from exegesis (always include by default)
         output.push_str(&self.format_doc_comment(&gene.exegesis));
-
-        // Derive macros
-        let derives = self.derive_clause();
+        output.push_str(&self.deprecated_attr(gene.deprecated()));
+
+        // Derive macros, plus any recorded by `#[derive(...)]` on the gene
+        let mut derives = self.derive_clause();
+        for name in gene.derives() {
+            if !derives.split(", ").any(|d| d == name) {
+                derives.push_str(", ");
+                derives.push_str(name);
+            }
+        }
         if !derives.is_empty() {
             output.push_str(&format!("#[derive({})]\n", derives));
         }
@@ -351,6 +484,9 @@ impl RustCodegen {
         output.push_str(&format!("{visibility}struct {struct_name} {{\n"));
 
         for (field_name, field_type, _, _) in &fields {
+            if let Some(since) = Self::field_since(gene, field_name) {
+                output.push_str(&format!("    /// Since version {since}.\n"));
+            }
             let rust_field = to_rust_ident(field_name);
             output.push_str(&format!("    {visibility}{rust_field}: {field_type},\n"));
         }
@@ -359,13 +495,269 @@ impl RustCodegen {
 
         // Generate impl block with constructor, validators, and methods
         output.push_str(&self.gen_gene_impl(&struct_name, &fields, &functions));
+        output.push_str(&self.gen_default_impl(&struct_name, &derives, &fields));
+        if self.options.generate_builders {
+            output.push_str(&self.gen_builder(&struct_name, &fields));
+        }
+        if gene.is_test() {
+            output.push_str(&self.gen_test_scaffold(gene.name.as_str(), &struct_name, &fields));
+        }
+
+        output
+    }
+
+    /// Generate a `#[cfg(test)]` module for a `#[test]`-annotated gene.
+    ///
+    /// The scaffold constructs an instance from declared field defaults (or
+    /// `Default::default()`) and asserts `validate_all` when the gene has
+    /// field constraints.
+    fn gen_test_scaffold(
+        &self,
+        gene_name: &str,
+        struct_name: &str,
+        fields: &[(String, String, Option<Expr>, Option<Expr>)],
+    ) -> String {
+        let snake = to_snake_case(gene_name);
+        let args: Vec<String> = fields
+            .iter()
+            .map(|(_, ty, default, _)| match default {
+                Some(expr) => self.gen_field_default(ty, expr),
+                None => "Default::default()".to_string(),
+            })
+            .collect();
+
+        let mut output = String::from("\n#[cfg(test)]\n");
+        output.push_str(&format!("mod {snake}_tests {{\n"));
+        output.push_str("    use super::*;\n\n");
+        output.push_str("    #[test]\n");
+        output.push_str(&format!("    fn {snake}_satisfies_constraints() {{\n"));
+        output.push_str(&format!(
+            "        let instance = {struct_name}::new({});\n",
+            args.join(", ")
+        ));
+        if fields.iter().any(|(_, _, _, c)| c.is_some()) {
+            output.push_str("        assert!(instance.validate_all());\n");
+        } else {
+            output.push_str("        let _ = instance;\n");
+        }
+        output.push_str("    }\n");
+        output.push_str("}\n");
+        output
+    }
+
+    /// Generate `impl Default` for a gene struct whose fields declare defaults.
+    ///
+    /// Fields without a declared default use `Default::default()`. Returns an
+    /// empty string when no field declares a default, when `derives` (the
+    /// struct's derive list) already includes `Default`, or when some field's
+    /// type has no sensible default.
+    fn gen_default_impl(
+        &self,
+        struct_name: &str,
+        derives: &str,
+        fields: &[(String, String, Option<Expr>, Option<Expr>)],
+    ) -> String {
+        let derives_default = derives.split(", ").any(|d| d == "Default");
+        let declares_default = fields.iter().any(|(_, _, default, _)| default.is_some());
+        let all_defaultable = fields
+            .iter()
+            .all(|(_, ty, default, _)| default.is_some() || Self::has_rust_default(ty));
+        if derives_default || !declares_default || !all_defaultable {
+            return String::new();
+        }
+
+        let mut output = format!("\nimpl Default for {struct_name} {{\n");
+        output.push_str("    fn default() -> Self {\n");
+        output.push_str("        Self {\n");
+        for (name, ty, default, _) in fields {
+            let value = match default {
+                Some(expr) => self.gen_field_default(ty, expr),
+                None => "Default::default()".to_string(),
+            };
+            output.push_str(&format!("            {}: {value},\n", to_rust_ident(name)));
+        }
+        output.push_str("        }\n");
+        output.push_str("    }\n");
+        output.push_str("}\n");
+        output
+    }
+
+    /// Generate a `{Struct}Builder` with a `with_*` setter per field.
+    ///
+    /// Fields with a declared default or an `Option` type may be left unset;
+    /// `build` returns a [`BuildError`](Self::gen_build_error) naming the
+    /// first other field that was not set.
+    fn gen_builder(
+        &self,
+        struct_name: &str,
+        fields: &[(String, String, Option<Expr>, Option<Expr>)],
+    ) -> String {
+        let visibility = self.visibility_str();
+        let builder = format!("{struct_name}Builder");
+
+        let mut output = format!("\n/// Builder for [`{struct_name}`].\n");
+        output.push_str("#[derive(Debug, Clone, Default)]\n");
+        output.push_str(&format!("{visibility}struct {builder} {{\n"));
+        for (name, ty, _, _) in fields {
+            let slot = match Self::option_inner(ty) {
+                Some(_) => ty.clone(),
+                None => format!("Option<{ty}>"),
+            };
+            output.push_str(&format!("    {}: {slot},\n", to_rust_ident(name)));
+        }
+        output.push_str("}\n\n");
+
+        output.push_str(&format!("impl {struct_name} {{\n"));
+        output.push_str(&format!("    /// Starts building a `{struct_name}`.\n"));
+        output.push_str(&format!("    {visibility}fn builder() -> {builder} {{\n"));
+        output.push_str(&format!("        {builder}::default()\n"));
+        output.push_str("    }\n");
+        output.push_str("}\n\n");
+
+        output.push_str(&format!("impl {builder} {{\n"));
+        for (name, ty, _, _) in fields {
+            let field = to_rust_ident(name);
+            let param = Self::option_inner(ty).unwrap_or(ty);
+            output.push_str(&format!("    /// Sets `{name}`.\n"));
+            output.push_str(&format!(
+                "    {visibility}fn with_{}(mut self, {field}: {param}) -> Self {{\n",
+                field.trim_start_matches("r#")
+            ));
+            output.push_str(&format!("        self.{field} = Some({field});\n"));
+            output.push_str("        self\n");
+            output.push_str("    }\n\n");
+        }
+        output.push_str(&format!(
+            "    /// Builds the `{struct_name}`, failing if a required field is unset.\n"
+        ));
+        output.push_str(&format!(
+            "    {visibility}fn build(self) -> Result<{struct_name}, BuildError> {{\n"
+        ));
+        output.push_str(&format!("        Ok({struct_name} {{\n"));
+        for (name, ty, default, _) in fields {
+            let field = to_rust_ident(name);
+            let value = match (default, Self::option_inner(ty)) {
+                (Some(expr), None) => format!(
+                    "self.{field}.unwrap_or_else(|| {})",
+                    self.gen_default_value(expr)
+                ),
+                (Some(expr), Some(_))
+                    if matches!(expr.unspanned(), Expr::Literal(Literal::Null)) =>
+                {
+                    format!("self.{field}")
+                }
+                (Some(expr), Some(_)) => format!(
+                    "self.{field}.or_else(|| {})",
+                    self.gen_field_default(ty, expr)
+                ),
+                (None, Some(_)) => format!("self.{field}"),
+                (None, None) => format!("self.{field}.ok_or(BuildError {{ field: \"{name}\" }})?"),
+            };
+            output.push_str(&format!("            {field}: {value},\n"));
+        }
+        output.push_str("        })\n");
+        output.push_str("    }\n");
+        output.push_str("}\n");
+        output
+    }
 
+    /// Generate the `BuildError` returned by builders' `build` methods.
+    ///
+    /// Emitted once ahead of the generated declarations by
+    /// [`build_error_prelude`](Self::build_error_prelude).
+    fn gen_build_error(&self) -> String {
+        let visibility = self.visibility_str();
+        let mut output =
+            String::from("/// Error returned by a builder when a required field is unset.\n");
+        output.push_str("#[derive(Debug, Clone, PartialEq, Eq)]\n");
+        output.push_str(&format!("{visibility}struct BuildError {{\n"));
+        output.push_str("    /// Name of the unset field\n");
+        output.push_str(&format!("    {visibility}field: &'static str,\n"));
+        output.push_str("}\n\n");
+        output.push_str("impl std::fmt::Display for BuildError {\n");
         output
+            .push_str("    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n");
+        output.push_str("        write!(f, \"missing required field `{}`\", self.field)\n");
+        output.push_str("    }\n");
+        output.push_str("}\n\n");
+        output.push_str("impl std::error::Error for BuildError {}\n");
+        output
+    }
+
+    /// Returns the shared `BuildError` definition when builders are enabled
+    /// and `decls` contain a gene, or an empty string otherwise.
+    fn build_error_prelude(&self, decls: &[Declaration]) -> String {
+        if self.options.generate_builders && decls.iter().any(|d| matches!(d, Declaration::Gene(_)))
+        {
+            format!("{}\n", self.gen_build_error())
+        } else {
+            String::new()
+        }
+    }
+
+    /// Returns `T` for a generated `Option<T>` type.
+    fn option_inner(ty: &str) -> Option<&str> {
+        ty.strip_prefix("Option<")?.strip_suffix('>')
+    }
+
+    /// Generate a field's default value for its generated Rust type,
+    /// wrapping it in `Some` for an `Option` field unless it is `null`.
+    fn gen_field_default(&self, ty: &str, expr: &Expr) -> String {
+        let value = self.gen_default_value(expr);
+        if Self::option_inner(ty).is_some()
+            && !matches!(expr.unspanned(), Expr::Literal(Literal::Null))
+        {
+            format!("Some({value})")
+        } else {
+            value
+        }
+    }
+
+    /// Generate a field's default value, leaving numeric literals unsuffixed
+    /// so they take the field's declared type.
+    fn gen_default_value(&self, expr: &Expr) -> String {
+        match expr.unspanned() {
+            Expr::Literal(Literal::Int(n)) => n.to_string(),
+            Expr::Literal(Literal::Float(f)) => format!("{:?}", f),
+            _ => self.gen_expr(expr),
+        }
+    }
+
+    /// Whether a generated Rust type implements `Default`.
+    fn has_rust_default(ty: &str) -> bool {
+        matches!(
+            ty,
+            "i8" | "i16"
+                | "i32"
+                | "i64"
+                | "u8"
+                | "u16"
+                | "u32"
+                | "u64"
+                | "isize"
+                | "usize"
+                | "f32"
+                | "f64"
+                | "bool"
+                | "char"
+                | "String"
+                | "()"
+        ) || ["Vec<", "Option<", "std::collections::HashMap<"]
+            .iter()
+            .any(|prefix| ty.starts_with(prefix))
+    }
+
+    /// Returns the `#[since]` version of a gene field, if it has one.
+    fn field_since<'g>(gene: &'g Gene, name: &str) -> Option<&'g str> {
+        gene.statements.iter().find_map(|stmt| match stmt {
+            Statement::HasField(field) if field.name == name => field.since.as_deref(),
+            _ => None,
+        })
     }
 
     /// Generate a Rust trait from a trait declaration.
     fn generate_trait(&self, trait_decl: &Trait) -> String {
-        let trait_name = to_pascal_case(&trait_decl.name);
+        let trait_name = to_pascal_case(trait_decl.name.as_str());
         let visibility = self.visibility_str();
 
         // Collect supertraits from "uses" statements
@@ -378,6 +770,7 @@ impl RustCodegen {
 
         // Doc comment
         output.push_str(&self.format_doc_comment(&trait_decl.exegesis));
+        output.push_str(&self.deprecated_attr(trait_decl.deprecated()));
 
         // Trait definition with supertraits
         let supertrait_clause = if supertraits.is_empty() {
@@ -413,7 +806,7 @@ impl RustCodegen {
 
     /// Generate Rust assertions/invariants from a constraint declaration.
     fn generate_constraint(&self, constraint: &Constraint) -> String {
-        let fn_name = to_rust_ident(&constraint.name);
+        let fn_name = to_rust_ident(constraint.name.as_str());
         let visibility = self.visibility_str();
 
         let mut output = String::new();
@@ -455,7 +848,7 @@ impl RustCodegen {
 
     /// Generate a Rust module from a system declaration.
     fn generate_system(&self, system: &System) -> String {
-        let mod_name = to_snake_case(&system.name);
+        let mod_name = to_snake_case(system.name.as_str());
         let visibility = self.visibility_str();
 
         let mut output = String::new();
@@ -478,12 +871,53 @@ impl RustCodegen {
             output.push('\n');
         }
 
+        if !system.states.is_empty() {
+            for line in self.gen_system_state(system).lines() {
+                if line.is_empty() {
+                    output.push('\n');
+                } else {
+                    output.push_str(&format!("    {line}\n"));
+                }
+            }
+            output.push('\n');
+        }
+
         output.push_str("    // Add system components and implementation here\n");
         output.push_str("}\n");
 
         output
     }
 
+    /// Generate the `State` struct holding a system's `state` declarations.
+    ///
+    /// Defaults are applied through `impl Default`, as for gene fields.
+    fn gen_system_state(&self, system: &System) -> String {
+        let visibility = self.visibility_str();
+        let fields: Vec<(String, String, Option<Expr>, Option<Expr>)> = system
+            .states
+            .iter()
+            .map(|state| {
+                (
+                    state.name.clone(),
+                    Self::map_type_expr(&state.type_),
+                    state.default.clone(),
+                    None,
+                )
+            })
+            .collect();
+
+        let mut output = format!("/// State of the `{}` system.\n", system.name);
+        let derives = self.derive_clause();
+        output.push_str(&format!("#[derive({derives})]\n"));
+        output.push_str(&format!("{visibility}struct State {{\n"));
+        for (name, ty, _, _) in &fields {
+            output.push_str(&format!("    {visibility}{}: {ty},\n", to_rust_ident(name)));
+        }
+        output.push_str("}\n");
+        output.push_str(&self.gen_default_impl("State", &derives, &fields));
+        output
+    }
+
     /// Generate documentation for an evolution declaration.
     fn generate_evolution(&self, evolution: &Evolution) -> String {
         let mut output = String::new();
@@ -522,6 +956,26 @@ impl RustCodegen {
             }
         }
 
+        // The migration takes the parent version's value as `old`
+        if let Some(migrate) = &evolution.migrate {
+            let visibility = self.visibility_str();
+            let type_name = to_pascal_case(&evolution.name);
+            let version = evolution.version.replace(['.', '-'], "_");
+            output.push_str(&format!("\nimpl {type_name} {{\n"));
+            output.push_str(&format!(
+                "    /// Migrates `{}` from {} to {}.\n",
+                evolution.name, evolution.parent_version, evolution.version
+            ));
+            output.push_str(&format!(
+                "    {visibility}fn migrate_{}_to_{version}(old: {type_name}) -> Self {{\n",
+                to_snake_case(&evolution.name)
+            ));
+            for stmt in migrate {
+                output.push_str(&self.gen_stmt(stmt, 2));
+            }
+            output.push_str("    }\n}\n");
+        }
+
         output
     }
 
@@ -739,7 +1193,8 @@ impl RustCodegen {
             .collect()
     }
 
-    /// Extract fields from statements, generating separate enum declarations for inline enums.
+    /// Extract fields from statements, generating separate enum and struct declarations for
+    /// inline enums and records.
     /// Returns (list of generated enum code, list of fields with resolved types)
     #[allow(clippy::type_complexity)]
     fn extract_fields_with_inline_enums(
@@ -815,9 +1270,23 @@ impl RustCodegen {
                             field.default.clone(),
                             field.constraint.clone(),
                         ));
+                    } else if let TypeExpr::Record(record_fields) = &field.type_ {
+                        // Generate a named struct for the inline record
+                        let struct_name = format!("{}{}", parent_name, to_pascal_case(&field.name));
+                        self.gen_inline_record(&struct_name, record_fields, &mut inline_enums);
+                        fields.push((
+                            field.name.clone(),
+                            struct_name,
+                            field.default.clone(),
+                            field.constraint.clone(),
+                        ));
                     } else {
-                        // Regular typed field
-                        let rust_type = Self::map_type_expr(&field.type_);
+                        // Regular typed field; a struct cannot hold itself by
+                        // value, so an optional self-reference is boxed
+                        let mut rust_type = Self::map_type_expr(&field.type_);
+                        if rust_type == format!("Option<{parent_name}>") {
+                            rust_type = format!("Option<Box<{parent_name}>>");
+                        }
                         fields.push((
                             field.name.clone(),
                             rust_type,
@@ -833,6 +1302,36 @@ impl RustCodegen {
         (inline_enums, fields)
     }
 
+    /// Generate a named struct for an inline record type, pushing it (and
+    /// any records nested inside it, first) onto `output`.
+    fn gen_inline_record(
+        &self,
+        struct_name: &str,
+        fields: &[(String, TypeExpr)],
+        output: &mut Vec<String>,
+    ) {
+        let visibility = self.visibility_str();
+        let mut record = String::new();
+        let derives = self.derive_clause();
+        if !derives.is_empty() {
+            record.push_str(&format!("#[derive({})]\n", derives));
+        }
+        record.push_str(&format!("{visibility}struct {struct_name} {{\n"));
+        for (name, ty) in fields {
+            let rust_type = if let TypeExpr::Record(nested) = ty {
+                let nested_name = format!("{}{}", struct_name, to_pascal_case(name));
+                self.gen_inline_record(&nested_name, nested, output);
+                nested_name
+            } else {
+                Self::map_type_expr(ty)
+            };
+            let rust_field = to_rust_ident(name);
+            record.push_str(&format!("    {visibility}{rust_field}: {rust_type},\n"));
+        }
+        record.push_str("}\n");
+        output.push(record);
+    }
+
     /// Extract function declarations from statements.
     fn extract_functions<'a>(&self, statements: &'a [Statement]) -> Vec<&'a FunctionDecl> {
         statements
@@ -886,9 +1385,11 @@ impl RustCodegen {
     }
 
     /// Format exegesis as a doc comment.
+    ///
+    /// Empty when the exegesis is empty or `include_docs` is off.
     fn format_doc_comment(&self, exegesis: &str) -> String {
         let trimmed = exegesis.trim();
-        if trimmed.is_empty() {
+        if !self.options.include_docs || trimmed.is_empty() {
             return String::new();
         }
 
@@ -898,13 +1399,24 @@ impl RustCodegen {
             .collect()
     }
 
+    /// Generate a `#[deprecated]` attribute for a deprecated declaration.
+    fn deprecated_attr(&self, note: Option<&str>) -> String {
+        match note {
+            None => String::new(),
+            Some("") => "#[deprecated]\n".to_string(),
+            Some(note) => format!("#[deprecated(note = {:?})]\n", note),
+        }
+    }
+
     /// Generate derive clause.
     /// Note: We don't include Eq because some types contain f64 which doesn't implement Eq.
     fn derive_clause(&self) -> String {
         let mut derives = vec!["Debug", "Clone", "PartialEq"];
 
-        if !self.options.derive_macros.is_empty() {
-            derives.extend(self.options.derive_macros.iter().map(|s| s.as_str()));
+        for derive in &self.options.derive_macros {
+            if !derives.contains(&derive.as_str()) {
+                derives.push(derive);
+            }
         }
 
         derives.join(", ")
@@ -1130,6 +1642,14 @@ impl RustCodegen {
                 output.push_str(&self.gen_expr(value));
                 output.push_str(";\n");
             }
+            Stmt::LetPattern { pattern, value } => {
+                output.push_str(&indent);
+                output.push_str("let ");
+                output.push_str(&self.gen_pattern(pattern));
+                output.push_str(" = ");
+                output.push_str(&self.gen_expr(value));
+                output.push_str(";\n");
+            }
             Stmt::Assign { target, value } => {
                 output.push_str(&indent);
                 output.push_str(&self.gen_expr(target));
@@ -1205,6 +1725,7 @@ impl RustCodegen {
     /// Generate Rust code for an expression.
     pub fn gen_expr(&self, expr: &Expr) -> String {
         match expr {
+            Expr::Spanned { expr, .. } => self.gen_expr(expr),
             Expr::Literal(lit) => self.gen_literal(lit),
             Expr::Identifier(name) => {
                 // Handle qualified identifiers (DOL lexer collects dots as part of identifiers)
@@ -1350,7 +1871,6 @@ impl RustCodegen {
                             crate::ast::BinaryOp::Ge => ">=",
                             crate::ast::BinaryOp::And => "&&",
                             crate::ast::BinaryOp::Or => "||",
-                            crate::ast::BinaryOp::Member => ".",
                             crate::ast::BinaryOp::Range => "..",
                             _ => "/* unsupported op */",
                         };
@@ -1710,6 +2230,14 @@ impl RustCodegen {
                 let elems: Vec<String> = elements.iter().map(|e| self.gen_expr(e)).collect();
                 format!("({})", elems.join(", "))
             }
+            // Map literal
+            Expr::Map(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(k, v)| format!("({}, {})", self.gen_expr(k), self.gen_expr(v)))
+                    .collect();
+                format!("std::collections::HashMap::from([{}])", entries.join(", "))
+            }
             // Type cast
             Expr::Cast { expr, target_type } => {
                 format!(
@@ -1781,7 +2309,7 @@ impl RustCodegen {
     /// Generate Rust code for an expression in a context where &str is expected.
     /// For literals, this avoids adding .to_string().
     fn gen_expr_as_str(&self, expr: &Expr) -> String {
-        match expr {
+        match expr.unspanned() {
             Expr::Literal(lit) => self.gen_literal_as_str(lit),
             _ => self.gen_expr(expr),
         }
@@ -1813,7 +2341,7 @@ impl RustCodegen {
 
     /// Static version for recursive calls without &self.
     fn is_string_expr_static(expr: &Expr) -> bool {
-        match expr {
+        match expr.unspanned() {
             Expr::Literal(Literal::String(_)) => true,
             Expr::Binary { left, op, right } => {
                 if matches!(op, crate::ast::BinaryOp::Add) {
@@ -1824,7 +2352,7 @@ impl RustCodegen {
             }
             // Method calls that return strings (common patterns)
             Expr::Call { callee, .. } => {
-                if let Expr::Member { field, .. } = callee.as_ref() {
+                if let Expr::Member { field, .. } = callee.unspanned() {
                     matches!(field.as_str(), "to_string" | "format" | "join")
                 } else {
                     false
@@ -1851,7 +2379,7 @@ impl RustCodegen {
 
     /// Recursively collect parts of a string concatenation.
     fn collect_string_parts(&self, expr: &Expr, parts: &mut Vec<String>) {
-        match expr {
+        match expr.unspanned() {
             Expr::Binary {
                 left,
                 op: crate::ast::BinaryOp::Add,
@@ -1908,7 +2436,7 @@ impl RustCodegen {
             return false;
         }
 
-        match callee {
+        match callee.unspanned() {
             Expr::Identifier(name) => {
                 // Only treat as indexing for known collection variable patterns
                 let collection_names = [
@@ -2464,7 +2992,7 @@ impl RustCodegen {
 
         // For flat enum genes, strip the .type/.kind field access from scrutinee
         // e.g., `match ty.type { ... }` becomes `match ty { ... }` for flat enums
-        let scrutinee_code = if let Expr::Member { object, field } = scrutinee {
+        let scrutinee_code = if let Expr::Member { object, field } = scrutinee.unspanned() {
             let field_name = field.trim_start_matches("r#");
             let object_expr: &Expr = object;
             if (field_name == "type" || field_name == "kind")
@@ -2475,7 +3003,7 @@ impl RustCodegen {
             } else {
                 self.gen_expr(scrutinee)
             }
-        } else if let Expr::Identifier(name) = scrutinee {
+        } else if let Expr::Identifier(name) = scrutinee.unspanned() {
             // DOL parser may parse "ty.type" as a single identifier with a dot
             // Check if it ends with .type or .kind and strip for flat enums
             if let Some((var_name, field_name)) = name.rsplit_once('.') {
@@ -2536,7 +3064,7 @@ impl RustCodegen {
     /// Try to infer the enum type from a scrutinee expression.
     /// This helps qualify unqualified enum variant patterns.
     fn infer_enum_type_from_scrutinee(&self, expr: &Expr) -> Option<String> {
-        match expr {
+        match expr.unspanned() {
             // If scrutinee is x.type or x.r#type, derive enum type from parent struct
             Expr::Member { object, field } => {
                 let field_name = field.trim_start_matches("r#");
@@ -2616,7 +3144,7 @@ impl RustCodegen {
 
     /// Try to infer the parent type from an expression (for member access).
     fn infer_parent_type(&self, expr: &Expr) -> Option<String> {
-        match expr {
+        match expr.unspanned() {
             Expr::Identifier(name) => self.var_name_to_type(name),
             _ => None,
         }
@@ -3428,7 +3956,7 @@ impl RustCodegen {
     ///     .with_doc("A user entity")
     /// ```
     pub fn gen_type_info(&self, gene: &Gene) -> String {
-        let type_name = to_pascal_case(&gene.name);
+        let type_name = to_pascal_case(gene.name.as_str());
         let mut output = String::new();
 
         // Start TypeInfo::record call
@@ -3528,13 +4056,14 @@ impl RustCodegen {
         variants: &[EnumVariant],
         extra_fields: &[(&str, String)],
     ) -> String {
-        let enum_name = to_pascal_case(&gene.name);
+        let enum_name = to_pascal_case(gene.name.as_str());
         let visibility = self.visibility_str();
 
         let mut output = String::new();
 
         // Doc comment from exegesis
         output.push_str(&self.format_doc_comment(&gene.exegesis));
+        output.push_str(&self.deprecated_attr(gene.deprecated()));
 
         // Derive macros
         let derives = self.derive_clause();
@@ -3589,6 +4118,10 @@ impl Codegen for RustCodegen {
     fn generate(decl: &Declaration) -> String {
         RustCodegen::generate(decl)
     }
+
+    fn generate_with(decl: &Declaration, opts: &CodegenOptions) -> String {
+        RustCodegen::generate_with(decl, opts)
+    }
 }
 
 impl TypeMapper for RustCodegen {
@@ -3619,6 +4152,8 @@ impl TypeMapper for RustCodegen {
                 let mapped: Vec<_> = types.iter().map(Self::map_type).collect();
                 format!("({})", mapped.join(", "))
             }
+            Type::Record(_) => INLINE_RECORD_UNSUPPORTED.to_string(),
+            Type::Option(inner) => format!("Option<{}>", Self::map_type(inner)),
             Type::Generic { name, args } => {
                 let mapped_args: Vec<_> = args.iter().map(Self::map_type).collect();
                 // Map DOL generic types to Rust equivalents
@@ -3693,8 +4228,10 @@ impl TypeMapper for RustCodegen {
                 return_type,
             } => {
                 let param_types: Vec<_> = params.iter().map(Self::map_type_expr).collect();
-                let ret = Self::map_type_expr(return_type);
-                format!("fn({}) -> {}", param_types.join(", "), ret)
+                match Self::map_type_expr(return_type).as_str() {
+                    "()" => format!("fn({})", param_types.join(", ")),
+                    ret => format!("fn({}) -> {}", param_types.join(", "), ret),
+                }
             }
             TypeExpr::Tuple(types) => {
                 let mapped: Vec<_> = types.iter().map(Self::map_type_expr).collect();
@@ -3707,6 +4244,9 @@ impl TypeMapper for RustCodegen {
                 let variant_names: Vec<_> = variants.iter().map(|v| v.name.as_str()).collect();
                 format!("/* inline enum: {} */", variant_names.join(" | "))
             }
+            // Inline records become named structs only as gene fields, where
+            // a name can be derived; anywhere else they are rejected
+            TypeExpr::Record(_) => INLINE_RECORD_UNSUPPORTED.to_string(),
         }
     }
 }
@@ -3714,12 +4254,12 @@ impl TypeMapper for RustCodegen {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ast::Span;
+    use crate::ast::{QualifiedName, Span};
 
     #[test]
     fn test_generate_gene_struct() {
         let gene = Gene {
-            name: "container.exists".to_string(),
+            name: QualifiedName::new("container.exists"),
             extends: None,
             statements: vec![
                 Statement::Has {
@@ -3733,7 +4273,9 @@ mod tests {
                     span: Span::default(),
                 },
             ],
-            exegesis: "A container is the fundamental unit.".to_string(),
+            exegesis: "A container is the fundamental unit.".into(),
+            visibility: crate::ast::Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -3748,7 +4290,7 @@ mod tests {
     #[test]
     fn test_generate_trait() {
         let trait_decl = Trait {
-            name: "container.lifecycle".to_string(),
+            name: QualifiedName::new("container.lifecycle"),
             statements: vec![
                 Statement::Uses {
                     reference: "container.exists".to_string(),
@@ -3765,7 +4307,10 @@ mod tests {
                     span: Span::default(),
                 },
             ],
-            exegesis: "Container lifecycle management.".to_string(),
+            laws: vec![],
+            exegesis: "Container lifecycle management.".into(),
+            visibility: crate::ast::Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -3832,7 +4377,7 @@ mod tests {
     #[test]
     fn test_generate_constraint() {
         let constraint = Constraint {
-            name: "container.integrity".to_string(),
+            name: QualifiedName::new("container.integrity"),
             statements: vec![
                 Statement::Matches {
                     subject: "state".to_string(),
@@ -3845,7 +4390,8 @@ mod tests {
                     span: Span::default(),
                 },
             ],
-            exegesis: "Container integrity constraints.".to_string(),
+            exegesis: "Container integrity constraints.".into(),
+            visibility: crate::ast::Visibility::Private,
             span: Span::default(),
         };
 
@@ -3859,7 +4405,7 @@ mod tests {
     #[test]
     fn test_generate_system() {
         let system = System {
-            name: "univrs.orchestrator".to_string(),
+            name: QualifiedName::new("univrs.orchestrator"),
             version: "0.1.0".to_string(),
             requirements: vec![crate::ast::Requirement {
                 name: "container.lifecycle".to_string(),
@@ -3868,7 +4414,10 @@ mod tests {
                 span: Span::default(),
             }],
             statements: vec![],
-            exegesis: "The Univrs orchestrator.".to_string(),
+            states: vec![],
+            exegesis: "The Univrs orchestrator.".into(),
+            capabilities: vec![],
+            visibility: crate::ast::Visibility::Private,
             span: Span::default(),
         };
 
@@ -4025,7 +4574,7 @@ mod tests {
                 op: crate::ast::BinaryOp::Add,
                 right: Box::new(Expr::Literal(Literal::Int(1))),
             }))],
-            exegesis: String::new(),
+            exegesis: Default::default(),
             span: Span::default(),
         };
 
@@ -4756,6 +5305,25 @@ mod tests {
         assert!(result.contains("None => 0_i64"));
     }
 
+    #[test]
+    fn test_gen_expr_map_literal() {
+        let gen = RustCodegen::new();
+        // { "a": 1, "b": 2 }
+        let expr = Expr::Map(vec![
+            (
+                Expr::Literal(Literal::String("a".to_string())),
+                Expr::Literal(Literal::Int(1)),
+            ),
+            (
+                Expr::Literal(Literal::String("b".to_string())),
+                Expr::Literal(Literal::Int(2)),
+            ),
+        ]);
+        let result = gen.gen_expr(&expr);
+        assert!(result.starts_with("std::collections::HashMap::from(["));
+        assert!(result.contains("(\"a\""));
+    }
+
     #[test]
     fn test_gen_match_nested_patterns() {
         let gen = RustCodegen::new();
@@ -4837,10 +5405,12 @@ mod tests {
     fn test_gen_file() {
         let gen = RustCodegen::new();
         let gene = Gene {
-            name: "TestStruct".to_string(),
+            name: QualifiedName::new("TestStruct"),
             extends: None,
             statements: vec![],
-            exegesis: "Test".to_string(),
+            exegesis: "Test".into(),
+            visibility: crate::ast::Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
         let output = gen.gen_file(&[Declaration::Gene(gene)]);
@@ -4852,7 +5422,7 @@ mod tests {
     fn test_gen_file_with_hashmap() {
         let gen = RustCodegen::new();
         let gene = Gene {
-            name: "TestStruct".to_string(),
+            name: QualifiedName::new("TestStruct"),
             extends: None,
             statements: vec![Statement::HasField(Box::new(crate::ast::HasField {
                 name: "data".to_string(),
@@ -4865,9 +5435,12 @@ mod tests {
                 },
                 default: None,
                 constraint: None,
+                since: None,
                 span: Span::default(),
             }))],
-            exegesis: "Test with HashMap".to_string(),
+            exegesis: "Test with HashMap".into(),
+            visibility: crate::ast::Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
         let output = gen.gen_file(&[Declaration::Gene(gene)]);
@@ -4880,7 +5453,7 @@ mod tests {
     fn test_gen_imports() {
         let gen = RustCodegen::new();
         let gene = Gene {
-            name: "TestStruct".to_string(),
+            name: QualifiedName::new("TestStruct"),
             extends: None,
             statements: vec![Statement::HasField(Box::new(crate::ast::HasField {
                 name: "data".to_string(),
@@ -4893,12 +5466,43 @@ mod tests {
                 },
                 default: None,
                 constraint: None,
+                since: None,
                 span: Span::default(),
             }))],
-            exegesis: "Test".to_string(),
+            exegesis: "Test".into(),
+            visibility: crate::ast::Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
         let imports = gen.gen_imports(&[Declaration::Gene(gene)]);
         assert!(imports.contains("use std::collections::HashMap;"));
     }
+
+    #[test]
+    fn test_generate_with_honors_options() {
+        let gene = Gene {
+            name: QualifiedName::new("container.exists"),
+            extends: None,
+            statements: vec![Statement::Has {
+                subject: "container".to_string(),
+                property: "id".to_string(),
+                span: Span::default(),
+            }],
+            exegesis: "A container is the fundamental unit.".into(),
+            visibility: crate::ast::Visibility::Private,
+            attributes: vec![],
+            span: Span::default(),
+        };
+        let opts = CodegenOptions {
+            visibility: Visibility::Crate,
+            derive_macros: vec!["Serialize".to_string()],
+            ..Default::default()
+        };
+
+        let code = <RustCodegen as Codegen>::generate_with(&Declaration::Gene(gene), &opts);
+
+        assert!(code.contains("pub(crate) struct ContainerExists"));
+        assert!(code.contains("pub(crate) id: String"));
+        assert!(code.contains("#[derive(Debug, Clone, PartialEq, Serialize)]"));
+    }
 }
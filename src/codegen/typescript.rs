@@ -22,14 +22,44 @@
 //! | `Option<T>` | `T \| undefined` |
 //! | `Result<T, E>` | `{ ok: true; value: T } \| { ok: false; error: E }` |
 //! | `List<T>` | `T[]` |
-//! | `Map<K, V>` | `Map<K, V>` or `Record<K, V>` |
+//! | `Map<K, V>` | `Record<K, V>` for string or number keys, else `Map<K, V>` |
 //! | `Tuple(A, B)` | `[A, B]` |
 //! | `Function` | `(args) => ReturnType` |
-
-use crate::ast::{Constraint, Declaration, Evolution, Gene, Statement, System, Trait, TypeExpr};
+//!
+//! # Zod Schemas
+//!
+//! [`TypeScriptCodegen::generate_zod`] emits a [Zod](https://zod.dev) schema
+//! for a gene, with the matching type inferred from it:
+//!
+//! ```rust
+//! use metadol::codegen::TypeScriptCodegen;
+//!
+//! let decl = metadol::parse_file("gene server { has id: String\n has port: Int32 = 80 }").unwrap();
+//! let zod = TypeScriptCodegen::generate_zod(&decl);
+//!
+//! assert!(zod.contains("export const Server = z.object({"));
+//! assert!(zod.contains("  port: z.number().int().default(80),"));
+//! assert!(zod.contains("export type Server = z.infer<typeof Server>;"));
+//! ```
+
+use crate::ast::{
+    is_implicit_subject, Constraint, Declaration, EnumVariant, Evolution, Expr, Gene, Literal,
+    Statement, System, Trait, TypeExpr, UnaryOp,
+};
 use crate::typechecker::Type;
 
-use super::{to_pascal_case, CodegenOptions, TypeMapper};
+use super::{to_pascal_case, Codegen, CodegenOptions, NamespaceTree, TypeMapper};
+
+/// Maps a DOL `Map<K, V>` with already-mapped key and value types.
+///
+/// Maps keyed by strings or numbers become plain-object `Record`s, which
+/// serialize to JSON directly; other key types need a real `Map`.
+fn map_container(key: &str, value: &str) -> String {
+    match key {
+        "string" | "number" => format!("Record<{}, {}>", key, value),
+        _ => format!("Map<{}, {}>", key, value),
+    }
+}
 
 /// Convert a DOL identifier to camelCase for TypeScript.
 fn to_camel_case(s: &str) -> String {
@@ -67,10 +97,26 @@ impl TypeScriptCodegen {
 
     /// Generate TypeScript code from multiple declarations.
     pub fn generate_all(decls: &[Declaration]) -> String {
-        let generator = Self::new();
+        Self::new().gen_file(decls)
+    }
+
+    /// Generate TypeScript code for multiple declarations using this
+    /// generator's options.
+    ///
+    /// With [`CodegenOptions::module_from_namespace`] set, declarations are
+    /// nested in `namespace` blocks following their qualified names.
+    pub fn gen_file(&self, decls: &[Declaration]) -> String {
+        if self.options.module_from_namespace {
+            let mut tree = NamespaceTree::default();
+            for decl in decls {
+                tree.insert(&decl.qualified_name(), self.generate_declaration(decl));
+            }
+            let open = |segment: &str| format!("export namespace {}", segment);
+            return tree.render(&open, "", "  ");
+        }
         decls
             .iter()
-            .map(|d| generator.generate_declaration(d))
+            .map(|d| self.generate_declaration(d))
             .collect::<Vec<_>>()
             .join("\n\n")
     }
@@ -86,6 +132,7 @@ impl TypeScriptCodegen {
             Declaration::Function(func) => self.generate_function(func),
             Declaration::Const(c) => self.generate_const(c),
             Declaration::SexVar(v) => self.generate_sex_var(v),
+            Declaration::Extern(e) => self.generate_extern(e),
         }
     }
 
@@ -114,6 +161,33 @@ impl TypeScriptCodegen {
         format!("export let {}: {} = {};", name, type_str, value)
     }
 
+    /// Generate ambient `declare function` signatures for an extern block.
+    fn generate_extern(&self, block: &crate::ast::ExternBlock) -> String {
+        block
+            .functions
+            .iter()
+            .map(|func| {
+                let params: Vec<String> = func
+                    .params
+                    .iter()
+                    .map(|p| format!("{}: {}", p.name, Self::map_type_expr(&p.type_ann)))
+                    .collect();
+                let ret = func
+                    .return_type
+                    .as_ref()
+                    .map(Self::map_type_expr)
+                    .unwrap_or_else(|| "void".to_string());
+                format!(
+                    "export declare function {}({}): {};",
+                    func.name,
+                    params.join(", "),
+                    ret
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     /// Generate a TypeScript function from a function declaration.
     fn generate_function(&self, func: &crate::ast::FunctionDecl) -> String {
         let mut output = String::new();
@@ -150,7 +224,7 @@ impl TypeScriptCodegen {
 
     /// Generate a TypeScript interface from a gene declaration.
     fn generate_gene(&self, gene: &Gene) -> String {
-        let interface_name = to_pascal_case(&gene.name);
+        let interface_name = to_pascal_case(gene.name.as_str());
 
         // Collect properties from "has" statements
         let fields = self.extract_fields(&gene.statements);
@@ -178,7 +252,7 @@ impl TypeScriptCodegen {
 
     /// Generate a TypeScript interface from a trait declaration.
     fn generate_trait(&self, trait_decl: &Trait) -> String {
-        let interface_name = to_pascal_case(&trait_decl.name);
+        let interface_name = to_pascal_case(trait_decl.name.as_str());
 
         // Find "uses" statements for extends clause
         let extends: Vec<String> = trait_decl
@@ -239,7 +313,7 @@ impl TypeScriptCodegen {
         // Type guard function
         output.push_str(&format!(
             "{export}function validate{}<T>(value: T): boolean {{\n",
-            to_pascal_case(&constraint.name)
+            to_pascal_case(constraint.name.as_str())
         ));
 
         // Generate constraint checks from statements
@@ -268,7 +342,7 @@ impl TypeScriptCodegen {
 
     /// Generate a TypeScript module from a system declaration.
     fn generate_system(&self, system: &System) -> String {
-        let namespace_name = to_pascal_case(&system.name);
+        let namespace_name = to_pascal_case(system.name.as_str());
 
         let mut output = String::new();
 
@@ -320,6 +394,11 @@ impl TypeScriptCodegen {
             output.push_str(" * Additions:\n");
             for addition in &evolution.additions {
                 match addition {
+                    Statement::Has {
+                        subject, property, ..
+                    } if is_implicit_subject(subject) => {
+                        output.push_str(&format!(" * - has {}\n", property));
+                    }
                     Statement::Has {
                         subject, property, ..
                     } => {
@@ -349,13 +428,13 @@ impl TypeScriptCodegen {
     fn extract_fields(&self, statements: &[Statement]) -> Vec<(String, String)> {
         statements
             .iter()
-            .filter_map(|stmt| {
-                if let Statement::Has { property, .. } = stmt {
-                    // Since AST doesn't have type annotations, use "unknown"
-                    Some((property.clone(), "unknown".to_string()))
-                } else {
-                    None
+            .filter_map(|stmt| match stmt {
+                // Untyped properties carry no annotation, so use "unknown"
+                Statement::Has { property, .. } => Some((property.clone(), "unknown".to_string())),
+                Statement::HasField(field) => {
+                    Some((field.name.clone(), Self::map_type_expr(&field.type_)))
                 }
+                _ => None,
             })
             .collect()
     }
@@ -391,6 +470,136 @@ impl TypeScriptCodegen {
         output
     }
 
+    /// Generate a Zod schema and its inferred type from a gene.
+    ///
+    /// Fields are validated by type, fields with literal defaults get
+    /// `.default(...)` and `Option<T>` fields are `.optional()`. Other genes
+    /// are referenced by their own schema constants, so those must be
+    /// generated too; see [`generate_zod_all`](Self::generate_zod_all).
+    /// Declarations other than genes produce an empty string.
+    pub fn generate_zod(decl: &Declaration) -> String {
+        match decl {
+            Declaration::Gene(gene) => {
+                let genes = [gene];
+                let deferred = [to_pascal_case(gene.name.as_str())];
+                Self::new().generate_gene_zod(gene, &genes, &deferred)
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// Generate Zod schemas for every gene, after a `zod` import.
+    ///
+    /// Genes are ordered so that each schema follows the schemas it
+    /// references. Genes in a reference cycle get an explicit interface and
+    /// a `z.ZodType` annotation, and references to schemas not yet defined
+    /// are wrapped in `z.lazy`.
+    pub fn generate_zod_all(decls: &[Declaration]) -> String {
+        let genes: Vec<&Gene> = decls
+            .iter()
+            .filter_map(|d| match d {
+                Declaration::Gene(g) => Some(g),
+                _ => None,
+            })
+            .collect();
+
+        let mut ordered = Vec::new();
+        for gene in &genes {
+            zod_visit(gene, &genes, &mut ordered);
+        }
+
+        let generator = Self::new();
+        let mut output = String::from("import { z } from \"zod\";\n");
+        for (i, gene) in ordered.iter().enumerate() {
+            let deferred: Vec<String> = ordered[i..]
+                .iter()
+                .map(|g| to_pascal_case(g.name.as_str()))
+                .collect();
+            output.push('\n');
+            output.push_str(&generator.generate_gene_zod(gene, &genes, &deferred));
+        }
+        output
+    }
+
+    /// Generate the Zod schema for a gene.
+    ///
+    /// `deferred` names the schemas defined at or after this one, which
+    /// must be referenced lazily.
+    fn generate_gene_zod(&self, gene: &Gene, genes: &[&Gene], deferred: &[String]) -> String {
+        let name = to_pascal_case(gene.name.as_str());
+        let export = if self.is_public() { "export " } else { "" };
+        let reachable = zod_reachable(gene, genes);
+        let recursive = reachable.iter().any(|g| g.name == gene.name);
+
+        let mut output = String::new();
+        if !gene.exegesis.is_empty() {
+            output.push_str(&self.format_jsdoc(&gene.exegesis));
+        }
+        if recursive {
+            // A recursive schema's type cannot be inferred from itself, so
+            // it is written out and the schema annotated with it
+            output.push_str(&format!("{export}interface {name} {{\n"));
+            for stmt in &gene.statements {
+                match stmt {
+                    Statement::Has { property, .. } => {
+                        output.push_str(&format!("  {}?: unknown;\n", to_camel_case(property)));
+                    }
+                    Statement::HasField(field) => {
+                        let member = match field.default.as_ref().and_then(zod_literal) {
+                            Some(_) => format!(
+                                "{}: {}",
+                                to_camel_case(&field.name),
+                                zod_type(&field.type_)
+                            ),
+                            None => zod_member(&field.name, &field.type_),
+                        };
+                        output.push_str(&format!("  {member};\n"));
+                    }
+                    _ => {}
+                }
+            }
+            output.push_str("}\n");
+            // Defaults make a schema's input type differ from its output
+            let defaults = std::iter::once(gene)
+                .chain(reachable.iter().copied())
+                .any(|g| {
+                    g.statements.iter().any(|stmt| {
+                        matches!(stmt, Statement::HasField(f)
+                            if f.default.as_ref().and_then(zod_literal).is_some())
+                    })
+                });
+            let annotation = if defaults {
+                format!("z.ZodType<{name}, z.ZodTypeDef, unknown>")
+            } else {
+                format!("z.ZodType<{name}>")
+            };
+            output.push_str(&format!(
+                "{export}const {name}: {annotation} = z.object({{\n"
+            ));
+        } else {
+            output.push_str(&format!("{export}const {name} = z.object({{\n"));
+        }
+        for stmt in &gene.statements {
+            let (field, schema) = match stmt {
+                Statement::Has { property, .. } => (property, "z.unknown()".to_string()),
+                Statement::HasField(field) => {
+                    let mut schema = zod_schema(&field.type_, deferred);
+                    if let Some(default) = field.default.as_ref().and_then(zod_literal) {
+                        schema.push_str(&format!(".default({default})"));
+                    }
+                    (&field.name, schema)
+                }
+                _ => continue,
+            };
+            output.push_str(&format!("  {}: {schema},\n", to_camel_case(field)));
+        }
+        output.push_str("});\n");
+        if !recursive {
+            output.push_str(&format!("{export}type {name} = z.infer<typeof {name}>;\n"));
+        }
+        output
+    }
+
     /// Check if we should export items.
     fn is_public(&self) -> bool {
         matches!(
@@ -400,6 +609,299 @@ impl TypeScriptCodegen {
     }
 }
 
+/// Appends `gene` to `ordered` after the genes its fields reference.
+fn zod_visit<'a>(gene: &'a Gene, genes: &[&'a Gene], ordered: &mut Vec<&'a Gene>) {
+    if ordered.iter().any(|g| g.name == gene.name) {
+        return;
+    }
+    // Mark the gene as visited before its dependencies to stop at cycles
+    ordered.push(gene);
+    let start = ordered.len() - 1;
+    let mut deps = Vec::new();
+    for stmt in &gene.statements {
+        if let Statement::HasField(field) = stmt {
+            collect_named(&field.type_, &mut deps);
+        }
+    }
+    for dep in deps {
+        if let Some(other) = genes
+            .iter()
+            .find(|g| to_pascal_case(g.name.as_str()) == dep)
+        {
+            zod_visit(other, genes, ordered);
+        }
+    }
+    // Move the gene after its dependencies
+    let gene = ordered.remove(start);
+    ordered.push(gene);
+}
+
+/// Returns the genes reachable through `gene`'s field types.
+///
+/// The result includes `gene` itself only when it is part of a cycle.
+fn zod_reachable<'a>(gene: &'a Gene, genes: &[&'a Gene]) -> Vec<&'a Gene> {
+    let mut reachable: Vec<&Gene> = Vec::new();
+    let mut stack = vec![gene];
+    while let Some(current) = stack.pop() {
+        let mut deps = Vec::new();
+        for stmt in &current.statements {
+            if let Statement::HasField(field) = stmt {
+                collect_named(&field.type_, &mut deps);
+            }
+        }
+        for dep in deps {
+            if let Some(other) = genes
+                .iter()
+                .find(|g| to_pascal_case(g.name.as_str()) == dep)
+            {
+                if !reachable.iter().any(|g| g.name == other.name) {
+                    reachable.push(other);
+                    stack.push(other);
+                }
+            }
+        }
+    }
+    reachable
+}
+
+/// Collects the PascalCase names of the types referenced by a type.
+fn collect_named(ty: &TypeExpr, names: &mut Vec<String>) {
+    match ty {
+        TypeExpr::Named(name) => names.push(to_pascal_case(name)),
+        TypeExpr::Generic { args, .. } | TypeExpr::Tuple(args) => {
+            args.iter().for_each(|a| collect_named(a, names))
+        }
+        TypeExpr::Record(fields) => fields.iter().for_each(|(_, t)| collect_named(t, names)),
+        TypeExpr::Enum { variants } => {
+            for variant in variants {
+                variant
+                    .fields
+                    .iter()
+                    .for_each(|(_, t)| collect_named(t, names));
+                variant
+                    .tuple_types
+                    .iter()
+                    .for_each(|t| collect_named(t, names));
+            }
+        }
+        TypeExpr::Function { .. } | TypeExpr::Never => {}
+    }
+}
+
+/// Maps a DOL type to a Zod schema expression.
+///
+/// References to `deferred` schemas, the one being defined and those
+/// defined after it, are wrapped in `z.lazy` so recursive types work.
+fn zod_schema(ty: &TypeExpr, deferred: &[String]) -> String {
+    match ty {
+        TypeExpr::Named(name) => match name.as_str() {
+            "Int8" | "Int16" | "Int32" | "Int64" => "z.number().int()".to_string(),
+            "UInt8" | "UInt16" | "UInt32" | "UInt64" => {
+                "z.number().int().nonnegative()".to_string()
+            }
+            "Float32" | "Float64" => "z.number()".to_string(),
+            "String" => "z.string()".to_string(),
+            "Bool" => "z.boolean()".to_string(),
+            "Void" => "z.void()".to_string(),
+            "Any" => "z.any()".to_string(),
+            _ => {
+                let schema = to_pascal_case(name);
+                if deferred.contains(&schema) {
+                    format!("z.lazy(() => {schema})")
+                } else {
+                    schema
+                }
+            }
+        },
+        TypeExpr::Generic { name, args } => {
+            let mapped: Vec<_> = args.iter().map(|a| zod_schema(a, deferred)).collect();
+            match (name.as_str(), mapped.as_slice()) {
+                ("List", [item]) => format!("z.array({item})"),
+                ("Set", [item]) => format!("z.set({item})"),
+                ("Option", [inner]) => format!("{inner}.optional()"),
+                ("Map", [key, value]) if matches!(key.as_str(), "z.string()")
+                    || key.starts_with("z.number()") =>
+                {
+                    format!("z.record({key}, {value})")
+                }
+                ("Map", [key, value]) => format!("z.map({key}, {value})"),
+                ("Result", [value, error]) => format!(
+                    "z.discriminatedUnion(\"ok\", [z.object({{ ok: z.literal(true), value: {value} }}), \
+                     z.object({{ ok: z.literal(false), error: {error} }})])"
+                ),
+                _ => zod_schema(&TypeExpr::Named(name.clone()), deferred),
+            }
+        }
+        TypeExpr::Tuple(types) => {
+            let mapped: Vec<_> = types.iter().map(|t| zod_schema(t, deferred)).collect();
+            format!("z.tuple([{}])", mapped.join(", "))
+        }
+        TypeExpr::Function { .. } => "z.function()".to_string(),
+        TypeExpr::Never => "z.never()".to_string(),
+        TypeExpr::Enum { variants } => zod_enum(variants, deferred),
+        TypeExpr::Record(fields) => {
+            let members: Vec<_> = fields
+                .iter()
+                .map(|(name, ty)| format!("{}: {}", to_camel_case(name), zod_schema(ty, deferred)))
+                .collect();
+            format!("z.object({{ {} }})", members.join(", "))
+        }
+    }
+}
+
+/// Maps an inline enum to a Zod schema.
+///
+/// Enums of plain variants become `z.enum([...])`. Otherwise each variant
+/// is an object tagged with its name in `kind`, carrying struct fields by
+/// name and tuple payloads as `values`.
+fn zod_enum(variants: &[EnumVariant], deferred: &[String]) -> String {
+    if variants
+        .iter()
+        .all(|v| v.fields.is_empty() && v.tuple_types.is_empty())
+    {
+        let names: Vec<_> = variants.iter().map(|v| format!("\"{}\"", v.name)).collect();
+        return format!("z.enum([{}])", names.join(", "));
+    }
+
+    let options: Vec<_> = variants
+        .iter()
+        .map(|variant| {
+            let mut members = vec![format!("kind: z.literal(\"{}\")", variant.name)];
+            for (name, ty) in &variant.fields {
+                members.push(format!(
+                    "{}: {}",
+                    to_camel_case(name),
+                    zod_schema(ty, deferred)
+                ));
+            }
+            if !variant.tuple_types.is_empty() {
+                let types: Vec<_> = variant
+                    .tuple_types
+                    .iter()
+                    .map(|t| zod_schema(t, deferred))
+                    .collect();
+                members.push(format!("values: z.tuple([{}])", types.join(", ")));
+            }
+            format!("z.object({{ {} }})", members.join(", "))
+        })
+        .collect();
+    format!("z.discriminatedUnion(\"kind\", [{}])", options.join(", "))
+}
+
+/// Maps a DOL type to the TypeScript type of its Zod schema's output.
+///
+/// This mirrors [`zod_schema`] and is used to write out the interface of a
+/// recursive schema.
+fn zod_type(ty: &TypeExpr) -> String {
+    match ty {
+        TypeExpr::Named(name) => match name.as_str() {
+            "Int8" | "Int16" | "Int32" | "Int64" | "UInt8" | "UInt16" | "UInt32" | "UInt64"
+            | "Float32" | "Float64" => "number".to_string(),
+            "String" => "string".to_string(),
+            "Bool" => "boolean".to_string(),
+            "Void" => "void".to_string(),
+            "Any" => "any".to_string(),
+            _ => to_pascal_case(name),
+        },
+        TypeExpr::Generic { name, args } => {
+            let mapped: Vec<_> = args.iter().map(zod_type).collect();
+            match (name.as_str(), mapped.as_slice()) {
+                ("List", [item]) => format!("Array<{item}>"),
+                ("Set", [item]) => format!("Set<{item}>"),
+                ("Option", [inner]) => format!("{inner} | undefined"),
+                ("Map", [key, value]) if matches!(key.as_str(), "string" | "number") => {
+                    format!("Record<{key}, {value}>")
+                }
+                ("Map", [key, value]) => format!("Map<{key}, {value}>"),
+                ("Result", [value, error]) => {
+                    format!("{{ ok: true; value: {value} }} | {{ ok: false; error: {error} }}")
+                }
+                _ => zod_type(&TypeExpr::Named(name.clone())),
+            }
+        }
+        TypeExpr::Tuple(types) => {
+            let mapped: Vec<_> = types.iter().map(zod_type).collect();
+            format!("[{}]", mapped.join(", "))
+        }
+        TypeExpr::Function { .. } => "(...args: unknown[]) => unknown".to_string(),
+        TypeExpr::Never => "never".to_string(),
+        TypeExpr::Enum { variants } => {
+            if variants
+                .iter()
+                .all(|v| v.fields.is_empty() && v.tuple_types.is_empty())
+            {
+                let names: Vec<_> = variants.iter().map(|v| format!("\"{}\"", v.name)).collect();
+                return names.join(" | ");
+            }
+            let options: Vec<_> = variants
+                .iter()
+                .map(|variant| {
+                    let mut members = vec![format!("kind: \"{}\"", variant.name)];
+                    for (name, ty) in &variant.fields {
+                        members.push(zod_member(name, ty));
+                    }
+                    if !variant.tuple_types.is_empty() {
+                        let types: Vec<_> = variant.tuple_types.iter().map(zod_type).collect();
+                        members.push(format!("values: [{}]", types.join(", ")));
+                    }
+                    format!("{{ {} }}", members.join("; "))
+                })
+                .collect();
+            options.join(" | ")
+        }
+        TypeExpr::Record(fields) => {
+            let members: Vec<_> = fields
+                .iter()
+                .map(|(name, ty)| zod_member(name, ty))
+                .collect();
+            format!("{{ {} }}", members.join("; "))
+        }
+    }
+}
+
+/// Renders an object member of a Zod output type; `Option` fields are
+/// optional keys, as `z.object` infers them.
+fn zod_member(name: &str, ty: &TypeExpr) -> String {
+    match ty {
+        TypeExpr::Generic {
+            name: generic,
+            args,
+        } if generic == "Option" && args.len() == 1 => {
+            format!("{}?: {}", to_camel_case(name), zod_type(&args[0]))
+        }
+        _ => format!("{}: {}", to_camel_case(name), zod_type(ty)),
+    }
+}
+
+/// Renders a literal default value as TypeScript, if it is one.
+fn zod_literal(expr: &Expr) -> Option<String> {
+    match expr.unspanned() {
+        Expr::Literal(Literal::Int(n)) => Some(n.to_string()),
+        Expr::Literal(Literal::Float(f)) => Some(f.to_string()),
+        Expr::Literal(Literal::String(s)) => Some(format!("{:?}", s)),
+        Expr::Literal(Literal::Bool(b)) => Some(b.to_string()),
+        Expr::Unary {
+            op: UnaryOp::Neg,
+            operand,
+        } => zod_literal(operand).map(|n| format!("-{n}")),
+        Expr::List(items) => {
+            let items: Option<Vec<_>> = items.iter().map(zod_literal).collect();
+            Some(format!("[{}]", items?.join(", ")))
+        }
+        _ => None,
+    }
+}
+
+impl Codegen for TypeScriptCodegen {
+    fn generate(decl: &Declaration) -> String {
+        TypeScriptCodegen::generate(decl)
+    }
+
+    fn generate_with(decl: &Declaration, opts: &CodegenOptions) -> String {
+        TypeScriptCodegen::with_options(opts.clone()).generate_declaration(decl)
+    }
+}
+
 impl TypeMapper for TypeScriptCodegen {
     fn map_type(ty: &Type) -> String {
         match ty {
@@ -425,6 +927,14 @@ impl TypeMapper for TypeScriptCodegen {
                 let mapped: Vec<_> = types.iter().map(Self::map_type).collect();
                 format!("[{}]", mapped.join(", "))
             }
+            Type::Record(fields) => {
+                let members: Vec<_> = fields
+                    .iter()
+                    .map(|(name, ty)| format!("{}: {}", name, Self::map_type(ty)))
+                    .collect();
+                format!("{{ {} }}", members.join("; "))
+            }
+            Type::Option(inner) => format!("{} | undefined", Self::map_type(inner)),
             Type::Generic { name, args } => {
                 let mapped_args: Vec<_> = args.iter().map(Self::map_type).collect();
                 // Map DOL generic types to TypeScript equivalents
@@ -438,7 +948,7 @@ impl TypeMapper for TypeScriptCodegen {
                     }
                     "Map" => {
                         if args.len() == 2 {
-                            format!("Map<{}, {}>", mapped_args[0], mapped_args[1])
+                            map_container(&mapped_args[0], &mapped_args[1])
                         } else {
                             "Map<unknown, unknown>".to_string()
                         }
@@ -501,7 +1011,7 @@ impl TypeMapper for TypeScriptCodegen {
                     }
                     "Map" => {
                         if args.len() == 2 {
-                            format!("Map<{}, {}>", mapped_args[0], mapped_args[1])
+                            map_container(&mapped_args[0], &mapped_args[1])
                         } else {
                             "Map<unknown, unknown>".to_string()
                         }
@@ -551,6 +1061,14 @@ impl TypeMapper for TypeScriptCodegen {
                     .collect::<Vec<_>>()
                     .join(" | ")
             }
+            TypeExpr::Record(fields) => {
+                // TypeScript inline object type
+                let members: Vec<_> = fields
+                    .iter()
+                    .map(|(name, ty)| format!("{}: {}", name, Self::map_type_expr(ty)))
+                    .collect();
+                format!("{{ {} }}", members.join("; "))
+            }
         }
     }
 }
@@ -558,12 +1076,12 @@ impl TypeMapper for TypeScriptCodegen {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ast::Span;
+    use crate::ast::{QualifiedName, Span, Visibility};
 
     #[test]
     fn test_generate_gene_interface() {
         let gene = Gene {
-            name: "container.exists".to_string(),
+            name: QualifiedName::new("container.exists"),
             extends: None,
             statements: vec![
                 Statement::Has {
@@ -577,7 +1095,9 @@ mod tests {
                     span: Span::default(),
                 },
             ],
-            exegesis: "A container is the fundamental unit.".to_string(),
+            exegesis: "A container is the fundamental unit.".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -591,7 +1111,7 @@ mod tests {
     #[test]
     fn test_generate_trait_interface() {
         let trait_decl = Trait {
-            name: "container.lifecycle".to_string(),
+            name: QualifiedName::new("container.lifecycle"),
             statements: vec![
                 Statement::Uses {
                     reference: "container.exists".to_string(),
@@ -603,7 +1123,10 @@ mod tests {
                     span: Span::default(),
                 },
             ],
-            exegesis: "Container lifecycle management.".to_string(),
+            laws: vec![],
+            exegesis: "Container lifecycle management.".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -637,7 +1160,14 @@ mod tests {
                 name: "Map".to_string(),
                 args: vec![Type::String, Type::Int64]
             }),
-            "Map<string, number>"
+            "Record<string, number>"
+        );
+        assert_eq!(
+            TypeScriptCodegen::map_type(&Type::Generic {
+                name: "Map".to_string(),
+                args: vec![Type::Bool, Type::Int64]
+            }),
+            "Map<boolean, number>"
         );
     }
 
@@ -669,13 +1199,14 @@ mod tests {
     #[test]
     fn test_generate_constraint_type_guard() {
         let constraint = Constraint {
-            name: "container.integrity".to_string(),
+            name: QualifiedName::new("container.integrity"),
             statements: vec![Statement::Matches {
                 subject: "state".to_string(),
                 target: "declared_state".to_string(),
                 span: Span::default(),
             }],
-            exegesis: "Container integrity constraints.".to_string(),
+            exegesis: "Container integrity constraints.".into(),
+            visibility: Visibility::Private,
             span: Span::default(),
         };
 
@@ -689,11 +1220,14 @@ mod tests {
     #[test]
     fn test_generate_system_namespace() {
         let system = System {
-            name: "container.runtime".to_string(),
+            name: QualifiedName::new("container.runtime"),
             version: "1.0.0".to_string(),
             requirements: vec![],
             statements: vec![],
-            exegesis: "Container runtime system.".to_string(),
+            states: vec![],
+            exegesis: "Container runtime system.".into(),
+            capabilities: vec![],
+            visibility: Visibility::Private,
             span: Span::default(),
         };
 
@@ -712,4 +1246,219 @@ mod tests {
         );
         assert_eq!(to_camel_case("Simple"), "simple");
     }
+
+    #[test]
+    fn test_generate_with_honors_visibility() {
+        let gene = Gene {
+            name: QualifiedName::new("container.exists"),
+            extends: None,
+            statements: vec![Statement::Has {
+                subject: "container".to_string(),
+                property: "id".to_string(),
+                span: Span::default(),
+            }],
+            exegesis: "A container is the fundamental unit.".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
+            span: Span::default(),
+        };
+        let opts = CodegenOptions {
+            visibility: crate::codegen::Visibility::Private,
+            ..Default::default()
+        };
+
+        let code = <TypeScriptCodegen as Codegen>::generate_with(&Declaration::Gene(gene), &opts);
+
+        assert!(code.contains("interface ContainerExists"));
+        assert!(!code.contains("export interface"));
+    }
+
+    #[test]
+    fn test_gen_file_module_from_namespace() {
+        let gene = |name: &str| {
+            Declaration::Gene(Gene {
+                name: QualifiedName::new(name),
+                extends: None,
+                statements: vec![],
+                exegesis: Default::default(),
+                visibility: Visibility::Private,
+                attributes: vec![],
+                span: Span::default(),
+            })
+        };
+        let opts = CodegenOptions {
+            module_from_namespace: true,
+            ..Default::default()
+        };
+
+        let code = TypeScriptCodegen::with_options(opts)
+            .gen_file(&[gene("univrs.container.exists"), gene("univrs.node.exists")]);
+
+        assert!(code.contains("export namespace univrs {"));
+        assert!(code.contains("  export namespace container {"));
+        assert!(code.contains("  export namespace node {"));
+        assert_eq!(code.matches("export namespace univrs").count(), 1);
+    }
+
+    #[test]
+    fn test_map_function_type() {
+        let callback = TypeExpr::Function {
+            params: vec![
+                TypeExpr::Named("Int64".to_string()),
+                TypeExpr::Named("String".to_string()),
+            ],
+            return_type: Box::new(TypeExpr::Named("Bool".to_string())),
+        };
+        assert_eq!(
+            TypeScriptCodegen::map_type_expr(&callback),
+            "(arg0: number, arg1: string) => boolean"
+        );
+
+        let thunk = TypeExpr::Function {
+            params: vec![],
+            return_type: Box::new(TypeExpr::Named("Void".to_string())),
+        };
+        assert_eq!(TypeScriptCodegen::map_type_expr(&thunk), "() => void");
+    }
+
+    #[test]
+    fn test_generate_gene_function_field() {
+        let source = "gene Button { has on_click: (Int64, String) -> Bool }";
+        let decl = crate::parser::Parser::new(source).parse().unwrap();
+
+        let code = TypeScriptCodegen::generate(&decl);
+        assert!(code.contains("onClick: (arg0: number, arg1: string) => boolean;"));
+    }
+
+    #[test]
+    fn test_generate_zod_gene() {
+        let source = "gene server.config {\n  has id: String\n  has port: UInt16 = 8080\n  \
+                      has tags: List<String>\n  has label: Option<String>\n  \
+                      has mode: enum { Dev, Prod } \n}";
+        let decl = crate::parse_file(source).unwrap();
+        let zod = TypeScriptCodegen::generate_zod(&decl);
+
+        assert!(zod.starts_with("export const ServerConfig = z.object({\n"));
+        assert!(zod.contains("  id: z.string(),\n"));
+        assert!(zod.contains("  port: z.number().int().nonnegative().default(8080),\n"));
+        assert!(zod.contains("  tags: z.array(z.string()),\n"));
+        assert!(zod.contains("  label: z.string().optional(),\n"));
+        assert!(zod.contains("  mode: z.enum([\"Dev\", \"Prod\"]),\n"));
+        assert!(zod.ends_with("});\nexport type ServerConfig = z.infer<typeof ServerConfig>;\n"));
+    }
+
+    #[test]
+    fn test_generate_zod_all_orders_references() {
+        let source = "gene tree.node {\n  has label: String\n  has parent: Option<TreeNode>\n  \
+                      has meta: Metadata\n}\n\ngene metadata {\n  has owner: String = \"root\"\n}";
+        let decls = crate::parse_file_all(source).unwrap();
+        let zod = TypeScriptCodegen::generate_zod_all(&decls);
+
+        assert!(zod.starts_with("import { z } from \"zod\";\n"));
+        assert!(zod.contains("  owner: z.string().default(\"root\"),\n"));
+        assert!(zod.contains("  parent: z.lazy(() => TreeNode).optional(),\n"));
+        assert!(zod.contains("  meta: Metadata,\n"));
+        let metadata = zod.find("const Metadata =").unwrap();
+        let node = zod.find("const TreeNode:").unwrap();
+        assert!(metadata < node);
+    }
+
+    #[test]
+    fn test_generate_zod_all_self_recursive() {
+        let source = "gene tree.node {\n  has label: String\n  has children: List<TreeNode>\n  \
+                      has parent: Option<TreeNode>\n}";
+        let decls = crate::parse_file_all(source).unwrap();
+        let zod = TypeScriptCodegen::generate_zod_all(&decls);
+
+        assert!(zod.contains(
+            "export interface TreeNode {\n  label: string;\n  children: Array<TreeNode>;\n  \
+             parent?: TreeNode;\n}\n"
+        ));
+        assert!(zod.contains("export const TreeNode: z.ZodType<TreeNode> = z.object({\n"));
+        assert!(zod.contains("  children: z.array(z.lazy(() => TreeNode)),\n"));
+        assert!(zod.contains("  parent: z.lazy(() => TreeNode).optional(),\n"));
+        assert!(!zod.contains("z.infer<typeof TreeNode>"));
+        assert!(zod.find("interface TreeNode").unwrap() < zod.find("const TreeNode").unwrap());
+
+        // A reachable default makes the input type differ from the output
+        let source = "gene tree.node {\n  has parent: Option<TreeNode>\n  has depth: Int64 = 0\n}";
+        let decls = crate::parse_file_all(source).unwrap();
+        let zod = TypeScriptCodegen::generate_zod_all(&decls);
+        assert!(zod.contains(
+            "export const TreeNode: z.ZodType<TreeNode, z.ZodTypeDef, unknown> = z.object({\n"
+        ));
+    }
+
+    #[test]
+    fn test_generate_zod_all_mutually_recursive() {
+        let source =
+            "gene expr {\n  has terms: List<Term>\n}\n\ngene term {\n  has value: Int64\n  \
+                      has nested: Option<Expr>\n}\n\ngene doc {\n  has root: Expr\n}";
+        let decls = crate::parse_file_all(source).unwrap();
+        let zod = TypeScriptCodegen::generate_zod_all(&decls);
+
+        assert!(zod.contains("export interface Term {\n  value: number;\n  nested?: Expr;\n}\n"));
+        assert!(zod.contains("export interface Expr {\n  terms: Array<Term>;\n}\n"));
+        assert!(zod.contains("export const Term: z.ZodType<Term> = z.object({\n"));
+        assert!(zod.contains("export const Expr: z.ZodType<Expr> = z.object({\n"));
+        // Term is defined first, so only its reference to Expr is lazy
+        assert!(zod.contains("  nested: z.lazy(() => Expr).optional(),\n"));
+        assert!(zod.contains("  terms: z.array(Term),\n"));
+        assert!(zod.find("const Term:").unwrap() < zod.find("const Expr:").unwrap());
+
+        // Doc is outside the cycle and keeps its inferred type
+        assert!(zod.contains("export const Doc = z.object({\n  root: Expr,\n});\n"));
+        assert!(zod.contains("export type Doc = z.infer<typeof Doc>;\n"));
+        assert!(zod.find("const Expr:").unwrap() < zod.find("const Doc =").unwrap());
+    }
+
+    #[test]
+    fn test_zod_schema_unions_and_containers() {
+        let ty = TypeExpr::Generic {
+            name: "Result".to_string(),
+            args: vec![
+                TypeExpr::Named("Int64".to_string()),
+                TypeExpr::Named("String".to_string()),
+            ],
+        };
+        assert_eq!(
+            zod_schema(&ty, &["Current".to_string()]),
+            "z.discriminatedUnion(\"ok\", [z.object({ ok: z.literal(true), value: z.number().int() }), \
+             z.object({ ok: z.literal(false), error: z.string() })])"
+        );
+
+        let map = TypeExpr::Generic {
+            name: "Map".to_string(),
+            args: vec![
+                TypeExpr::Named("String".to_string()),
+                TypeExpr::Named("Bool".to_string()),
+            ],
+        };
+        assert_eq!(
+            zod_schema(&map, &["Current".to_string()]),
+            "z.record(z.string(), z.boolean())"
+        );
+
+        let shape = TypeExpr::Enum {
+            variants: vec![
+                EnumVariant {
+                    name: "Circle".to_string(),
+                    fields: vec![("radius".to_string(), TypeExpr::Named("Float64".to_string()))],
+                    tuple_types: vec![],
+                    discriminant: None,
+                },
+                EnumVariant {
+                    name: "Empty".to_string(),
+                    fields: vec![],
+                    tuple_types: vec![],
+                    discriminant: None,
+                },
+            ],
+        };
+        assert_eq!(
+            zod_schema(&shape, &["Current".to_string()]),
+            "z.discriminatedUnion(\"kind\", [z.object({ kind: z.literal(\"Circle\"), radius: z.number() }), \
+             z.object({ kind: z.literal(\"Empty\") })])"
+        );
+    }
 }
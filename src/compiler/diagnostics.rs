@@ -0,0 +1,283 @@
+//! Compiler Diagnostics
+//!
+//! Error aggregation and source maps shared by every compilation pipeline.
+//!
+//! These types do not depend on any backend feature, so the Rust pipeline
+//! ([`compile_rust_source`]) and the WASM pipeline (`compiler::spirit`, behind
+//! the `wasm` feature) report errors, warnings, and source locations the
+//! same way.
+//!
+//! # Example
+//!
+//! ```rust
+//! use metadol::compiler::diagnostics::compile_rust_source;
+//!
+//! let source = r#"
+//! gene container.exists {
+//!     container has id
+//! }
+//!
+//! exegesis {
+//!     A container.
+//! }
+//! "#;
+//!
+//! let compiled = compile_rust_source(source, "container.dol").unwrap();
+//! assert!(compiled.code.contains("pub struct"));
+//! assert_eq!(compiled.source_map.entries.len(), 1);
+//! ```
+
+use crate::codegen::HirRustCodegen;
+use crate::error::ParseError;
+use crate::lower::{self, DiagnosticKind, LowerDiagnostic, LoweringContext};
+use crate::parser::Parser;
+
+/// Source map for debugging.
+///
+/// Maps offsets in compiled output back to original DOL source locations,
+/// enabling debuggers to show DOL source while stepping through the output.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    /// Individual source map entries
+    pub entries: Vec<SourceMapEntry>,
+}
+
+impl SourceMap {
+    /// Creates an empty source map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the entry covering `offset`: the last one starting at or
+    /// before it.
+    pub fn lookup(&self, offset: u32) -> Option<&SourceMapEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.output_offset <= offset)
+            .max_by_key(|entry| entry.output_offset)
+    }
+}
+
+/// Single source map entry.
+///
+/// Maps an offset in compiled output (WASM bytecode or generated source
+/// text) to a DOL source location.
+#[derive(Debug, Clone)]
+pub struct SourceMapEntry {
+    /// Byte offset in the compiled output
+    pub output_offset: u32,
+
+    /// Source file path
+    pub source_file: String,
+
+    /// Line number in source file (1-indexed)
+    pub line: u32,
+
+    /// Column number in line (1-indexed)
+    pub column: u32,
+}
+
+/// Non-fatal warning from compilation.
+///
+/// Warnings indicate potential issues that don't prevent compilation,
+/// such as deprecated syntax, unused variables, or optimization hints.
+#[derive(Debug, Clone)]
+pub struct CompilerWarning {
+    /// Warning message
+    pub message: String,
+
+    /// Optional source location (file, line, column)
+    pub location: Option<(String, u32, u32)>,
+}
+
+impl CompilerWarning {
+    /// Converts a lowering diagnostic, locating it in `filename`.
+    pub fn from_lowering(diag: &LowerDiagnostic, filename: &str) -> Self {
+        Self {
+            message: diag.message.clone(),
+            location: diag
+                .span
+                .as_ref()
+                .map(|span| (filename.to_string(), span.line as u32, span.column as u32)),
+        }
+    }
+}
+
+/// Compiler error type.
+///
+/// Represents all errors that can occur during the compilation pipeline,
+/// from lexing through WASM emission.
+#[derive(Debug)]
+pub enum CompilerError {
+    /// Lexer error during tokenization
+    LexError(String),
+
+    /// Parser error during syntax analysis
+    ParseError(ParseError),
+
+    /// Error during AST to HIR lowering
+    HirError(String),
+
+    /// Error during HIR to MLIR lowering
+    MlirError(String),
+
+    /// Error during WASM emission
+    WasmError(String),
+
+    /// I/O error (file not found, permission denied, etc.)
+    IoError(std::io::Error),
+
+    /// Project structure error (missing manifest, invalid entry point, etc.)
+    ProjectError(String),
+}
+
+impl std::fmt::Display for CompilerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompilerError::LexError(msg) => write!(f, "Lexer error: {}", msg),
+            CompilerError::ParseError(err) => write!(f, "Parse error: {}", err),
+            CompilerError::HirError(msg) => write!(f, "HIR lowering error: {}", msg),
+            CompilerError::MlirError(msg) => write!(f, "MLIR lowering error: {}", msg),
+            CompilerError::WasmError(msg) => write!(f, "WASM emission error: {}", msg),
+            CompilerError::IoError(err) => write!(f, "I/O error: {}", err),
+            CompilerError::ProjectError(msg) => write!(f, "Project error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CompilerError {}
+
+impl From<ParseError> for CompilerError {
+    fn from(err: ParseError) -> Self {
+        CompilerError::ParseError(err)
+    }
+}
+
+impl From<std::io::Error> for CompilerError {
+    fn from(err: std::io::Error) -> Self {
+        CompilerError::IoError(err)
+    }
+}
+
+/// Aggregates the diagnostics of a lowering pass.
+///
+/// Every diagnostic becomes a [`CompilerWarning`]. If any of them is an
+/// error, the error messages are joined into a single
+/// [`CompilerError::HirError`] instead.
+pub fn collect_lowering_diagnostics(
+    ctx: &LoweringContext,
+    filename: &str,
+) -> Result<Vec<CompilerWarning>, CompilerError> {
+    if ctx.has_errors() {
+        let errors: Vec<String> = ctx
+            .diagnostics()
+            .iter()
+            .filter(|d| matches!(d.kind, DiagnosticKind::Error))
+            .map(|d| d.message.clone())
+            .collect();
+        return Err(CompilerError::HirError(errors.join("; ")));
+    }
+    Ok(ctx
+        .diagnostics()
+        .iter()
+        .map(|diag| CompilerWarning::from_lowering(diag, filename))
+        .collect())
+}
+
+/// Result of compiling DOL source to Rust through HIR.
+#[derive(Debug)]
+pub struct CompiledRust {
+    /// Generated Rust source code
+    pub code: String,
+
+    /// Maps byte offsets in `code` to the declarations they were generated from
+    pub source_map: SourceMap,
+
+    /// Non-fatal warnings emitted during compilation
+    pub warnings: Vec<CompilerWarning>,
+}
+
+/// Compile DOL source code to Rust via HIR.
+///
+/// Runs the same parse and lowering phases as the WASM pipeline, then
+/// generates Rust with [`HirRustCodegen`]. The source map holds one entry
+/// per top-level declaration, pointing at the start of its generated item.
+///
+/// # Errors
+///
+/// Returns [`CompilerError::ParseError`] on syntax errors and
+/// [`CompilerError::HirError`] if lowering reports errors.
+pub fn compile_rust_source(source: &str, filename: &str) -> Result<CompiledRust, CompilerError> {
+    let file = Parser::new(source).with_expr_spans().parse_file()?;
+
+    let mut ctx = LoweringContext::new();
+    let hir = lower::lower_module(&mut ctx, &file);
+    let warnings = collect_lowering_diagnostics(&ctx, filename)?;
+
+    let mut codegen = HirRustCodegen::with_symbols(ctx.symbols);
+    let (code, offsets) = codegen.generate_mapped(&hir);
+
+    let mut source_map = SourceMap::new();
+    for (decl, offset) in file.declarations.iter().zip(offsets) {
+        let span = decl.span();
+        source_map.entries.push(SourceMapEntry {
+            output_offset: offset as u32,
+            source_file: filename.to_string(),
+            line: span.line as u32,
+            column: span.column as u32,
+        });
+    }
+
+    Ok(CompiledRust {
+        code,
+        source_map,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_pipeline_source_map() {
+        let source = r#"
+gene counter.state {
+    counter has value
+}
+
+exegesis {
+    A counter.
+}
+"#;
+        let compiled = compile_rust_source(source, "counter.dol").unwrap();
+        assert_eq!(compiled.source_map.entries.len(), 1);
+
+        let entry = &compiled.source_map.entries[0];
+        assert_eq!(entry.source_file, "counter.dol");
+        assert_eq!(entry.line, 2);
+        assert_eq!(entry.column, 1);
+        assert!(
+            compiled.code[entry.output_offset as usize..].starts_with("/// Gene: counter.state")
+        );
+        assert_eq!(
+            compiled
+                .source_map
+                .lookup(entry.output_offset + 4)
+                .map(|e| e.line),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_rust_pipeline_parse_error() {
+        let result = compile_rust_source("gene {", "broken.dol");
+        assert!(matches!(result, Err(CompilerError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_compiler_error_display() {
+        let err = CompilerError::HirError("type mismatch".to_string());
+        assert_eq!(err.to_string(), "HIR lowering error: type mismatch");
+    }
+}
@@ -21,7 +21,9 @@
 //!
 //! # Feature Flags
 //!
-//! This module requires the `wasm` feature flag:
+//! Error aggregation and source maps live in [`diagnostics`], which is always
+//! available and also drives the HIR-to-Rust pipeline. The WASM pipeline in
+//! `spirit` requires the `wasm` feature flag:
 //!
 //! ```toml
 //! [dependencies]
@@ -68,11 +70,14 @@
 //! let compiled = compile_spirit_project(project_dir)?;
 //! ```
 
+pub mod diagnostics;
+
 #[cfg(feature = "wasm")]
 pub mod spirit;
 
-#[cfg(feature = "wasm")]
-pub use spirit::{
-    compile_file, compile_source, compile_spirit_project, CompiledSpirit, CompilerError,
-    CompilerWarning, SourceMap, SourceMapEntry,
+pub use diagnostics::{
+    compile_rust_source, CompiledRust, CompilerError, CompilerWarning, SourceMap, SourceMapEntry,
 };
+
+#[cfg(feature = "wasm")]
+pub use spirit::{compile_file, compile_source, compile_spirit_project, CompiledSpirit};
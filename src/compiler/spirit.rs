@@ -62,9 +62,10 @@
 
 use std::path::Path;
 
-use crate::error::ParseError;
 use crate::parser::Parser;
 
+pub use super::diagnostics::{CompilerError, CompilerWarning, SourceMap, SourceMapEntry};
+
 #[cfg(feature = "wasm")]
 use crate::lower;
 
@@ -84,103 +85,6 @@ pub struct CompiledSpirit {
     pub warnings: Vec<CompilerWarning>,
 }
 
-/// Source map for debugging.
-///
-/// Maps WASM bytecode offsets back to original DOL source locations,
-/// enabling debuggers to show DOL source while stepping through WASM execution.
-#[derive(Debug, Clone)]
-pub struct SourceMap {
-    /// Individual source map entries
-    pub entries: Vec<SourceMapEntry>,
-}
-
-/// Single source map entry.
-///
-/// Maps a WASM bytecode offset to a DOL source location.
-#[derive(Debug, Clone)]
-pub struct SourceMapEntry {
-    /// Offset in WASM bytecode
-    pub wasm_offset: u32,
-
-    /// Source file path
-    pub source_file: String,
-
-    /// Line number in source file (1-indexed)
-    pub line: u32,
-
-    /// Column number in line (1-indexed)
-    pub column: u32,
-}
-
-/// Non-fatal warning from compilation.
-///
-/// Warnings indicate potential issues that don't prevent compilation,
-/// such as deprecated syntax, unused variables, or optimization hints.
-#[derive(Debug, Clone)]
-pub struct CompilerWarning {
-    /// Warning message
-    pub message: String,
-
-    /// Optional source location (file, line, column)
-    pub location: Option<(String, u32, u32)>,
-}
-
-/// Compiler error type.
-///
-/// Represents all errors that can occur during the compilation pipeline,
-/// from lexing through WASM emission.
-#[derive(Debug)]
-pub enum CompilerError {
-    /// Lexer error during tokenization
-    LexError(String),
-
-    /// Parser error during syntax analysis
-    ParseError(ParseError),
-
-    /// Error during AST to HIR lowering
-    HirError(String),
-
-    /// Error during HIR to MLIR lowering
-    MlirError(String),
-
-    /// Error during WASM emission
-    WasmError(String),
-
-    /// I/O error (file not found, permission denied, etc.)
-    IoError(std::io::Error),
-
-    /// Project structure error (missing manifest, invalid entry point, etc.)
-    ProjectError(String),
-}
-
-impl std::fmt::Display for CompilerError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            CompilerError::LexError(msg) => write!(f, "Lexer error: {}", msg),
-            CompilerError::ParseError(err) => write!(f, "Parse error: {}", err),
-            CompilerError::HirError(msg) => write!(f, "HIR lowering error: {}", msg),
-            CompilerError::MlirError(msg) => write!(f, "MLIR lowering error: {}", msg),
-            CompilerError::WasmError(msg) => write!(f, "WASM emission error: {}", msg),
-            CompilerError::IoError(err) => write!(f, "I/O error: {}", err),
-            CompilerError::ProjectError(msg) => write!(f, "Project error: {}", msg),
-        }
-    }
-}
-
-impl std::error::Error for CompilerError {}
-
-impl From<ParseError> for CompilerError {
-    fn from(err: ParseError) -> Self {
-        CompilerError::ParseError(err)
-    }
-}
-
-impl From<std::io::Error> for CompilerError {
-    fn from(err: std::io::Error) -> Self {
-        CompilerError::IoError(err)
-    }
-}
-
 /// Compile a DOL source file to WASM.
 ///
 /// Reads the file, parses it, and compiles it through the full pipeline.
@@ -261,12 +165,10 @@ pub fn compile_file(path: &Path) -> Result<CompiledSpirit, CompilerError> {
 /// ```
 #[cfg(feature = "wasm")]
 pub fn compile_source(source: &str, filename: &str) -> Result<CompiledSpirit, CompilerError> {
-    let mut warnings = Vec::new();
-
     // ========================================================================
     // Phase 1: Parse DOL source to AST
     // ========================================================================
-    let mut parser = Parser::new(source);
+    let mut parser = Parser::new(source).with_expr_spans();
     let ast_file = parser.parse_file().map_err(CompilerError::ParseError)?;
 
     // ========================================================================
@@ -275,27 +177,8 @@ pub fn compile_source(source: &str, filename: &str) -> Result<CompiledSpirit, Co
     let mut lowering_ctx = lower::LoweringContext::new();
     let hir_module = lower::lower_module(&mut lowering_ctx, &ast_file);
 
-    // Collect lowering diagnostics as warnings
-    for diag in lowering_ctx.diagnostics() {
-        warnings.push(CompilerWarning {
-            message: diag.message.clone(),
-            location: diag
-                .span
-                .as_ref()
-                .map(|span| (filename.to_string(), span.line as u32, span.column as u32)),
-        });
-    }
-
-    // Check for lowering errors
-    if lowering_ctx.has_errors() {
-        let errors: Vec<String> = lowering_ctx
-            .diagnostics()
-            .iter()
-            .filter(|d| matches!(d.kind, lower::DiagnosticKind::Error))
-            .map(|d| d.message.clone())
-            .collect();
-        return Err(CompilerError::HirError(errors.join("; ")));
-    }
+    // Collect lowering diagnostics as warnings, failing on errors
+    let warnings = super::diagnostics::collect_lowering_diagnostics(&lowering_ctx, filename)?;
 
     // ========================================================================
     // Phase 3: Lower HIR to MLIR
@@ -573,13 +456,13 @@ gene Invalid {
     #[test]
     fn test_source_map_entry() {
         let entry = SourceMapEntry {
-            wasm_offset: 42,
+            output_offset: 42,
             source_file: "test.dol".to_string(),
             line: 10,
             column: 5,
         };
 
-        assert_eq!(entry.wasm_offset, 42);
+        assert_eq!(entry.output_offset, 42);
         assert_eq!(entry.line, 10);
         assert_eq!(entry.column, 5);
     }
@@ -0,0 +1,393 @@
+//! Severity-tagged diagnostics for reporting.
+//!
+//! A [`DiagnosticCollector`] gathers [`Diagnostic`]s from validation and can
+//! organize them by the declaration they originate from, so that CLI and IDE
+//! output can be presented per declaration rather than as a flat list.
+//!
+//! # Example
+//!
+//! ```rust
+//! use metadol::diagnostic::DiagnosticCollector;
+//! use metadol::parser::Parser;
+//! use metadol::repository::Repository;
+//! use metadol::validator::validate_file;
+//!
+//! let source = "gene container.exists { container has id }\nexegesis { Short. }";
+//! let file = Parser::new(source).parse_file().unwrap();
+//!
+//! let mut collector = DiagnosticCollector::new();
+//! for result in validate_file(&file) {
+//!     collector.add_validation(&result);
+//! }
+//! let groups = collector.group_by_declaration(&Repository::new(&file.declarations));
+//! assert!(groups.keys().all(|name| name.is_empty() || name.to_string() == "container.exists"));
+//! ```
+
+use crate::ast::{QualifiedName, Span};
+use crate::repository::Repository;
+use crate::validator::ValidationResult;
+use std::collections::BTreeMap;
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Must be fixed
+    Error,
+    /// Should be reviewed
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A single reported problem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// How serious the problem is
+    pub severity: Severity,
+    /// Human-readable description
+    pub message: String,
+    /// Source location, if the problem has one
+    pub span: Option<Span>,
+    /// Stable error code (e.g. `E0209`), if the problem has one
+    pub code: Option<&'static str>,
+}
+
+impl Diagnostic {
+    /// Creates an error diagnostic.
+    pub fn error(message: impl Into<String>, span: Option<Span>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+            code: None,
+        }
+    }
+
+    /// Creates a warning diagnostic.
+    pub fn warning(message: impl Into<String>, span: Option<Span>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            span,
+            code: None,
+        }
+    }
+
+    /// Attaches a stable error code, shown in the rendered label.
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+}
+
+impl Severity {
+    /// ANSI escape sequence used to color this severity's label.
+    fn ansi_color(self) -> &'static str {
+        match self {
+            Severity::Error => "\x1b[1;31m",
+            Severity::Warning => "\x1b[1;33m",
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Renders the diagnostic rustc-style against the source it refers to.
+    ///
+    /// The `error:`/`warning:` label is colored by severity and carries the
+    /// error code when there is one (`error[E0209]:`), followed by the
+    /// offending source line in a line-number gutter with `^` carets under
+    /// the span. A span covering several lines underlines the rest of its
+    /// first line and notes where it ends. Use [`Diagnostic::render_plain`]
+    /// for output without ANSI escapes.
+    ///
+    /// ```rust
+    /// use metadol::ast::Span;
+    /// use metadol::diagnostic::Diagnostic;
+    ///
+    /// let source = "gene a.b {\n  a has x\n}";
+    /// let diagnostic = Diagnostic::error("unknown property", Some(Span::new(19, 20, 2, 9)));
+    /// let rendered = diagnostic.render_plain(source);
+    /// assert!(rendered.contains("2 |   a has x"));
+    /// assert!(rendered.ends_with("  |         ^\n"));
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        self.render_with(source, true)
+    }
+
+    /// Renders the diagnostic like [`Diagnostic::render`], without color.
+    pub fn render_plain(&self, source: &str) -> String {
+        self.render_with(source, false)
+    }
+
+    fn render_with(&self, source: &str, color: bool) -> String {
+        let label = match self.code {
+            Some(code) => format!("{}[{}]", self.severity, code),
+            None => self.severity.to_string(),
+        };
+        let mut out = if color {
+            format!(
+                "{}{}\x1b[0m\x1b[1m: {}\x1b[0m\n",
+                self.severity.ansi_color(),
+                label,
+                self.message
+            )
+        } else {
+            format!("{}: {}\n", label, self.message)
+        };
+        let Some(span) = self.span else {
+            return out;
+        };
+
+        let start = floor_char_boundary(source, span.start);
+        let end = floor_char_boundary(source, span.end.max(span.start));
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |i| start + i);
+        let line_number = source[..start].matches('\n').count() + 1;
+        let column = source[line_start..start].chars().count() + 1;
+
+        let line = &source[line_start..line_end];
+        let underlined = source[start..end.min(line_end)].chars().count().max(1);
+        let gutter = " ".repeat(line_number.to_string().len());
+        let carets = "^".repeat(underlined);
+        let carets = if color {
+            format!("{}{}\x1b[0m", self.severity.ansi_color(), carets)
+        } else {
+            carets
+        };
+
+        out.push_str(&format!("{} --> {}:{}\n", gutter, line_number, column));
+        out.push_str(&format!("{} |\n", gutter));
+        out.push_str(&format!("{} | {}\n", line_number, line));
+        out.push_str(&format!(
+            "{} | {}{}\n",
+            gutter,
+            " ".repeat(column - 1),
+            carets
+        ));
+        if end > line_end {
+            let last_line = line_number + source[line_end..end].matches('\n').count();
+            out.push_str(&format!(
+                "{} = note: span continues to line {}\n",
+                gutter, last_line
+            ));
+        }
+        out
+    }
+}
+
+/// Clamps a byte offset into `source`, moving back to a character boundary.
+fn floor_char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// An ordered collection of diagnostics.
+///
+/// By default a diagnostic with the same severity, message, and span as one
+/// already collected is dropped, so repeated detections of one problem are
+/// reported once.
+#[derive(Debug, Clone)]
+pub struct DiagnosticCollector {
+    diagnostics: Vec<Diagnostic>,
+    dedup: bool,
+}
+
+impl Default for DiagnosticCollector {
+    fn default() -> Self {
+        Self {
+            diagnostics: Vec::new(),
+            dedup: true,
+        }
+    }
+}
+
+impl DiagnosticCollector {
+    /// Creates an empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables deduplication (enabled by default).
+    pub fn set_dedup(&mut self, enabled: bool) {
+        self.dedup = enabled;
+    }
+
+    /// Adds a diagnostic, unless deduplication is on and an identical one
+    /// was already collected.
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        let duplicate = self.dedup
+            && self.diagnostics.iter().any(|d| {
+                d.severity == diagnostic.severity
+                    && d.message == diagnostic.message
+                    && d.span == diagnostic.span
+            });
+        if !duplicate {
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Adds the errors and warnings of a validation result.
+    pub fn add_validation(&mut self, result: &ValidationResult) {
+        for error in &result.errors {
+            self.push(Diagnostic::error(error.to_string(), error.span()).with_code(error.code()));
+        }
+        for warning in &result.warnings {
+            self.push(Diagnostic::warning(warning.to_string(), warning.span()));
+        }
+    }
+
+    /// Returns the collected diagnostics, in insertion order.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Returns true if any collected diagnostic is an error.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+    }
+
+    /// Groups the diagnostics by the declaration whose span contains them.
+    ///
+    /// When declarations nest, the innermost (smallest) containing span
+    /// wins. Diagnostics without a span, or outside every declaration, are
+    /// grouped under the empty [`QualifiedName`].
+    pub fn group_by_declaration(
+        &self,
+        repo: &Repository<'_>,
+    ) -> BTreeMap<QualifiedName, Vec<Diagnostic>> {
+        let mut groups: BTreeMap<QualifiedName, Vec<Diagnostic>> = BTreeMap::new();
+        for diagnostic in &self.diagnostics {
+            let owner = diagnostic.span.and_then(|span| {
+                repo.iter()
+                    .filter(|decl| {
+                        let outer = decl.span();
+                        outer.start <= span.start && span.end <= outer.end
+                    })
+                    .min_by_key(|decl| decl.span().len())
+            });
+            let key = owner.map(|decl| decl.qualified_name()).unwrap_or_default();
+            groups.entry(key).or_default().push(diagnostic.clone());
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_group_by_declaration() {
+        let source = "gene a.first { a has x }\ngene b.second { b has y }";
+        let file = Parser::new(source).parse_file().unwrap();
+        let repo = Repository::new(&file.declarations);
+        let first = file.declarations[0].span();
+        let second = file.declarations[1].span();
+
+        let mut collector = DiagnosticCollector::new();
+        collector.push(Diagnostic::error("in first", Some(first)));
+        collector.push(Diagnostic::warning("in second", Some(second)));
+        collector.push(Diagnostic::warning("also first", Some(first)));
+        collector.push(Diagnostic::error("file level", None));
+
+        let groups = collector.group_by_declaration(&repo);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[&QualifiedName::new("a.first")].len(), 2);
+        assert_eq!(
+            groups[&QualifiedName::new("b.second")][0].message,
+            "in second"
+        );
+        assert_eq!(groups[&QualifiedName::default()][0].message, "file level");
+    }
+
+    #[test]
+    fn test_duplicate_diagnostics_are_collapsed() {
+        let diagnostic = Diagnostic::error("undefined 'x'", Some(Span::new(4, 5, 1, 5)));
+        let mut collector = DiagnosticCollector::new();
+        collector.push(diagnostic.clone());
+        collector.push(diagnostic.clone());
+        assert_eq!(collector.diagnostics().len(), 1);
+
+        collector.push(Diagnostic::error(
+            "undefined 'x'",
+            Some(Span::new(10, 11, 1, 11)),
+        ));
+        collector.push(Diagnostic::warning(
+            "undefined 'x'",
+            Some(Span::new(4, 5, 1, 5)),
+        ));
+        assert_eq!(collector.diagnostics().len(), 3);
+
+        collector.set_dedup(false);
+        collector.push(diagnostic);
+        assert_eq!(collector.diagnostics().len(), 4);
+    }
+
+    #[test]
+    fn test_render_underlines_span() {
+        let source = "gene a.b {\n  a has x\n  a has x\n}";
+        let diagnostic = Diagnostic::error("duplicate property 'x'", Some(Span::new(29, 30, 3, 9)));
+        assert_eq!(
+            diagnostic.render_plain(source),
+            "error: duplicate property 'x'\n  --> 3:9\n  |\n3 |   a has x\n  |         ^\n"
+        );
+    }
+
+    #[test]
+    fn test_render_multiline_span() {
+        let source = "gene a.b {\n  a has x\n}";
+        let diagnostic =
+            Diagnostic::warning("missing exegesis", Some(Span::new(0, source.len(), 1, 1)));
+        let rendered = diagnostic.render_plain(source);
+        assert!(rendered.starts_with("warning: missing exegesis\n"));
+        assert!(rendered.contains("1 | gene a.b {\n  | ^^^^^^^^^^\n"));
+        assert!(rendered.ends_with("  = note: span continues to line 3\n"));
+    }
+
+    #[test]
+    fn test_render_colors_by_severity() {
+        let error = Diagnostic::error("bad", None).render("");
+        let warning = Diagnostic::warning("odd", None).render("");
+        assert!(error.starts_with("\x1b[1;31merror"));
+        assert!(warning.starts_with("\x1b[1;33mwarning"));
+        assert_eq!(
+            Diagnostic::error("bad", None).render_plain(""),
+            "error: bad\n"
+        );
+    }
+
+    #[test]
+    fn test_render_includes_error_code() {
+        let source = "gene a.b {\n  a has x\n  a has x\n}";
+        let file = Parser::new(source).parse_file().unwrap();
+        let mut collector = DiagnosticCollector::new();
+        for result in crate::validator::validate_file(&file) {
+            collector.add_validation(&result);
+        }
+        let error = collector
+            .diagnostics()
+            .iter()
+            .find(|d| d.severity == Severity::Error)
+            .unwrap();
+        assert_eq!(error.code, Some("E0209"));
+        assert!(error
+            .render_plain(source)
+            .starts_with("error[E0209]: duplicate property 'x'"));
+        assert!(error.render(source).starts_with("\x1b[1;31merror[E0209]"));
+    }
+}
@@ -9,6 +9,17 @@
 //! - [`ParseError`]: Errors during parsing
 //! - [`ValidationError`]: Errors during semantic validation
 //!
+//! # Error Codes
+//!
+//! Every error variant has a stable code returned by its `code()` method,
+//! suitable for matching in CI and for `--explain`-style lookups. Codes are
+//! grouped by phase and are never reused:
+//!
+//! - `E00xx`: lexical errors
+//! - `E01xx`: parse errors
+//! - `E02xx`: validation errors
+//! - `E03xx`: type errors ([`TypeError`](crate::typechecker::TypeError))
+//!
 //! # Example
 //!
 //! ```rust
@@ -75,6 +86,26 @@ pub enum LexError {
         /// Location of the escape sequence
         span: Span,
     },
+
+    /// A block comment was not closed before the end of the source.
+    #[error("unterminated block comment starting at line {}, column {}", span.line, span.column)]
+    UnterminatedComment {
+        /// Location of the opening `/*`
+        span: Span,
+    },
+}
+
+impl LexError {
+    /// Returns the stable error code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexError::UnexpectedChar { .. } => "E0001",
+            LexError::UnterminatedString { .. } => "E0002",
+            LexError::InvalidVersion { .. } => "E0003",
+            LexError::InvalidEscape { .. } => "E0004",
+            LexError::UnterminatedComment { .. } => "E0005",
+        }
+    }
 }
 
 /// Errors that can occur during parsing.
@@ -137,9 +168,49 @@ pub enum ParseError {
     /// A lexer error occurred during parsing.
     #[error("lexer error: {0}")]
     LexerError(#[from] LexError),
+
+    /// The source text could not be read.
+    #[error("failed to read source: {message}")]
+    Io {
+        /// The underlying I/O error message
+        message: String,
+    },
+
+    /// Expressions or statements are nested deeper than the parser allows.
+    #[error("nesting exceeds the maximum depth of {limit} at line {}, column {}", span.line, span.column)]
+    NestingTooDeep {
+        /// The configured maximum depth
+        limit: usize,
+        /// Location where the limit was exceeded
+        span: Span,
+    },
+
+    /// An exegesis block was not closed before the end of the source.
+    #[error("unterminated exegesis block opened at line {}, column {}", span.line, span.column)]
+    UnterminatedExegesis {
+        /// Location of the opening `{`
+        span: Span,
+    },
 }
 
 impl ParseError {
+    /// Returns the stable error code for this error.
+    ///
+    /// Lexer errors surfaced through the parser keep their lexical code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::UnexpectedToken { .. } => "E0101",
+            ParseError::MissingExegesis { .. } => "E0102",
+            ParseError::InvalidStatement { .. } => "E0103",
+            ParseError::InvalidDeclaration { .. } => "E0104",
+            ParseError::UnexpectedEof { .. } => "E0105",
+            ParseError::Io { .. } => "E0106",
+            ParseError::NestingTooDeep { .. } => "E0107",
+            ParseError::UnterminatedExegesis { .. } => "E0108",
+            ParseError::LexerError(lex_err) => lex_err.code(),
+        }
+    }
+
     /// Returns the source span where this error occurred.
     pub fn span(&self) -> Span {
         match self {
@@ -153,9 +224,29 @@ impl ParseError {
                 LexError::UnterminatedString { span } => *span,
                 LexError::InvalidVersion { span, .. } => *span,
                 LexError::InvalidEscape { span, .. } => *span,
+                LexError::UnterminatedComment { span } => *span,
             },
+            ParseError::Io { .. } => Span::default(),
+            ParseError::NestingTooDeep { span, .. } => *span,
+            ParseError::UnterminatedExegesis { span } => *span,
         }
     }
+
+    /// Formats the error as `path:line:column: error[CODE]: message`.
+    ///
+    /// Use [`STDIN_PATH`](crate::STDIN_PATH) as the path for source read
+    /// from standard input.
+    pub fn located(&self, path: &str) -> String {
+        let span = self.span();
+        format!(
+            "{}:{}:{}: error[{}]: {}",
+            path,
+            span.line,
+            span.column,
+            self.code(),
+            self
+        )
+    }
 }
 
 /// Errors that can occur during semantic validation.
@@ -200,6 +291,34 @@ pub enum ValidationError {
         name: String,
     },
 
+    /// Two declarations in the same file share a qualified name.
+    #[error(
+        "duplicate declaration '{name}' at line {}, column {} (first defined at line {}, column {})",
+        span.line, span.column, first.line, first.column
+    )]
+    DuplicateDeclaration {
+        /// The duplicated qualified name
+        name: String,
+        /// Location of the repeated declaration
+        span: Span,
+        /// Location of the first declaration with this name
+        first: Span,
+    },
+
+    /// A gene declares the same property more than once.
+    #[error(
+        "duplicate property '{name}' at line {}, column {} (first declared at line {}, column {})",
+        span.line, span.column, first.line, first.column
+    )]
+    DuplicateProperty {
+        /// The repeated property name
+        name: String,
+        /// Location of the repeated property
+        span: Span,
+        /// Location of the first property with this name
+        first: Span,
+    },
+
     /// An evolution references a non-existent parent version.
     #[error("evolution references non-existent parent version '{parent}' for '{name}'")]
     InvalidEvolutionLineage {
@@ -209,6 +328,19 @@ pub enum ValidationError {
         parent: String,
     },
 
+    /// An evolution removes or deprecates something absent at its parent version.
+    #[error("evolution of '{name}' cannot {action} '{item}': not present at version {version}")]
+    MissingEvolutionTarget {
+        /// The evolved declaration name
+        name: String,
+        /// The change being made ("remove" or "deprecate")
+        action: String,
+        /// The member that does not exist
+        item: String,
+        /// The parent version that was checked
+        version: String,
+    },
+
     /// A type error occurred during type checking.
     #[error("type error at line {}, column {}: {message}", span.line, span.column)]
     TypeError {
@@ -223,6 +355,56 @@ pub enum ValidationError {
     },
 }
 
+impl ValidationError {
+    /// Returns the stable error code for this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::InvalidIdentifier { .. } => "E0201",
+            ValidationError::UnresolvedReference { .. } => "E0202",
+            ValidationError::InvalidVersion { .. } => "E0203",
+            ValidationError::DuplicateDefinition { .. } => "E0204",
+            ValidationError::InvalidEvolutionLineage { .. } => "E0205",
+            ValidationError::MissingEvolutionTarget { .. } => "E0206",
+            ValidationError::TypeError { .. } => "E0207",
+            ValidationError::DuplicateDeclaration { .. } => "E0208",
+            ValidationError::DuplicateProperty { .. } => "E0209",
+        }
+    }
+
+    /// Returns the source span of this error, if it carries one.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ValidationError::UnresolvedReference { span, .. }
+            | ValidationError::DuplicateDeclaration { span, .. }
+            | ValidationError::DuplicateProperty { span, .. }
+            | ValidationError::TypeError { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
+}
+
+/// A dependency cycle found while ordering declarations.
+///
+/// Produced by [`Repository::topological_order`](crate::repository::Repository::topological_order).
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("dependency cycle: {}", chain.join(" -> "))]
+pub struct CycleError {
+    /// Declaration names along the cycle, starting and ending with the same name
+    pub chain: Vec<String>,
+}
+
+/// A code generation target name that no backend is registered under.
+///
+/// Produced by [`CodegenRegistry::generate`](crate::codegen::CodegenRegistry::generate).
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("unknown codegen target '{target}' (available: {})", available.join(", "))]
+pub struct UnknownTargetError {
+    /// The requested target name
+    pub target: String,
+    /// Registered target names, sorted
+    pub available: Vec<String>,
+}
+
 /// A collection of validation errors and warnings.
 ///
 /// This struct aggregates multiple validation issues that may be found
@@ -273,6 +455,18 @@ impl ValidationErrors {
 /// from being valid but should be reviewed.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ValidationWarning {
+    /// The declaration has no exegesis block.
+    MissingExegesis {
+        /// Location of the declaration
+        span: Span,
+    },
+
+    /// The exegesis block is present but has no content.
+    EmptyExegesis {
+        /// Location of the exegesis
+        span: Span,
+    },
+
     /// The exegesis is unusually short.
     ShortExegesis {
         /// Number of characters in the exegesis
@@ -296,11 +490,79 @@ pub enum ValidationWarning {
         /// Suggested alternative
         alternative: String,
     },
+
+    /// A declaration marked `#[deprecated]` is still referenced.
+    DeprecatedReference {
+        /// The deprecated declaration being referenced
+        reference: String,
+        /// The deprecation note (may be empty)
+        note: String,
+        /// Location of the reference
+        span: Span,
+    },
+
+    /// A declaration is not reachable from any root of the repository.
+    OrphanDeclaration {
+        /// The unreferenced declaration
+        name: String,
+        /// Location of the declaration
+        span: Span,
+    },
+
+    /// A trait law refers to a name that none of its parameters bind.
+    UnboundLawParameter {
+        /// The law whose body contains the reference
+        law: String,
+        /// The unbound name
+        name: String,
+        /// Location of the law
+        span: Span,
+    },
+
+    /// Two properties of a gene differ only in letter case.
+    SimilarProperty {
+        /// The later property name
+        name: String,
+        /// The earlier property it resembles
+        similar: String,
+        /// Location of the later property
+        span: Span,
+    },
+}
+
+impl ValidationWarning {
+    /// Returns the source span of this warning, if it carries one.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ValidationWarning::MissingExegesis { span }
+            | ValidationWarning::EmptyExegesis { span }
+            | ValidationWarning::ShortExegesis { span, .. }
+            | ValidationWarning::DeprecatedReference { span, .. }
+            | ValidationWarning::OrphanDeclaration { span, .. }
+            | ValidationWarning::UnboundLawParameter { span, .. }
+            | ValidationWarning::SimilarProperty { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for ValidationWarning {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            ValidationWarning::MissingExegesis { span } => {
+                write!(
+                    f,
+                    "missing exegesis at line {}, column {}",
+                    span.line, span.column
+                )
+            }
+            ValidationWarning::EmptyExegesis { span } => {
+                write!(
+                    f,
+                    "exegesis block is empty at line {}, column {}",
+                    span.line, span.column
+                )
+            }
             ValidationWarning::ShortExegesis { length, span } => {
                 write!(
                     f,
@@ -325,6 +587,42 @@ impl std::fmt::Display for ValidationWarning {
                     feature, alternative
                 )
             }
+            ValidationWarning::DeprecatedReference {
+                reference,
+                note,
+                span,
+            } => {
+                write!(f, "'{}' is deprecated", reference)?;
+                if !note.is_empty() {
+                    write!(f, ": {}", note)?;
+                }
+                write!(f, " at line {}, column {}", span.line, span.column)
+            }
+            ValidationWarning::OrphanDeclaration { name, span } => {
+                write!(
+                    f,
+                    "'{}' is never referenced at line {}, column {}",
+                    name, span.line, span.column
+                )
+            }
+            ValidationWarning::UnboundLawParameter { law, name, span } => {
+                write!(
+                    f,
+                    "law '{}' refers to unbound parameter '{}' at line {}, column {}",
+                    law, name, span.line, span.column
+                )
+            }
+            ValidationWarning::SimilarProperty {
+                name,
+                similar,
+                span,
+            } => {
+                write!(
+                    f,
+                    "property '{}' differs from '{}' only in case at line {}, column {}",
+                    name, similar, span.line, span.column
+                )
+            }
         }
     }
 }
@@ -357,6 +655,120 @@ mod tests {
         assert!(msg.contains("'gene'"));
     }
 
+    #[test]
+    fn test_error_codes_are_stable_and_unique() {
+        let span = Span::default();
+        let unexpected = ParseError::UnexpectedToken {
+            expected: "identifier".to_string(),
+            found: "'gene'".to_string(),
+            span,
+        };
+        assert_eq!(unexpected.code(), "E0101");
+        assert!(unexpected
+            .located("a.dol")
+            .contains("error[E0101]: expected identifier"));
+
+        let codes = [
+            LexError::UnexpectedChar { ch: '$', span }.code(),
+            LexError::UnterminatedString { span }.code(),
+            LexError::InvalidVersion {
+                text: String::new(),
+                span,
+            }
+            .code(),
+            LexError::InvalidEscape { ch: 'q', span }.code(),
+            LexError::UnterminatedComment { span }.code(),
+            unexpected.code(),
+            ParseError::MissingExegesis { span }.code(),
+            ParseError::InvalidStatement {
+                message: String::new(),
+                span,
+            }
+            .code(),
+            ParseError::InvalidDeclaration {
+                found: String::new(),
+                span,
+            }
+            .code(),
+            ParseError::UnexpectedEof {
+                context: String::new(),
+                span,
+            }
+            .code(),
+            ParseError::Io {
+                message: String::new(),
+            }
+            .code(),
+            ParseError::NestingTooDeep { limit: 1, span }.code(),
+            ParseError::UnterminatedExegesis { span }.code(),
+            ValidationError::InvalidIdentifier {
+                name: String::new(),
+                reason: String::new(),
+            }
+            .code(),
+            ValidationError::UnresolvedReference {
+                reference: String::new(),
+                span,
+            }
+            .code(),
+            ValidationError::InvalidVersion {
+                version: String::new(),
+                reason: String::new(),
+            }
+            .code(),
+            ValidationError::DuplicateDefinition {
+                kind: String::new(),
+                name: String::new(),
+            }
+            .code(),
+            ValidationError::InvalidEvolutionLineage {
+                name: String::new(),
+                parent: String::new(),
+            }
+            .code(),
+            ValidationError::DuplicateDeclaration {
+                name: String::new(),
+                span,
+                first: span,
+            }
+            .code(),
+            ValidationError::DuplicateProperty {
+                name: String::new(),
+                span,
+                first: span,
+            }
+            .code(),
+            ValidationError::MissingEvolutionTarget {
+                name: String::new(),
+                action: String::new(),
+                item: String::new(),
+                version: String::new(),
+            }
+            .code(),
+            ValidationError::TypeError {
+                message: String::new(),
+                expected: None,
+                actual: None,
+                span,
+            }
+            .code(),
+            crate::typechecker::TypeError::new("").code(),
+            crate::typechecker::TypeError::mismatch(
+                crate::typechecker::Type::Int64,
+                crate::typechecker::Type::Bool,
+            )
+            .code(),
+        ];
+        let unique: std::collections::HashSet<_> = codes.iter().collect();
+        assert_eq!(unique.len(), codes.len());
+        assert!(codes
+            .iter()
+            .all(|code| code.len() == 5 && code.starts_with('E')));
+
+        let lexer = ParseError::LexerError(LexError::UnterminatedString { span });
+        assert_eq!(lexer.code(), "E0002");
+    }
+
     #[test]
     fn test_validation_errors_collection() {
         let mut errors = ValidationErrors::new();
@@ -18,10 +18,92 @@ pub fn call_builtin(name: &str, args: &[Value]) -> Result<Value, EvalError> {
         "pop" => builtin_pop(args),
         "keys" => builtin_keys(args),
         "values" => builtin_values(args),
+        "to_string" => builtin_to_string(args),
+        "ok" => builtin_ok(args),
+        "err" => builtin_err(args),
+        "from_hex" => builtin_from_hex(args),
+        "to_hex" => builtin_to_hex(args),
+        "from_base64" => builtin_from_base64(args),
+        "to_base64" => builtin_to_base64(args),
         _ => Err(EvalError::new(format!("unknown builtin: {}", name))),
     }
 }
 
+/// Calls a method on a string receiver, e.g. `"abc".len()`.
+///
+/// Supported methods: `len`, `is_empty`, `to_uppercase`, `to_lowercase`,
+/// `trim`, `contains`, `starts_with`, `ends_with`, `split` and `replace`.
+pub fn call_string_method(
+    receiver: &str,
+    method: &str,
+    args: &[Value],
+) -> Result<Value, EvalError> {
+    let expect_arity = |n: usize| {
+        if args.len() == n {
+            Ok(())
+        } else {
+            Err(EvalError::arity_mismatch(n, args.len()))
+        }
+    };
+    let string_arg = |i: usize| match &args[i] {
+        Value::String(s) => Ok(s.as_str()),
+        other => Err(EvalError::type_error("String", other.type_name())),
+    };
+
+    match method {
+        "len" => {
+            expect_arity(0)?;
+            Ok(Value::Int(receiver.chars().count() as i64))
+        }
+        "is_empty" => {
+            expect_arity(0)?;
+            Ok(Value::Bool(receiver.is_empty()))
+        }
+        "to_uppercase" => {
+            expect_arity(0)?;
+            Ok(Value::String(receiver.to_uppercase()))
+        }
+        "to_lowercase" => {
+            expect_arity(0)?;
+            Ok(Value::String(receiver.to_lowercase()))
+        }
+        "trim" => {
+            expect_arity(0)?;
+            Ok(Value::String(receiver.trim().to_string()))
+        }
+        "contains" => {
+            expect_arity(1)?;
+            Ok(Value::Bool(receiver.contains(string_arg(0)?)))
+        }
+        "starts_with" => {
+            expect_arity(1)?;
+            Ok(Value::Bool(receiver.starts_with(string_arg(0)?)))
+        }
+        "ends_with" => {
+            expect_arity(1)?;
+            Ok(Value::Bool(receiver.ends_with(string_arg(0)?)))
+        }
+        "split" => {
+            expect_arity(1)?;
+            let parts = receiver
+                .split(string_arg(0)?)
+                .map(|part| Value::String(part.to_string()))
+                .collect();
+            Ok(Value::Array(parts))
+        }
+        "replace" => {
+            expect_arity(2)?;
+            Ok(Value::String(
+                receiver.replace(string_arg(0)?, string_arg(1)?),
+            ))
+        }
+        _ => Err(EvalError::new(format!(
+            "unknown method '{}' on String",
+            method
+        ))),
+    }
+}
+
 /// print(value) - Prints a value to stdout.
 ///
 /// Returns Void.
@@ -59,9 +141,10 @@ fn builtin_len(args: &[Value]) -> Result<Value, EvalError> {
     match &args[0] {
         Value::Array(items) => Ok(Value::Int(items.len() as i64)),
         Value::String(s) => Ok(Value::Int(s.len() as i64)),
-        Value::Record(fields) => Ok(Value::Int(fields.len() as i64)),
+        Value::Bytes(bytes) => Ok(Value::Int(bytes.len() as i64)),
+        Value::Record { fields, .. } => Ok(Value::Int(fields.len() as i64)),
         _ => Err(EvalError::type_error(
-            "Array, String, or Record",
+            "Array, String, Bytes, or Record",
             args[0].type_name(),
         )),
     }
@@ -108,6 +191,24 @@ fn builtin_pop(args: &[Value]) -> Result<Value, EvalError> {
     }
 }
 
+/// ok(value) - Wraps a value in a successful `Result`.
+fn builtin_ok(args: &[Value]) -> Result<Value, EvalError> {
+    if args.len() != 1 {
+        return Err(EvalError::arity_mismatch(1, args.len()));
+    }
+
+    Ok(Value::Result(Ok(Box::new(args[0].clone()))))
+}
+
+/// err(error) - Wraps an error in a failed `Result`.
+fn builtin_err(args: &[Value]) -> Result<Value, EvalError> {
+    if args.len() != 1 {
+        return Err(EvalError::arity_mismatch(1, args.len()));
+    }
+
+    Ok(Value::Result(Err(Box::new(args[0].clone()))))
+}
+
 /// keys(record) - Returns an array of record keys.
 fn builtin_keys(args: &[Value]) -> Result<Value, EvalError> {
     if args.len() != 1 {
@@ -115,7 +216,7 @@ fn builtin_keys(args: &[Value]) -> Result<Value, EvalError> {
     }
 
     match &args[0] {
-        Value::Record(fields) => {
+        Value::Record { fields, .. } => {
             let keys: Vec<Value> = fields.keys().map(|k| Value::String(k.clone())).collect();
             Ok(Value::Array(keys))
         }
@@ -130,7 +231,7 @@ fn builtin_values(args: &[Value]) -> Result<Value, EvalError> {
     }
 
     match &args[0] {
-        Value::Record(fields) => {
+        Value::Record { fields, .. } => {
             let values: Vec<Value> = fields.values().cloned().collect();
             Ok(Value::Array(values))
         }
@@ -138,6 +239,130 @@ fn builtin_values(args: &[Value]) -> Result<Value, EvalError> {
     }
 }
 
+/// to_string(value) - Converts a value to its display string.
+///
+/// Strings are returned unquoted, so `to_string("a")` is `"a"`.
+fn builtin_to_string(args: &[Value]) -> Result<Value, EvalError> {
+    if args.len() != 1 {
+        return Err(EvalError::arity_mismatch(1, args.len()));
+    }
+
+    match &args[0] {
+        Value::String(s) => Ok(Value::String(s.clone())),
+        other => Ok(Value::String(other.to_string())),
+    }
+}
+
+/// Standard base64 alphabet (RFC 4648).
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Returns the single string argument of a one-argument builtin.
+fn string_arg(args: &[Value]) -> Result<&str, EvalError> {
+    if args.len() != 1 {
+        return Err(EvalError::arity_mismatch(1, args.len()));
+    }
+    match &args[0] {
+        Value::String(s) => Ok(s),
+        other => Err(EvalError::type_error("String", other.type_name())),
+    }
+}
+
+/// Returns the single bytes argument of a one-argument builtin.
+fn bytes_arg(args: &[Value]) -> Result<&[u8], EvalError> {
+    if args.len() != 1 {
+        return Err(EvalError::arity_mismatch(1, args.len()));
+    }
+    match &args[0] {
+        Value::Bytes(bytes) => Ok(bytes),
+        other => Err(EvalError::type_error("Bytes", other.type_name())),
+    }
+}
+
+/// from_hex(string) - Decodes a hexadecimal string into bytes.
+///
+/// Accepts upper- and lowercase digits; errors on odd length or a
+/// non-hex character.
+fn builtin_from_hex(args: &[Value]) -> Result<Value, EvalError> {
+    let text = string_arg(args)?;
+    if text.len() % 2 != 0 {
+        return Err(EvalError::new(format!(
+            "invalid hex '{}': odd number of digits",
+            text
+        )));
+    }
+    let digit = |c: u8| {
+        (c as char)
+            .to_digit(16)
+            .ok_or_else(|| EvalError::new(format!("invalid hex digit '{}'", c as char)))
+    };
+    let bytes = text
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| Ok((digit(pair[0])? * 16 + digit(pair[1])?) as u8))
+        .collect::<Result<Vec<u8>, EvalError>>()?;
+    Ok(Value::Bytes(bytes))
+}
+
+/// to_hex(bytes) - Encodes bytes as a lowercase hexadecimal string.
+fn builtin_to_hex(args: &[Value]) -> Result<Value, EvalError> {
+    let bytes = bytes_arg(args)?;
+    Ok(Value::String(
+        bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+    ))
+}
+
+/// from_base64(string) - Decodes a padded standard base64 string into bytes.
+fn builtin_from_base64(args: &[Value]) -> Result<Value, EvalError> {
+    let text = string_arg(args)?;
+    let invalid = |reason: &str| EvalError::new(format!("invalid base64 '{}': {}", text, reason));
+    if text.len() % 4 != 0 {
+        return Err(invalid("length is not a multiple of 4"));
+    }
+
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    let chunks: Vec<&[u8]> = text.as_bytes().chunks(4).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && i + 1 != chunks.len()) {
+            return Err(invalid("misplaced padding"));
+        }
+        let mut group = 0u32;
+        for &c in &chunk[..4 - padding] {
+            let sextet = BASE64_ALPHABET
+                .iter()
+                .position(|&a| a == c)
+                .ok_or_else(|| invalid(&format!("unexpected character '{}'", c as char)))?;
+            group = group << 6 | sextet as u32;
+        }
+        group <<= 6 * padding as u32;
+        let decoded = [(group >> 16) as u8, (group >> 8) as u8, group as u8];
+        bytes.extend_from_slice(&decoded[..3 - padding]);
+    }
+    Ok(Value::Bytes(bytes))
+}
+
+/// to_base64(bytes) - Encodes bytes as padded standard base64.
+fn builtin_to_base64(args: &[Value]) -> Result<Value, EvalError> {
+    let bytes = bytes_arg(args)?;
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let sextet = (group >> (18 - 6 * i)) & 0x3f;
+                text.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    Ok(Value::String(text))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,7 +420,10 @@ mod tests {
         let mut fields = HashMap::new();
         fields.insert("a".to_string(), Value::Int(1));
         fields.insert("b".to_string(), Value::Int(2));
-        let record = Value::Record(fields);
+        let record = Value::Record {
+            type_name: "Pair".to_string(),
+            fields,
+        };
 
         let keys = builtin_keys(std::slice::from_ref(&record)).unwrap();
         if let Value::Array(items) = keys {
@@ -212,6 +440,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_string_methods() {
+        assert_eq!(
+            call_string_method("abc", "len", &[]).unwrap(),
+            Value::Int(3)
+        );
+        assert_eq!(
+            call_string_method("abc", "to_uppercase", &[]).unwrap(),
+            Value::String("ABC".to_string())
+        );
+        assert_eq!(
+            call_string_method("abc", "contains", &[Value::String("bc".to_string())]).unwrap(),
+            Value::Bool(true)
+        );
+        assert!(call_string_method("abc", "contains", &[Value::Int(1)]).is_err());
+        assert!(call_string_method("abc", "reverse", &[]).is_err());
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = builtin_from_hex(&[Value::String("00ff10Ab".to_string())]).unwrap();
+        assert_eq!(bytes, Value::Bytes(vec![0x00, 0xff, 0x10, 0xab]));
+        assert_eq!(
+            builtin_to_hex(&[bytes]).unwrap(),
+            Value::String("00ff10ab".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_hex_rejects_malformed_input() {
+        assert!(builtin_from_hex(&[Value::String("abc".to_string())]).is_err());
+        assert!(builtin_from_hex(&[Value::String("zz".to_string())]).is_err());
+        assert!(builtin_from_hex(&[Value::Int(1)]).is_err());
+    }
+
+    #[test]
+    fn test_base64_round_trip() {
+        for (raw, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            let bytes = Value::Bytes(raw.as_bytes().to_vec());
+            assert_eq!(
+                builtin_to_base64(std::slice::from_ref(&bytes)).unwrap(),
+                Value::String(encoded.to_string())
+            );
+            assert_eq!(
+                builtin_from_base64(&[Value::String(encoded.to_string())]).unwrap(),
+                bytes
+            );
+        }
+        assert!(builtin_from_base64(&[Value::String("Zg=".to_string())]).is_err());
+        assert!(builtin_from_base64(&[Value::String("Z=g=".to_string())]).is_err());
+        assert!(builtin_from_base64(&[Value::String("Zm9*".to_string())]).is_err());
+    }
+
     #[test]
     fn test_arity_errors() {
         assert!(builtin_typeof(&[]).is_err());
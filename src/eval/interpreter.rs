@@ -3,9 +3,15 @@
 //! This module implements the core evaluation logic for DOL expressions,
 //! handling arithmetic, control flow, functions, and metaprogramming features.
 
-use crate::ast::{BinaryOp, Expr, Literal, Pattern, Stmt, TypeExpr, UnaryOp};
+use crate::ast::{
+    BinaryOp, Declaration, Expr, FunctionDecl, Literal, Pattern, Stmt, TypeExpr, UnaryOp,
+};
 use crate::eval::builtins;
 use crate::eval::value::{Environment, EvalError, Value};
+use crate::macros::builtin::{stringify_expr, CfgMacro};
+use crate::macros::{Macro, MacroContext, MacroInput};
+use crate::transform::Fold;
+use std::cmp::Ordering;
 
 /// The expression interpreter.
 ///
@@ -15,8 +21,17 @@ use crate::eval::value::{Environment, EvalError, Value};
 pub struct Interpreter {
     /// Current evaluation environment
     env: Environment,
+    /// Configuration used to resolve `#cfg(...)` guards
+    macro_ctx: MacroContext,
 }
 
+/// A saved copy of an interpreter's global environment.
+///
+/// Created by [`Interpreter::snapshot`] and handed back to
+/// [`Interpreter::restore`] to undo the definitions made since.
+#[derive(Debug, Clone)]
+pub struct EnvSnapshot(Environment);
+
 impl Default for Interpreter {
     fn default() -> Self {
         Self::new()
@@ -36,26 +51,161 @@ impl Interpreter {
         env.bind("pop", Value::Builtin("pop".to_string()));
         env.bind("keys", Value::Builtin("keys".to_string()));
         env.bind("values", Value::Builtin("values".to_string()));
+        env.bind("to_string", Value::Builtin("to_string".to_string()));
+        env.bind("ok", Value::Builtin("ok".to_string()));
+        env.bind("err", Value::Builtin("err".to_string()));
+        env.bind("from_hex", Value::Builtin("from_hex".to_string()));
+        env.bind("to_hex", Value::Builtin("to_hex".to_string()));
+        env.bind("from_base64", Value::Builtin("from_base64".to_string()));
+        env.bind("to_base64", Value::Builtin("to_base64".to_string()));
+
+        Self {
+            env,
+            macro_ctx: MacroContext::new(),
+        }
+    }
 
-        Self { env }
+    /// Sets the macro context whose flags and features decide `#cfg(...)`
+    /// guards left in expressions after expansion.
+    pub fn with_macro_context(mut self, ctx: MacroContext) -> Self {
+        self.macro_ctx = ctx;
+        self
     }
 
     /// Evaluates an expression in the current environment.
+    ///
+    /// A `return`, or an `Err` propagated by `?`, outside any function call
+    /// becomes the value of the whole expression.
     pub fn eval(&mut self, expr: &Expr) -> Result<Value, EvalError> {
-        self.eval_in_env(expr, &mut self.env.clone())
+        Self::catch_propagated(self.eval_in_env(expr, &mut self.env.clone()))
+    }
+
+    /// Binds every function declared in `declarations` by name.
+    ///
+    /// Other declaration kinds are ignored. Names a function does not
+    /// capture are looked up among the globals when it runs, so functions
+    /// may call each other in any order, including mutually recursively.
+    pub fn load(&mut self, declarations: &[Declaration]) {
+        for decl in declarations {
+            if let Declaration::Function(func) = decl {
+                self.define_function(func);
+            }
+        }
+    }
+
+    /// Binds a declared function by name in the current environment.
+    pub fn define_function(&mut self, func: &FunctionDecl) {
+        let mut statements = func.body.clone();
+        let final_expr = match statements.pop() {
+            Some(Stmt::Expr(expr)) | Some(Stmt::Return(Some(expr))) => Some(Box::new(expr)),
+            Some(other) => {
+                statements.push(other);
+                None
+            }
+            None => None,
+        };
+        let value = Value::Function {
+            name: Some(func.name.clone()),
+            params: func.params.iter().map(|p| p.name.clone()).collect(),
+            body: Box::new(Expr::Block {
+                statements,
+                final_expr,
+            }),
+            env: self.env.clone(),
+        };
+        self.env.bind(func.name.clone(), value);
+    }
+
+    /// Captures the global environment so it can be restored later.
+    pub fn snapshot(&self) -> EnvSnapshot {
+        EnvSnapshot(self.env.clone())
+    }
+
+    /// Replaces the global environment with a previously captured one,
+    /// discarding every binding made after the snapshot was taken.
+    pub fn restore(&mut self, snapshot: EnvSnapshot) {
+        self.env = snapshot.0;
+    }
+
+    /// Calls a function bound in the current environment with host values.
+    ///
+    /// This is the embedding entry point for invoking DOL functions from
+    /// Rust. Fails if `name` is unbound, is not callable, or takes a
+    /// different number of arguments.
+    pub fn call_function(&mut self, name: &str, args: Vec<Value>) -> Result<Value, EvalError> {
+        let func = self
+            .env
+            .lookup(name)
+            .cloned()
+            .ok_or_else(|| EvalError::undefined_variable(name))?;
+        self.apply(func, args)
+    }
+
+    /// Applies a callable value to evaluated arguments.
+    fn apply(&mut self, func: Value, args: Vec<Value>) -> Result<Value, EvalError> {
+        match &func {
+            Value::Function {
+                name,
+                params,
+                body,
+                env: closure_env,
+            } => {
+                if params.len() != args.len() {
+                    return Err(EvalError::arity_mismatch(params.len(), args.len()));
+                }
+
+                let mut call_env = Self::call_env(&func, name, closure_env);
+                for (param, arg) in params.iter().zip(args) {
+                    call_env.bind(param, arg);
+                }
+
+                Self::catch_propagated(self.eval_in_env(body, &mut call_env))
+            }
+            Value::Builtin(name) => builtins::call_builtin(name, &args),
+            _ => Err(EvalError::type_error("function", func.type_name())),
+        }
+    }
+
+    /// Creates the environment a function body runs in: a child of the
+    /// captured environment in which a named function is bound to itself.
+    fn call_env(func: &Value, name: &Option<String>, closure_env: &Environment) -> Environment {
+        let mut call_env = closure_env.child();
+        if let Some(name) = name {
+            call_env.bind(name.clone(), func.clone());
+        }
+        call_env
+    }
+
+    /// Turns a `return` or an `Err` propagated by `?` back into a value.
+    fn catch_propagated(result: Result<Value, EvalError>) -> Result<Value, EvalError> {
+        match result {
+            Err(EvalError {
+                propagated: Some(value),
+                ..
+            }) => Ok(*value),
+            other => other,
+        }
     }
 
     /// Evaluates an expression in a specific environment.
     pub fn eval_in_env(&mut self, expr: &Expr, env: &mut Environment) -> Result<Value, EvalError> {
         match expr {
+            // Errors keep the location of the innermost spanned expression
+            Expr::Spanned { expr, span } => {
+                self.eval_in_env(expr, env).map_err(|err| match err.span {
+                    Some(_) => err,
+                    None => err.with_span(*span),
+                })
+            }
             // Literals - convert to values
             Expr::Literal(lit) => self.eval_literal(lit),
 
-            // Identifiers - lookup in environment
-            Expr::Identifier(name) => env
-                .lookup(name)
-                .cloned()
-                .ok_or_else(|| EvalError::undefined_variable(name)),
+            // Identifiers - lookup in environment, then among the globals as
+            // they stand now, so functions can call ones loaded after them
+            Expr::Identifier(name) => match env.lookup(name).or_else(|| self.env.lookup(name)) {
+                Some(value) => Ok(value.clone()),
+                None => Err(EvalError::undefined_variable(name)),
+            },
 
             // Binary operations
             Expr::Binary { left, op, right } => self.eval_binary(left, op, right, env),
@@ -79,6 +229,7 @@ impl Interpreter {
                 let param_names: Vec<String> =
                     params.iter().map(|(name, _)| name.clone()).collect();
                 Ok(Value::Function {
+                    name: None,
                     params: param_names,
                     body: body.clone(),
                     env: env.clone(),
@@ -120,7 +271,7 @@ impl Interpreter {
                     }
                 }
 
-                Err(EvalError::new("no match arm matched"))
+                Err(EvalError::non_exhaustive_match(&scrutinee_value))
             }
 
             // Block expressions
@@ -173,22 +324,7 @@ impl Interpreter {
                 // Apply function to arguments (simplified: direct application)
                 // A full implementation would use Functor/Applicative type class instances
                 match func_val {
-                    Value::Function {
-                        params,
-                        body,
-                        env: closure_env,
-                    } => {
-                        if params.len() != arg_vals.len() {
-                            return Err(EvalError::arity_mismatch(params.len(), arg_vals.len()));
-                        }
-                        // Create new environment from closure
-                        let mut call_env = closure_env;
-                        for (param, val) in params.iter().zip(arg_vals) {
-                            call_env.bind(param, val);
-                        }
-                        self.eval_in_env(&body, &mut call_env)
-                    }
-                    Value::Builtin(name) => builtins::call_builtin(&name, &arg_vals),
+                    Value::Function { .. } | Value::Builtin(_) => self.apply(func_val, arg_vals),
                     _ => Err(EvalError::new(format!(
                         "cannot apply idiom brackets to non-function: {}",
                         func_val.type_name()
@@ -203,19 +339,43 @@ impl Interpreter {
                 self.eval_in_env(inner, env)
             }
 
-            // Quasi-quote - quote with splicing support
+            // Quasi-quote - quote with unquote holes evaluated and spliced in
             Expr::QuasiQuote(inner) => {
-                // For now, treat quasi-quote like regular quote
-                // TODO: Implement proper quasi-quotation with unquote splicing
-                Ok(Value::Quoted(inner.clone()))
+                let mut splicer = UnquoteSplicer {
+                    interpreter: self,
+                    env,
+                    error: None,
+                };
+                let spliced = splicer.fold_expr((**inner).clone());
+                match splicer.error {
+                    Some(error) => Err(error),
+                    None => Ok(Value::Quoted(Box::new(spliced))),
+                }
             }
 
             // Forall - universal quantification (logic operator)
-            Expr::Forall(forall_expr) => {
+            Expr::Forall(forall_expr) => match &forall_expr.iter {
+                // `forall x in xs { ... }` holds when the body holds for every element
+                Some(iter) => match self.eval_in_env(iter, env)? {
+                    Value::Array(items) => {
+                        for item in items {
+                            let mut item_env = env.child();
+                            item_env.bind(forall_expr.var.clone(), item);
+                            if !self
+                                .eval_in_env(&forall_expr.body, &mut item_env)?
+                                .is_truthy()
+                            {
+                                return Ok(Value::Bool(false));
+                            }
+                        }
+                        Ok(Value::Bool(true))
+                    }
+                    other => Err(EvalError::type_error("Array", other.type_name())),
+                },
                 // Forall expressions are logic predicates, evaluate the body
                 // In a full implementation, this would verify the property for all values of the type
-                self.eval_in_env(&forall_expr.body, env)
-            }
+                None => self.eval_in_env(&forall_expr.body, env),
+            },
 
             // Exists - existential quantification (logic operator)
             Expr::Exists(exists_expr) => {
@@ -264,6 +424,20 @@ impl Interpreter {
                 Ok(Value::Array(values))
             }
 
+            // Map literal - evaluate entries in source order; later keys win
+            Expr::Map(entries) => {
+                let mut map: Vec<(Value, Value)> = Vec::new();
+                for (key, value) in entries {
+                    let key = self.eval_in_env(key, env)?;
+                    let value = self.eval_in_env(value, env)?;
+                    match map.iter_mut().find(|(k, _)| *k == key) {
+                        Some(entry) => entry.1 = value,
+                        None => map.push((key, value)),
+                    }
+                }
+                Ok(Value::Map(map))
+            }
+
             // Tuple literal - evaluate elements (stored as array)
             Expr::Tuple(elements) => {
                 let mut values = Vec::new();
@@ -277,20 +451,24 @@ impl Interpreter {
             Expr::Cast { expr, .. } => self.eval_in_env(expr, env),
 
             // Struct literal - evaluate field expressions and build record
-            Expr::StructLiteral {
-                type_name: _,
-                fields,
-            } => {
+            Expr::StructLiteral { type_name, fields } => {
                 let mut record = std::collections::HashMap::new();
                 for (name, expr) in fields {
                     let value = self.eval_in_env(expr, env)?;
                     record.insert(name.clone(), value);
                 }
-                Ok(Value::Record(record))
+                Ok(Value::Record {
+                    type_name: type_name.clone(),
+                    fields: record,
+                })
             }
 
-            // Try expression - evaluate the inner expression
-            Expr::Try(inner) => self.eval_in_env(inner, env),
+            // Try expression - unwrap `Ok`, propagate `Err` to the enclosing call
+            Expr::Try(inner) => match self.eval_in_env(inner, env)? {
+                Value::Result(Ok(value)) => Ok(*value),
+                error @ Value::Result(Err(_)) => Err(EvalError::propagate(error)),
+                other => Err(EvalError::type_error("Result", other.type_name())),
+            },
         }
     }
 
@@ -327,12 +505,12 @@ impl Interpreter {
             BinaryOp::Pow => self.eval_pow(&left_val, &right_val),
 
             // Comparison
-            BinaryOp::Eq => Ok(Value::Bool(left_val == right_val)),
-            BinaryOp::Ne => Ok(Value::Bool(left_val != right_val)),
-            BinaryOp::Lt => self.eval_lt(&left_val, &right_val),
-            BinaryOp::Le => self.eval_le(&left_val, &right_val),
-            BinaryOp::Gt => self.eval_gt(&left_val, &right_val),
-            BinaryOp::Ge => self.eval_ge(&left_val, &right_val),
+            BinaryOp::Eq => Ok(Value::Bool(left_val.equals(&right_val)?)),
+            BinaryOp::Ne => Ok(Value::Bool(!left_val.equals(&right_val)?)),
+            BinaryOp::Lt => self.eval_compare(&left_val, &right_val, Ordering::is_lt),
+            BinaryOp::Le => self.eval_compare(&left_val, &right_val, Ordering::is_le),
+            BinaryOp::Gt => self.eval_compare(&left_val, &right_val, Ordering::is_gt),
+            BinaryOp::Ge => self.eval_compare(&left_val, &right_val, Ordering::is_ge),
 
             // Logical
             BinaryOp::And => Ok(Value::Bool(left_val.is_truthy() && right_val.is_truthy())),
@@ -344,12 +522,6 @@ impl Interpreter {
             BinaryOp::Apply => self.eval_apply(&left_val, &right_val, env),
             BinaryOp::Bind => Ok(right_val),
 
-            // Member access
-            BinaryOp::Member => {
-                // This shouldn't happen as Member has its own Expr variant
-                Err(EvalError::new("member access should use Expr::Member"))
-            }
-
             // Functor map <$>
             BinaryOp::Map => {
                 // Map applies a function to a value inside a functor
@@ -427,30 +599,38 @@ impl Interpreter {
         args: &[Expr],
         env: &mut Environment,
     ) -> Result<Value, EvalError> {
-        let func = self.eval_in_env(callee, env)?;
+        // Residual `#cfg(...)` guards resolve against the macro context;
+        // the predicate is never evaluated as an expression
+        if matches!(callee.unspanned(), Expr::Identifier(name) if name == "#cfg") {
+            return self.eval_cfg(args);
+        }
+        if matches!(callee.unspanned(), Expr::Identifier(name) if name == "#assert") {
+            return self.eval_assert(args, env);
+        }
+
+        let func = match callee.unspanned() {
+            // Method call: string receivers dispatch to built-in string methods
+            Expr::Member { object, field } => {
+                let receiver = self.eval_in_env(object, env)?;
+                if let Value::String(s) = &receiver {
+                    let arg_values: Result<Vec<_>, _> =
+                        args.iter().map(|a| self.eval_in_env(a, env)).collect();
+                    return builtins::call_string_method(s, field, &arg_values?);
+                }
+                Self::member_of(receiver, field)?
+            }
+            _ => self.eval_in_env(callee, env)?,
+        };
         let arg_values: Result<Vec<_>, _> = args.iter().map(|a| self.eval_in_env(a, env)).collect();
         let arg_values = arg_values?;
 
         match func {
-            Value::Function {
-                params,
-                body,
-                env: closure_env,
-            } => {
-                if params.len() != arg_values.len() {
-                    return Err(EvalError::arity_mismatch(params.len(), arg_values.len()));
-                }
-
-                // Create new environment from closure
-                let mut call_env = closure_env.child();
-                for (param, arg) in params.iter().zip(arg_values.iter()) {
-                    call_env.bind(param.clone(), arg.clone());
-                }
-
-                self.eval_in_env(&body, &mut call_env)
-            }
-            Value::Builtin(name) => builtins::call_builtin(&name, &arg_values),
-            _ => Err(EvalError::type_error("function", func.type_name())),
+            // Indexing `map[key]` parses as a call with a single argument
+            Value::Map(_) if arg_values.len() == 1 => func
+                .map_get(&arg_values[0])
+                .cloned()
+                .ok_or_else(|| EvalError::missing_key(&arg_values[0])),
+            _ => self.apply(func, arg_values),
         }
     }
 
@@ -462,9 +642,13 @@ impl Interpreter {
         env: &mut Environment,
     ) -> Result<Value, EvalError> {
         let obj_value = self.eval_in_env(object, env)?;
+        Self::member_of(obj_value, field)
+    }
 
+    /// Looks up a field on an evaluated value.
+    fn member_of(obj_value: Value, field: &str) -> Result<Value, EvalError> {
         match obj_value {
-            Value::Record(fields) => fields
+            Value::Record { fields, .. } => fields
                 .get(field)
                 .cloned()
                 .ok_or_else(|| EvalError::new(format!("field '{}' not found", field))),
@@ -527,6 +711,14 @@ impl Interpreter {
                     .map(|v| (v.name.clone(), "variant".to_string()))
                     .collect(),
             ),
+            TypeExpr::Record(fields) => (
+                "Record".to_string(),
+                "record".to_string(),
+                fields
+                    .iter()
+                    .map(|(name, _)| (name.clone(), "field".to_string()))
+                    .collect(),
+            ),
         };
 
         Ok(Value::TypeInfo { name, kind, fields })
@@ -564,10 +756,45 @@ impl Interpreter {
                     Ok(false)
                 }
             }
-            Pattern::Constructor { .. } => {
-                // Constructor patterns not fully implemented yet
-                Ok(false)
-            }
+            Pattern::Constructor { name, fields } => match (name.as_str(), value) {
+                ("Ok", Value::Result(Ok(inner))) | ("Err", Value::Result(Err(inner)))
+                    if fields.len() == 1 =>
+                {
+                    self.match_pattern(&fields[0], inner, env)
+                }
+                // Struct patterns destructure records of the named type field by field
+                (
+                    _,
+                    Value::Record {
+                        type_name,
+                        fields: record,
+                    },
+                ) if !fields.is_empty() => {
+                    if !same_type_name(name, type_name) {
+                        return Ok(false);
+                    }
+                    for field in fields {
+                        let (field_name, field_pattern) = match field {
+                            Pattern::Identifier(field_name) => (field_name, field),
+                            Pattern::Constructor { name, fields } if fields.len() == 1 => {
+                                (name, &fields[0])
+                            }
+                            _ => return Ok(false),
+                        };
+                        match record.get(field_name) {
+                            Some(field_value) => {
+                                if !self.match_pattern(field_pattern, field_value, env)? {
+                                    return Ok(false);
+                                }
+                            }
+                            None => return Ok(false),
+                        }
+                    }
+                    Ok(true)
+                }
+                // Other constructor patterns not fully implemented yet
+                _ => Ok(false),
+            },
             Pattern::Or(patterns) => {
                 // Try each pattern alternative
                 for pat in patterns {
@@ -592,9 +819,20 @@ impl Interpreter {
                 env.bind(name.clone(), val);
                 Ok(())
             }
+            Stmt::LetPattern { pattern, value } => {
+                let val = self.eval_in_env(value, env)?;
+                if self.match_pattern(pattern, &val, env)? {
+                    Ok(())
+                } else {
+                    Err(EvalError::new(format!(
+                        "pattern does not match value {}",
+                        val
+                    )))
+                }
+            }
             Stmt::Assign { target, value } => {
                 let val = self.eval_in_env(value, env)?;
-                if let Expr::Identifier(name) = target {
+                if let Expr::Identifier(name) = target.unspanned() {
                     env.update(name, val)?;
                     Ok(())
                 } else {
@@ -605,7 +843,13 @@ impl Interpreter {
                 self.eval_in_env(expr, env)?;
                 Ok(())
             }
-            Stmt::Return(_) => Err(EvalError::new("return outside function not yet supported")),
+            Stmt::Return(value) => {
+                let value = match value {
+                    Some(expr) => self.eval_in_env(expr, env)?,
+                    None => Value::Void,
+                };
+                Err(EvalError::early_return(value))
+            }
             Stmt::For { .. } => Err(EvalError::new("for loops not yet implemented")),
             Stmt::While { .. } => Err(EvalError::new("while loops not yet implemented")),
             Stmt::Loop { .. } => Err(EvalError::new("loops not yet implemented")),
@@ -734,60 +978,13 @@ impl Interpreter {
     }
 
     // Comparison helpers
-    fn eval_lt(&self, left: &Value, right: &Value) -> Result<Value, EvalError> {
-        match (left, right) {
-            (Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a < b)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Bool(a < b)),
-            (Value::Int(a), Value::Float(b)) => Ok(Value::Bool((*a as f64) < *b)),
-            (Value::Float(a), Value::Int(b)) => Ok(Value::Bool(*a < (*b as f64))),
-            _ => Err(EvalError::invalid_operation(
-                "<",
-                left.type_name(),
-                right.type_name(),
-            )),
-        }
-    }
-
-    fn eval_le(&self, left: &Value, right: &Value) -> Result<Value, EvalError> {
-        match (left, right) {
-            (Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a <= b)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Bool(a <= b)),
-            (Value::Int(a), Value::Float(b)) => Ok(Value::Bool((*a as f64) <= *b)),
-            (Value::Float(a), Value::Int(b)) => Ok(Value::Bool(*a <= (*b as f64))),
-            _ => Err(EvalError::invalid_operation(
-                "<=",
-                left.type_name(),
-                right.type_name(),
-            )),
-        }
-    }
-
-    fn eval_gt(&self, left: &Value, right: &Value) -> Result<Value, EvalError> {
-        match (left, right) {
-            (Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a > b)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Bool(a > b)),
-            (Value::Int(a), Value::Float(b)) => Ok(Value::Bool((*a as f64) > *b)),
-            (Value::Float(a), Value::Int(b)) => Ok(Value::Bool(*a > (*b as f64))),
-            _ => Err(EvalError::invalid_operation(
-                ">",
-                left.type_name(),
-                right.type_name(),
-            )),
-        }
-    }
-
-    fn eval_ge(&self, left: &Value, right: &Value) -> Result<Value, EvalError> {
-        match (left, right) {
-            (Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a >= b)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Bool(a >= b)),
-            (Value::Int(a), Value::Float(b)) => Ok(Value::Bool((*a as f64) >= *b)),
-            (Value::Float(a), Value::Int(b)) => Ok(Value::Bool(*a >= (*b as f64))),
-            _ => Err(EvalError::invalid_operation(
-                ">=",
-                left.type_name(),
-                right.type_name(),
-            )),
-        }
+    fn eval_compare(
+        &self,
+        left: &Value,
+        right: &Value,
+        test: fn(Ordering) -> bool,
+    ) -> Result<Value, EvalError> {
+        Ok(Value::Bool(left.compare(right)?.is_some_and(test)))
     }
 
     // Functional operators
@@ -799,6 +996,7 @@ impl Interpreter {
     ) -> Result<Value, EvalError> {
         match func {
             Value::Function {
+                name,
                 params,
                 body,
                 env: closure_env,
@@ -809,10 +1007,10 @@ impl Interpreter {
                     ));
                 }
 
-                let mut call_env = closure_env.child();
+                let mut call_env = Self::call_env(func, name, closure_env);
                 call_env.bind(params[0].clone(), value.clone());
 
-                self.eval_in_env(body, &mut call_env)
+                Self::catch_propagated(self.eval_in_env(body, &mut call_env))
             }
             Value::Builtin(name) => builtins::call_builtin(name, std::slice::from_ref(value)),
             _ => Err(EvalError::type_error("function", func.type_name())),
@@ -824,6 +1022,51 @@ impl Interpreter {
         Err(EvalError::new("function composition not yet implemented"))
     }
 
+    fn eval_cfg(&self, args: &[Expr]) -> Result<Value, EvalError> {
+        let input = match args {
+            [arg] => match arg.unspanned() {
+                Expr::Identifier(name) => MacroInput::Ident(name.clone()),
+                _ => MacroInput::ExprList(args.to_vec()),
+            },
+            _ => MacroInput::ExprList(args.to_vec()),
+        };
+        let output = CfgMacro
+            .expand(input, &self.macro_ctx)
+            .map_err(|e| EvalError::new(e.to_string()))?;
+        match output.as_expr() {
+            Some(Expr::Literal(Literal::Bool(b))) => Ok(Value::Bool(*b)),
+            _ => Err(EvalError::new("#cfg did not resolve to a boolean")),
+        }
+    }
+
+    /// Evaluates `#assert(cond)` or `#assert(cond, message)`, reporting a
+    /// failure at the condition rather than the enclosing statement.
+    fn eval_assert(&mut self, args: &[Expr], env: &mut Environment) -> Result<Value, EvalError> {
+        let (condition, message) = match args {
+            [condition] => (condition, None),
+            [condition, message] => (condition, Some(message)),
+            _ => return Err(EvalError::arity_mismatch(1, args.len())),
+        };
+        if self.eval_in_env(condition, env)?.is_truthy() {
+            return Ok(Value::Void);
+        }
+        let message = match message {
+            Some(message) => match self.eval_in_env(message, env)? {
+                Value::String(s) => s,
+                other => other.to_string(),
+            },
+            None => format!(
+                "assertion failed: {}",
+                stringify_expr(condition.unspanned())
+            ),
+        };
+        let error = EvalError::new(message);
+        Err(match condition.span() {
+            Some(span) => error.with_span(span),
+            None => error,
+        })
+    }
+
     fn eval_apply(
         &mut self,
         value: &Value,
@@ -835,9 +1078,56 @@ impl Interpreter {
     }
 }
 
+/// Whether a struct pattern's type name names a record's type, allowing
+/// either side to be written without its module path.
+fn same_type_name(pattern: &str, record: &str) -> bool {
+    pattern == record
+        || record.rsplit('.').next() == Some(pattern)
+        || pattern.rsplit('.').next() == Some(record)
+}
+
+/// Evaluates the `,expr` holes of a quasi-quote and splices the results back
+/// into the quoted AST. Nested quasi-quotes are left for their own evaluation.
+struct UnquoteSplicer<'a> {
+    interpreter: &'a mut Interpreter,
+    env: &'a mut Environment,
+    error: Option<EvalError>,
+}
+
+impl Fold for UnquoteSplicer<'_> {
+    fn fold_unquote(&mut self, inner: Expr) -> Expr {
+        if self.error.is_some() {
+            return Expr::Unquote(Box::new(inner));
+        }
+        let result = self
+            .interpreter
+            .eval_in_env(&inner, self.env)
+            .and_then(|value| {
+                value.to_expr().ok_or_else(|| {
+                    EvalError::new(format!(
+                        "cannot splice {} into a quasi-quote",
+                        value.type_name()
+                    ))
+                })
+            });
+        match result {
+            Ok(expr) => expr,
+            Err(error) => {
+                self.error = Some(error);
+                Expr::Unquote(Box::new(inner))
+            }
+        }
+    }
+
+    fn fold_quasi_quote(&mut self, inner: Expr) -> Expr {
+        Expr::QuasiQuote(Box::new(inner))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::MatchArm;
 
     #[test]
     fn test_eval_literal() {
@@ -920,4 +1210,296 @@ mod tests {
 
         assert_eq!(interp.eval(&evaled).unwrap(), Value::Int(42));
     }
+
+    #[test]
+    fn test_eval_cfg_guard() {
+        let cfg = |flag: &str| Expr::Call {
+            callee: Box::new(Expr::Identifier("#cfg".to_string())),
+            args: vec![Expr::Identifier(flag.to_string())],
+        };
+        let mut ctx = MacroContext::new();
+        ctx.set_cfg("debug", true);
+
+        let mut debug = Interpreter::new().with_macro_context(ctx);
+        assert_eq!(debug.eval(&cfg("debug")).unwrap(), Value::Bool(true));
+        let mut release = Interpreter::new();
+        assert_eq!(release.eval(&cfg("debug")).unwrap(), Value::Bool(false));
+
+        // The cfg-false branch would fail on an unbound name if evaluated
+        let guarded = Expr::If {
+            condition: Box::new(cfg("debug")),
+            then_branch: Box::new(Expr::Identifier("debug_only".to_string())),
+            else_branch: Some(Box::new(Expr::Literal(Literal::Int(0)))),
+        };
+        assert_eq!(release.eval(&guarded).unwrap(), Value::Int(0));
+        assert!(debug.eval(&guarded).is_err());
+    }
+
+    /// Builds `match pair { (0, y) => y, (x, _) if x > 0 => x, _ => -1 }`.
+    fn pair_match(pair: Expr, with_fallback: bool) -> Expr {
+        let mut arms = vec![
+            MatchArm {
+                pattern: Pattern::Tuple(vec![
+                    Pattern::Literal(Literal::Int(0)),
+                    Pattern::Identifier("y".to_string()),
+                ]),
+                guard: None,
+                body: Box::new(Expr::Identifier("y".to_string())),
+            },
+            MatchArm {
+                pattern: Pattern::Tuple(vec![
+                    Pattern::Identifier("x".to_string()),
+                    Pattern::Wildcard,
+                ]),
+                guard: Some(Box::new(Expr::Binary {
+                    left: Box::new(Expr::Identifier("x".to_string())),
+                    op: BinaryOp::Gt,
+                    right: Box::new(Expr::Literal(Literal::Int(0))),
+                })),
+                body: Box::new(Expr::Identifier("x".to_string())),
+            },
+        ];
+        if with_fallback {
+            arms.push(MatchArm {
+                pattern: Pattern::Wildcard,
+                guard: None,
+                body: Box::new(Expr::Literal(Literal::Int(-1))),
+            });
+        }
+        Expr::Match {
+            scrutinee: Box::new(pair),
+            arms,
+        }
+    }
+
+    fn int_pair(a: i64, b: i64) -> Expr {
+        Expr::Tuple(vec![
+            Expr::Literal(Literal::Int(a)),
+            Expr::Literal(Literal::Int(b)),
+        ])
+    }
+
+    #[test]
+    fn test_eval_match_literal_and_tuple() {
+        let mut interp = Interpreter::new();
+        assert_eq!(
+            interp.eval(&pair_match(int_pair(0, 7), true)).unwrap(),
+            Value::Int(7)
+        );
+    }
+
+    #[test]
+    fn test_eval_match_guard_falls_through() {
+        let mut interp = Interpreter::new();
+        assert_eq!(
+            interp.eval(&pair_match(int_pair(5, 7), true)).unwrap(),
+            Value::Int(5)
+        );
+        // The guard rejects x = -3, so the wildcard arm is taken
+        assert_eq!(
+            interp.eval(&pair_match(int_pair(-3, 7), true)).unwrap(),
+            Value::Int(-1)
+        );
+    }
+
+    #[test]
+    fn test_eval_match_bindings_do_not_leak() {
+        let mut interp = Interpreter::new();
+        interp.eval(&pair_match(int_pair(0, 7), true)).unwrap();
+        assert!(interp.eval(&Expr::Identifier("y".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_eval_match_struct_pattern() {
+        let mut interp = Interpreter::new();
+        let point = Expr::StructLiteral {
+            type_name: "Point".to_string(),
+            fields: vec![
+                ("x".to_string(), Expr::Literal(Literal::Int(1))),
+                ("y".to_string(), Expr::Literal(Literal::Int(2))),
+            ],
+        };
+        let expr = Expr::Match {
+            scrutinee: Box::new(point),
+            arms: vec![MatchArm {
+                pattern: Pattern::Constructor {
+                    name: "Point".to_string(),
+                    fields: vec![
+                        Pattern::Identifier("x".to_string()),
+                        Pattern::Constructor {
+                            name: "y".to_string(),
+                            fields: vec![Pattern::Identifier("py".to_string())],
+                        },
+                    ],
+                },
+                guard: None,
+                body: Box::new(Expr::Binary {
+                    left: Box::new(Expr::Identifier("x".to_string())),
+                    op: BinaryOp::Add,
+                    right: Box::new(Expr::Identifier("py".to_string())),
+                }),
+            }],
+        };
+        assert_eq!(interp.eval(&expr).unwrap(), Value::Int(3));
+    }
+
+    #[test]
+    fn test_eval_match_struct_pattern_checks_type_name() {
+        let mut interp = Interpreter::new();
+        let vec2 = Expr::StructLiteral {
+            type_name: "Vec2".to_string(),
+            fields: vec![
+                ("x".to_string(), Expr::Literal(Literal::Int(1))),
+                ("y".to_string(), Expr::Literal(Literal::Int(2))),
+            ],
+        };
+        let arm = |name: &str, result: &str| MatchArm {
+            pattern: Pattern::Constructor {
+                name: name.to_string(),
+                fields: vec![
+                    Pattern::Identifier("x".to_string()),
+                    Pattern::Identifier("y".to_string()),
+                ],
+            },
+            guard: None,
+            body: Box::new(Expr::Literal(Literal::String(result.to_string()))),
+        };
+        let expr = Expr::Match {
+            scrutinee: Box::new(vec2),
+            arms: vec![arm("Point", "point"), arm("geometry.Vec2", "vec2")],
+        };
+        assert_eq!(
+            interp.eval(&expr).unwrap(),
+            Value::String("vec2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_eval_match_non_exhaustive() {
+        let mut interp = Interpreter::new();
+        let err = interp
+            .eval(&pair_match(int_pair(-3, 7), false))
+            .unwrap_err();
+        assert!(err.message.starts_with("non-exhaustive match"));
+    }
+
+    fn eval_source(source: &str) -> Result<Value, EvalError> {
+        let expr = crate::parser::Parser::new(source).parse_expr(0).unwrap();
+        Interpreter::new().eval(&expr)
+    }
+
+    #[test]
+    fn test_eval_quote_round_trip() {
+        assert_eq!(eval_source("!{ '(1 + 2) }").unwrap(), Value::Int(3));
+    }
+
+    #[test]
+    fn test_eval_quasi_quote_splices_unquote() {
+        let mut interp = Interpreter::new();
+
+        // ''(x * ,(2 + 3)) keeps x quoted but splices the evaluated hole
+        let quasi = Expr::QuasiQuote(Box::new(Expr::Binary {
+            left: Box::new(Expr::Identifier("x".to_string())),
+            op: BinaryOp::Mul,
+            right: Box::new(Expr::Unquote(Box::new(Expr::Binary {
+                left: Box::new(Expr::Literal(Literal::Int(2))),
+                op: BinaryOp::Add,
+                right: Box::new(Expr::Literal(Literal::Int(3))),
+            }))),
+        }));
+        let Value::Quoted(expr) = interp.eval(&quasi).unwrap() else {
+            panic!("expected a quoted value");
+        };
+        assert_eq!(
+            *expr,
+            Expr::Binary {
+                left: Box::new(Expr::Identifier("x".to_string())),
+                op: BinaryOp::Mul,
+                right: Box::new(Expr::Literal(Literal::Int(5))),
+            }
+        );
+    }
+
+    #[test]
+    fn test_eval_quasi_quote_splices_quoted_ast() {
+        assert_eq!(
+            eval_source("!{ ''(,('(2 * 3)) + 1) }").unwrap(),
+            Value::Int(7)
+        );
+    }
+
+    #[test]
+    fn test_eval_rejects_unquoted_value() {
+        let err = eval_source("!{ 42 }").unwrap_err();
+        assert_eq!(err.message, "type error: expected Quoted, found Int");
+    }
+
+    #[test]
+    fn test_eval_error_points_at_failing_subexpression() {
+        let source = "1 + (2 / 0)";
+        let expr = crate::parser::Parser::new(source)
+            .with_expr_spans()
+            .parse_expr(0)
+            .unwrap();
+        let err = Interpreter::new().eval(&expr).unwrap_err();
+        let span = err.span.expect("division should carry a span");
+        assert_eq!(&source[span.start..span.end], "2 / 0");
+    }
+
+    #[test]
+    fn test_eval_assert_points_at_condition() {
+        let source = "{ let x = 0; #assert(x > 0) }";
+        let expr = crate::parser::Parser::new(source)
+            .with_expr_spans()
+            .parse_expr(0)
+            .unwrap();
+        let err = Interpreter::new().eval(&expr).unwrap_err();
+        assert_eq!(err.message, "assertion failed: (x > 0)");
+        let span = err.span.expect("assertion should carry a span");
+        assert_eq!(&source[span.start..span.end], "x > 0");
+        assert_eq!((span.line, span.column), (1, 22));
+    }
+
+    #[test]
+    fn test_eval_string_method_with_spans() {
+        for source in ["\"abc\".len()", "{ let s = \"abc\"; s.len() }"] {
+            let expr = crate::parser::Parser::new(source)
+                .with_expr_spans()
+                .parse_expr(0)
+                .unwrap();
+            assert_eq!(
+                Interpreter::new().eval(&expr).unwrap(),
+                Value::Int(3),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_eval_cfg_with_spans() {
+        let mut ctx = MacroContext::new();
+        ctx.set_cfg("debug", true);
+        ctx.add_feature("async");
+        let mut interp = Interpreter::new().with_macro_context(ctx);
+
+        for (source, expected) in [
+            ("#cfg(debug)", true),
+            ("#cfg(async)", true),
+            ("#cfg(release)", false),
+            ("#cfg(any(release, feature(\"gpu\")))", false),
+            ("#cfg(any(release, feature(\"async\")))", true),
+        ] {
+            let expr = crate::parser::Parser::new(source)
+                .with_expr_spans()
+                .parse_expr(0)
+                .unwrap();
+            assert_eq!(
+                interp.eval(&expr).unwrap(),
+                Value::Bool(expected),
+                "{}",
+                source
+            );
+        }
+    }
 }
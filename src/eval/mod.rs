@@ -19,5 +19,5 @@ pub mod builtins;
 pub mod interpreter;
 pub mod value;
 
-pub use interpreter::Interpreter;
+pub use interpreter::{EnvSnapshot, Interpreter};
 pub use value::{Environment, EvalError, Value};
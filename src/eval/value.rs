@@ -3,14 +3,21 @@
 //! This module defines the value representation used during expression
 //! evaluation, including primitives, functions, quoted AST, and reflection data.
 
-use crate::ast::Expr;
+use crate::ast::{Expr, Literal, Span};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt;
 
 /// Runtime value representation.
 ///
 /// Values are the results of evaluating expressions at runtime.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Equality and ordering follow the rules used by the comparison operators:
+/// numbers compare numerically with `Int` coerced to `Float` when the kinds
+/// differ, strings compare lexicographically, and bools and arrays compare
+/// structurally. See [`Value::equals`] and [`Value::compare`] for the
+/// fallible forms that reject incomparable operands.
+#[derive(Debug, Clone)]
 pub enum Value {
     /// Void/unit value (no meaningful result)
     Void,
@@ -27,11 +34,17 @@ pub enum Value {
     /// String value
     String(String),
 
+    /// Binary data, built by `from_hex(s)` and `from_base64(s)`
+    Bytes(Vec<u8>),
+
     /// Quoted AST (captured expression for metaprogramming)
     Quoted(Box<Expr>),
 
     /// Function closure
     Function {
+        /// Name the function was declared under, bound inside its own body
+        /// so it can call itself (`None` for lambdas)
+        name: Option<String>,
         /// Parameter names
         params: Vec<String>,
         /// Function body expression
@@ -56,8 +69,19 @@ pub enum Value {
     /// Array/list of values
     Array(Vec<Value>),
 
-    /// Record/struct (key-value pairs)
-    Record(HashMap<String, Value>),
+    /// Record/struct built from a struct literal
+    Record {
+        /// Type name, as written in the literal
+        type_name: String,
+        /// Field values by name
+        fields: HashMap<String, Value>,
+    },
+
+    /// Map/dictionary with arbitrary keys, in insertion order
+    Map(Vec<(Value, Value)>),
+
+    /// Result of a fallible computation, built by `ok(v)` and `err(e)`
+    Result(Result<Box<Value>, Box<Value>>),
 }
 
 impl Value {
@@ -69,6 +93,7 @@ impl Value {
             Value::Int(0) => false,
             Value::Float(f) if *f == 0.0 => false,
             Value::String(s) if s.is_empty() => false,
+            Value::Bytes(b) if b.is_empty() => false,
             Value::Array(a) if a.is_empty() => false,
             _ => true,
         }
@@ -102,6 +127,79 @@ impl Value {
         }
     }
 
+    /// Looks up `key` in a map value.
+    ///
+    /// Returns `None` if this is not a map or the key is absent.
+    pub fn map_get(&self, key: &Value) -> Option<&Value> {
+        match self {
+            Value::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Tests two values for equality as the `==` operator does.
+    ///
+    /// Numbers are equal if they are numerically equal, and values of the
+    /// same kind are compared structurally. Functions, builtins, and values
+    /// of different kinds cannot be compared and produce an error.
+    pub fn equals(&self, other: &Value) -> Result<bool, EvalError> {
+        match (self, other) {
+            (Value::Function { .. } | Value::Builtin(_), _)
+            | (_, Value::Function { .. } | Value::Builtin(_)) => Err(self.incomparable(other)),
+            (Value::Array(a), Value::Array(b)) => {
+                if a.len() != b.len() {
+                    return Ok(false);
+                }
+                for (x, y) in a.iter().zip(b) {
+                    if !x.equals(y)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            _ if self.is_number() && other.is_number() => Ok(self == other),
+            _ if self.type_name() == other.type_name() => Ok(self == other),
+            _ => Err(self.incomparable(other)),
+        }
+    }
+
+    /// Orders two values as the `<`, `<=`, `>` and `>=` operators do.
+    ///
+    /// Numbers are ordered numerically, strings lexicographically, bools
+    /// with `false < true`, and arrays element by element. Returns
+    /// `Ok(None)` when both operands are numbers but one is NaN, and an
+    /// error for any other pair of values that has no ordering.
+    pub fn compare(&self, other: &Value) -> Result<Option<Ordering>, EvalError> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Some(a.cmp(b))),
+            (Value::Float(a), Value::Float(b)) => Ok(a.partial_cmp(b)),
+            (Value::Int(a), Value::Float(b)) => Ok((*a as f64).partial_cmp(b)),
+            (Value::Float(a), Value::Int(b)) => Ok(a.partial_cmp(&(*b as f64))),
+            (Value::String(a), Value::String(b)) => Ok(Some(a.cmp(b))),
+            (Value::Bytes(a), Value::Bytes(b)) => Ok(Some(a.cmp(b))),
+            (Value::Bool(a), Value::Bool(b)) => Ok(Some(a.cmp(b))),
+            (Value::Void, Value::Void) => Ok(Some(Ordering::Equal)),
+            (Value::Array(a), Value::Array(b)) => {
+                for (x, y) in a.iter().zip(b) {
+                    match x.compare(y)? {
+                        Some(Ordering::Equal) => continue,
+                        ordering => return Ok(ordering),
+                    }
+                }
+                Ok(Some(a.len().cmp(&b.len())))
+            }
+            _ => Err(self.incomparable(other)),
+        }
+    }
+
+    fn is_number(&self) -> bool {
+        matches!(self, Value::Int(_) | Value::Float(_))
+    }
+
+    fn incomparable(&self, other: &Value) -> EvalError {
+        EvalError::invalid_operation("comparison", self.type_name(), other.type_name())
+    }
+
     /// Returns the type name of this value.
     pub fn type_name(&self) -> &str {
         match self {
@@ -110,12 +208,39 @@ impl Value {
             Value::Int(_) => "Int",
             Value::Float(_) => "Float",
             Value::String(_) => "String",
+            Value::Bytes(_) => "Bytes",
             Value::Quoted(_) => "Quoted",
             Value::Function { .. } => "Function",
             Value::Builtin(_) => "Builtin",
             Value::TypeInfo { .. } => "TypeInfo",
             Value::Array(_) => "Array",
-            Value::Record(_) => "Record",
+            Value::Record { .. } => "Record",
+            Value::Map(_) => "Map",
+            Value::Result(_) => "Result",
+        }
+    }
+}
+
+impl Value {
+    /// Converts this value back into an expression for quasi-quote splicing.
+    ///
+    /// Quoted values splice their captured AST; primitives and arrays become
+    /// literals. Returns `None` for values with no source representation,
+    /// such as closures and builtins.
+    pub fn to_expr(&self) -> Option<Expr> {
+        match self {
+            Value::Void => Some(Expr::Literal(Literal::Null)),
+            Value::Bool(b) => Some(Expr::Literal(Literal::Bool(*b))),
+            Value::Int(n) => Some(Expr::Literal(Literal::Int(*n))),
+            Value::Float(fl) => Some(Expr::Literal(Literal::Float(*fl))),
+            Value::String(s) => Some(Expr::Literal(Literal::String(s.clone()))),
+            Value::Quoted(expr) => Some((**expr).clone()),
+            Value::Array(items) => items
+                .iter()
+                .map(Value::to_expr)
+                .collect::<Option<_>>()
+                .map(Expr::List),
+            _ => None,
         }
     }
 }
@@ -128,6 +253,13 @@ impl fmt::Display for Value {
             Value::Int(n) => write!(f, "{}", n),
             Value::Float(fl) => write!(f, "{}", fl),
             Value::String(s) => write!(f, "\"{}\"", s),
+            Value::Bytes(bytes) => {
+                write!(f, "0x")?;
+                for byte in bytes {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
             Value::Quoted(_) => write!(f, "'<expr>"),
             Value::Function { params, .. } => {
                 write!(f, "<function({})>", params.join(", "))
@@ -144,7 +276,7 @@ impl fmt::Display for Value {
                 }
                 write!(f, "]")
             }
-            Value::Record(fields) => {
+            Value::Record { fields, .. } => {
                 write!(f, "{{")?;
                 for (i, (k, v)) in fields.iter().enumerate() {
                     if i > 0 {
@@ -154,10 +286,84 @@ impl fmt::Display for Value {
                 }
                 write!(f, "}}")
             }
+            Value::Map(entries) => {
+                write!(f, "{{")?;
+                for (i, (k, v)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", k, v)?;
+                }
+                write!(f, "}}")
+            }
+            Value::Result(Ok(value)) => write!(f, "Ok({})", value),
+            Value::Result(Err(error)) => write!(f, "Err({})", error),
         }
     }
 }
 
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Void, Value::Void) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a == b,
+            (Value::Int(a), Value::Float(b)) | (Value::Float(b), Value::Int(a)) => *a as f64 == *b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Bytes(a), Value::Bytes(b)) => a == b,
+            (Value::Quoted(a), Value::Quoted(b)) => a == b,
+            (
+                Value::Function {
+                    name: n1,
+                    params: p1,
+                    body: b1,
+                    env: e1,
+                },
+                Value::Function {
+                    name: n2,
+                    params: p2,
+                    body: b2,
+                    env: e2,
+                },
+            ) => n1 == n2 && p1 == p2 && b1 == b2 && e1 == e2,
+            (Value::Builtin(a), Value::Builtin(b)) => a == b,
+            (
+                Value::TypeInfo {
+                    name: n1,
+                    kind: k1,
+                    fields: f1,
+                },
+                Value::TypeInfo {
+                    name: n2,
+                    kind: k2,
+                    fields: f2,
+                },
+            ) => n1 == n2 && k1 == k2 && f1 == f2,
+            (Value::Array(a), Value::Array(b)) => a == b,
+            (
+                Value::Record {
+                    type_name: t1,
+                    fields: f1,
+                },
+                Value::Record {
+                    type_name: t2,
+                    fields: f2,
+                },
+            ) => t1 == t2 && f1 == f2,
+            (Value::Map(a), Value::Map(b)) => a == b,
+            (Value::Result(a), Value::Result(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Value) -> Option<Ordering> {
+        self.compare(other).ok().flatten()
+    }
+}
+
 /// Runtime environment for variable bindings.
 ///
 /// Environments form a tree structure through parent pointers,
@@ -223,6 +429,11 @@ impl Default for Environment {
 pub struct EvalError {
     /// Error message
     pub message: String,
+    /// Value returned early by `return` or an `Err` propagated by the `?`
+    /// operator, unwinding to the enclosing function call
+    pub propagated: Option<Box<Value>>,
+    /// Location of the innermost spanned expression that failed (if known)
+    pub span: Option<Span>,
 }
 
 impl EvalError {
@@ -230,9 +441,35 @@ impl EvalError {
     pub fn new(message: impl Into<String>) -> Self {
         Self {
             message: message.into(),
+            propagated: None,
+            span: None,
+        }
+    }
+
+    /// Creates an early return of an `Err` result through `?`.
+    pub fn propagate(result: Value) -> Self {
+        Self {
+            message: format!("error propagated by '?': {}", result),
+            propagated: Some(Box::new(result)),
+            span: None,
+        }
+    }
+
+    /// Creates an early exit from the enclosing function through `return`.
+    pub fn early_return(value: Value) -> Self {
+        Self {
+            message: "return outside function".to_string(),
+            propagated: Some(Box::new(value)),
+            span: None,
         }
     }
 
+    /// Attaches a source location to this error.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
     /// Creates a type error.
     pub fn type_error(expected: &str, actual: &str) -> Self {
         Self::new(format!(
@@ -241,6 +478,11 @@ impl EvalError {
         ))
     }
 
+    /// Creates an error for a match expression where no arm matched.
+    pub fn non_exhaustive_match(value: &Value) -> Self {
+        Self::new(format!("non-exhaustive match: no arm matches {}", value))
+    }
+
     /// Creates an undefined variable error.
     pub fn undefined_variable(name: &str) -> Self {
         Self::new(format!("undefined variable: {}", name))
@@ -259,6 +501,11 @@ impl EvalError {
         Self::new("division by zero")
     }
 
+    /// Creates a missing map key error.
+    pub fn missing_key(key: &Value) -> Self {
+        Self::new(format!("key not found: {}", key))
+    }
+
     /// Creates an invalid operation error.
     pub fn invalid_operation(op: &str, left: &str, right: &str) -> Self {
         Self::new(format!(
@@ -270,7 +517,14 @@ impl EvalError {
 
 impl fmt::Display for EvalError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.message)
+        match self.span {
+            Some(span) => write!(
+                f,
+                "{} at line {}, column {}",
+                self.message, span.line, span.column
+            ),
+            None => write!(f, "{}", self.message),
+        }
     }
 }
 
@@ -320,4 +574,37 @@ mod tests {
         assert_eq!(Value::Bool(true).type_name(), "Bool");
         assert_eq!(Value::String("hello".to_string()).type_name(), "String");
     }
+
+    #[test]
+    fn test_value_numeric_coercion_comparison() {
+        assert_eq!(Value::Int(2), Value::Float(2.0));
+        assert!(Value::Int(1) < Value::Float(1.5));
+        assert!(Value::Float(2.5) > Value::Int(2));
+        assert_eq!(Value::Int(3).equals(&Value::Float(3.0)), Ok(true));
+        assert_eq!(Value::Float(f64::NAN).compare(&Value::Int(1)), Ok(None));
+    }
+
+    #[test]
+    fn test_value_string_comparison() {
+        let apple = Value::String("apple".to_string());
+        let banana = Value::String("banana".to_string());
+        assert_eq!(apple.compare(&banana), Ok(Some(Ordering::Less)));
+        assert!(banana > apple);
+        assert_eq!(apple.equals(&apple.clone()), Ok(true));
+        assert!(apple.equals(&Value::Int(1)).is_err());
+    }
+
+    #[test]
+    fn test_value_function_comparison_is_error() {
+        let func = Value::Function {
+            name: None,
+            params: vec!["x".to_string()],
+            body: Box::new(Expr::Identifier("x".to_string())),
+            env: Environment::new(),
+        };
+        let err = func.compare(&Value::Int(1)).unwrap_err();
+        assert!(err.message.contains("Function"), "{}", err.message);
+        assert!(func.equals(&Value::Int(1)).is_err());
+        assert_eq!(func.partial_cmp(&Value::Int(1)), None);
+    }
 }
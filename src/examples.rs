@@ -0,0 +1,133 @@
+//! Sample JSON instances for DOL genes.
+//!
+//! Produces a placeholder JSON object for a gene, suitable for API
+//! documentation and test fixtures. Field types go through the same mapping
+//! as [`JsonSchemaCodegen`], so samples always agree with generated schemas.
+//!
+//! # Example
+//!
+//! ```rust
+//! use metadol::ast::Declaration;
+//! use metadol::examples::sample_json;
+//! use metadol::parser::Parser;
+//!
+//! let source = "gene Point { has x: Int64 has label: String = \"origin\" }";
+//! let Declaration::Gene(gene) = Parser::new(source).parse().unwrap() else {
+//!     unreachable!()
+//! };
+//!
+//! let sample = sample_json(&gene);
+//! assert_eq!(sample["x"], 0);
+//! assert_eq!(sample["label"], "origin");
+//! ```
+
+use crate::ast::{Expr, Gene, Literal, Statement};
+use crate::codegen::{JsonSchemaCodegen, TypeMapper};
+use serde_json::{Map, Value};
+
+/// Returns a sample JSON object for a gene.
+///
+/// Each field holds its declared default when that default is a literal,
+/// and a type-appropriate placeholder otherwise: `0` for integers, `""` for
+/// strings, `false` for booleans, `[]` for lists and `{}` for references to
+/// other types. Untyped `has` properties are sampled as strings.
+pub fn sample_json(gene: &Gene) -> Value {
+    let mut object = Map::new();
+    for stmt in &gene.statements {
+        match stmt {
+            Statement::HasField(field) => {
+                let value = field
+                    .default
+                    .as_ref()
+                    .and_then(literal_json)
+                    .unwrap_or_else(|| {
+                        let schema = JsonSchemaCodegen::map_type_expr(&field.type_);
+                        sample_for_schema(&serde_json::from_str(&schema).unwrap_or_default())
+                    });
+                object.insert(field.name.clone(), value);
+            }
+            Statement::Has { property, .. } => {
+                object.insert(property.clone(), Value::String(String::new()));
+            }
+            _ => {}
+        }
+    }
+    Value::Object(object)
+}
+
+/// Returns a placeholder value satisfying a JSON schema.
+fn sample_for_schema(schema: &Value) -> Value {
+    if let Some(variant) = schema.get("enum").and_then(|e| e.get(0)) {
+        return variant.clone();
+    }
+    if let Some(first) = schema.get("oneOf").and_then(|o| o.get(0)) {
+        return sample_for_schema(first);
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("integer") | Some("number") => Value::from(0),
+        Some("string") => Value::String(String::new()),
+        Some("boolean") => Value::Bool(false),
+        Some("array") => Value::Array(Vec::new()),
+        Some("object") => Value::Object(Map::new()),
+        Some("null") => Value::Null,
+        _ if schema.get("$ref").is_some() => Value::Object(Map::new()),
+        _ => Value::Null,
+    }
+}
+
+/// Converts a literal default value to JSON.
+fn literal_json(expr: &Expr) -> Option<Value> {
+    match expr.unspanned() {
+        Expr::Literal(Literal::Int(i)) => Some(Value::from(*i)),
+        Expr::Literal(Literal::Float(f)) => Some(Value::from(*f)),
+        Expr::Literal(Literal::Bool(b)) => Some(Value::Bool(*b)),
+        Expr::Literal(Literal::String(s)) => Some(Value::String(s.clone())),
+        Expr::Literal(Literal::Char(c)) => Some(Value::String(c.to_string())),
+        Expr::Literal(Literal::Null) => Some(Value::Null),
+        Expr::List(items) => items
+            .iter()
+            .map(literal_json)
+            .collect::<Option<_>>()
+            .map(Value::Array),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Declaration;
+    use crate::parser::Parser;
+
+    fn gene(source: &str) -> Gene {
+        match Parser::new(source).parse().unwrap() {
+            Declaration::Gene(gene) => gene,
+            other => panic!("expected gene, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sample_uses_type_placeholders() {
+        let gene = gene(
+            "gene Account { has id: Int64 has owner: String has active: Bool has tags: List<String> }",
+        );
+        let sample = sample_json(&gene);
+
+        assert_eq!(sample["id"], Value::from(0));
+        assert_eq!(sample["owner"], Value::String(String::new()));
+        assert_eq!(sample["active"], Value::Bool(false));
+        assert_eq!(sample["tags"], Value::Array(Vec::new()));
+    }
+
+    #[test]
+    fn test_sample_uses_declared_defaults() {
+        let gene = gene(
+            "gene Config { has retries: Int64 = 3 has name: String = \"primary\" has debug: Bool = true }",
+        );
+        let sample = sample_json(&gene);
+
+        assert_eq!(sample["retries"], Value::from(3));
+        assert_eq!(sample["name"], Value::from("primary"));
+        assert_eq!(sample["debug"], Value::Bool(true));
+    }
+}
@@ -0,0 +1,576 @@
+//! Canonical formatting of DOL source.
+//!
+//! [`format_source`] re-emits a file in a canonical layout: declaration
+//! bodies are indented, each statement sits on its own line, runs of spaces
+//! collapse to one, and exegesis prose is reflowed to the configured width.
+//! Comments are kept, and formatting already formatted source is a no-op.
+//!
+//! The formatter works on the token stream of a trivia-preserving
+//! [`Lexer`], using the parsed declarations only to locate statement
+//! boundaries. Source that does not parse is rejected rather than
+//! formatted.
+//!
+//! Output goes through [`Printer`], the line printer shared with
+//! [`to_dol`](crate::ast::Declaration::to_dol) and
+//! [`unparse`](crate::unparse), so all three indent bodies and lay out
+//! exegesis blocks the same way.
+//!
+//! # Example
+//!
+//! ```rust
+//! use metadol::format::{format_source, FormatOptions};
+//!
+//! let source = "gene container.exists { container has   id container is created }\nexegesis { A   container. }";
+//! let formatted = format_source(source, &FormatOptions::default()).unwrap();
+//! assert_eq!(
+//!     formatted,
+//!     "gene container.exists {\n  container has id\n  container is created\n}\nexegesis {\n  A container.\n}\n"
+//! );
+//! ```
+
+use std::collections::HashSet;
+
+use crate::ast::Declaration;
+use crate::error::ParseError;
+use crate::lexer::{Lexer, TokenKind};
+use crate::parser::Parser;
+use crate::unparse::matching_brace;
+
+/// Layout settings for [`format_source`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Spaces per indentation level
+    pub indent_width: usize,
+
+    /// Column at which exegesis prose is wrapped
+    pub max_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 2,
+            max_width: 80,
+        }
+    }
+}
+
+/// Formats DOL source into its canonical layout.
+///
+/// # Errors
+///
+/// Returns the parse error if `source` is not valid DOL.
+pub fn format_source(source: &str, opts: &FormatOptions) -> Result<String, ParseError> {
+    let file = Parser::new(source).parse_file()?;
+
+    let mut layout = Layout::default();
+    for decl in &file.declarations {
+        layout.add_declaration(source, decl);
+    }
+
+    let mut formatter = Formatter {
+        source,
+        opts,
+        layout,
+        printer: Printer::new(opts.indent_width),
+        newlines: 0,
+        space: false,
+        after_open: false,
+    };
+    formatter.run();
+    Ok(formatter.printer.finish())
+}
+
+/// Line-oriented output shared by the DOL printers.
+///
+/// Text pushed at the start of a line is indented by the current depth,
+/// and trailing spaces are dropped when a line ends.
+#[derive(Debug)]
+pub(crate) struct Printer {
+    out: String,
+    /// Indentation of depth zero
+    base: String,
+    depth: usize,
+    indent_width: usize,
+    /// Whether text has been written to the current line
+    line_started: bool,
+}
+
+impl Printer {
+    /// Creates a printer at the start of an empty output.
+    pub(crate) fn new(indent_width: usize) -> Self {
+        Self {
+            out: String::new(),
+            base: String::new(),
+            depth: 0,
+            indent_width,
+            line_started: false,
+        }
+    }
+
+    /// Creates a printer that continues a source line indented by `base`.
+    ///
+    /// Its output can be spliced in after existing text on that line;
+    /// later lines are indented relative to `base`.
+    pub(crate) fn continuing(base: &str, indent_width: usize) -> Self {
+        Self {
+            base: base.to_string(),
+            line_started: true,
+            ..Self::new(indent_width)
+        }
+    }
+
+    /// Appends `text` to the current line, indenting it if the line is new.
+    pub(crate) fn push(&mut self, text: &str) {
+        if !self.line_started {
+            let indent = self.indent(self.depth);
+            self.out.push_str(&indent);
+            self.line_started = true;
+        }
+        self.out.push_str(text);
+    }
+
+    /// Ends the current line; on an empty line this writes a blank line.
+    pub(crate) fn newline(&mut self) {
+        let trimmed = self.out.trim_end_matches([' ', '\t']).len();
+        self.out.truncate(trimmed);
+        self.out.push('\n');
+        self.line_started = false;
+    }
+
+    /// Returns true if text has been written to the current line.
+    pub(crate) fn line_started(&self) -> bool {
+        self.line_started
+    }
+
+    /// Nests subsequent lines one level deeper.
+    pub(crate) fn indent_more(&mut self) {
+        self.depth += 1;
+    }
+
+    /// Undoes one [`Printer::indent_more`].
+    pub(crate) fn indent_less(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// Writes an `exegesis` block, starting on the current line.
+    ///
+    /// The prose sits one level deeper than the braces. With `max_width`
+    /// it is reflowed to fit; otherwise it is written verbatim, which the
+    /// parser reads back unchanged since only the first line is indented.
+    pub(crate) fn exegesis(&mut self, text: &str, max_width: Option<usize>) {
+        let text = text.trim();
+        if text.is_empty() {
+            self.push("exegesis {}");
+            return;
+        }
+        self.push("exegesis {");
+        self.newline();
+        self.indent_more();
+        match max_width {
+            None => {
+                self.push(text);
+                self.newline();
+            }
+            Some(max_width) => {
+                let width = max_width.saturating_sub(self.indent(self.depth).len());
+                for (i, paragraph) in reflow(text, width).iter().enumerate() {
+                    if i > 0 {
+                        self.newline();
+                    }
+                    for line in paragraph {
+                        self.push(line);
+                        self.newline();
+                    }
+                }
+            }
+        }
+        self.indent_less();
+        self.push("}");
+    }
+
+    fn indent(&self, depth: usize) -> String {
+        format!("{}{}", self.base, " ".repeat(depth * self.indent_width))
+    }
+
+    /// Returns the output, ending any unfinished line.
+    pub(crate) fn finish(mut self) -> String {
+        if self.line_started {
+            self.newline();
+        }
+        self.out
+    }
+
+    /// Returns the output as is, without ending the current line.
+    pub(crate) fn into_string(self) -> String {
+        self.out
+    }
+}
+
+/// Offsets where the formatter must break lines.
+#[derive(Debug, Default)]
+struct Layout {
+    /// A token starting here begins a new line
+    break_before: HashSet<usize>,
+    /// A token starting here ends its line
+    break_after: HashSet<usize>,
+}
+
+impl Layout {
+    /// Records the body braces and statement starts of `decl`.
+    fn add_declaration(&mut self, source: &str, decl: &Declaration) {
+        let mut starts: Vec<usize> = match decl {
+            Declaration::Gene(g) => g.statements.iter().map(|s| s.span().start).collect(),
+            Declaration::Trait(t) => t
+                .statements
+                .iter()
+                .map(|s| s.span().start)
+                .chain(t.laws.iter().map(|l| l.span.start))
+                .collect(),
+            Declaration::Constraint(c) => c.statements.iter().map(|s| s.span().start).collect(),
+            Declaration::System(s) => s
+                .requirements
+                .iter()
+                .map(|r| r.span.start)
+                .chain(s.capabilities.iter().map(|c| c.span.start))
+                .chain(s.states.iter().map(|st| st.span.start))
+                .chain(s.statements.iter().map(|st| st.span().start))
+                .collect(),
+            // Evolution statement spans start after their `adds` or
+            // `deprecates` keyword, so the keywords are found while scanning
+            Declaration::Evolution(_) => Vec::new(),
+            Declaration::Function(_)
+            | Declaration::Const(_)
+            | Declaration::SexVar(_)
+            | Declaration::Extern(_) => return,
+        };
+
+        let start = decl.span().start.min(source.len());
+        let mut lexer = Lexer::new(&source[start..]);
+        let mut depth = 0usize;
+        let mut open = None;
+        loop {
+            let token = lexer.next_token();
+            let offset = start + token.span.start;
+            match token.kind {
+                TokenKind::Eof => return,
+                TokenKind::LeftBrace => {
+                    depth += 1;
+                    open.get_or_insert(offset);
+                }
+                TokenKind::RightBrace => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 && open.is_some() {
+                        break;
+                    }
+                }
+                TokenKind::Adds
+                | TokenKind::Deprecates
+                | TokenKind::Removes
+                | TokenKind::Because
+                | TokenKind::Migrate
+                    if depth == 1 && matches!(decl, Declaration::Evolution(_)) =>
+                {
+                    starts.push(offset);
+                }
+                _ => {}
+            }
+        }
+
+        let Some(open) = open else { return };
+        let Some(close) = matching_brace(source, open) else {
+            return;
+        };
+        starts.retain(|&s| open < s && s < close);
+        if starts.is_empty() {
+            return;
+        }
+        self.break_after.insert(open);
+        self.break_before.insert(close);
+        self.break_before.extend(starts);
+    }
+}
+
+/// Re-emits tokens line by line.
+struct Formatter<'a> {
+    source: &'a str,
+    opts: &'a FormatOptions,
+    layout: Layout,
+    printer: Printer,
+    /// Newlines requested before the next token (capped at two)
+    newlines: usize,
+    /// Whether a space separates the next token from the previous one
+    space: bool,
+    /// Whether the last token emitted was an opening brace
+    after_open: bool,
+}
+
+impl Formatter<'_> {
+    fn run(&mut self) {
+        let mut pos = 0;
+        'relex: while pos < self.source.len() {
+            let mut lexer = Lexer::with_trivia(&self.source[pos..]);
+            loop {
+                let token = lexer.next_token();
+                let start = pos + token.span.start;
+                let end = pos + token.span.end;
+                match token.kind {
+                    TokenKind::Eof => break 'relex,
+                    TokenKind::Whitespace => {
+                        let breaks = token.lexeme.matches('\n').count();
+                        self.newlines = (self.newlines + breaks).min(2);
+                        self.space = true;
+                    }
+                    TokenKind::Exegesis => {
+                        if let Some((open, close)) = self.exegesis_block(end) {
+                            self.exegesis(open, close);
+                            pos = close + 1;
+                            continue 'relex;
+                        }
+                        self.token(start, end);
+                    }
+                    _ => self.token(start, end),
+                }
+            }
+        }
+    }
+
+    /// Emits the token covering `source[start..end]`.
+    fn token(&mut self, start: usize, end: usize) {
+        let text = self.source[start..end].trim_end();
+        let closing = text == "}";
+        if self.layout.break_before.contains(&start) {
+            self.newlines = self.newlines.max(1);
+        }
+        if closing {
+            self.printer.indent_less();
+        }
+        // Blank lines directly inside braces are dropped
+        if self.after_open || closing {
+            self.newlines = self.newlines.min(1);
+        }
+        self.separate();
+        self.printer.push(text);
+
+        self.after_open = text == "{";
+        if self.after_open {
+            self.printer.indent_more();
+        }
+        if self.layout.break_after.contains(&start) {
+            self.newlines = 1;
+        }
+    }
+
+    /// Writes the line breaks or space requested before the next token.
+    fn separate(&mut self) {
+        if self.newlines > 0 && self.printer.line_started() {
+            self.printer.newline();
+            if self.newlines > 1 {
+                self.printer.newline();
+            }
+        } else if self.space && self.printer.line_started() {
+            self.printer.push(" ");
+        }
+        self.newlines = 0;
+        self.space = false;
+    }
+
+    /// Returns the braces of the exegesis block whose keyword ends at `end`.
+    fn exegesis_block(&self, end: usize) -> Option<(usize, usize)> {
+        let rest = &self.source[end..];
+        let open = end + (rest.len() - rest.trim_start().len());
+        if !self.source[open..].starts_with('{') {
+            return None;
+        }
+        Some((open, matching_brace(self.source, open)?))
+    }
+
+    /// Emits an exegesis block on its own lines, with the prose reflowed.
+    fn exegesis(&mut self, open: usize, close: usize) {
+        if self.printer.line_started() {
+            self.newlines = self.newlines.max(1);
+        }
+        if self.after_open {
+            self.newlines = self.newlines.min(1);
+        }
+        self.separate();
+        let text = &self.source[open + 1..close];
+        self.printer.exegesis(text, Some(self.opts.max_width));
+        self.newlines = 1;
+        self.after_open = false;
+    }
+}
+
+/// A unit of exegesis text.
+enum Item<'a> {
+    /// A paragraph or list item, joined and rewrapped
+    Prose(String),
+    /// A line kept as written
+    Verbatim(&'a str),
+}
+
+/// Wraps prose into lines of at most `width` columns.
+///
+/// Paragraphs are separated by blank lines. Lines starting with `- ` or
+/// `* ` begin list items, whose continuation lines are indented by two
+/// spaces. Headings, fenced code blocks, and lines containing comment
+/// markers are kept as written: the parser tokenizes exegesis when skipping
+/// over it, so joining a comment with the next line could hide a brace.
+/// Returns the lines of each paragraph.
+fn reflow(text: &str, width: usize) -> Vec<Vec<String>> {
+    // The first line has been trimmed; the others keep their indentation
+    let base = text
+        .lines()
+        .skip(1)
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut paragraphs: Vec<Vec<Item>> = vec![Vec::new()];
+    let mut fenced = false;
+    for raw in text.lines() {
+        let line = raw.trim();
+        let items = paragraphs.last_mut().expect("at least one paragraph");
+        if line.starts_with("```") {
+            fenced = !fenced;
+            items.push(Item::Verbatim(line));
+        } else if fenced {
+            let indent = (raw.len() - raw.trim_start().len()).min(base);
+            items.push(Item::Verbatim(raw[indent..].trim_end()));
+        } else if line.is_empty() {
+            if !items.is_empty() {
+                paragraphs.push(Vec::new());
+            }
+        } else if line.starts_with('#') || line.contains("//") || line.contains("--") {
+            items.push(Item::Verbatim(line));
+        } else if let (Some(Item::Prose(item)), false) = (items.last_mut(), is_list_item(line)) {
+            item.push(' ');
+            item.push_str(line);
+        } else {
+            items.push(Item::Prose(line.to_string()));
+        }
+    }
+
+    paragraphs
+        .iter()
+        .filter(|items| !items.is_empty())
+        .map(|items| wrap(items, width))
+        .collect()
+}
+
+/// Wraps the prose items of a paragraph, hanging list item continuations.
+fn wrap(items: &[Item], width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for item in items {
+        let item = match item {
+            Item::Prose(item) => item,
+            Item::Verbatim(line) => {
+                lines.push(line.to_string());
+                continue;
+            }
+        };
+        let hang = if is_list_item(item) { "  " } else { "" };
+        let mut line = String::new();
+        for word in item.split_whitespace() {
+            let started = !line.trim().is_empty();
+            if started && line.len() + 1 + word.len() > width {
+                lines.push(std::mem::replace(&mut line, hang.to_string()));
+            } else if started {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+fn is_list_item(line: &str) -> bool {
+    line.starts_with("- ") || line.starts_with("* ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(source: &str) -> String {
+        let formatted = format_source(source, &FormatOptions::default()).unwrap();
+        let again = format_source(&formatted, &FormatOptions::default()).unwrap();
+        assert_eq!(formatted, again, "formatting is not idempotent");
+        formatted
+    }
+
+    #[test]
+    fn test_format_gene_layout() {
+        let source = "gene   container.exists {\n\n      container has   identity // stable\n  container is created container derives from image\n\n\n}\n\n\n\nexegesis {\n  Short.\n}\n";
+        assert_eq!(
+            format(source),
+            "gene container.exists {\n  container has identity // stable\n  container is created\n  container derives from image\n}\n\nexegesis {\n  Short.\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_trait_laws() {
+        let source = "trait Ordered { uses Comparable law reflexive(a: Int64) { a <= a } }\n";
+        assert_eq!(
+            format(source),
+            "trait Ordered {\n  uses Comparable\n  law reflexive(a: Int64) { a <= a }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_system_and_evolution() {
+        let source = "system univrs.node @ 0.1.0 { requires container.exists >= 0.0.1 state ticks: Int64 = 0 node has id exegesis { A node. } }\nevolves container.lifecycle @ 0.0.2 > 0.0.1 { adds container is paused because \"pausing\" }\nexegesis { Adds pause. }";
+        assert_eq!(
+            format(source),
+            "system univrs.node @ 0.1.0 {\n  requires container.exists >= 0.0.1\n  state ticks: Int64 = 0\n  node has id\n  exegesis {\n    A node.\n  }\n}\nevolves container.lifecycle @ 0.0.2 > 0.0.1 {\n  adds container is paused\n  because \"pausing\"\n}\nexegesis {\n  Adds pause.\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_reflow_exegesis() {
+        let words = "word ".repeat(30);
+        let source = format!(
+            "gene a.b {{ a has b }}\nexegesis {{\n{words}\n\n- first item\n  continues\n- second\n\n```\nlet x = (y)\n  // keep\n```\n}}"
+        );
+        let formatted = format(&source);
+        assert!(formatted.lines().all(|line| line.len() <= 80));
+        assert!(formatted.contains("\n\n  - first item continues\n  - second\n\n"));
+        assert!(formatted.contains("\n  ```\n  let x = (y)\n    // keep\n  ```\n}"));
+        assert_eq!(
+            Parser::new(&formatted)
+                .parse()
+                .unwrap()
+                .exegesis()
+                .split_whitespace()
+                .count(),
+            44
+        );
+    }
+
+    #[test]
+    fn test_printer_exegesis_layout() {
+        let mut printer = Printer::new(2);
+        printer.push("gene a.b {");
+        printer.indent_more();
+        printer.newline();
+        printer.exegesis("First.\n    kept as written", None);
+        printer.indent_less();
+        printer.newline();
+        printer.push("}");
+        assert_eq!(
+            printer.finish(),
+            "gene a.b {\n  exegesis {\n    First.\n    kept as written\n  }\n}\n"
+        );
+
+        let mut printer = Printer::continuing("\t", 2);
+        printer.exegesis("Spliced.", None);
+        assert_eq!(printer.into_string(), "exegesis {\n\t  Spliced.\n\t}");
+    }
+
+    #[test]
+    fn test_format_rejects_invalid_source() {
+        assert!(format_source("gene {", &FormatOptions::default()).is_err());
+    }
+}
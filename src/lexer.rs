@@ -60,6 +60,11 @@ impl Token {
             span,
         }
     }
+
+    /// Returns true if this token is a keyword.
+    pub fn is_keyword(&self) -> bool {
+        self.kind.is_keyword()
+    }
 }
 
 impl Default for Token {
@@ -290,6 +295,24 @@ pub enum TokenKind {
     Extends,
     /// The `type` keyword - type declaration (v0.3.0)
     Type,
+    /// The contextual `enum` keyword, as in `has kind: enum { A, B }`
+    Enum,
+    /// The contextual `parent` keyword, as in `pub(parent)`
+    Parent,
+    /// The contextual `to` keyword, as in `evolves name from 1.0.0 to 1.1.0`
+    To,
+    /// The contextual `and` keyword, combining capability requirements
+    AndKeyword,
+    /// The contextual `or` keyword, combining capability requirements
+    OrKeyword,
+    /// The contextual `include` keyword, as in `#include("path.dol")`
+    Include,
+    /// The contextual `deprecated` keyword, as in `#[deprecated("note")]`
+    Deprecated,
+    /// The contextual `note` keyword, as in `#[deprecated(note = "...")]`
+    Note,
+    /// The contextual `since` keyword, as in `#[since("1.2.0")]`
+    Since,
 
     // === Boolean and Null Literals (DOL 2.0) ===
     /// The `true` literal
@@ -372,9 +395,19 @@ pub enum TokenKind {
     Version,
     /// A quoted string literal
     String,
+    /// A string literal with `${expr}` interpolations, written `f"..."`
+    FormatString,
     /// A character literal (single-quoted)
     Char,
 
+    // === Trivia (only produced by a trivia-preserving lexer) ===
+    /// A run of whitespace
+    Whitespace,
+    /// A `//` or `--` comment, excluding the trailing newline
+    LineComment,
+    /// A `/* ... */` comment
+    BlockComment,
+
     // === Special ===
     /// End of file
     Eof,
@@ -383,98 +416,238 @@ pub enum TokenKind {
 }
 
 impl TokenKind {
-    /// Returns true if this is a keyword.
-    pub fn is_keyword(&self) -> bool {
+    /// Returns the keyword kind for a word, across all dialects.
+    ///
+    /// This is the canonical keyword table; [`keyword_str`](Self::keyword_str)
+    /// is its inverse. `mod` is accepted as a short form of `module`.
+    pub fn from_keyword(word: &str) -> Option<TokenKind> {
+        match word {
+            // DOL 1.x keywords
+            "gene" => Some(TokenKind::Gene),
+            "trait" => Some(TokenKind::Trait),
+            "constraint" => Some(TokenKind::Constraint),
+            "system" => Some(TokenKind::System),
+            "evolves" => Some(TokenKind::Evolves),
+            "exegesis" => Some(TokenKind::Exegesis),
+            "has" => Some(TokenKind::Has),
+            "is" => Some(TokenKind::Is),
+            "derives" => Some(TokenKind::Derives),
+            "from" => Some(TokenKind::From),
+            "requires" => Some(TokenKind::Requires),
+            "uses" => Some(TokenKind::Uses),
+            "emits" => Some(TokenKind::Emits),
+            "matches" => Some(TokenKind::Matches),
+            "never" => Some(TokenKind::Never),
+            "adds" => Some(TokenKind::Adds),
+            "deprecates" => Some(TokenKind::Deprecates),
+            "removes" => Some(TokenKind::Removes),
+            "because" => Some(TokenKind::Because),
+            "test" => Some(TokenKind::Test),
+            "given" => Some(TokenKind::Given),
+            "when" => Some(TokenKind::When),
+            "then" => Some(TokenKind::Then),
+            "always" => Some(TokenKind::Always),
+            "each" => Some(TokenKind::Each),
+            "all" => Some(TokenKind::All),
+            "no" => Some(TokenKind::No),
+            // DOL 2.0 control flow keywords
+            "let" => Some(TokenKind::Let),
+            "if" => Some(TokenKind::If),
+            "else" => Some(TokenKind::Else),
+            "match" => Some(TokenKind::Match),
+            "for" => Some(TokenKind::For),
+            "while" => Some(TokenKind::While),
+            "loop" => Some(TokenKind::Loop),
+            "break" => Some(TokenKind::Break),
+            "continue" => Some(TokenKind::Continue),
+            "return" => Some(TokenKind::Return),
+            "in" => Some(TokenKind::In),
+            "where" => Some(TokenKind::Where),
+            // DOL 2.0 type keywords
+            "Int8" => Some(TokenKind::Int8),
+            "Int16" => Some(TokenKind::Int16),
+            "Int32" => Some(TokenKind::Int32),
+            "Int64" => Some(TokenKind::Int64),
+            "UInt8" => Some(TokenKind::UInt8),
+            "UInt16" => Some(TokenKind::UInt16),
+            "UInt32" => Some(TokenKind::UInt32),
+            "UInt64" => Some(TokenKind::UInt64),
+            "Float32" => Some(TokenKind::Float32),
+            "Float64" => Some(TokenKind::Float64),
+            "Bool" => Some(TokenKind::BoolType),
+            "String" => Some(TokenKind::StringType),
+            "Void" => Some(TokenKind::VoidType),
+            "fun" => Some(TokenKind::Function),
+            // DOL 2.0 visibility keywords
+            "pub" => Some(TokenKind::Pub),
+            "module" => Some(TokenKind::Module),
+            "mod" => Some(TokenKind::Module), // Short form of module
+            "use" => Some(TokenKind::Use),
+            "spirit" => Some(TokenKind::Spirit),
+            // DOL 2.0 SEX keywords
+            "sex" => Some(TokenKind::Sex),
+            "var" => Some(TokenKind::Var),
+            "val" => Some(TokenKind::Val),
+            "const" => Some(TokenKind::Const),
+            "extern" => Some(TokenKind::Extern),
+            // DOL 2.0 logic keywords
+            "implies" => Some(TokenKind::Implies),
+            "forall" => Some(TokenKind::Forall),
+            "exists" => Some(TokenKind::Exists),
+            // DOL 2.0 other keywords
+            "impl" => Some(TokenKind::Impl),
+            "as" => Some(TokenKind::As),
+            "state" => Some(TokenKind::State),
+            "law" => Some(TokenKind::Law),
+            "mut" => Some(TokenKind::Mut),
+            "not" => Some(TokenKind::Not),
+            "migrate" => Some(TokenKind::Migrate),
+            "extends" => Some(TokenKind::Extends),
+            "type" => Some(TokenKind::Type),
+            "enum" => Some(TokenKind::Enum),
+            "parent" => Some(TokenKind::Parent),
+            "to" => Some(TokenKind::To),
+            "and" => Some(TokenKind::AndKeyword),
+            "or" => Some(TokenKind::OrKeyword),
+            "include" => Some(TokenKind::Include),
+            "deprecated" => Some(TokenKind::Deprecated),
+            "note" => Some(TokenKind::Note),
+            "since" => Some(TokenKind::Since),
+            // DOL 2.0 boolean and null literals
+            "true" => Some(TokenKind::True),
+            "false" => Some(TokenKind::False),
+            "null" => Some(TokenKind::Null),
+            _ => None,
+        }
+    }
+
+    /// Returns the source spelling of a keyword kind, or `None` if this
+    /// kind is not a keyword.
+    pub fn keyword_str(&self) -> Option<&'static str> {
+        match self {
+            // DOL 1.x keywords
+            TokenKind::Gene => Some("gene"),
+            TokenKind::Trait => Some("trait"),
+            TokenKind::Constraint => Some("constraint"),
+            TokenKind::System => Some("system"),
+            TokenKind::Evolves => Some("evolves"),
+            TokenKind::Exegesis => Some("exegesis"),
+            TokenKind::Has => Some("has"),
+            TokenKind::Is => Some("is"),
+            TokenKind::Derives => Some("derives"),
+            TokenKind::From => Some("from"),
+            TokenKind::Requires => Some("requires"),
+            TokenKind::Uses => Some("uses"),
+            TokenKind::Emits => Some("emits"),
+            TokenKind::Matches => Some("matches"),
+            TokenKind::Never => Some("never"),
+            TokenKind::Adds => Some("adds"),
+            TokenKind::Deprecates => Some("deprecates"),
+            TokenKind::Removes => Some("removes"),
+            TokenKind::Because => Some("because"),
+            TokenKind::Test => Some("test"),
+            TokenKind::Given => Some("given"),
+            TokenKind::When => Some("when"),
+            TokenKind::Then => Some("then"),
+            TokenKind::Always => Some("always"),
+            TokenKind::Each => Some("each"),
+            TokenKind::All => Some("all"),
+            TokenKind::No => Some("no"),
+            // DOL 2.0 control flow keywords
+            TokenKind::Let => Some("let"),
+            TokenKind::If => Some("if"),
+            TokenKind::Else => Some("else"),
+            TokenKind::Match => Some("match"),
+            TokenKind::For => Some("for"),
+            TokenKind::While => Some("while"),
+            TokenKind::Loop => Some("loop"),
+            TokenKind::Break => Some("break"),
+            TokenKind::Continue => Some("continue"),
+            TokenKind::Return => Some("return"),
+            TokenKind::In => Some("in"),
+            TokenKind::Where => Some("where"),
+            // DOL 2.0 type keywords
+            TokenKind::Int8 => Some("Int8"),
+            TokenKind::Int16 => Some("Int16"),
+            TokenKind::Int32 => Some("Int32"),
+            TokenKind::Int64 => Some("Int64"),
+            TokenKind::UInt8 => Some("UInt8"),
+            TokenKind::UInt16 => Some("UInt16"),
+            TokenKind::UInt32 => Some("UInt32"),
+            TokenKind::UInt64 => Some("UInt64"),
+            TokenKind::Float32 => Some("Float32"),
+            TokenKind::Float64 => Some("Float64"),
+            TokenKind::BoolType => Some("Bool"),
+            TokenKind::StringType => Some("String"),
+            TokenKind::VoidType => Some("Void"),
+            TokenKind::Function => Some("fun"),
+            // DOL 2.0 visibility keywords
+            TokenKind::Pub => Some("pub"),
+            TokenKind::Module => Some("module"),
+            TokenKind::Use => Some("use"),
+            TokenKind::Spirit => Some("spirit"),
+            // DOL 2.0 SEX keywords
+            TokenKind::Sex => Some("sex"),
+            TokenKind::Var => Some("var"),
+            TokenKind::Val => Some("val"),
+            TokenKind::Const => Some("const"),
+            TokenKind::Extern => Some("extern"),
+            // DOL 2.0 logic keywords
+            TokenKind::Implies => Some("implies"),
+            TokenKind::Forall => Some("forall"),
+            TokenKind::Exists => Some("exists"),
+            // DOL 2.0 other keywords
+            TokenKind::Impl => Some("impl"),
+            TokenKind::As => Some("as"),
+            TokenKind::State => Some("state"),
+            TokenKind::Law => Some("law"),
+            TokenKind::Mut => Some("mut"),
+            TokenKind::Not => Some("not"),
+            TokenKind::Migrate => Some("migrate"),
+            TokenKind::Extends => Some("extends"),
+            TokenKind::Type => Some("type"),
+            TokenKind::Enum => Some("enum"),
+            TokenKind::Parent => Some("parent"),
+            TokenKind::To => Some("to"),
+            TokenKind::AndKeyword => Some("and"),
+            TokenKind::OrKeyword => Some("or"),
+            TokenKind::Include => Some("include"),
+            TokenKind::Deprecated => Some("deprecated"),
+            TokenKind::Note => Some("note"),
+            TokenKind::Since => Some("since"),
+            // DOL 2.0 boolean and null literals
+            TokenKind::True => Some("true"),
+            TokenKind::False => Some("false"),
+            TokenKind::Null => Some("null"),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this keyword is only recognized in specific
+    /// positions.
+    ///
+    /// The lexer emits contextual keywords as identifiers so they stay
+    /// usable as names; the parser matches them where they apply.
+    pub fn is_contextual_keyword(&self) -> bool {
         matches!(
             self,
-            TokenKind::Gene
-                | TokenKind::Trait
-                | TokenKind::Constraint
-                | TokenKind::System
-                | TokenKind::Evolves
-                | TokenKind::Exegesis
-                | TokenKind::Has
-                | TokenKind::Is
-                | TokenKind::Derives
-                | TokenKind::From
-                | TokenKind::Requires
-                | TokenKind::Uses
-                | TokenKind::Emits
-                | TokenKind::Matches
-                | TokenKind::Never
-                | TokenKind::Adds
-                | TokenKind::Deprecates
-                | TokenKind::Removes
-                | TokenKind::Because
-                | TokenKind::Test
-                | TokenKind::Given
-                | TokenKind::When
-                | TokenKind::Then
-                | TokenKind::Always
-                | TokenKind::Each
-                | TokenKind::All
-                | TokenKind::No
-                // DOL 2.0 Control Flow Keywords
-                | TokenKind::Let
-                | TokenKind::If
-                | TokenKind::Else
-                | TokenKind::Match
-                | TokenKind::For
-                | TokenKind::While
-                | TokenKind::Loop
-                | TokenKind::Break
-                | TokenKind::Continue
-                | TokenKind::Return
-                | TokenKind::In
-                | TokenKind::Where
-                // DOL 2.0 Type Keywords
-                | TokenKind::Int8
-                | TokenKind::Int16
-                | TokenKind::Int32
-                | TokenKind::Int64
-                | TokenKind::UInt8
-                | TokenKind::UInt16
-                | TokenKind::UInt32
-                | TokenKind::UInt64
-                | TokenKind::Float32
-                | TokenKind::Float64
-                | TokenKind::BoolType
-                | TokenKind::StringType
-                | TokenKind::VoidType
-                // DOL 2.0 Function Keyword
-                | TokenKind::Function
-                // DOL 2.0 Visibility Keywords
-                | TokenKind::Pub
-                | TokenKind::Module
-                | TokenKind::Use
-                | TokenKind::Spirit
-                // DOL 2.0 SEX Keywords
-                | TokenKind::Sex
-                | TokenKind::Var
-                | TokenKind::Val
-                | TokenKind::Const
-                | TokenKind::Extern
-                // DOL 2.0 Logic Keywords
-                | TokenKind::Implies
-                | TokenKind::Forall
-                | TokenKind::Exists
-                // DOL 2.0 Other Keywords
-                | TokenKind::Impl
-                | TokenKind::As
-                | TokenKind::State
-                | TokenKind::Law
-                | TokenKind::Mut
-                | TokenKind::Not
-                | TokenKind::Migrate
-                | TokenKind::Extends
-                | TokenKind::Type
-                // DOL 2.0 Boolean and Null Literals
-                | TokenKind::True
-                | TokenKind::False
-                | TokenKind::Null
+            TokenKind::Enum
+                | TokenKind::Parent
+                | TokenKind::To
+                | TokenKind::AndKeyword
+                | TokenKind::OrKeyword
+                | TokenKind::Include
+                | TokenKind::Deprecated
+                | TokenKind::Note
+                | TokenKind::Since
         )
     }
 
+    /// Returns true if this is a keyword.
+    pub fn is_keyword(&self) -> bool {
+        self.keyword_str().is_some()
+    }
+
     /// Returns true if this is a predicate keyword.
     pub fn is_predicate(&self) -> bool {
         matches!(
@@ -594,6 +767,15 @@ impl std::fmt::Display for TokenKind {
             TokenKind::Migrate => write!(f, "migrate"),
             TokenKind::Extends => write!(f, "extends"),
             TokenKind::Type => write!(f, "type"),
+            TokenKind::Enum => write!(f, "enum"),
+            TokenKind::Parent => write!(f, "parent"),
+            TokenKind::To => write!(f, "to"),
+            TokenKind::AndKeyword => write!(f, "and"),
+            TokenKind::OrKeyword => write!(f, "or"),
+            TokenKind::Include => write!(f, "include"),
+            TokenKind::Deprecated => write!(f, "deprecated"),
+            TokenKind::Note => write!(f, "note"),
+            TokenKind::Since => write!(f, "since"),
             // DOL 2.0 Boolean and Null Literals
             TokenKind::True => write!(f, "true"),
             TokenKind::False => write!(f, "false"),
@@ -635,7 +817,12 @@ impl std::fmt::Display for TokenKind {
             TokenKind::Identifier => write!(f, "identifier"),
             TokenKind::Version => write!(f, "version"),
             TokenKind::String => write!(f, "string"),
+            TokenKind::FormatString => write!(f, "format string"),
             TokenKind::Char => write!(f, "char"),
+            // Trivia
+            TokenKind::Whitespace => write!(f, "whitespace"),
+            TokenKind::LineComment => write!(f, "line comment"),
+            TokenKind::BlockComment => write!(f, "block comment"),
             // Special
             TokenKind::Eof => write!(f, "end of file"),
             TokenKind::Error => write!(f, "error"),
@@ -643,6 +830,61 @@ impl std::fmt::Display for TokenKind {
     }
 }
 
+/// A DOL language version, selecting which words are reserved as keywords.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// DOL 1.x: only the ontology keywords (`gene`, `has`, `each`, ...).
+    /// DOL 2.0 words such as `let`, `fun` and `forall` lex as identifiers.
+    Dol1,
+    /// DOL 2.0: the full keyword set, with the legacy quantifiers `each`
+    /// and `all` (superseded by `forall`) lexed as identifiers.
+    Dol2,
+}
+
+/// Finds the `}` closing an interpolation in a format string.
+///
+/// `hole` is the text after `${`. Nested braces and string literals are
+/// skipped, so `${greet("}")}` ends at the last brace. Returns `None` if
+/// the interpolation is not closed on the same line.
+pub(crate) fn interpolation_end(hole: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, ch) in hole.char_indices() {
+        match ch {
+            '\n' => return None,
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Configuration for a [`Lexer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LexerConfig {
+    /// The active dialect, or `None` to accept the keywords of every dialect
+    pub dialect: Option<Dialect>,
+    /// Emit whitespace and comment tokens instead of skipping them
+    pub preserve_trivia: bool,
+}
+
+impl LexerConfig {
+    /// Creates a configuration restricted to a single dialect.
+    pub fn dialect(dialect: Dialect) -> Self {
+        Self {
+            dialect: Some(dialect),
+            ..Self::default()
+        }
+    }
+}
+
 /// The lexer for Metal DOL source text.
 ///
 /// The lexer maintains internal state as it scans through source text,
@@ -683,6 +925,12 @@ pub struct Lexer<'a> {
 
     /// Accumulated errors
     errors: Vec<LexError>,
+
+    /// Keyword set configuration
+    config: LexerConfig,
+
+    /// Whether the iterator has yielded the `Eof` token
+    finished: bool,
 }
 
 impl<'a> Lexer<'a> {
@@ -696,6 +944,20 @@ impl<'a> Lexer<'a> {
     ///
     /// A new `Lexer` instance positioned at the start of the source
     pub fn new(source: &'a str) -> Self {
+        Self::with_config(source, LexerConfig::default())
+    }
+
+    /// Creates a new lexer with the given configuration.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metadol::lexer::{Dialect, Lexer, LexerConfig, TokenKind};
+    ///
+    /// let mut lexer = Lexer::with_config("let", LexerConfig::dialect(Dialect::Dol1));
+    /// assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+    /// ```
+    pub fn with_config(source: &'a str, config: LexerConfig) -> Self {
         Lexer {
             source,
             remaining: source,
@@ -703,9 +965,45 @@ impl<'a> Lexer<'a> {
             line: 1,
             column: 1,
             errors: Vec::new(),
+            config,
+            finished: false,
         }
     }
 
+    /// Creates a lexer that emits whitespace and comments as tokens.
+    ///
+    /// Trivia tokens ([`TokenKind::Whitespace`], [`TokenKind::LineComment`]
+    /// and [`TokenKind::BlockComment`]) are interleaved with the other
+    /// tokens, so their spans tile the source exactly. Block comments are
+    /// only recognized in this mode; [`Lexer::new`] lexes `/*` as `/` `*`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metadol::lexer::{Lexer, TokenKind};
+    ///
+    /// let kinds: Vec<_> = Lexer::with_trivia("gene // note\n").map(|t| t.kind).collect();
+    /// assert_eq!(
+    ///     kinds,
+    ///     [
+    ///         TokenKind::Gene,
+    ///         TokenKind::Whitespace,
+    ///         TokenKind::LineComment,
+    ///         TokenKind::Whitespace,
+    ///         TokenKind::Eof,
+    ///     ]
+    /// );
+    /// ```
+    pub fn with_trivia(source: &'a str) -> Self {
+        Self::with_config(
+            source,
+            LexerConfig {
+                preserve_trivia: true,
+                ..LexerConfig::default()
+            },
+        )
+    }
+
     /// Returns any errors accumulated during lexing.
     pub fn errors(&self) -> &[LexError] {
         &self.errors
@@ -716,7 +1014,13 @@ impl<'a> Lexer<'a> {
     /// Advances the lexer position and returns the next token.
     /// Returns `TokenKind::Eof` when the source is exhausted.
     pub fn next_token(&mut self) -> Token {
-        self.skip_whitespace_and_comments();
+        if self.config.preserve_trivia {
+            if let Some(token) = self.try_trivia() {
+                return token;
+            }
+        } else {
+            self.skip_whitespace_and_comments();
+        }
 
         if self.remaining.is_empty() {
             return Token::new(
@@ -805,16 +1109,76 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    /// Tries to lex a string literal.
+    /// Tries to lex a whitespace or comment token.
+    fn try_trivia(&mut self) -> Option<Token> {
+        let start_pos = self.position;
+        let start_line = self.line;
+        let start_col = self.column;
+
+        let kind = if self.remaining.starts_with(char::is_whitespace) {
+            self.skip_whitespace();
+            TokenKind::Whitespace
+        } else if self.remaining.starts_with("//") || self.remaining.starts_with("--") {
+            let len = self.remaining.find('\n').unwrap_or(self.remaining.len());
+            self.advance(len);
+            TokenKind::LineComment
+        } else if self.remaining.starts_with("/*") {
+            return Some(self.lex_block_comment());
+        } else {
+            return None;
+        };
+
+        Some(Token::new(
+            kind,
+            &self.source[start_pos..self.position],
+            Span::new(start_pos, self.position, start_line, start_col),
+        ))
+    }
+
+    /// Lexes a `/* ... */` comment. Block comments do not nest.
+    fn lex_block_comment(&mut self) -> Token {
+        let start_pos = self.position;
+        let start_line = self.line;
+        let start_col = self.column;
+
+        let len = match self.remaining[2..].find("*/") {
+            Some(end) => end + 4,
+            None => {
+                self.errors.push(LexError::UnterminatedComment {
+                    span: Span::new(start_pos, start_pos + 2, start_line, start_col),
+                });
+                self.remaining.len()
+            }
+        };
+        self.advance(len);
+
+        Token::new(
+            TokenKind::BlockComment,
+            &self.source[start_pos..self.position],
+            Span::new(start_pos, self.position, start_line, start_col),
+        )
+    }
+
+    /// Tries to lex a string literal, or a format string prefixed with `f`.
+    ///
+    /// A format string's `${...}` interpolations are lexed verbatim, without
+    /// escapes, so they may contain string literals of their own.
     fn try_string(&mut self) -> Option<Token> {
-        if !self.remaining.starts_with('"') {
+        let kind = if self.remaining.starts_with('"') {
+            TokenKind::String
+        } else if self.remaining.starts_with("f\"") {
+            TokenKind::FormatString
+        } else {
             return None;
-        }
+        };
 
         let start_pos = self.position;
         let start_line = self.line;
         let start_col = self.column;
 
+        if kind == TokenKind::FormatString {
+            self.advance(1); // Skip the `f` prefix
+        }
         self.advance(1); // Skip opening quote
 
         let mut content = String::new();
@@ -847,10 +1211,20 @@ impl<'a> Lexer<'a> {
             } else if ch == '\\' {
                 escaped = true;
                 self.advance(ch.len_utf8());
+            } else if let Some(len) = self
+                .remaining
+                .strip_prefix("${")
+                .filter(|_| kind == TokenKind::FormatString)
+                .and_then(interpolation_end)
+            {
+                // Interpolations are kept verbatim, quotes included
+                let len = len + 3;
+                content.push_str(&self.remaining[..len]);
+                self.advance(len);
             } else if ch == '"' {
                 self.advance(1); // Skip closing quote
                 return Some(Token::new(
-                    TokenKind::String,
+                    kind,
                     content,
                     Span::new(start_pos, self.position, start_line, start_col),
                 ));
@@ -1017,7 +1391,9 @@ impl<'a> Lexer<'a> {
             (TokenKind::Star, 1)
         } else if self.remaining.starts_with('/') {
             // Check if this is a comment, not division
-            if self.remaining.starts_with("//") {
+            if self.remaining.starts_with("//")
+                || (self.config.preserve_trivia && self.remaining.starts_with("/*"))
+            {
                 return None;
             }
             (TokenKind::Slash, 1)
@@ -1179,98 +1555,68 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    /// Returns the keyword kind for a lexeme, if it's a keyword.
+    /// Returns the keyword kind for a lexeme, if it's a keyword in the
+    /// configured dialect.
     fn keyword_kind(&self, lexeme: &str) -> Option<TokenKind> {
-        match lexeme {
-            // DOL 1.x keywords
-            "gene" => Some(TokenKind::Gene),
-            "trait" => Some(TokenKind::Trait),
-            "constraint" => Some(TokenKind::Constraint),
-            "system" => Some(TokenKind::System),
-            "evolves" => Some(TokenKind::Evolves),
-            "exegesis" => Some(TokenKind::Exegesis),
-            "has" => Some(TokenKind::Has),
-            "is" => Some(TokenKind::Is),
-            "derives" => Some(TokenKind::Derives),
-            "from" => Some(TokenKind::From),
-            "requires" => Some(TokenKind::Requires),
-            "uses" => Some(TokenKind::Uses),
-            "emits" => Some(TokenKind::Emits),
-            "matches" => Some(TokenKind::Matches),
-            "never" => Some(TokenKind::Never),
-            "adds" => Some(TokenKind::Adds),
-            "deprecates" => Some(TokenKind::Deprecates),
-            "removes" => Some(TokenKind::Removes),
-            "because" => Some(TokenKind::Because),
-            "test" => Some(TokenKind::Test),
-            "given" => Some(TokenKind::Given),
-            "when" => Some(TokenKind::When),
-            "then" => Some(TokenKind::Then),
-            "always" => Some(TokenKind::Always),
-            "each" => Some(TokenKind::Each),
-            "all" => Some(TokenKind::All),
-            "no" => Some(TokenKind::No),
-            // DOL 2.0 control flow keywords
-            "let" => Some(TokenKind::Let),
-            "if" => Some(TokenKind::If),
-            "else" => Some(TokenKind::Else),
-            "match" => Some(TokenKind::Match),
-            "for" => Some(TokenKind::For),
-            "while" => Some(TokenKind::While),
-            "loop" => Some(TokenKind::Loop),
-            "break" => Some(TokenKind::Break),
-            "continue" => Some(TokenKind::Continue),
-            "return" => Some(TokenKind::Return),
-            "in" => Some(TokenKind::In),
-            "where" => Some(TokenKind::Where),
-            // DOL 2.0 type keywords
-            "Int8" => Some(TokenKind::Int8),
-            "Int16" => Some(TokenKind::Int16),
-            "Int32" => Some(TokenKind::Int32),
-            "Int64" => Some(TokenKind::Int64),
-            "UInt8" => Some(TokenKind::UInt8),
-            "UInt16" => Some(TokenKind::UInt16),
-            "UInt32" => Some(TokenKind::UInt32),
-            "UInt64" => Some(TokenKind::UInt64),
-            "Float32" => Some(TokenKind::Float32),
-            "Float64" => Some(TokenKind::Float64),
-            "Bool" => Some(TokenKind::BoolType),
-            "String" => Some(TokenKind::StringType),
-            "Void" => Some(TokenKind::VoidType),
-            // DOL 2.0 function keyword
-            "fun" => Some(TokenKind::Function),
-            // DOL 2.0 visibility keywords
-            "pub" => Some(TokenKind::Pub),
-            "module" => Some(TokenKind::Module),
-            "mod" => Some(TokenKind::Module), // Short form of module
-            "use" => Some(TokenKind::Use),
-            "spirit" => Some(TokenKind::Spirit),
-            // DOL 2.0 SEX keywords
-            "sex" => Some(TokenKind::Sex),
-            "var" => Some(TokenKind::Var),
-            "val" => Some(TokenKind::Val),
-            "const" => Some(TokenKind::Const),
-            "extern" => Some(TokenKind::Extern),
-            // DOL 2.0 logic keywords
-            "implies" => Some(TokenKind::Implies),
-            "forall" => Some(TokenKind::Forall),
-            "exists" => Some(TokenKind::Exists),
-            // DOL 2.0 other keywords
-            "impl" => Some(TokenKind::Impl),
-            "as" => Some(TokenKind::As),
-            "state" => Some(TokenKind::State),
-            "law" => Some(TokenKind::Law),
-            "mut" => Some(TokenKind::Mut),
-            "not" => Some(TokenKind::Not),
-            "migrate" => Some(TokenKind::Migrate),
-            "extends" => Some(TokenKind::Extends),
-            "type" => Some(TokenKind::Type),
-            // DOL 2.0 boolean and null literals
-            "true" => Some(TokenKind::True),
-            "false" => Some(TokenKind::False),
-            "null" => Some(TokenKind::Null),
-            _ => None,
+        let kind = TokenKind::from_keyword(lexeme).filter(|k| !k.is_contextual_keyword())?;
+        match self.config.dialect {
+            Some(Dialect::Dol1) if !Self::is_dol1_keyword(kind) => None,
+            Some(Dialect::Dol2) if matches!(kind, TokenKind::Each | TokenKind::All) => None,
+            _ => Some(kind),
+        }
+    }
+
+    /// Returns true if the keyword exists in DOL 1.x.
+    fn is_dol1_keyword(kind: TokenKind) -> bool {
+        matches!(
+            kind,
+            TokenKind::Gene
+                | TokenKind::Trait
+                | TokenKind::Constraint
+                | TokenKind::System
+                | TokenKind::Evolves
+                | TokenKind::Exegesis
+                | TokenKind::Has
+                | TokenKind::Is
+                | TokenKind::Derives
+                | TokenKind::From
+                | TokenKind::Requires
+                | TokenKind::Uses
+                | TokenKind::Emits
+                | TokenKind::Matches
+                | TokenKind::Never
+                | TokenKind::Adds
+                | TokenKind::Deprecates
+                | TokenKind::Removes
+                | TokenKind::Because
+                | TokenKind::Test
+                | TokenKind::Given
+                | TokenKind::When
+                | TokenKind::Then
+                | TokenKind::Always
+                | TokenKind::Each
+                | TokenKind::All
+                | TokenKind::No
+        )
+    }
+
+    /// Returns the configuration this lexer was created with.
+    pub(crate) fn config(&self) -> LexerConfig {
+        self.config
+    }
+
+    /// Moves the lexer to the byte offset `position`, so that the next token
+    /// starts there.
+    ///
+    /// Lets the parser resume after text it reads raw, such as exegesis.
+    pub(crate) fn seek(&mut self, position: usize) {
+        if position < self.position {
+            self.remaining = self.source;
+            self.position = 0;
+            self.line = 1;
+            self.column = 1;
         }
+        self.advance(position - self.position);
     }
 
     /// Advances the lexer by the given number of bytes.
@@ -1289,16 +1635,74 @@ impl<'a> Lexer<'a> {
     }
 }
 
+/// Yields tokens lazily, ending with the `Eof` token.
+///
+/// The `Eof` token is the last item; its span is empty and sits at the end
+/// of the source. Tokens come out in source order without overlapping, and
+/// with [`Lexer::with_trivia`] their spans tile the whole source.
 impl<'a> Iterator for Lexer<'a> {
     type Item = Token;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
         let token = self.next_token();
-        if token.kind == TokenKind::Eof {
-            None
-        } else {
-            Some(token)
+        self.finished = token.kind == TokenKind::Eof;
+        Some(token)
+    }
+}
+
+impl std::iter::FusedIterator for Lexer<'_> {}
+
+/// Tokenizes `source`, including the final `Eof` token.
+///
+/// Collects the [`Lexer`] iterator; use the lexer directly to process tokens
+/// without allocating them all.
+///
+/// # Example
+///
+/// ```rust
+/// use metadol::lexer::{tokenize, TokenKind};
+///
+/// let tokens = tokenize("gene x { }");
+/// assert_eq!(tokens.len(), 5);
+/// assert_eq!(tokens[4].kind, TokenKind::Eof);
+/// assert_eq!(tokens[4].span.start, 10);
+/// ```
+pub fn tokenize(source: &str) -> Vec<Token> {
+    Lexer::new(source).collect()
+}
+
+/// Returns the token under byte `offset` in `source`, for hover and
+/// selection support.
+///
+/// The source is lexed lazily up to the offset. A token matches if its span
+/// contains the offset, or if it ends exactly at the offset (a cursor just
+/// after an identifier still hovers it). Offsets inside whitespace or
+/// comments, and past the end of the source, return `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use metadol::lexer::{token_at, TokenKind};
+///
+/// let token = token_at("gene container.exists { }", 8).unwrap();
+/// assert_eq!(token.kind, TokenKind::Identifier);
+/// assert_eq!(token.lexeme, "container.exists");
+/// ```
+pub fn token_at(source: &str, offset: usize) -> Option<Token> {
+    let mut lexer = Lexer::new(source);
+    let mut adjacent = None;
+    loop {
+        let token = lexer.next_token();
+        if token.kind == TokenKind::Eof || token.span.start > offset {
+            return adjacent;
+        }
+        if offset < token.span.end {
+            return Some(token);
         }
+        adjacent = (token.span.end == offset).then_some(token);
     }
 }
 
@@ -1530,4 +1934,243 @@ mod tests {
         assert_eq!(lexer.next_token().kind, TokenKind::Forall);
         assert_eq!(lexer.next_token().kind, TokenKind::Type);
     }
+
+    #[test]
+    fn test_dialect_keyword_sets() {
+        let dol1 = LexerConfig::dialect(Dialect::Dol1);
+        let dol2 = LexerConfig::dialect(Dialect::Dol2);
+
+        let mut lexer = Lexer::with_config("let each forall gene", dol2);
+        assert_eq!(lexer.next_token().kind, TokenKind::Let);
+        assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+        assert_eq!(lexer.next_token().kind, TokenKind::Forall);
+        assert_eq!(lexer.next_token().kind, TokenKind::Gene);
+
+        let mut lexer = Lexer::with_config("let each forall gene", dol1);
+        assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+        assert_eq!(lexer.next_token().kind, TokenKind::Each);
+        assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+        assert_eq!(lexer.next_token().kind, TokenKind::Gene);
+    }
+
+    #[test]
+    fn test_default_config_accepts_all_dialects() {
+        let mut lexer = Lexer::new("let each");
+        assert_eq!(lexer.next_token().kind, TokenKind::Let);
+        assert_eq!(lexer.next_token().kind, TokenKind::Each);
+    }
+
+    #[test]
+    fn test_token_at_identifier() {
+        let source = "gene container.exists {\n  container has id\n}";
+        let token = token_at(source, 10).unwrap();
+        assert_eq!(token.kind, TokenKind::Identifier);
+        assert_eq!(token.lexeme, "container.exists");
+
+        // A cursor just past the identifier still hovers it
+        let token = token_at(source, 21).unwrap();
+        assert_eq!(token.lexeme, "container.exists");
+    }
+
+    #[test]
+    fn test_token_at_keyword() {
+        let source = "gene container.exists {\n  container has id\n}";
+        assert_eq!(token_at(source, 0).unwrap().kind, TokenKind::Gene);
+        assert_eq!(token_at(source, 37).unwrap().kind, TokenKind::Has);
+    }
+
+    #[test]
+    fn test_token_at_whitespace_and_comments() {
+        let source = "gene  container.exists // note\n{ }";
+        assert!(token_at(source, 5).is_none());
+        assert!(token_at(source, 26).is_none());
+        assert!(token_at(source, source.len() + 10).is_none());
+    }
+
+    #[test]
+    fn test_keyword_round_trip() {
+        let keywords = [
+            "gene",
+            "trait",
+            "constraint",
+            "system",
+            "evolves",
+            "exegesis",
+            "has",
+            "is",
+            "derives",
+            "from",
+            "requires",
+            "uses",
+            "emits",
+            "matches",
+            "never",
+            "adds",
+            "deprecates",
+            "removes",
+            "because",
+            "test",
+            "given",
+            "when",
+            "then",
+            "always",
+            "each",
+            "all",
+            "no",
+            "let",
+            "if",
+            "else",
+            "match",
+            "for",
+            "while",
+            "loop",
+            "break",
+            "continue",
+            "return",
+            "in",
+            "where",
+            "Int8",
+            "Int16",
+            "Int32",
+            "Int64",
+            "UInt8",
+            "UInt16",
+            "UInt32",
+            "UInt64",
+            "Float32",
+            "Float64",
+            "Bool",
+            "String",
+            "Void",
+            "fun",
+            "pub",
+            "module",
+            "use",
+            "spirit",
+            "sex",
+            "var",
+            "val",
+            "const",
+            "extern",
+            "implies",
+            "forall",
+            "exists",
+            "impl",
+            "as",
+            "state",
+            "law",
+            "mut",
+            "not",
+            "migrate",
+            "extends",
+            "type",
+            "enum",
+            "parent",
+            "to",
+            "and",
+            "or",
+            "include",
+            "deprecated",
+            "note",
+            "since",
+            "true",
+            "false",
+            "null",
+        ];
+        for word in keywords {
+            let kind = TokenKind::from_keyword(word).unwrap();
+            assert_eq!(kind.keyword_str(), Some(word));
+            assert!(kind.is_keyword());
+
+            let token = Lexer::new(word).next_token();
+            if kind.is_contextual_keyword() {
+                assert_eq!(token.kind, TokenKind::Identifier);
+            } else {
+                assert_eq!(token.kind, kind);
+            }
+        }
+
+        assert_eq!(TokenKind::from_keyword("mod"), Some(TokenKind::Module));
+        assert_eq!(TokenKind::from_keyword("container"), None);
+        assert_eq!(TokenKind::Identifier.keyword_str(), None);
+    }
+
+    #[test]
+    fn test_default_lexer_does_not_skip_block_comments() {
+        let kinds: Vec<_> = Lexer::new("src/*.rs").map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Identifier,
+                TokenKind::Slash,
+                TokenKind::Star,
+                TokenKind::Dot,
+                TokenKind::Identifier,
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trivia_tokens() {
+        let source = "gene x { -- old\n  /* a { } */ x has id // done\n}";
+        let tokens: Vec<Token> = Lexer::with_trivia(source).collect();
+
+        let rebuilt: String = tokens
+            .iter()
+            .map(|t| &source[t.span.start..t.span.end])
+            .collect();
+        assert_eq!(rebuilt, source);
+
+        let comments: Vec<_> = tokens
+            .iter()
+            .filter(|t| matches!(t.kind, TokenKind::LineComment | TokenKind::BlockComment))
+            .map(|t| (t.kind, t.lexeme.as_str(), t.span.line))
+            .collect();
+        assert_eq!(
+            comments,
+            [
+                (TokenKind::LineComment, "-- old", 1),
+                (TokenKind::BlockComment, "/* a { } */", 2),
+                (TokenKind::LineComment, "// done", 2),
+            ]
+        );
+
+        let significant: Vec<_> = tokens
+            .iter()
+            .filter(|t| {
+                !matches!(
+                    t.kind,
+                    TokenKind::Whitespace | TokenKind::LineComment | TokenKind::BlockComment
+                )
+            })
+            .map(|t| t.kind)
+            .collect();
+        assert_eq!(
+            significant,
+            [
+                TokenKind::Gene,
+                TokenKind::Identifier,
+                TokenKind::LeftBrace,
+                TokenKind::Identifier,
+                TokenKind::Has,
+                TokenKind::Identifier,
+                TokenKind::RightBrace,
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() {
+        let mut lexer = Lexer::with_trivia("gene /* never closed");
+        assert_eq!(lexer.next_token().kind, TokenKind::Gene);
+        assert_eq!(lexer.next_token().kind, TokenKind::Whitespace);
+        assert_eq!(lexer.next_token().kind, TokenKind::BlockComment);
+        assert_eq!(lexer.next_token().kind, TokenKind::Eof);
+        assert!(matches!(
+            lexer.errors(),
+            [LexError::UnterminatedComment { .. }]
+        ));
+    }
 }
@@ -50,12 +50,19 @@
 //! - [`lexer`]: Tokenization of DOL source text
 //! - [`parser`]: Recursive descent parser producing AST
 //! - [`error`]: Error types with source location information
+//! - [`diagnostic`]: Severity-tagged diagnostics and report grouping
 //! - [`validator`]: Semantic validation rules
+//! - [`repository`]: Declaration index with evolution lineage
 //! - [`typechecker`]: DOL 2.0 type inference and checking
+//! - [`unparse`]: Re-emitting DOL source from edited declarations
+//! - [`format`]: Canonical formatting of DOL source
 //! - [`eval`]: Expression evaluation for DOL 2.0
+//! - [`examples`]: Sample JSON instances for genes (requires `serde` feature)
 //! - [`macros`]: Macro system for compile-time metaprogramming
 //! - [`transform`]: AST transformation framework with passes
 //! - [`codegen`]: Code generation from DOL declarations
+//! - [`naming`]: Identifier casing shared by codegen and the type checker
+//! - [`compiler`]: Compilation pipelines with aggregated, source-mapped errors
 //! - [`sex`]: Side Effect eXecution system for purity tracking
 //! - [`mcp`]: Model Context Protocol server (requires `serde` feature)
 //! - [`mlir`]: MLIR code generation backend (requires `mlir` feature)
@@ -69,24 +76,33 @@
 
 pub mod ast;
 pub mod codegen;
+pub mod diagnostic;
 pub mod error;
 pub mod eval;
+pub mod format;
 pub mod hir;
 pub mod lexer;
 pub mod lower;
 pub mod macros;
+pub mod naming;
 pub mod parser;
 pub mod pratt;
 pub mod reflect;
+pub mod repository;
 pub mod sex;
 pub mod transform;
 pub mod typechecker;
+pub mod unparse;
 pub mod validator;
 
 // Hyphal network modules
 pub mod network;
 pub mod swarm;
 
+// Sample JSON generation (requires serde feature)
+#[cfg(feature = "serde")]
+pub mod examples;
+
 // MCP server (requires serde feature)
 #[cfg(feature = "serde")]
 pub mod mcp;
@@ -98,8 +114,7 @@ pub mod mlir;
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
-// Spirit compiler (requires wasm feature)
-#[cfg(feature = "wasm")]
+// Compilation pipelines (the WASM pipeline requires the wasm feature)
 pub mod compiler;
 
 // Test file parser for .dol.test files
@@ -107,13 +122,16 @@ pub mod compiler;
 pub mod test_parser;
 
 // Re-exports for convenience
-pub use ast::{Constraint, Declaration, DolFile, Evolution, Gene, Span, Statement, System, Trait};
+pub use ast::{
+    Constraint, Declaration, DeclarationKind, DolFile, Evolution, Gene, Span, Statement, System,
+    Trait,
+};
 pub use error::{LexError, ParseError, ValidationError};
 pub use eval::{EvalError, Interpreter, Value};
-pub use lexer::{Lexer, Token, TokenKind};
+pub use lexer::{Dialect, Lexer, LexerConfig, Token, TokenKind};
 pub use parser::Parser;
 pub use typechecker::{Type, TypeChecker, TypeEnv, TypeError};
-pub use validator::{validate, ValidationResult};
+pub use validator::{validate, validate_file, ValidationResult};
 
 // Codegen re-exports
 pub use codegen::{
@@ -129,12 +147,13 @@ pub use macros::{
 
 // Transform framework re-exports
 pub use transform::{
-    ConstantFolding, DeadCodeElimination, Fold, MutVisitor, Pass, PassConfig, PassError,
+    ConstantFolding, DeadCodeElimination, Fold, MutVisitor, Normalize, Pass, PassConfig, PassError,
     PassPipeline, PassResult, PassStats, Visitor,
 };
 
 // Reflection system re-exports
 pub use reflect::{FieldInfo, MethodInfo, TypeInfo, TypeKind, TypeRegistry};
+pub use repository::Repository;
 
 // SEX (Side Effect eXecution) system re-exports
 pub use sex::{
@@ -238,6 +257,38 @@ pub fn parse_file_all(source: &str) -> Result<Vec<Declaration>, ParseError> {
     parser.parse_all()
 }
 
+/// The path used to label diagnostics for source read from standard input.
+pub const STDIN_PATH: &str = "<stdin>";
+
+/// Parse all declarations from standard input.
+///
+/// Reads stdin to the end and parses it like [`parse_file_all`]. Report
+/// errors with [`ParseError::located`] and [`STDIN_PATH`] so they point at
+/// `<stdin>`.
+pub fn parse_stdin() -> Result<Vec<Declaration>, ParseError> {
+    parse_reader(std::io::stdin().lock())
+}
+
+/// Parse all declarations from a reader.
+///
+/// # Example
+///
+/// ```rust
+/// use metadol::{parse_reader, STDIN_PATH};
+///
+/// let err = parse_reader("gene {".as_bytes()).unwrap_err();
+/// assert!(err.located(STDIN_PATH).starts_with("<stdin>:1:"));
+/// ```
+pub fn parse_reader(mut reader: impl std::io::Read) -> Result<Vec<Declaration>, ParseError> {
+    let mut source = String::new();
+    reader
+        .read_to_string(&mut source)
+        .map_err(|e| ParseError::Io {
+            message: e.to_string(),
+        })?;
+    parse_file_all(&source)
+}
+
 /// Parse a complete DOL file with module and use declarations.
 ///
 /// Returns a `DolFile` containing the module declaration (if any),
@@ -293,10 +344,78 @@ pub fn parse_and_validate(source: &str) -> Result<(Declaration, ValidationResult
     Ok((decl, validation))
 }
 
+/// Parse a DOL source string and lower it to HIR.
+///
+/// Returns the parsed file alongside the lowered module, so callers that
+/// need both representations don't have to run each stage by hand. The
+/// returned [`lower::LoweringContext`] resolves HIR symbols and holds any
+/// lowering diagnostics. Expressions in the returned file carry spans (see
+/// [`Parser::with_expr_spans`]).
+///
+/// # Example
+///
+/// ```rust
+/// use metadol::parse_and_lower;
+///
+/// let source = "gene example.thing { thing has property }";
+///
+/// let (file, hir, ctx) = parse_and_lower(source)?;
+/// assert_eq!(file.declarations.len(), hir.decls.len());
+/// assert!(ctx.diagnostics().is_empty());
+/// # Ok::<(), metadol::ParseError>(())
+/// ```
+pub fn parse_and_lower(
+    source: &str,
+) -> Result<(ast::DolFile, hir::HirModule, lower::LoweringContext), ParseError> {
+    let file = Parser::new(source).with_expr_spans().parse_file()?;
+    let mut ctx = lower::LoweringContext::new();
+    let hir = lower::lower_module(&mut ctx, &file);
+    Ok((file, hir, ctx))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_reader_labels_stdin() {
+        let decls = parse_reader("gene container.exists { container has id }".as_bytes()).unwrap();
+        assert_eq!(decls.len(), 1);
+
+        let err =
+            parse_reader("gene container.exists {\n  container has\n".as_bytes()).unwrap_err();
+        let located = err.located(STDIN_PATH);
+        let span = err.span();
+        assert!(located.starts_with(&format!("<stdin>:{}:{}: ", span.line, span.column)));
+        assert!(span.line > 1);
+    }
+
+    #[test]
+    fn test_parse_and_lower_keeps_names() {
+        let source = "gene container.exists { container has id }";
+        let (file, hir, ctx) = parse_and_lower(source).unwrap();
+
+        assert!(ctx.diagnostics().is_empty());
+        let hir::HirDecl::Type(ty) = &hir.decls[0] else {
+            panic!("expected a type declaration, got {:?}", hir.decls[0]);
+        };
+        assert_eq!(ctx.resolve(ty.name), Some(file.declarations[0].name()));
+    }
+
+    #[test]
+    fn test_parse_and_lower_spans_expressions() {
+        let source = "fun double(x: Int64) -> Int64 { return x * 2 }";
+        let (file, hir, ctx) = parse_and_lower(source).unwrap();
+
+        assert!(ctx.diagnostics().is_empty());
+        assert_eq!(hir.decls.len(), 1);
+        let mut spans = Vec::new();
+        ast::walk::for_each_expr(&file.declarations[0], &mut |e| spans.extend(e.span()));
+        assert!(spans
+            .iter()
+            .any(|span| &source[span.start..span.end] == "x * 2"));
+    }
+
     #[test]
     fn test_parse_simple_gene() {
         let source = r#"
@@ -42,12 +42,8 @@ impl LoweringContext {
 
     /// Emit a deprecation warning
     pub fn emit_deprecation(&mut self, old: &str, new: &str, span: ast::Span) {
-        self.diagnostics.push(LowerDiagnostic {
-            kind: DiagnosticKind::Deprecation,
-            message: format!("'{}' is deprecated", old),
-            span: Some(span),
-            suggestion: Some(format!("use '{}' instead", new)),
-        });
+        self.diagnostics
+            .push(LowerDiagnostic::deprecation(old, new, span));
     }
 
     /// Emit a warning
@@ -70,6 +66,18 @@ impl LoweringContext {
         });
     }
 
+    /// Emit an error for a construct that has no HIR representation
+    ///
+    /// Used instead of silently dropping the construct during lowering.
+    pub fn emit_unsupported(&mut self, construct: &str, span: ast::Span) {
+        self.diagnostics.push(LowerDiagnostic {
+            kind: DiagnosticKind::Error,
+            message: format!("{} has no HIR representation", construct),
+            span: Some(span),
+            suggestion: None,
+        });
+    }
+
     /// Get all diagnostics
     pub fn diagnostics(&self) -> &[LowerDiagnostic] {
         &self.diagnostics
@@ -141,6 +149,16 @@ mod tests {
         assert!(ctx.has_errors());
     }
 
+    #[test]
+    fn test_unsupported_is_error() {
+        let mut ctx = LoweringContext::new();
+        ctx.emit_unsupported("'adds' clause", ast::Span::new(0, 4, 3, 5));
+        assert!(ctx.has_errors());
+        assert_eq!(ctx.diagnostics()[0].kind, DiagnosticKind::Error);
+        assert_eq!(ctx.diagnostics()[0].span.map(|s| s.line), Some(3));
+        assert!(ctx.diagnostics()[0].message.contains("'adds' clause"));
+    }
+
     #[test]
     fn test_take_diagnostics() {
         let mut ctx = LoweringContext::new();
@@ -34,7 +34,7 @@ impl LoweringContext {
 
     /// Lower a gene declaration to HIR
     pub fn lower_gene(&mut self, gene: &ast::Gene) -> HirDecl {
-        let name = self.intern(&gene.name);
+        let name = self.intern(gene.name.as_str());
         let statements: Vec<HirStatement> = gene
             .statements
             .iter()
@@ -51,20 +51,23 @@ impl LoweringContext {
 
     /// Lower a trait declaration to HIR
     pub fn lower_trait(&mut self, trait_decl: &ast::Trait) -> HirDecl {
-        let name = self.intern(&trait_decl.name);
+        let name = self.intern(trait_decl.name.as_str());
 
-        // Convert statements to trait items
-        let items: Vec<HirTraitItem> = trait_decl
-            .statements
-            .iter()
-            .filter_map(|s| {
-                if let ast::Statement::Function(func) = s {
-                    Some(HirTraitItem::Method(self.lower_function_decl(func)))
-                } else {
-                    None
+        // Convert statements to trait items. Predicate statements describe the
+        // trait contract and have no trait-item form; typed fields have no
+        // HIR mapping at all and are reported rather than dropped.
+        let mut items = Vec::new();
+        for stmt in &trait_decl.statements {
+            match stmt {
+                ast::Statement::Function(func) => {
+                    items.push(HirTraitItem::Method(self.lower_function_decl(func)));
                 }
-            })
-            .collect();
+                ast::Statement::HasField(field) => {
+                    self.emit_unsupported("typed 'has' field in a trait", field.span);
+                }
+                _ => {}
+            }
+        }
 
         HirDecl::Trait(HirTraitDecl {
             id: self.fresh_id(),
@@ -156,6 +159,12 @@ impl LoweringContext {
             }
             ast::TypeExpr::Never => HirType::Error, // Map Never to Error for now
             ast::TypeExpr::Enum { .. } => HirType::Error, // Inline enums not fully supported yet
+            ast::TypeExpr::Record(fields) => HirType::Tuple(
+                fields
+                    .iter()
+                    .map(|(_, t)| self.lower_type_expr(t))
+                    .collect(),
+            ),
         }
     }
 
@@ -167,7 +176,17 @@ impl LoweringContext {
             ast::Declaration::Function(func) => HirDecl::Function(self.lower_function_decl(func)),
             ast::Declaration::Constraint(constraint) => {
                 // Lower constraint as a trait with constraint semantics
-                let name = self.intern(&constraint.name);
+                for stmt in &constraint.statements {
+                    if matches!(
+                        stmt,
+                        ast::Statement::Function(_) | ast::Statement::HasField(_)
+                    ) {
+                        let construct =
+                            format!("{} in a constraint", Self::describe_statement(stmt));
+                        self.emit_unsupported(&construct, stmt.span());
+                    }
+                }
+                let name = self.intern(constraint.name.as_str());
                 HirDecl::Trait(HirTraitDecl {
                     id: self.fresh_id(),
                     name,
@@ -177,8 +196,18 @@ impl LoweringContext {
                 })
             }
             ast::Declaration::System(system) => {
-                // Lower system as a module
-                let name = self.intern(&system.name);
+                // Lower system as a module; its composition is metadata, but
+                // functions and typed fields in it would be lost
+                for stmt in &system.statements {
+                    if matches!(
+                        stmt,
+                        ast::Statement::Function(_) | ast::Statement::HasField(_)
+                    ) {
+                        let construct = format!("{} in a system", Self::describe_statement(stmt));
+                        self.emit_unsupported(&construct, stmt.span());
+                    }
+                }
+                let name = self.intern(system.name.as_str());
                 HirDecl::Module(HirModuleDecl {
                     id: self.fresh_id(),
                     name,
@@ -212,6 +241,15 @@ impl LoweringContext {
                     decls: vec![],
                 })
             }
+            ast::Declaration::Extern(block) => {
+                // Foreign signatures have no bodies to lower; keep the name
+                let name = self.intern(block.name());
+                HirDecl::Module(HirModuleDecl {
+                    id: self.fresh_id(),
+                    name,
+                    decls: vec![],
+                })
+            }
         }
     }
 }
@@ -219,19 +257,22 @@ impl LoweringContext {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::QualifiedName;
 
     #[test]
     fn test_lower_gene() {
         let mut ctx = LoweringContext::new();
         let gene = ast::Gene {
-            name: "container.exists".to_string(),
+            name: QualifiedName::new("container.exists"),
             extends: None,
             statements: vec![ast::Statement::Has {
                 subject: "container".to_string(),
                 property: "identity".to_string(),
                 span: ast::Span::default(),
             }],
-            exegesis: "Test gene".to_string(),
+            exegesis: "Test gene".into(),
+            visibility: ast::Visibility::Private,
+            attributes: vec![],
             span: ast::Span::default(),
         };
 
@@ -130,6 +130,7 @@ exegesis {
         let empty_file = crate::ast::DolFile {
             module: None,
             uses: vec![],
+            includes: vec![],
             declarations: vec![],
         };
         let hir = lower_module(&mut ctx, &empty_file);
@@ -214,19 +215,24 @@ exegesis {
         let file = crate::ast::DolFile {
             module: None,
             uses: vec![],
+            includes: vec![],
             declarations: vec![
                 crate::ast::Declaration::Gene(crate::ast::Gene {
-                    name: "gene.one".to_string(),
+                    name: crate::ast::QualifiedName::new("gene.one"),
                     extends: None,
                     statements: vec![],
-                    exegesis: "First gene".to_string(),
+                    exegesis: "First gene".into(),
+                    visibility: crate::ast::Visibility::Private,
+                    attributes: vec![],
                     span: crate::ast::Span::default(),
                 }),
                 crate::ast::Declaration::Gene(crate::ast::Gene {
-                    name: "gene.two".to_string(),
+                    name: crate::ast::QualifiedName::new("gene.two"),
                     extends: None,
                     statements: vec![],
-                    exegesis: "Second gene".to_string(),
+                    exegesis: "Second gene".into(),
+                    visibility: crate::ast::Visibility::Private,
+                    attributes: vec![],
                     span: crate::ast::Span::default(),
                 }),
             ],
@@ -259,8 +265,9 @@ exegesis {
         let file = crate::ast::DolFile {
             module: None,
             uses: vec![],
+            includes: vec![],
             declarations: vec![crate::ast::Declaration::Gene(crate::ast::Gene {
-                name: "test.comprehensive".to_string(),
+                name: crate::ast::QualifiedName::new("test.comprehensive"),
                 extends: None,
                 statements: vec![
                     crate::ast::Statement::Has {
@@ -288,7 +295,9 @@ exegesis {
                         span: crate::ast::Span::default(),
                     },
                 ],
-                exegesis: "Comprehensive test".to_string(),
+                exegesis: "Comprehensive test".into(),
+                visibility: crate::ast::Visibility::Private,
+                attributes: vec![],
                 span: crate::ast::Span::default(),
             })],
         };
@@ -311,4 +320,51 @@ exegesis {
             _ => panic!("Expected Type declaration"),
         }
     }
+
+    #[test]
+    fn test_lower_reports_unsupported_statement() {
+        let source = r#"
+trait container.sized {
+    uses container.exists
+    has capacity: Int64
+}
+
+exegesis {
+    A trait with a typed field.
+}
+"#;
+        let (hir, ctx) = lower_file(source).unwrap();
+        assert_eq!(hir.decls.len(), 1);
+        assert!(ctx.has_errors());
+
+        let diag = &ctx.diagnostics()[0];
+        assert_eq!(diag.kind, crate::lower::DiagnosticKind::Error);
+        assert!(diag.message.contains("typed 'has' field"), "{}", diag);
+        assert_eq!(diag.span.map(|s| s.line), Some(4));
+    }
+
+    #[test]
+    fn test_lower_metadata_is_not_reported() {
+        let source = r#"
+evolves container.exists from 0.0.1 to 0.0.2 {
+    adds container has name
+    removes legacy_id
+}
+
+exegesis {
+    Containers gain a name.
+}
+
+system container.runtime @ 0.1.0 {
+    requires container.exists >= 0.0.2
+    container has runtime
+}
+
+exegesis {
+    Runs containers.
+}
+"#;
+        let (_hir, ctx) = lower_file(source).unwrap();
+        assert!(ctx.diagnostics().is_empty(), "{:?}", ctx.diagnostics());
+    }
 }
@@ -33,6 +33,7 @@ impl LoweringContext {
     /// It handles all AST expression types and applies desugaring.
     pub fn lower_ast_expr(&mut self, expr: &ast::Expr) -> HirExpr {
         match expr {
+            ast::Expr::Spanned { expr, .. } => self.lower_ast_expr(expr),
             ast::Expr::Literal(lit) => HirExpr::Literal(self.lower_literal(lit)),
 
             ast::Expr::Identifier(name) => HirExpr::Var(self.intern(name)),
@@ -136,6 +137,18 @@ impl LoweringContext {
                 }))
             }
 
+            ast::Expr::Map(entries) => {
+                // Lower map to a call to a 'map' constructor over key/value pairs
+                let args: Vec<HirExpr> = entries
+                    .iter()
+                    .flat_map(|(k, v)| [self.lower_ast_expr(k), self.lower_ast_expr(v)])
+                    .collect();
+                HirExpr::Call(Box::new(HirCallExpr {
+                    func: HirExpr::Var(self.intern("map")),
+                    args,
+                }))
+            }
+
             ast::Expr::Tuple(items) => {
                 // Lower tuple to a block with the last item as the expression
                 // For proper tuple support, we'd need HirExpr::Tuple
@@ -201,11 +214,16 @@ impl LoweringContext {
             }
 
             ast::Expr::Forall(forall) => {
-                // Lower forall to a call
-                let body = self.lower_ast_expr(&forall.body);
+                // Lower forall to a call, passing the iterated collection first
+                let mut args: Vec<HirExpr> = forall
+                    .iter
+                    .iter()
+                    .map(|iter| self.lower_ast_expr(iter))
+                    .collect();
+                args.push(self.lower_ast_expr(&forall.body));
                 HirExpr::Call(Box::new(HirCallExpr {
                     func: HirExpr::Var(self.intern("forall")),
-                    args: vec![body],
+                    args,
                 }))
             }
 
@@ -321,17 +339,6 @@ impl LoweringContext {
                 }))
             }
 
-            // Member access: a.b
-            ast::BinaryOp::Member => {
-                let base = self.lower_ast_expr(left);
-                // Right side should be an identifier
-                let field = match right {
-                    ast::Expr::Identifier(name) => self.intern(name),
-                    _ => self.intern("unknown"),
-                };
-                HirExpr::Field(Box::new(HirFieldExpr { base, field }))
-            }
-
             // Map functor: f <$> a
             ast::BinaryOp::Map => {
                 let func = self.lower_ast_expr(left);
@@ -538,6 +545,15 @@ impl LoweringContext {
                 })
             }
 
+            ast::Stmt::LetPattern { pattern, value } => {
+                let init = self.lower_ast_expr(value);
+                HirStmt::Val(HirValStmt {
+                    pat: self.lower_pattern(pattern),
+                    ty: None,
+                    init,
+                })
+            }
+
             ast::Stmt::Assign { target, value } => {
                 let lhs = self.lower_ast_expr(target);
                 let rhs = self.lower_ast_expr(value);
@@ -686,7 +702,6 @@ impl LoweringContext {
             | crate::ast::BinaryOp::Compose
             | crate::ast::BinaryOp::Apply
             | crate::ast::BinaryOp::Bind
-            | crate::ast::BinaryOp::Member
             | crate::ast::BinaryOp::Map
             | crate::ast::BinaryOp::Ap
             | crate::ast::BinaryOp::Implies
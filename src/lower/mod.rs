@@ -28,6 +28,13 @@
 //! ## Types
 //! - `gene Foo { }` -> `HirDecl::Type { }` (supported)
 //! - `type Foo { }` -> `HirDecl::Type { }` (preferred)
+//!
+//! ## Unsupported Constructs
+//! Functions and typed fields where HIR has no place for them (typed fields
+//! in traits, functions and typed fields in constraints and systems) are
+//! reported as [`DiagnosticKind::Error`] diagnostics with their source span
+//! instead of being silently dropped. System requirements and evolution
+//! changes are metadata and lower to empty modules without diagnostics.
 
 mod context;
 mod decl;
@@ -62,6 +69,18 @@ pub enum DiagnosticKind {
     Error,
 }
 
+impl LowerDiagnostic {
+    /// Creates a deprecation diagnostic suggesting `new` in place of `old`.
+    pub fn deprecation(old: &str, new: &str, span: crate::ast::Span) -> Self {
+        Self {
+            kind: DiagnosticKind::Deprecation,
+            message: format!("'{}' is deprecated", old),
+            span: Some(span),
+            suggestion: Some(format!("use '{}' instead", new)),
+        }
+    }
+}
+
 impl std::fmt::Display for LowerDiagnostic {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let prefix = match self.kind {
@@ -58,7 +58,7 @@ impl LoweringContext {
             crate::ast::Statement::Uses { reference, .. } => {
                 // For uses statements, we treat the reference as both subject and resource
                 HirStatementKind::Uses {
-                    subject: self.intern("self"),
+                    subject: self.intern(crate::ast::IMPLICIT_SUBJECT),
                     resource: self.intern(reference),
                 }
             }
@@ -87,7 +87,14 @@ impl LoweringContext {
                     dependency: self.intern(&format!("!{}", action)),
                 }
             }
-            crate::ast::Statement::Quantified { phrase, .. } => {
+            crate::ast::Statement::Quantified {
+                quantifier,
+                phrase,
+                span,
+            } => {
+                if quantifier.is_deprecated() {
+                    self.emit_deprecation(&quantifier.to_string(), "forall", *span);
+                }
                 // Map quantified to has for now (simplified)
                 HirStatementKind::Has {
                     subject: self.intern("quantified"),
@@ -95,20 +102,43 @@ impl LoweringContext {
                 }
             }
             crate::ast::Statement::HasField(field) => HirStatementKind::Has {
-                subject: self.intern("self"),
+                subject: self.intern(crate::ast::IMPLICIT_SUBJECT),
                 property: self.intern(&field.name),
             },
             crate::ast::Statement::Function(func) => {
                 // Functions in statement position become method declarations
                 HirStatementKind::Has {
-                    subject: self.intern("self"),
+                    subject: self.intern(crate::ast::IMPLICIT_SUBJECT),
                     property: self.intern(&func.name),
                 }
             }
+            crate::ast::Statement::Constraint(constraint) => HirStatementKind::Requires {
+                subject: self.intern(crate::ast::IMPLICIT_SUBJECT),
+                dependency: self.intern(&constraint.name),
+            },
         };
 
         HirStatement { id, kind }
     }
+
+    /// Describe a DOL statement for diagnostics (e.g. `'has' statement`)
+    pub(crate) fn describe_statement(stmt: &crate::ast::Statement) -> &'static str {
+        use crate::ast::Statement;
+        match stmt {
+            Statement::Has { .. } => "'has' statement",
+            Statement::HasField(_) => "typed 'has' field",
+            Statement::Is { .. } => "'is' statement",
+            Statement::DerivesFrom { .. } => "'derives from' statement",
+            Statement::Requires { .. } => "'requires' statement",
+            Statement::Uses { .. } => "'uses' statement",
+            Statement::Emits { .. } => "'emits' statement",
+            Statement::Matches { .. } => "'matches' statement",
+            Statement::Never { .. } => "'never' statement",
+            Statement::Quantified { .. } => "quantified statement",
+            Statement::Function(_) => "function",
+            Statement::Constraint(_) => "inline constraint",
+        }
+    }
 }
 
 #[cfg(test)]
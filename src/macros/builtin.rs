@@ -17,6 +17,7 @@
 //! | `#line` | Current line number | `#line()` |
 //! | `#column` | Current column number | `#column()` |
 //! | `#include_str` | Include file contents | `#include_str("path/to/file")` |
+//! | `#include` | Splice another file's declarations | `#include("common.dol")` |
 //! | `#debug_assert` | Debug-only assertion | `#debug_assert(x > 0)` |
 //! | `#todo` | Mark unimplemented code | `#todo("implement later")` |
 //! | `#unreachable` | Mark unreachable code | `#unreachable()` |
@@ -32,6 +33,7 @@
 use super::{Macro, MacroContext, MacroError, MacroInput, MacroOutput};
 use crate::ast::{Expr, Literal};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Registry of built-in macros.
@@ -57,6 +59,7 @@ impl BuiltinMacros {
         macros.insert("column".to_string(), Arc::new(ColumnMacro));
         macros.insert("option_env".to_string(), Arc::new(OptionEnvMacro));
         macros.insert("include_str".to_string(), Arc::new(IncludeStrMacro));
+        macros.insert("include".to_string(), Arc::new(IncludeMacro));
         macros.insert("debug_assert".to_string(), Arc::new(DebugAssertMacro));
         macros.insert("todo".to_string(), Arc::new(TodoMacro));
         macros.insert("unreachable".to_string(), Arc::new(UnreachableMacro));
@@ -283,6 +286,10 @@ impl Macro for ConcatMacro {
         "Concatenate strings at compile time"
     }
 
+    fn expands_arguments(&self) -> bool {
+        true
+    }
+
     fn min_args(&self) -> usize {
         0
     }
@@ -630,6 +637,106 @@ impl Macro for IncludeStrMacro {
     }
 }
 
+/// `#include` - Splice the declarations of another DOL file.
+///
+/// The path is resolved relative to the directory of the including file
+/// (`MacroContext::file_path`). Include directives in the included file are
+/// expanded first, and a file that includes itself, directly or
+/// transitively, is an error.
+///
+/// # Syntax
+///
+/// ```dol
+/// #include("common/identity.dol")
+/// ```
+pub struct IncludeMacro;
+
+impl Macro for IncludeMacro {
+    fn name(&self) -> &str {
+        "include"
+    }
+
+    fn expand(&self, input: MacroInput, ctx: &MacroContext) -> Result<MacroOutput, MacroError> {
+        let path = match input {
+            MacroInput::Expr(expr) => match *expr {
+                Expr::Literal(Literal::String(s)) => s,
+                _ => return Err(MacroError::type_error("string literal", "expression")),
+            },
+            _ => {
+                return Err(MacroError::invalid_argument(
+                    "include expects a file path string",
+                ))
+            }
+        };
+
+        let resolved = match ctx.file_path.as_deref().and_then(|f| Path::new(f).parent()) {
+            Some(dir) => dir.join(&path),
+            None => PathBuf::from(&path),
+        };
+        let key = include_key(&resolved);
+
+        let mut chain = ctx.include_chain.clone();
+        if let Some(including) = &ctx.file_path {
+            chain.push(include_key(Path::new(including)));
+        }
+        if chain.contains(&key) {
+            chain.push(key);
+            return Err(MacroError::new(format!(
+                "include cycle: {}",
+                chain.join(" -> ")
+            )));
+        }
+
+        let source = std::fs::read_to_string(&resolved).map_err(|e| {
+            MacroError::new(format!(
+                "failed to read file '{}': {}",
+                resolved.display(),
+                e
+            ))
+        })?;
+        let file = crate::parser::Parser::new(&source)
+            .parse_file()
+            .map_err(|e| MacroError::new(format!("in '{}': {}", resolved.display(), e)))?;
+
+        let nested_ctx = MacroContext {
+            file_path: Some(resolved.display().to_string()),
+            include_chain: chain,
+            ..ctx.clone()
+        };
+        let mut declarations = Vec::new();
+        for nested in file.includes {
+            let input = MacroInput::Expr(Box::new(Expr::Literal(Literal::String(nested))));
+            if let MacroOutput::DeclarationList(decls) = self.expand(input, &nested_ctx)? {
+                declarations.extend(decls);
+            }
+        }
+        declarations.extend(file.declarations);
+
+        Ok(MacroOutput::DeclarationList(declarations))
+    }
+
+    fn description(&self) -> &str {
+        "Splice the declarations of another DOL file"
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// Returns the name used to compare included files: the canonical path
+/// when the file exists, the path as written otherwise.
+fn include_key(path: &Path) -> String {
+    std::fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
 /// `#debug_assert` - Assert that is only checked in debug mode.
 ///
 /// Expands to an assertion in debug builds, nothing in release.
@@ -1045,15 +1152,23 @@ impl Macro for FormatMacro {
                     )));
                 }
 
-                // Build concatenation expression
+                // Build concatenation expression, folding literal arguments
+                // into the surrounding text
                 let parts: Vec<&str> = format_string.split("{}").collect();
                 let mut result_exprs: Vec<Expr> = Vec::new();
+                let mut text = String::new();
 
                 for (i, part) in parts.iter().enumerate() {
-                    if !part.is_empty() {
-                        result_exprs.push(Expr::Literal(Literal::String(part.to_string())));
-                    }
+                    text.push_str(part);
                     if i < args.len() {
+                        if let Some(literal) = literal_text(&args[i]) {
+                            text.push_str(&literal);
+                            continue;
+                        }
+                        if !text.is_empty() {
+                            result_exprs
+                                .push(Expr::Literal(Literal::String(std::mem::take(&mut text))));
+                        }
                         // Wrap arg in a to_string call for runtime formatting
                         result_exprs.push(Expr::Call {
                             callee: Box::new(Expr::Member {
@@ -1065,6 +1180,13 @@ impl Macro for FormatMacro {
                     }
                 }
 
+                if result_exprs.is_empty() {
+                    return Ok(MacroOutput::expr(Expr::Literal(Literal::String(text))));
+                }
+                if !text.is_empty() {
+                    result_exprs.push(Expr::Literal(Literal::String(text)));
+                }
+
                 // Generate concat call with all parts
                 Ok(MacroOutput::expr(Expr::Call {
                     callee: Box::new(Expr::Identifier("concat".to_string())),
@@ -1088,6 +1210,10 @@ impl Macro for FormatMacro {
         "Format a string with placeholders"
     }
 
+    fn expands_arguments(&self) -> bool {
+        true
+    }
+
     fn min_args(&self) -> usize {
         1
     }
@@ -1283,13 +1409,29 @@ impl Macro for VecMacro {
     fn description(&self) -> &str {
         "Create a vector from elements"
     }
+
+    fn expands_arguments(&self) -> bool {
+        true
+    }
 }
 
 // === Helper Functions ===
 
+/// Returns the text of a string, number or boolean literal.
+fn literal_text(expr: &Expr) -> Option<String> {
+    match expr.unspanned() {
+        Expr::Literal(Literal::String(s)) => Some(s.clone()),
+        Expr::Literal(Literal::Int(n)) => Some(n.to_string()),
+        Expr::Literal(Literal::Float(f)) => Some(f.to_string()),
+        Expr::Literal(Literal::Bool(b)) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
 /// Converts an expression to its string representation.
-fn stringify_expr(expr: &Expr) -> String {
+pub(crate) fn stringify_expr(expr: &Expr) -> String {
     match expr {
+        Expr::Spanned { expr, .. } => stringify_expr(expr),
         Expr::Literal(lit) => match lit {
             Literal::Int(n) => n.to_string(),
             Literal::Float(f) => f.to_string(),
@@ -1319,7 +1461,6 @@ fn stringify_expr(expr: &Expr) -> String {
                 crate::ast::BinaryOp::Compose => ">>",
                 crate::ast::BinaryOp::Apply => "@",
                 crate::ast::BinaryOp::Bind => ":=",
-                crate::ast::BinaryOp::Member => ".",
                 crate::ast::BinaryOp::Map => "<$>",
                 crate::ast::BinaryOp::Ap => "<*>",
                 crate::ast::BinaryOp::Implies => "=>",
@@ -1390,14 +1531,20 @@ fn stringify_expr(expr: &Expr) -> String {
                 .join(" ");
             format!("[| {} {} |]", stringify_expr(func), args_str)
         }
-        Expr::Forall(forall_expr) => {
-            format!(
+        Expr::Forall(forall_expr) => match &forall_expr.iter {
+            Some(iter) => format!(
+                "forall {} in {} {}",
+                forall_expr.var,
+                stringify_expr(iter),
+                stringify_expr(&forall_expr.body)
+            ),
+            None => format!(
                 "forall {}: {:?}. {}",
                 forall_expr.var,
                 forall_expr.type_,
                 stringify_expr(&forall_expr.body)
-            )
-        }
+            ),
+        },
         Expr::Exists(exists_expr) => {
             format!(
                 "exists {}: {:?}. {}",
@@ -1424,6 +1571,13 @@ fn stringify_expr(expr: &Expr) -> String {
             let elems_str: Vec<String> = elements.iter().map(stringify_expr).collect();
             format!("({})", elems_str.join(", "))
         }
+        Expr::Map(entries) => {
+            let entry_strs: Vec<String> = entries
+                .iter()
+                .map(|(k, v)| format!("{}: {}", stringify_expr(k), stringify_expr(v)))
+                .collect();
+            format!("{{ {} }}", entry_strs.join(", "))
+        }
         Expr::Cast { expr, target_type } => {
             format!("{} as {:?}", stringify_expr(expr), target_type)
         }
@@ -1442,10 +1596,10 @@ fn stringify_expr(expr: &Expr) -> String {
 
 /// Evaluates a cfg expression.
 fn evaluate_cfg_expr(expr: &Expr, ctx: &MacroContext) -> Result<bool, MacroError> {
-    match expr {
+    match expr.unspanned() {
         Expr::Identifier(name) => Ok(ctx.get_cfg(name) || ctx.has_feature(name)),
         Expr::Call { callee, args } => {
-            if let Expr::Identifier(func) = callee.as_ref() {
+            if let Expr::Identifier(func) = callee.unspanned() {
                 match func.as_str() {
                     "not" => {
                         if args.len() != 1 {
@@ -1473,7 +1627,7 @@ fn evaluate_cfg_expr(expr: &Expr, ctx: &MacroContext) -> Result<bool, MacroError
                         if args.len() != 1 {
                             return Err(MacroError::invalid_argument("feature expects 1 argument"));
                         }
-                        if let Expr::Literal(Literal::String(s)) = &args[0] {
+                        if let Expr::Literal(Literal::String(s)) = args[0].unspanned() {
                             Ok(ctx.has_feature(s))
                         } else {
                             Err(MacroError::type_error("string literal", "expression"))
@@ -1493,7 +1647,7 @@ fn evaluate_cfg_expr(expr: &Expr, ctx: &MacroContext) -> Result<bool, MacroError
                 crate::ast::BinaryOp::Eq => {
                     // Handle feature = "name" style
                     if let (Expr::Identifier(key), Expr::Literal(Literal::String(value))) =
-                        (left.as_ref(), right.as_ref())
+                        (left.unspanned(), right.unspanned())
                     {
                         if key == "feature" {
                             return Ok(ctx.has_feature(value));
@@ -1970,8 +2124,9 @@ mod tests {
         assert!(builtins.get("dbg").is_some());
         assert!(builtins.get("compile_error").is_some());
         assert!(builtins.get("vec").is_some());
+        assert!(builtins.get("include").is_some());
 
-        // Should now have 20 macros (13 original + 7 new)
-        assert_eq!(builtins.len(), 20);
+        // Should now have 21 macros (13 original + 8 new)
+        assert_eq!(builtins.len(), 21);
     }
 }
@@ -25,9 +25,10 @@
 
 use super::{
     AttributeArg, BuiltinMacros, Macro, MacroAttribute, MacroContext, MacroError, MacroInput,
-    MacroInvocation, MacroOutput,
+    MacroInvocation, MacroOutput, Spanned,
 };
-use crate::ast::{Declaration, Expr, Literal, Span, Stmt};
+use crate::ast::{Declaration, DolFile, Expr, Literal, Span, Stmt};
+use crate::transform::visitor::strip_spans;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -35,9 +36,15 @@ use std::sync::Arc;
 ///
 /// The expander maintains a registry of available macros and handles
 /// the expansion of both attribute macros and expression macros.
+///
+/// User macros added with [`register`](Self::register) are looked up
+/// before the builtins, so they can shadow a builtin of the same name.
 pub struct MacroExpander {
-    /// Registered macros by name
-    macros: HashMap<String, Arc<dyn Macro>>,
+    /// Built-in macros by name
+    builtins: HashMap<String, Arc<dyn Macro>>,
+
+    /// User-registered macros by name, checked before the builtins
+    user: HashMap<String, Arc<dyn Macro>>,
 
     /// Whether to enable recursive macro expansion
     recursive: bool,
@@ -50,7 +57,8 @@ impl MacroExpander {
     /// Creates a new macro expander with no macros registered.
     pub fn new() -> Self {
         Self {
-            macros: HashMap::new(),
+            builtins: HashMap::new(),
+            user: HashMap::new(),
             recursive: true,
             max_depth: 64,
         }
@@ -63,30 +71,54 @@ impl MacroExpander {
         expander
     }
 
+    /// Creates a macro expander with the builtins plus the given user macros.
+    ///
+    /// Fails if two of the user macros share a name.
+    pub fn with_macros(
+        macros: impl IntoIterator<Item = Arc<dyn Macro>>,
+    ) -> Result<Self, MacroError> {
+        let mut expander = Self::with_builtins();
+        for macro_impl in macros {
+            expander.register(macro_impl)?;
+        }
+        Ok(expander)
+    }
+
     /// Registers all built-in macros.
     pub fn register_builtins(&mut self) {
         let builtins = BuiltinMacros::new();
         for name in builtins.names() {
             if let Some(m) = builtins.get(name) {
-                self.macros.insert(name.to_string(), m);
+                self.builtins.insert(name.to_string(), m);
             }
         }
     }
 
     /// Registers a custom macro.
-    pub fn register(&mut self, macro_impl: Arc<dyn Macro>) {
-        self.macros
-            .insert(macro_impl.name().to_string(), macro_impl);
+    ///
+    /// A custom macro shadows any builtin of the same name. Registering
+    /// two custom macros under one name is an error.
+    pub fn register(&mut self, macro_impl: Arc<dyn Macro>) -> Result<(), MacroError> {
+        let name = macro_impl.name().to_string();
+        if self.user.contains_key(&name) {
+            return Err(MacroError::duplicate(&name));
+        }
+        self.user.insert(name, macro_impl);
+        Ok(())
     }
 
-    /// Looks up a macro by name.
+    /// Looks up a macro by name, checking user macros before builtins.
     pub fn get(&self, name: &str) -> Option<Arc<dyn Macro>> {
-        self.macros.get(name).cloned()
+        self.lookup(name).cloned()
     }
 
     /// Returns true if a macro with the given name is registered.
     pub fn has_macro(&self, name: &str) -> bool {
-        self.macros.contains_key(name)
+        self.lookup(name).is_some()
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Arc<dyn Macro>> {
+        self.user.get(name).or_else(|| self.builtins.get(name))
     }
 
     /// Sets the maximum recursion depth.
@@ -117,6 +149,16 @@ impl MacroExpander {
         self.expand_with_depth(invocation, ctx, 0)
     }
 
+    /// Expands a macro invocation, tagging the output with the invocation span.
+    pub fn expand_spanned(
+        &self,
+        invocation: &MacroInvocation,
+        ctx: &MacroContext,
+    ) -> Result<Spanned<MacroOutput>, MacroError> {
+        let output = self.expand(invocation, ctx)?;
+        Ok(Spanned::new(output, invocation.span))
+    }
+
     /// Expands a macro invocation with depth tracking.
     fn expand_with_depth(
         &self,
@@ -131,15 +173,25 @@ impl MacroExpander {
             )));
         }
 
-        let macro_impl = self.macros.get(&invocation.name).ok_or_else(|| {
+        let macro_impl = self.lookup(&invocation.name).ok_or_else(|| {
             MacroError::with_span(
                 format!("undefined macro: #{}", invocation.name),
                 invocation.span,
             )
         })?;
 
-        // Prepare input from arguments
-        let input = self.prepare_input(&invocation.args)?;
+        // Prepare input from arguments, expanding nested invocations first
+        // for macros that fold their arguments
+        let input = if macro_impl.expands_arguments() {
+            let args = invocation
+                .args
+                .iter()
+                .map(|arg| self.expand_argument(arg, ctx, depth + 1))
+                .collect::<Result<Vec<_>, _>>()?;
+            self.prepare_input(&args)?
+        } else {
+            self.prepare_input(&invocation.args)?
+        };
 
         // Validate input
         macro_impl.validate(&input)?;
@@ -155,6 +207,28 @@ impl MacroExpander {
         Ok(output)
     }
 
+    /// Expands an argument that is itself a macro invocation.
+    ///
+    /// Other arguments are returned unchanged.
+    fn expand_argument(
+        &self,
+        arg: &Expr,
+        ctx: &MacroContext,
+        depth: usize,
+    ) -> Result<Expr, MacroError> {
+        let Some((name, args)) = macro_call(arg) else {
+            return Ok(arg.clone());
+        };
+        let invocation = MacroInvocation::new(name, args.to_vec(), Span::default());
+        match self.expand_with_depth(&invocation, ctx, depth)? {
+            MacroOutput::Expr(expr) => Ok(*expr),
+            _ => Err(MacroError::new(format!(
+                "macro #{} must produce a single expression when used as an argument",
+                name
+            ))),
+        }
+    }
+
     /// Expands an attribute macro on a declaration.
     ///
     /// # Arguments
@@ -172,7 +246,7 @@ impl MacroExpander {
         declaration: Declaration,
         ctx: &MacroContext,
     ) -> Result<Declaration, MacroError> {
-        let macro_impl = self.macros.get(&attribute.name).ok_or_else(|| {
+        let macro_impl = self.lookup(&attribute.name).ok_or_else(|| {
             MacroError::with_span(
                 format!("undefined attribute macro: #{}", attribute.name),
                 attribute.span,
@@ -217,6 +291,72 @@ impl MacroExpander {
         }
     }
 
+    /// Expands a declaration's attribute macros in order.
+    ///
+    /// Each macro receives the output of the one before it. Expanded
+    /// attributes are removed from the declaration's stored `attributes`,
+    /// except `#derive`, which stays as the record of the traits code
+    /// generators emit. `#[deprecated]` and `#[test]` are read by the
+    /// compiler rather than expanded, so they are skipped.
+    pub fn expand_declaration(
+        &self,
+        decl: Declaration,
+        attrs: &[MacroAttribute],
+        ctx: &MacroContext,
+    ) -> Result<Declaration, MacroError> {
+        let mut decl = decl;
+        let mut expanded = Vec::new();
+        for attr in attrs {
+            if matches!(attr.name.as_str(), "deprecated" | "test") {
+                continue;
+            }
+            decl = self.expand_attribute(attr, decl, ctx)?;
+            if attr.name != "derive" {
+                expanded.push(attr);
+            }
+        }
+
+        let stored = match &mut decl {
+            Declaration::Gene(gene) => Some(&mut gene.attributes),
+            Declaration::Trait(trait_decl) => Some(&mut trait_decl.attributes),
+            _ => None,
+        };
+        if let Some(stored) = stored {
+            stored.retain(|attr| !expanded.contains(&attr));
+        }
+        Ok(decl)
+    }
+
+    /// Splices the declarations named by a file's `#include` directives
+    /// into the file.
+    ///
+    /// Included declarations come before the file's own, in directive
+    /// order, and the directives are consumed. `ctx.file_path` should name
+    /// the file being expanded so that relative paths and include cycles
+    /// resolve against it.
+    pub fn expand_includes(
+        &self,
+        file: &mut DolFile,
+        ctx: &MacroContext,
+    ) -> Result<(), MacroError> {
+        let mut declarations = Vec::new();
+        for path in std::mem::take(&mut file.includes) {
+            let invocation = MacroInvocation::new(
+                "include",
+                vec![Expr::Literal(Literal::String(path))],
+                Span::default(),
+            );
+            match self.expand(&invocation, ctx)? {
+                MacroOutput::DeclarationList(decls) => declarations.extend(decls),
+                MacroOutput::Declaration(decl) => declarations.push(*decl),
+                _ => return Err(MacroError::new("#include must produce declarations")),
+            }
+        }
+        declarations.append(&mut file.declarations);
+        file.declarations = declarations;
+        Ok(())
+    }
+
     /// Expands a macro expression inline.
     ///
     /// # Arguments
@@ -251,11 +391,38 @@ impl MacroExpander {
         }
     }
 
+    /// Expands a macro expression inline, keeping the invocation span.
+    ///
+    /// Like [`expand_expr`](Self::expand_expr), but the returned expression is
+    /// tagged with `span` so diagnostics in the expansion point at the call site.
+    pub fn expand_expr_spanned(
+        &self,
+        name: &str,
+        args: Vec<Expr>,
+        span: Span,
+        ctx: &MacroContext,
+    ) -> Result<Spanned<Expr>, MacroError> {
+        let expr = self.expand_expr(name, args, span, ctx)?;
+        Ok(Spanned::new(expr, span))
+    }
+
     /// Prepares macro input from expression arguments.
+    ///
+    /// Macros match on the shape of their arguments, so any expression spans
+    /// are stripped first.
     fn prepare_input(&self, args: &[Expr]) -> Result<MacroInput, MacroError> {
         if args.is_empty() {
             return Ok(MacroInput::Empty);
         }
+        let args: Vec<Expr> = args
+            .iter()
+            .cloned()
+            .map(|mut arg| {
+                strip_spans(&mut arg);
+                arg
+            })
+            .collect();
+        let args = args.as_slice();
 
         if args.len() == 1 {
             // Single argument - could be ident or expr
@@ -328,10 +495,15 @@ impl MacroExpander {
         depth: usize,
     ) -> Result<Expr, MacroError> {
         match expr {
+            Expr::Spanned { expr, span } => Ok(Expr::Spanned {
+                expr: Box::new(self.expand_expr_recursively(*expr, ctx, depth)?),
+                span,
+            }),
+
             // Handle macro calls (represented as function calls to macro names)
             Expr::Call { callee, args } => {
                 // Check if this is a macro call
-                if let Expr::Identifier(name) = callee.as_ref() {
+                if let Some(name) = macro_name(&callee) {
                     if self.has_macro(name) {
                         let invocation = MacroInvocation::new(name, args.clone(), Span::default());
                         let output = self.expand_with_depth(&invocation, ctx, depth)?;
@@ -495,6 +667,14 @@ impl MacroExpander {
                 })
             }
 
+            Stmt::LetPattern { pattern, value } => {
+                let expanded = self.expand_expr_recursively(value, ctx, depth)?;
+                Ok(Stmt::LetPattern {
+                    pattern,
+                    value: expanded,
+                })
+            }
+
             Stmt::Assign { target, value } => {
                 let expanded_target = self.expand_expr_recursively(target, ctx, depth)?;
                 let expanded_value = self.expand_expr_recursively(value, ctx, depth)?;
@@ -568,6 +748,11 @@ impl MacroExpander {
         depth: usize,
     ) -> Result<Expr, MacroError> {
         match expr {
+            Expr::Spanned { expr, span } => Ok(Expr::Spanned {
+                expr: Box::new(self.expand_quasi_quote(*expr, ctx, depth)?),
+                span,
+            }),
+
             Expr::Unquote(inner) => {
                 // Expand the unquoted expression
                 self.expand_expr_recursively(*inner, ctx, depth)
@@ -601,6 +786,24 @@ impl MacroExpander {
     }
 }
 
+/// Returns the macro name of a `#name` callee, as the parser encodes
+/// macro invocations in expressions.
+fn macro_name(callee: &Expr) -> Option<&str> {
+    match callee.unspanned() {
+        Expr::Identifier(name) => name.strip_prefix('#'),
+        _ => None,
+    }
+}
+
+/// Returns the name and arguments of a `#name(args)` invocation.
+fn macro_call(expr: &Expr) -> Option<(&str, &[Expr])> {
+    match expr {
+        Expr::Spanned { expr, .. } => macro_call(expr),
+        Expr::Call { callee, args } => macro_name(callee).map(|name| (name, args.as_slice())),
+        _ => None,
+    }
+}
+
 impl Default for MacroExpander {
     fn default() -> Self {
         Self::with_builtins()
@@ -763,4 +966,266 @@ mod tests {
         let input = expander.prepare_input(&args).unwrap();
         assert!(matches!(input, MacroInput::ExprList(_)));
     }
+
+    #[test]
+    fn test_expansion_type_error_reports_invocation_span() {
+        use crate::ast::BinaryOp;
+        use crate::typechecker::{Type, TypeChecker, TypeEnv};
+
+        let expander = MacroExpander::with_builtins();
+        let ctx = MacroContext::new();
+
+        // #assert(x > 0) on line 7, where x is a String
+        let condition = Expr::Binary {
+            left: Box::new(Expr::Identifier("x".to_string())),
+            op: BinaryOp::Gt,
+            right: Box::new(Expr::Literal(Literal::Int(0))),
+        };
+        let span = Span::new(120, 134, 7, 5);
+        let expanded = expander
+            .expand_expr_spanned("assert", vec![condition], span, &ctx)
+            .unwrap();
+        assert_eq!(expanded.span, span);
+
+        let mut env = TypeEnv::new();
+        env.bind("x", Type::String);
+        env.bind(
+            "panic",
+            Type::Function {
+                params: vec![Type::String],
+                return_type: Box::new(Type::Never),
+            },
+        );
+        let mut checker = TypeChecker::with_env(env);
+        let _ = checker.infer_spanned(&expanded.node, expanded.span);
+
+        assert!(!checker.is_ok());
+        let err = &checker.errors()[0];
+        assert_eq!(err.span.map(|s| s.line), Some(7));
+        assert!(err.to_string().contains("line 7"));
+    }
+
+    #[test]
+    fn test_expand_includes_splices_declarations() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("common")).unwrap();
+        std::fs::write(
+            dir.path().join("common/identity.dol"),
+            "gene identity.exists { identity has key }",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("common.dol"),
+            "#include(\"common/identity.dol\")\ngene node.exists { node has address }",
+        )
+        .unwrap();
+        let main = dir.path().join("main.dol");
+        let source = "#include(\"common.dol\")\ngene container.exists { container has id }";
+
+        let mut file = crate::parser::Parser::new(source).parse_file().unwrap();
+        assert_eq!(file.includes, ["common.dol"]);
+
+        let ctx = MacroContext::with_location(Some(main.display().to_string()), 1, 1);
+        MacroExpander::with_builtins()
+            .expand_includes(&mut file, &ctx)
+            .unwrap();
+
+        let names: Vec<&str> = file.declarations.iter().map(|d| d.name()).collect();
+        assert_eq!(
+            names,
+            ["identity.exists", "node.exists", "container.exists"]
+        );
+        assert!(file.includes.is_empty());
+    }
+
+    #[test]
+    fn test_expand_includes_detects_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("a.dol"),
+            "#include(\"b.dol\")\ngene a.exists { a has id }",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("b.dol"),
+            "#include(\"a.dol\")\ngene b.exists { b has id }",
+        )
+        .unwrap();
+        let main = dir.path().join("a.dol");
+        let source = std::fs::read_to_string(&main).unwrap();
+
+        let mut file = crate::parser::Parser::new(&source).parse_file().unwrap();
+        let ctx = MacroContext::with_location(Some(main.display().to_string()), 1, 1);
+        let err = MacroExpander::with_builtins()
+            .expand_includes(&mut file, &ctx)
+            .unwrap_err();
+        assert!(err.message.contains("include cycle"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_expand_declaration_records_derives() {
+        let source =
+            "#[derive(Debug, Clone)]\n#[derive(Debug)]\ngene container.exists { container has id }";
+        let decl = crate::parser::Parser::new(source).parse().unwrap();
+        let Declaration::Gene(gene) = &decl else {
+            panic!("expected gene");
+        };
+        let attrs = gene.attributes.clone();
+        assert_eq!(attrs.len(), 2);
+
+        let expanded = MacroExpander::with_builtins()
+            .expand_declaration(decl, &attrs, &MacroContext::new())
+            .unwrap();
+        let Declaration::Gene(gene) = expanded else {
+            panic!("expected gene");
+        };
+        assert_eq!(gene.derives(), ["Debug", "Clone"]);
+    }
+
+    #[test]
+    fn test_expand_declaration_undefined_attribute() {
+        let source = "#[unknown]\ntrait container.lifecycle { container is created }";
+        let decl = crate::parser::Parser::new(source).parse().unwrap();
+        let attrs = match &decl {
+            Declaration::Trait(t) => t.attributes.clone(),
+            _ => panic!("expected trait"),
+        };
+
+        let err = MacroExpander::with_builtins()
+            .expand_declaration(decl, &attrs, &MacroContext::new())
+            .unwrap_err();
+        assert!(err.message.contains("undefined attribute macro: #unknown"));
+        assert_eq!(err.span, Some(attrs[0].span));
+    }
+
+    struct FixedMacro {
+        name: &'static str,
+        value: &'static str,
+    }
+
+    impl Macro for FixedMacro {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn expand(
+            &self,
+            _input: MacroInput,
+            _ctx: &MacroContext,
+        ) -> Result<MacroOutput, MacroError> {
+            Ok(MacroOutput::expr(Expr::Literal(Literal::String(
+                self.value.to_string(),
+            ))))
+        }
+    }
+
+    fn fixed(name: &'static str, value: &'static str) -> Arc<dyn Macro> {
+        Arc::new(FixedMacro { name, value })
+    }
+
+    #[test]
+    fn test_custom_macros_extend_and_shadow_builtins() {
+        let expander = MacroExpander::with_macros([
+            fixed("uuid", "00000000-0000-0000-0000-000000000000"),
+            fixed("stringify", "shadowed"),
+        ])
+        .unwrap();
+        let ctx = MacroContext::new();
+
+        let uuid = expander
+            .expand_expr("uuid", vec![], Span::default(), &ctx)
+            .unwrap();
+        assert_eq!(
+            uuid,
+            Expr::Literal(Literal::String(
+                "00000000-0000-0000-0000-000000000000".to_string()
+            ))
+        );
+
+        let shadowed = expander
+            .expand_expr(
+                "stringify",
+                vec![Expr::Identifier("foo".to_string())],
+                Span::default(),
+                &ctx,
+            )
+            .unwrap();
+        assert_eq!(
+            shadowed,
+            Expr::Literal(Literal::String("shadowed".to_string()))
+        );
+        assert!(expander.has_macro("concat"));
+    }
+
+    #[test]
+    fn test_register_duplicate_macro_is_error() {
+        let mut expander = MacroExpander::with_builtins();
+        expander.register(fixed("timestamp", "0")).unwrap();
+
+        let err = expander.register(fixed("timestamp", "1")).unwrap_err();
+        assert_eq!(err.message, "macro #timestamp is already registered");
+
+        let err = MacroExpander::with_macros([fixed("uuid", "a"), fixed("uuid", "b")])
+            .err()
+            .unwrap();
+        assert!(err.message.contains("#uuid"));
+    }
+
+    /// Expands a parsed `#name(args)` expression.
+    fn expand_source(source: &str) -> Result<Expr, MacroError> {
+        let expr = crate::parser::Parser::new(source).parse_expr(0).unwrap();
+        let (name, args) = macro_call(&expr).expect("macro invocation");
+        MacroExpander::with_builtins().expand_expr(
+            name,
+            args.to_vec(),
+            Span::default(),
+            &MacroContext::new(),
+        )
+    }
+
+    #[test]
+    fn test_nested_macro_arguments_fold() {
+        let expr = expand_source(r#"#concat("v", #format("{}.{}", #stringify(major), 2))"#);
+        assert_eq!(
+            expr.unwrap(),
+            Expr::Literal(Literal::String("vmajor.2".to_string()))
+        );
+
+        let expr = expand_source(r#"#vec(#concat("a", "b"), 1)"#).unwrap();
+        let Expr::Call { args, .. } = expr else {
+            panic!("expected Vec::from call");
+        };
+        let Expr::Call { args: elements, .. } = &args[0] else {
+            panic!("expected array");
+        };
+        assert_eq!(
+            elements[0],
+            Expr::Literal(Literal::String("ab".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_expand_arguments_with_expr_spans() {
+        let expr = crate::parser::Parser::new(r#"#concat("v", #stringify(major))"#)
+            .with_expr_spans()
+            .parse_expr(0)
+            .unwrap();
+        let (name, args) = macro_call(&expr).expect("macro invocation");
+        let expanded = MacroExpander::with_builtins()
+            .expand_expr(name, args.to_vec(), Span::default(), &MacroContext::new())
+            .unwrap();
+        assert_eq!(
+            expanded,
+            Expr::Literal(Literal::String("vmajor".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_nested_macro_argument_of_wrong_type() {
+        let err = expand_source(r#"#concat("v", #vec(1, 2))"#).unwrap_err();
+        assert_eq!(
+            err.message,
+            "macro type error: expected string literal, got complex expression"
+        );
+    }
 }
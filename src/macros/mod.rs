@@ -36,7 +36,8 @@
 //! Implement the [`Macro`] trait to create custom macros:
 //!
 //! ```rust,ignore
-//! use metadol::macros::{Macro, MacroInput, MacroOutput, MacroError, MacroContext};
+//! use metadol::macros::{Macro, MacroContext, MacroError, MacroExpander, MacroInput, MacroOutput};
+//! use std::sync::Arc;
 //!
 //! struct MyMacro;
 //!
@@ -48,11 +49,15 @@
 //!         Ok(MacroOutput::Expr(Box::new(input.as_expr().clone())))
 //!     }
 //! }
+//!
+//! // User macros are looked up before the builtins
+//! let expander = MacroExpander::with_macros([Arc::new(MyMacro) as Arc<dyn Macro>])?;
 //! ```
 
 pub mod builtin;
 pub mod expand;
 
+pub use crate::ast::{AttributeArg, MacroAttribute};
 use crate::ast::{Declaration, Expr, Span, Stmt, TypeExpr};
 use std::collections::HashMap;
 use std::fmt;
@@ -88,6 +93,11 @@ impl MacroError {
         Self::new(format!("undefined macro: #{}", name))
     }
 
+    /// Creates an error for a second macro registered under a taken name.
+    pub fn duplicate(name: &str) -> Self {
+        Self::new(format!("macro #{} is already registered", name))
+    }
+
     /// Creates an invalid argument error.
     pub fn invalid_argument(msg: &str) -> Self {
         Self::new(format!("invalid macro argument: {}", msg))
@@ -373,6 +383,31 @@ impl MacroOutput {
     }
 }
 
+/// A node synthesized by a macro, tagged with the span of its invocation.
+///
+/// Expressions and statements produced by macros carry no locations of
+/// their own. Keeping the invocation span alongside the generated node lets
+/// later phases (such as type checking) report errors at the macro call site.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    /// The generated node
+    pub node: T,
+    /// Span of the macro invocation that produced the node
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    /// Tags a node with its originating span.
+    pub fn new(node: T, span: Span) -> Self {
+        Self { node, span }
+    }
+
+    /// Discards the span, returning the generated node.
+    pub fn into_inner(self) -> T {
+        self.node
+    }
+}
+
 /// Context provided to macros during expansion.
 ///
 /// Contains information about the expansion environment that macros
@@ -396,6 +431,9 @@ pub struct MacroContext {
 
     /// Feature flags enabled
     pub features: Vec<String>,
+
+    /// Files currently being included, outermost first, for cycle detection
+    pub include_chain: Vec<String>,
 }
 
 impl MacroContext {
@@ -408,6 +446,7 @@ impl MacroContext {
             env_vars: std::env::vars().collect(),
             cfg_flags: HashMap::new(),
             features: Vec::new(),
+            include_chain: Vec::new(),
         }
     }
 
@@ -504,6 +543,12 @@ pub trait Macro: Send + Sync {
         true
     }
 
+    /// Returns whether macro invocations among the arguments are expanded
+    /// before this macro runs, as `#concat` does.
+    fn expands_arguments(&self) -> bool {
+        false
+    }
+
     /// Returns the minimum number of arguments this macro accepts.
     fn min_args(&self) -> usize {
         0
@@ -564,64 +609,6 @@ impl MacroInvocation {
     }
 }
 
-/// Attribute macro on a declaration.
-///
-/// Represents a `#[macro_name(args)]` annotation.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct MacroAttribute {
-    /// Macro name (without #)
-    pub name: String,
-    /// Arguments as identifiers or key-value pairs
-    pub args: Vec<AttributeArg>,
-    /// Source location
-    pub span: Span,
-}
-
-/// Argument to an attribute macro.
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub enum AttributeArg {
-    /// Simple identifier: `Debug`
-    Ident(String),
-    /// Key-value pair: `name = "value"`
-    KeyValue {
-        /// The key name
-        key: String,
-        /// The value expression
-        value: Expr,
-    },
-    /// Nested attribute: `serde(rename = "foo")`
-    Nested {
-        /// The nested attribute name
-        name: String,
-        /// The nested arguments
-        args: Vec<AttributeArg>,
-    },
-}
-
-impl MacroAttribute {
-    /// Creates a new macro attribute.
-    pub fn new(name: impl Into<String>, args: Vec<AttributeArg>, span: Span) -> Self {
-        Self {
-            name: name.into(),
-            args,
-            span,
-        }
-    }
-
-    /// Creates an attribute with no arguments.
-    pub fn simple(name: impl Into<String>, span: Span) -> Self {
-        Self::new(name, Vec::new(), span)
-    }
-
-    /// Creates an attribute with identifier arguments.
-    pub fn with_idents(name: impl Into<String>, idents: Vec<String>, span: Span) -> Self {
-        let args = idents.into_iter().map(AttributeArg::Ident).collect();
-        Self::new(name, args, span)
-    }
-}
-
 // Re-export commonly used items
 pub use builtin::BuiltinMacros;
 pub use expand::MacroExpander;
@@ -18,7 +18,7 @@
 //! - **compile_wasm**: Compile DOL to WebAssembly (future)
 //! - **eval**: Evaluate DOL expressions at runtime
 //! - **reflect**: Get runtime type information for DOL types
-//! - **format**: Format DOL source code (future)
+//! - **format**: Format DOL source code
 //! - **list_macros**: List all available macros
 //! - **expand_macro**: Expand a specific macro invocation
 //!
@@ -5,7 +5,8 @@
 
 use super::DolTool;
 use crate::{
-    codegen::{RustCodegen, TypeScriptCodegen},
+    codegen::{CodegenOptions, CodegenRegistry},
+    format::{format_source, FormatOptions},
     macros::BuiltinMacros,
     parse_file,
     reflect::TypeRegistry,
@@ -27,6 +28,8 @@ pub struct McpServer {
     pub name: String,
     /// Server version
     pub version: String,
+    /// Backends for the `compile_*` tools, by target name
+    codegen: CodegenRegistry,
 }
 
 impl McpServer {
@@ -44,9 +47,15 @@ impl McpServer {
         Self {
             name: "metadol-mcp".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
+            codegen: CodegenRegistry::with_builtins(),
         }
     }
 
+    /// Returns the code generation backends, for registering more.
+    pub fn codegen_registry_mut(&mut self) -> &mut CodegenRegistry {
+        &mut self.codegen
+    }
+
     /// Handles a tool invocation.
     ///
     /// Dispatches to the appropriate tool handler based on the tool type.
@@ -115,27 +124,22 @@ impl McpServer {
     }
 
     fn tool_compile_rust(&self, args: ToolArgs) -> Result<ToolResult, String> {
-        let source = args.get_string("source")?;
-
-        match parse_file(&source) {
-            Ok(decl) => {
-                let rust_code = RustCodegen::generate(&decl);
-                Ok(ToolResult::text(rust_code))
-            }
-            Err(e) => Err(format!("Parse error: {}", e)),
-        }
+        self.compile_to("rust", args)
     }
 
     fn tool_compile_typescript(&self, args: ToolArgs) -> Result<ToolResult, String> {
-        let source = args.get_string("source")?;
+        self.compile_to("typescript", args)
+    }
 
-        match parse_file(&source) {
-            Ok(decl) => {
-                let ts_code = TypeScriptCodegen::generate(&decl);
-                Ok(ToolResult::text(ts_code))
-            }
-            Err(e) => Err(format!("Parse error: {}", e)),
-        }
+    /// Parses the `source` argument and generates code for `target`.
+    fn compile_to(&self, target: &str, args: ToolArgs) -> Result<ToolResult, String> {
+        let source = args.get_string("source")?;
+        let decl = parse_file(&source).map_err(|e| format!("Parse error: {}", e))?;
+        let code = self
+            .codegen
+            .generate(target, &decl, &CodegenOptions::default())
+            .map_err(|e| e.to_string())?;
+        Ok(ToolResult::text(code))
     }
 
     fn tool_compile_wasm(&self, _args: ToolArgs) -> Result<ToolResult, String> {
@@ -172,17 +176,23 @@ impl McpServer {
         }
     }
 
-    fn tool_format(&self, _args: ToolArgs) -> Result<ToolResult, String> {
-        Err("DOL source formatting is not yet implemented".to_string())
+    fn tool_format(&self, args: ToolArgs) -> Result<ToolResult, String> {
+        let source = args.get_string("source")?;
+        format_source(&source, &FormatOptions::default())
+            .map(ToolResult::text)
+            .map_err(|e| format!("Parse error: {}", e))
     }
 
     fn tool_list_macros(&self, _args: ToolArgs) -> Result<ToolResult, String> {
-        let builtins = BuiltinMacros::new();
-        let macro_names: Vec<&str> = builtins.names().collect();
-
         let mut output = String::from("Available macros:\n");
-        for name in macro_names {
-            output.push_str(&format!("  - #{}\n", name));
+        for doc in crate::reflect::macro_catalog() {
+            output.push_str(&format!("  - #{}: {}\n", doc.name, doc.description));
+            match &doc.expansion {
+                Ok(expansion) => {
+                    output.push_str(&format!("      {} => {}\n", doc.example, expansion))
+                }
+                Err(error) => output.push_str(&format!("      {} fails: {}\n", doc.example, error)),
+            }
         }
 
         Ok(ToolResult::text(output))
@@ -277,7 +287,7 @@ impl McpServer {
                 },
                 ToolDef {
                     name: "format".to_string(),
-                    description: "Format DOL source code (future feature)".to_string(),
+                    description: "Format DOL source code into its canonical layout".to_string(),
                     parameters: vec![ParamDef {
                         name: "source".to_string(),
                         description: "DOL source code to format".to_string(),
@@ -485,6 +495,25 @@ mod tests {
         assert!(output.content.contains("Available macros"));
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_format_tool() {
+        let server = McpServer::new();
+        let mut args_map = HashMap::new();
+        args_map.insert(
+            "source".to_string(),
+            serde_json::Value::String(
+                "gene counter.state { counter has value }\nexegesis { A counter. }".to_string(),
+            ),
+        );
+
+        let output = server.tool_format(ToolArgs::new(args_map)).unwrap();
+        assert_eq!(
+            output.content,
+            "gene counter.state {\n  counter has value\n}\nexegesis {\n  A counter.\n}\n"
+        );
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn test_parse_tool() {
@@ -354,6 +354,9 @@ impl<'ctx> MlirCodegen<'ctx> {
                 }
                 Ok(None)
             }
+            Stmt::LetPattern { .. } => Err(CodegenError::UnsupportedStatement(
+                "destructuring binding".to_string(),
+            )),
             Stmt::Expr(expr) => self.compile_expr(block, expr),
             Stmt::Assign { target, value } => {
                 // For now, assignments are not fully supported
@@ -419,6 +422,7 @@ impl<'ctx> MlirCodegen<'ctx> {
         expr: &Expr,
     ) -> CodegenResult<Option<Value<'ctx, 'ctx>>> {
         match expr {
+            Expr::Spanned { expr, .. } => self.compile_expr(block, expr),
             Expr::Literal(lit) => self.compile_literal(block, lit),
             Expr::Identifier(name) => {
                 let (val, _ty) = self
@@ -653,7 +657,7 @@ impl<'ctx> MlirCodegen<'ctx> {
         args: &[Expr],
     ) -> CodegenResult<Option<Value<'ctx, 'ctx>>> {
         // For now, only direct function calls are supported
-        if let Expr::Identifier(func_name) = callee {
+        if let Expr::Identifier(func_name) = callee.unspanned() {
             let arg_vals: Vec<Value> = args
                 .iter()
                 .map(|arg| {
@@ -847,9 +851,11 @@ mod tests {
 
         let mut codegen = MlirCodegen::new(&ctx);
         let gene = Gene {
-            name: "test.gene".to_string(),
+            name: QualifiedName::new("test.gene"),
             statements: vec![],
-            exegesis: "Test gene".to_string(),
+            exegesis: "Test gene".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -132,9 +132,6 @@ impl<'c> OpBuilder<'c> {
                     op
                 )))
             }
-            BinaryOp::Member => Err(MlirError::new(
-                "member access requires struct/object lowering",
-            )),
             BinaryOp::Map | BinaryOp::Ap => Err(MlirError::new(format!(
                 "functor operator {:?} requires custom dialect",
                 op
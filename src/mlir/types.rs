@@ -166,6 +166,15 @@ impl<'ctx> TypeLowering<'ctx> {
                 Ok(MlirType::tuple(self.context, &element_types))
             }
 
+            // Record types - laid out like a tuple of their field types
+            Type::Record(fields) => {
+                let element_types: Result<Vec<_>, _> =
+                    fields.iter().map(|(_, t)| self.lower(t)).collect();
+                let element_types = element_types?;
+
+                Ok(MlirType::tuple(self.context, &element_types))
+            }
+
             // Generic types - handle known type constructors
             Type::Generic { name, args } => self.lower_generic(name, args),
 
@@ -0,0 +1,58 @@
+//! Name casing shared by code generation and type checking.
+//!
+//! DOL identifiers are dotted and snake_case (`container.exists`); generated
+//! code and type annotations refer to them in PascalCase or snake_case.
+
+/// Convert a DOL identifier to a valid identifier in the target language.
+pub fn to_pascal_case(s: &str) -> String {
+    s.split('.')
+        .flat_map(|part| part.split('_'))
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                None => String::new(),
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+            }
+        })
+        .collect()
+}
+
+/// Convert a DOL identifier to snake_case.
+pub fn to_snake_case(s: &str) -> String {
+    s.split('.')
+        .collect::<Vec<_>>()
+        .join("_")
+        .chars()
+        .enumerate()
+        .flat_map(|(i, c)| {
+            if c.is_uppercase() && i > 0 {
+                vec!['_', c.to_lowercase().next().unwrap()]
+            } else {
+                vec![c.to_lowercase().next().unwrap()]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_pascal_case() {
+        assert_eq!(to_pascal_case("container.exists"), "ContainerExists");
+        assert_eq!(
+            to_pascal_case("identity.cryptographic"),
+            "IdentityCryptographic"
+        );
+        assert_eq!(to_pascal_case("simple"), "Simple");
+        assert_eq!(to_pascal_case("snake_case"), "SnakeCase");
+    }
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("container.exists"), "container_exists");
+        assert_eq!(to_snake_case("ContainerExists"), "container_exists");
+        assert_eq!(to_snake_case("simple"), "simple");
+    }
+}
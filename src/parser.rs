@@ -25,11 +25,18 @@
 //! ```
 
 use crate::ast::*;
+use crate::diagnostic::Diagnostic;
 use crate::error::ParseError;
-use crate::lexer::{Lexer, Token, TokenKind};
-use crate::macros::{AttributeArg, MacroAttribute, MacroInvocation};
+use crate::lexer::{interpolation_end, Lexer, LexerConfig, Token, TokenKind};
+use crate::macros::MacroInvocation;
 use crate::pratt::{infix_binding_power, prefix_binding_power};
 
+/// Default limit on nested expressions and statements.
+///
+/// Deep enough for any hand-written DOL, shallow enough that adversarial
+/// input cannot overflow the native stack.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
 /// The parser for Metal DOL source text.
 ///
 /// The parser uses recursive descent to transform tokens into an AST.
@@ -52,12 +59,35 @@ pub struct Parser<'a> {
 
     /// Second peeked token for two-token lookahead (if any)
     peeked2: Option<Token>,
+
+    /// Whether expressions are wrapped in `Expr::Spanned` nodes
+    expr_spans: bool,
+
+    /// Current expression/statement nesting depth
+    depth: usize,
+
+    /// Maximum nesting depth before parsing fails
+    max_depth: usize,
+
+    /// Non-fatal diagnostics, such as deprecated syntax
+    warnings: Vec<Diagnostic>,
 }
 
 impl<'a> Parser<'a> {
     /// Creates a new parser for the given source text.
     pub fn new(source: &'a str) -> Self {
-        let mut lexer = Lexer::new(source);
+        Self::with_lexer_config(source, LexerConfig::default())
+    }
+
+    /// Creates a new parser whose lexer uses the given configuration.
+    ///
+    /// Use this to parse files written for a specific [`Dialect`](crate::lexer::Dialect).
+    pub fn with_lexer_config(source: &'a str, config: LexerConfig) -> Self {
+        Self::with_lexer(source, Lexer::with_config(source, config))
+    }
+
+    /// Creates a parser reading tokens of `source` from `lexer`.
+    fn with_lexer(source: &'a str, mut lexer: Lexer<'a>) -> Self {
         let current = lexer.next_token();
         let previous = Token::new(TokenKind::Eof, "", Span::default());
 
@@ -68,29 +98,78 @@ impl<'a> Parser<'a> {
             previous,
             peeked: None,
             peeked2: None,
+            expr_spans: false,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Returns the warnings accumulated while parsing, such as uses of
+    /// deprecated syntax.
+    pub fn warnings(&self) -> &[Diagnostic] {
+        &self.warnings
+    }
+
+    /// Enables source spans on parsed expressions.
+    ///
+    /// Every expression produced by [`Parser::parse_expr`] is then wrapped in
+    /// an [`Expr::Spanned`] node so later passes can report precise locations.
+    /// `dol-check`, the compiler pipelines and [`parse_and_lower`](crate::parse_and_lower)
+    /// parse this way.
+    pub fn with_expr_spans(mut self) -> Self {
+        self.expr_spans = true;
+        self
+    }
+
+    /// Sets the maximum nesting depth of expressions and statements.
+    ///
+    /// Input nested deeper than this fails with
+    /// [`ParseError::NestingTooDeep`] instead of exhausting the stack. The
+    /// default is [`DEFAULT_MAX_DEPTH`].
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Runs `parse` one nesting level deeper, failing past the depth limit.
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        if self.depth >= self.max_depth {
+            return Err(ParseError::NestingTooDeep {
+                limit: self.max_depth,
+                span: self.current.span,
+            });
         }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
     }
 
-    /// Parses the source into a declaration.
+    /// Parses the source and returns its first declaration.
+    ///
+    /// Content after the first declaration is left unparsed, so errors in
+    /// it are not reported. Use [`Parser::parse_all`] to parse every
+    /// declaration in the source.
     ///
     /// # Returns
     ///
-    /// The parsed `Declaration` on success, or a `ParseError` on failure.
+    /// The first parsed `Declaration` on success, or a `ParseError` on failure.
     pub fn parse(&mut self) -> Result<Declaration, ParseError> {
         // Skip module declaration if present
         self.skip_module_and_uses()?;
 
-        let decl = self.parse_declaration()?;
-
-        // Allow trailing content (other declarations in the file)
-        // For now, we just return the first declaration
-        Ok(decl)
+        self.parse_declaration()
     }
 
     /// Parses all declarations from the input.
     ///
     /// Skips module declarations and use statements, then parses all
-    /// top-level declarations until EOF.
+    /// top-level declarations until EOF. Each declaration keeps its own
+    /// span and the exegesis block that follows it.
     ///
     /// # Returns
     ///
@@ -102,6 +181,11 @@ impl<'a> Parser<'a> {
         let mut declarations = Vec::new();
 
         while self.current.kind != TokenKind::Eof {
+            // An exegesis block not attached to a declaration declares nothing
+            if self.current.kind == TokenKind::Exegesis {
+                self.skip_exegesis_block()?;
+                continue;
+            }
             let decl = self.parse_declaration()?;
             declarations.push(decl);
         }
@@ -109,6 +193,27 @@ impl<'a> Parser<'a> {
         Ok(declarations)
     }
 
+    /// Skips an `exegesis { ... }` block, including nested braces.
+    fn skip_exegesis_block(&mut self) -> Result<(), ParseError> {
+        self.expect(TokenKind::Exegesis)?;
+        let open = self.current.span;
+        self.expect(TokenKind::LeftBrace)?;
+        let mut depth = 1;
+        while depth > 0 && self.current.kind != TokenKind::Eof {
+            if self.current.kind == TokenKind::LeftBrace {
+                depth += 1;
+            }
+            if self.current.kind == TokenKind::RightBrace {
+                depth -= 1;
+            }
+            self.advance();
+        }
+        if depth > 0 {
+            return Err(ParseError::UnterminatedExegesis { span: open });
+        }
+        Ok(())
+    }
+
     /// Parses a complete DOL file including module and use declarations.
     ///
     /// Returns a `DolFile` containing the module declaration (if any),
@@ -137,9 +242,16 @@ impl<'a> Parser<'a> {
             }
         }
 
-        // Parse all declarations
+        // Parse all declarations and include directives
+        let mut includes = Vec::new();
         let mut declarations = Vec::new();
         while self.current.kind != TokenKind::Eof {
+            if self.current.kind == TokenKind::Macro
+                && Self::is_contextual_keyword(self.peek(), TokenKind::Include)
+            {
+                includes.push(self.parse_include()?);
+                continue;
+            }
             let decl = self.parse_declaration()?;
             declarations.push(decl);
         }
@@ -147,10 +259,29 @@ impl<'a> Parser<'a> {
         Ok(DolFile {
             module,
             uses,
+            includes,
             declarations,
         })
     }
 
+    /// Parses a top-level include directive: `#include("path.dol")`
+    fn parse_include(&mut self) -> Result<String, ParseError> {
+        self.expect(TokenKind::Macro)?; // consume #
+        self.advance(); // consume 'include'
+        self.expect(TokenKind::LeftParen)?;
+        if self.current.kind != TokenKind::String {
+            return Err(ParseError::UnexpectedToken {
+                expected: "include path string".to_string(),
+                found: format!("'{}'", self.current.lexeme),
+                span: self.current.span,
+            });
+        }
+        let path = self.current.lexeme.clone();
+        self.advance();
+        self.expect(TokenKind::RightParen)?;
+        Ok(path)
+    }
+
     /// Skips module declaration and use statements at the start of a file.
     fn skip_module_and_uses(&mut self) -> Result<(), ParseError> {
         // Skip module declaration
@@ -232,7 +363,7 @@ impl<'a> Parser<'a> {
     #[allow(dead_code)]
     fn skip_type_expr(&mut self) -> Result<(), ParseError> {
         // Handle enum keyword with brace block
-        if self.current.kind == TokenKind::Identifier && self.current.lexeme == "enum" {
+        if self.at_contextual_keyword(TokenKind::Enum) {
             self.advance(); // consume 'enum'
             if self.current.kind == TokenKind::LeftBrace {
                 self.advance(); // consume '{'
@@ -289,21 +420,55 @@ impl<'a> Parser<'a> {
 
     /// Parses a declaration.
     fn parse_declaration(&mut self) -> Result<Declaration, ParseError> {
+        let mut attributes = Vec::new();
+
         // Handle attribute annotations like #[test]
         while self.current.kind == TokenKind::Macro {
-            // Skip the attribute and the following declaration (tests are skipped)
-            self.advance(); // consume #
-            if self.current.kind == TokenKind::LeftBracket {
-                self.advance(); // consume [
-                                // Skip to closing ]
-                let mut depth = 1;
-                while depth > 0 && self.current.kind != TokenKind::Eof {
-                    match self.current.kind {
-                        TokenKind::LeftBracket => depth += 1,
-                        TokenKind::RightBracket => depth -= 1,
-                        _ => {}
+            if self.peek().kind == TokenKind::LeftBracket
+                && self.peek2().kind == TokenKind::Identifier
+            {
+                // #[deprecated("note")] attaches to the following declaration
+                if Self::is_contextual_keyword(self.peek2(), TokenKind::Deprecated) {
+                    attributes.push(self.parse_deprecated_attribute()?);
+                    continue;
+                }
+                // Attributes such as #[derive(Debug)] are kept on the gene or
+                // trait they annotate, for MacroExpander to expand.
+                // Attributed functions are skipped below, like test functions.
+                let attribute = self.parse_macro_attribute()?;
+                if self.current.kind != TokenKind::Function {
+                    attributes.push(attribute);
+                    continue;
+                }
+            } else {
+                // Skip the attribute and the following declaration (tests are skipped)
+                let start_span = self.current.span;
+                self.advance(); // consume #
+                if self.current.kind == TokenKind::LeftBracket {
+                    self.advance(); // consume [
+                    let is_test = self.current.kind == TokenKind::Test
+                        && self.peek().kind == TokenKind::RightBracket;
+                    // Skip to closing ]
+                    let mut depth = 1;
+                    while depth > 0 && self.current.kind != TokenKind::Eof {
+                        match self.current.kind {
+                            TokenKind::LeftBracket => depth += 1,
+                            TokenKind::RightBracket => depth -= 1,
+                            _ => {}
+                        }
+                        self.advance();
+                    }
+                    // #[test] on a gene requests a generated test scaffold
+                    if is_test
+                        && matches!(
+                            self.current.kind,
+                            TokenKind::Gene | TokenKind::Type | TokenKind::Pub
+                        )
+                    {
+                        let span = start_span.merge(&self.previous.span);
+                        attributes.push(MacroAttribute::simple("test", span));
+                        continue;
                     }
-                    self.advance();
                 }
             }
             // Skip the following function (test function)
@@ -332,29 +497,21 @@ impl<'a> Parser<'a> {
             if self.current.kind == TokenKind::Eof {
                 // Return a placeholder for files that only have tests after the main content
                 return Ok(Declaration::Gene(Gene {
-                    name: "_test_skipped".to_string(),
+                    name: QualifiedName::verbatim("_test_skipped"),
                     extends: None,
                     statements: vec![],
-                    exegesis: "Tests skipped".to_string(),
+                    exegesis: "Tests skipped".into(),
+                    visibility: Visibility::Private,
+                    attributes: vec![],
                     span: self.current.span,
                 }));
             }
         }
 
-        // Skip visibility modifier
-        if self.current.kind == TokenKind::Pub {
-            self.advance();
-            // Skip optional (spirit) or (parent)
-            if self.current.kind == TokenKind::LeftParen {
-                self.advance(); // (
-                self.advance(); // spirit/parent
-                if self.current.kind == TokenKind::RightParen {
-                    self.advance(); // )
-                }
-            }
-        }
+        let visibility = self.parse_visibility()?;
+        let keyword = self.current.kind;
 
-        match self.current.kind {
+        let decl = match keyword {
             TokenKind::Gene => self.parse_gene(),
             // type is an alias for gene (v0.3.0)
             TokenKind::Type => self.parse_type_declaration(),
@@ -374,25 +531,16 @@ impl<'a> Parser<'a> {
             }
             TokenKind::Exegesis => {
                 // Skip file-level exegesis block
-                self.advance(); // consume 'exegesis'
-                self.expect(TokenKind::LeftBrace)?;
-                let mut depth = 1;
-                while depth > 0 && self.current.kind != TokenKind::Eof {
-                    if self.current.kind == TokenKind::LeftBrace {
-                        depth += 1;
-                    }
-                    if self.current.kind == TokenKind::RightBrace {
-                        depth -= 1;
-                    }
-                    self.advance();
-                }
+                self.skip_exegesis_block()?;
                 // Try to parse next declaration, or return placeholder if EOF
                 if self.current.kind == TokenKind::Eof {
                     Ok(Declaration::Gene(Gene {
-                        name: "_module_doc".to_string(),
+                        name: QualifiedName::verbatim("_module_doc"),
                         extends: None,
                         statements: vec![],
-                        exegesis: "Module-level documentation".to_string(),
+                        exegesis: "Module-level documentation".into(),
+                        visibility: Visibility::Private,
+                        attributes: vec![],
                         span: self.current.span,
                     }))
                 } else {
@@ -422,10 +570,12 @@ impl<'a> Parser<'a> {
                 // Parse next declaration
                 if self.current.kind == TokenKind::Eof {
                     Ok(Declaration::Gene(Gene {
-                        name: "_use_only".to_string(),
+                        name: QualifiedName::verbatim("_use_only"),
                         extends: None,
                         statements: vec![],
-                        exegesis: "Use-only file".to_string(),
+                        exegesis: "Use-only file".into(),
+                        visibility: Visibility::Private,
+                        attributes: vec![],
                         span: self.current.span,
                     }))
                 } else {
@@ -456,10 +606,12 @@ impl<'a> Parser<'a> {
                 // Parse next declaration
                 if self.current.kind == TokenKind::Eof {
                     Ok(Declaration::Gene(Gene {
-                        name: "_module_decl".to_string(),
+                        name: QualifiedName::verbatim("_module_decl"),
                         extends: None,
                         statements: vec![],
-                        exegesis: "Module-only file".to_string(),
+                        exegesis: "Module-only file".into(),
+                        visibility: Visibility::Private,
+                        attributes: vec![],
                         span: self.current.span,
                     }))
                 } else {
@@ -470,12 +622,65 @@ impl<'a> Parser<'a> {
                 found: self.current.lexeme.clone(),
                 span: self.current.span,
             }),
+        };
+
+        // Skipped `use`, `module` and exegesis blocks parse the declaration
+        // after them, which carries its own visibility
+        let mut decl = decl?;
+        if !matches!(
+            keyword,
+            TokenKind::Use | TokenKind::Module | TokenKind::Exegesis
+        ) {
+            Self::set_visibility(&mut decl, visibility);
+        }
+        self.attach_attributes(&mut decl, attributes);
+        Ok(decl)
+    }
+
+    /// Records attributes parsed before a declaration.
+    ///
+    /// Only genes and traits carry attributes; on anything else they are
+    /// dropped with a warning.
+    fn attach_attributes(&mut self, decl: &mut Declaration, attributes: Vec<MacroAttribute>) {
+        match decl {
+            Declaration::Gene(gene) => gene.attributes.extend(attributes),
+            Declaration::Trait(trait_decl) => trait_decl.attributes.extend(attributes),
+            other => {
+                for attribute in attributes {
+                    self.warnings.push(Diagnostic::warning(
+                        format!(
+                            "#[{}] is ignored; attributes are only supported on genes \
+                             and traits, not {}",
+                            attribute.name,
+                            other.kind()
+                        ),
+                        Some(attribute.span),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Records a parsed visibility modifier on a declaration.
+    ///
+    /// Evolutions, constants, SexVars and extern blocks have no visibility
+    /// to record.
+    fn set_visibility(decl: &mut Declaration, visibility: Visibility) {
+        match decl {
+            Declaration::Gene(g) => g.visibility = visibility,
+            Declaration::Trait(t) => t.visibility = visibility,
+            Declaration::Constraint(c) => c.visibility = visibility,
+            Declaration::System(s) => s.visibility = visibility,
+            Declaration::Function(f) => f.visibility = visibility,
+            Declaration::Evolution(_)
+            | Declaration::Const(_)
+            | Declaration::SexVar(_)
+            | Declaration::Extern(_) => {}
         }
     }
 
     /// Parses an optional visibility modifier.
     /// Returns Visibility::Private if no modifier is present.
-    #[allow(dead_code)]
     fn parse_visibility(&mut self) -> Result<Visibility, ParseError> {
         match self.current.kind {
             TokenKind::Pub => {
@@ -487,7 +692,7 @@ impl<'a> Parser<'a> {
                         self.advance();
                         self.expect(TokenKind::RightParen)?;
                         Ok(Visibility::PubSpirit)
-                    } else if self.current.lexeme == "parent" {
+                    } else if self.at_contextual_keyword(TokenKind::Parent) {
                         self.advance();
                         self.expect(TokenKind::RightParen)?;
                         Ok(Visibility::PubParent)
@@ -544,24 +749,9 @@ impl<'a> Parser<'a> {
     /// Parses a version: 1.2.3 or 1.2.3-alpha
     fn parse_version(&mut self) -> Result<Version, ParseError> {
         let version_str = self.expect_version()?;
-        // Parse the version string into components
-        let mut parts = version_str.splitn(2, '-');
-        let numbers_part = parts.next().unwrap();
-        let suffix = parts.next().map(|s| s.to_string());
-
-        let numbers: Vec<&str> = numbers_part.split('.').collect();
-        if numbers.len() != 3 {
-            return Err(ParseError::InvalidStatement {
-                message: "version must have three parts".to_string(),
-                span: self.previous.span,
-            });
-        }
-
-        Ok(Version {
-            major: numbers[0].parse().unwrap_or(0),
-            minor: numbers[1].parse().unwrap_or(0),
-            patch: numbers[2].parse().unwrap_or(0),
-            suffix,
+        Version::parse(&version_str).map_err(|message| ParseError::InvalidStatement {
+            message,
+            span: self.previous.span,
         })
     }
 
@@ -640,7 +830,7 @@ impl<'a> Parser<'a> {
         let start_span = self.current.span;
         self.expect(TokenKind::Gene)?;
 
-        let name = self.expect_identifier()?;
+        let name = self.expect_qualified_name()?;
         // Skip generic type parameters if present: <T, U: Bound>
         self.skip_type_params()?;
 
@@ -668,7 +858,7 @@ impl<'a> Parser<'a> {
         } else if self.current.kind == TokenKind::Exegesis {
             self.parse_exegesis()?
         } else {
-            String::new() // DOL 2.0 tolerant: empty exegesis if none
+            Exegesis::default() // DOL 2.0 tolerant: empty exegesis if none
         };
 
         let span = start_span.merge(&self.previous.span);
@@ -678,6 +868,8 @@ impl<'a> Parser<'a> {
             extends,
             statements,
             exegesis,
+            visibility: Visibility::Private,
+            attributes: vec![],
             span,
         }))
     }
@@ -691,7 +883,7 @@ impl<'a> Parser<'a> {
         let start_span = self.current.span;
         self.expect(TokenKind::Type)?;
 
-        let name = self.expect_identifier()?;
+        let name = self.expect_qualified_name()?;
         // Skip generic type parameters if present: <T, U: Bound>
         self.skip_type_params()?;
 
@@ -719,7 +911,7 @@ impl<'a> Parser<'a> {
         } else if self.current.kind == TokenKind::Exegesis {
             self.parse_exegesis()?
         } else {
-            String::new() // DOL 2.0 tolerant: empty exegesis if none
+            Exegesis::default() // DOL 2.0 tolerant: empty exegesis if none
         };
 
         let span = start_span.merge(&self.previous.span);
@@ -730,6 +922,8 @@ impl<'a> Parser<'a> {
             extends,
             statements,
             exegesis,
+            visibility: Visibility::Private,
+            attributes: vec![],
             span,
         }))
     }
@@ -739,13 +933,13 @@ impl<'a> Parser<'a> {
         let start_span = self.current.span;
         self.expect(TokenKind::Trait)?;
 
-        let name = self.expect_identifier()?;
+        let name = self.expect_qualified_name()?;
         // Skip generic type parameters if present
         self.skip_type_params()?;
         self.expect(TokenKind::LeftBrace)?;
 
         let mut statements = Vec::new();
-        let mut _laws: Vec<LawDecl> = Vec::new();
+        let mut laws = Vec::new();
 
         while self.current.kind != TokenKind::RightBrace
             && self.current.kind != TokenKind::Eof
@@ -754,9 +948,10 @@ impl<'a> Parser<'a> {
             // Check for law declarations
             if self.current.kind == TokenKind::Law {
                 let law = self.parse_law_decl()?;
-                _laws.push(law);
+                laws.push(law);
             } else {
                 statements.push(self.parse_statement()?);
+                self.consume_optional_semicolon();
             }
         }
 
@@ -771,7 +966,7 @@ impl<'a> Parser<'a> {
         } else if self.current.kind == TokenKind::Exegesis {
             self.parse_exegesis()?
         } else {
-            String::new()
+            Exegesis::default()
         };
 
         let span = start_span.merge(&self.previous.span);
@@ -779,7 +974,10 @@ impl<'a> Parser<'a> {
         Ok(Declaration::Trait(Trait {
             name,
             statements,
+            laws,
             exegesis,
+            visibility: Visibility::Private,
+            attributes: vec![],
             span,
         }))
     }
@@ -789,7 +987,7 @@ impl<'a> Parser<'a> {
         let start_span = self.current.span;
         self.expect(TokenKind::Constraint)?;
 
-        let name = self.expect_identifier()?;
+        let name = self.expect_qualified_name()?;
         // Skip generic type parameters if present
         self.skip_type_params()?;
         self.expect(TokenKind::LeftBrace)?;
@@ -807,7 +1005,7 @@ impl<'a> Parser<'a> {
         } else if self.current.kind == TokenKind::Exegesis {
             self.parse_exegesis()?
         } else {
-            String::new()
+            Exegesis::default()
         };
 
         let span = start_span.merge(&self.previous.span);
@@ -816,6 +1014,7 @@ impl<'a> Parser<'a> {
             name,
             statements,
             exegesis,
+            visibility: Visibility::Private,
             span,
         }))
     }
@@ -825,7 +1024,7 @@ impl<'a> Parser<'a> {
         let start_span = self.current.span;
         self.expect(TokenKind::System)?;
 
-        let name = self.expect_identifier()?;
+        let name = self.expect_qualified_name()?;
         // Skip generic type parameters if present
         self.skip_type_params()?;
 
@@ -840,8 +1039,9 @@ impl<'a> Parser<'a> {
         self.expect(TokenKind::LeftBrace)?;
 
         let mut requirements = Vec::new();
+        let mut capabilities = Vec::new();
         let mut statements = Vec::new();
-        let mut _states: Vec<StateDecl> = Vec::new(); // States for future use
+        let mut states = Vec::new();
 
         while self.current.kind != TokenKind::RightBrace
             && self.current.kind != TokenKind::Eof
@@ -852,13 +1052,14 @@ impl<'a> Parser<'a> {
                 && self.peek_is_version_constraint()
             {
                 requirements.push(self.parse_requirement()?);
+            } else if self.current.kind == TokenKind::Requires && self.is_capability_requirement() {
+                capabilities.push(self.parse_capability_requirement()?);
             } else if self.current.kind == TokenKind::State {
-                // Parse state declaration
-                let state = self.parse_state_decl()?;
-                _states.push(state); // Store in local vector for future use
+                states.push(self.parse_state_decl()?);
             } else {
                 statements.push(self.parse_statement()?);
             }
+            self.consume_optional_semicolon();
         }
 
         // DOL 2.0: exegesis can be inside braces
@@ -872,7 +1073,7 @@ impl<'a> Parser<'a> {
         } else if self.current.kind == TokenKind::Exegesis {
             self.parse_exegesis()?
         } else {
-            String::new()
+            Exegesis::default()
         };
 
         let span = start_span.merge(&self.previous.span);
@@ -881,8 +1082,11 @@ impl<'a> Parser<'a> {
             name,
             version,
             requirements,
+            capabilities,
             statements,
+            states,
             exegesis,
+            visibility: Visibility::Private,
             span,
         }))
     }
@@ -893,17 +1097,42 @@ impl<'a> Parser<'a> {
         self.expect(TokenKind::Evolves)?;
 
         let name = self.expect_identifier()?;
-        self.expect(TokenKind::At)?;
-        let version = self.expect_version()?;
-        self.expect(TokenKind::Greater)?;
-        let parent_version = self.expect_version()?;
+        let (version, parent_version) = if self.current.kind == TokenKind::From {
+            // evolves name from 0.0.1 to 0.0.2
+            self.advance();
+            let parent_version = self.expect_version()?;
+            if !self.at_contextual_keyword(TokenKind::To) {
+                return Err(ParseError::UnexpectedToken {
+                    expected: "'to'".to_string(),
+                    found: format!("'{}'", self.current.lexeme),
+                    span: self.current.span,
+                });
+            }
+            self.advance();
+            (self.expect_version()?, parent_version)
+        } else {
+            // Deprecated: evolves name @ 0.0.2 > 0.0.1
+            let legacy_span = self.current.span;
+            self.expect(TokenKind::At)?;
+            let version = self.expect_version()?;
+            self.expect(TokenKind::Greater)?;
+            let parent_version = self.expect_version()?;
+            self.warnings.push(Diagnostic::warning(
+                format!(
+                    "'@ {version} > {parent_version}' is deprecated; \
+                         use 'from {parent_version} to {version}' instead"
+                ),
+                Some(legacy_span.merge(&self.previous.span)),
+            ));
+            (version, parent_version)
+        };
         self.expect(TokenKind::LeftBrace)?;
 
         let mut additions = Vec::new();
         let mut deprecations = Vec::new();
         let mut removals = Vec::new();
         let mut rationale = None;
-        let mut _migrate: Option<Vec<Stmt>> = None;
+        let mut migrate = None;
 
         while self.current.kind != TokenKind::RightBrace
             && self.current.kind != TokenKind::Eof
@@ -929,7 +1158,7 @@ impl<'a> Parser<'a> {
                     rationale = Some(text);
                 }
                 TokenKind::Migrate => {
-                    _migrate = Some(self.parse_migrate_block()?);
+                    migrate = Some(self.parse_migrate_block()?);
                 }
                 _ => {
                     return Err(ParseError::UnexpectedToken {
@@ -952,7 +1181,7 @@ impl<'a> Parser<'a> {
         } else if self.current.kind == TokenKind::Exegesis {
             self.parse_exegesis()?
         } else {
-            String::new()
+            Exegesis::default()
         };
 
         let span = start_span.merge(&self.previous.span);
@@ -965,12 +1194,15 @@ impl<'a> Parser<'a> {
             deprecations,
             removals,
             rationale,
+            migrate,
             exegesis,
             span,
         }))
     }
 
     /// Parses multiple statements until a closing brace.
+    ///
+    /// Each statement may be followed by an optional `;`, which is skipped.
     fn parse_statements(&mut self) -> Result<Vec<Statement>, ParseError> {
         let mut statements = Vec::new();
 
@@ -978,6 +1210,7 @@ impl<'a> Parser<'a> {
         // DOL 2.0/v0.4.0: exegesis blocks can appear throughout gene body, handled in parse_statement
         while self.current.kind != TokenKind::RightBrace && self.current.kind != TokenKind::Eof {
             statements.push(self.parse_statement()?);
+            self.consume_optional_semicolon();
         }
 
         Ok(statements)
@@ -985,12 +1218,18 @@ impl<'a> Parser<'a> {
 
     /// Parses a single statement.
     fn parse_statement(&mut self) -> Result<Statement, ParseError> {
+        self.nested(Self::parse_statement_unguarded)
+    }
+
+    /// Parses a single statement, without depth tracking.
+    fn parse_statement_unguarded(&mut self) -> Result<Statement, ParseError> {
         let start_span = self.current.span;
 
         // Handle DOL 2.0/v0.4.0 inline exegesis blocks - skip them
         while self.current.kind == TokenKind::Exegesis {
             self.advance(); // consume 'exegesis'
             if self.current.kind == TokenKind::LeftBrace {
+                let open = self.current.span;
                 self.advance();
                 let mut depth = 1;
                 while depth > 0 && self.current.kind != TokenKind::Eof {
@@ -1001,6 +1240,9 @@ impl<'a> Parser<'a> {
                     }
                     self.advance();
                 }
+                if depth > 0 {
+                    return Err(ParseError::UnterminatedExegesis { span: open });
+                }
             }
             // If we're at the end of the block, return a no-op marker
             if self.current.kind == TokenKind::RightBrace || self.current.kind == TokenKind::Eof {
@@ -1012,6 +1254,14 @@ impl<'a> Parser<'a> {
             }
         }
 
+        // #[since("1.2.0")] attaches version provenance to the following field
+        if self.current.kind == TokenKind::Macro
+            && self.peek().kind == TokenKind::LeftBracket
+            && Self::is_contextual_keyword(self.peek2(), TokenKind::Since)
+        {
+            return self.parse_since_field();
+        }
+
         // Handle 'uses' statements
         if self.current.kind == TokenKind::Uses {
             self.advance();
@@ -1023,10 +1273,14 @@ impl<'a> Parser<'a> {
         }
 
         // Handle quantified statements
-        if matches!(self.current.kind, TokenKind::Each | TokenKind::All) {
+        if matches!(
+            self.current.kind,
+            TokenKind::Each | TokenKind::All | TokenKind::Forall
+        ) {
             let quantifier = match self.current.kind {
                 TokenKind::Each => Quantifier::Each,
                 TokenKind::All => Quantifier::All,
+                TokenKind::Forall => Quantifier::Forall,
                 _ => unreachable!(),
             };
             self.advance();
@@ -1048,17 +1302,14 @@ impl<'a> Parser<'a> {
                 self.advance();
                 let type_ = self.parse_type()?;
                 // Parse optional default value: = expr
-                let default = if self.current.kind == TokenKind::Equal {
-                    self.advance();
-                    Some(self.parse_expr(0)?)
-                } else {
-                    None
-                };
+                let default = self.parse_field_default()?;
+                let constraint = self.parse_field_constraint()?;
                 return Ok(Statement::HasField(Box::new(HasField {
                     name,
                     type_,
                     default,
-                    constraint: None,
+                    constraint,
+                    since: None,
                     span: start_span.merge(&self.previous.span),
                 })));
             } else {
@@ -1068,7 +1319,7 @@ impl<'a> Parser<'a> {
                     self.parse_expr(0)?;
                 }
                 return Ok(Statement::Has {
-                    subject: "self".to_string(),
+                    subject: IMPLICIT_SUBJECT.to_string(),
                     property: name,
                     span: start_span.merge(&self.previous.span),
                 });
@@ -1079,24 +1330,19 @@ impl<'a> Parser<'a> {
         if self.current.kind == TokenKind::Constraint {
             self.advance();
             let name = self.expect_identifier()?;
-            // Skip constraint body: { ... }
-            if self.current.kind == TokenKind::LeftBrace {
+            let body = if self.current.kind == TokenKind::LeftBrace {
                 self.advance();
-                let mut depth = 1;
-                while depth > 0 && self.current.kind != TokenKind::Eof {
-                    match self.current.kind {
-                        TokenKind::LeftBrace => depth += 1,
-                        TokenKind::RightBrace => depth -= 1,
-                        _ => {}
-                    }
-                    self.advance();
-                }
-            }
-            return Ok(Statement::Requires {
-                subject: "self".to_string(),
-                requirement: name,
+                let predicate = self.parse_expr(0)?;
+                self.expect(TokenKind::RightBrace)?;
+                Some(predicate)
+            } else {
+                None
+            };
+            return Ok(Statement::Constraint(Box::new(InlineConstraint {
+                name,
+                body,
                 span: start_span.merge(&self.previous.span),
-            });
+            })));
         }
 
         // Handle DOL 2.0 function declarations inside genes: [pub] [sex] fun name(...) -> Type { ... }
@@ -1162,7 +1408,7 @@ impl<'a> Parser<'a> {
                 }
             }
             return Ok(Statement::Requires {
-                subject: "self".to_string(),
+                subject: IMPLICIT_SUBJECT.to_string(),
                 requirement: name,
                 span: start_span.merge(&self.previous.span),
             });
@@ -1201,17 +1447,14 @@ impl<'a> Parser<'a> {
                     self.advance(); // consume ':'
                     let type_ = self.parse_type()?;
                     // Parse optional default value: = expr
-                    let default = if self.current.kind == TokenKind::Equal {
-                        self.advance();
-                        Some(self.parse_expr(0)?)
-                    } else {
-                        None
-                    };
+                    let default = self.parse_field_default()?;
+                    let constraint = self.parse_field_constraint()?;
                     Ok(Statement::HasField(Box::new(HasField {
                         name: property,
                         type_,
                         default,
-                        constraint: None,
+                        constraint,
+                        since: None,
                         span: start_span.merge(&self.previous.span),
                     })))
                 } else {
@@ -1283,17 +1526,14 @@ impl<'a> Parser<'a> {
                                 // Parse the type expression
                 let type_ = self.parse_type()?;
                 // Parse optional default value
-                let default = if self.current.kind == TokenKind::Equal {
-                    self.advance();
-                    Some(self.parse_expr(0)?)
-                } else {
-                    None
-                };
+                let default = self.parse_field_default()?;
+                let constraint = self.parse_field_constraint()?;
                 Ok(Statement::HasField(Box::new(HasField {
                     name: subject,
                     type_,
                     default,
-                    constraint: None,
+                    constraint,
+                    since: None,
                     span: start_span.merge(&self.previous.span),
                 })))
             }
@@ -1502,6 +1742,8 @@ impl<'a> Parser<'a> {
                 self.advance();
                 "=".to_string()
             }
+            // A bare version is an exact match
+            TokenKind::Version => "=".to_string(),
             _ => {
                 return Err(ParseError::UnexpectedToken {
                     expected: "version constraint (>=, >, =)".to_string(),
@@ -1521,6 +1763,73 @@ impl<'a> Parser<'a> {
         })
     }
 
+    /// Checks whether a `requires` introduces a capability requirement
+    /// rather than a `requires name predicate ...` statement.
+    ///
+    /// Versioned dependencies are ruled out first by
+    /// [`peek_is_version_constraint`](Self::peek_is_version_constraint).
+    fn is_capability_requirement(&mut self) -> bool {
+        match self.peek().kind {
+            TokenKind::LeftParen => true,
+            TokenKind::Identifier => !matches!(
+                self.peek2().kind,
+                TokenKind::Has
+                    | TokenKind::Is
+                    | TokenKind::Derives
+                    | TokenKind::Emits
+                    | TokenKind::Matches
+                    | TokenKind::Never
+            ),
+            _ => false,
+        }
+    }
+
+    /// Parses a capability requirement: `requires (a and b) or c`.
+    fn parse_capability_requirement(&mut self) -> Result<CapabilityRequirement, ParseError> {
+        let start_span = self.current.span;
+        self.expect(TokenKind::Requires)?;
+
+        let expr = self.parse_capability_or()?;
+
+        Ok(CapabilityRequirement {
+            expr,
+            span: start_span.merge(&self.previous.span),
+        })
+    }
+
+    /// Parses a disjunction of capability conjunctions.
+    fn parse_capability_or(&mut self) -> Result<CapabilityExpr, ParseError> {
+        let mut lhs = self.parse_capability_and()?;
+        while self.at_contextual_keyword(TokenKind::OrKeyword) {
+            self.advance();
+            let rhs = self.parse_capability_and()?;
+            lhs = CapabilityExpr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    /// Parses a conjunction of capability atoms.
+    fn parse_capability_and(&mut self) -> Result<CapabilityExpr, ParseError> {
+        let mut lhs = self.parse_capability_atom()?;
+        while self.at_contextual_keyword(TokenKind::AndKeyword) {
+            self.advance();
+            let rhs = self.parse_capability_atom()?;
+            lhs = CapabilityExpr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    /// Parses a capability name or a parenthesized capability expression.
+    fn parse_capability_atom(&mut self) -> Result<CapabilityExpr, ParseError> {
+        if self.current.kind == TokenKind::LeftParen {
+            self.advance();
+            let expr = self.parse_capability_or()?;
+            self.expect(TokenKind::RightParen)?;
+            return Ok(expr);
+        }
+        Ok(CapabilityExpr::Capability(self.expect_identifier()?))
+    }
+
     /// Parses a has statement with optional default value and constraint.
     /// Syntax: subject has property [: Type] [= default] [where constraint]
     /// Returns a Statement::Has with extended information
@@ -1562,26 +1871,17 @@ impl<'a> Parser<'a> {
         };
 
         // Parse optional default
-        let default = if self.current.kind == TokenKind::Equal {
-            self.advance();
-            Some(self.parse_expr(0)?)
-        } else {
-            None
-        };
+        let default = self.parse_field_default()?;
 
         // Parse optional constraint
-        let constraint = if self.current.kind == TokenKind::Where {
-            self.advance();
-            Some(self.parse_expr(0)?)
-        } else {
-            None
-        };
+        let constraint = self.parse_field_constraint()?;
 
         Ok(HasField {
             name,
             type_,
             default,
             constraint,
+            since: None,
             span: start_span.merge(&self.previous.span),
         })
     }
@@ -1599,12 +1899,7 @@ impl<'a> Parser<'a> {
         let type_ = self.parse_type()?;
 
         // Parse optional default
-        let default = if self.current.kind == TokenKind::Equal {
-            self.advance();
-            Some(self.parse_expr(0)?)
-        } else {
-            None
-        };
+        let default = self.parse_field_default()?;
 
         Ok(StateDecl {
             name,
@@ -1704,6 +1999,56 @@ impl<'a> Parser<'a> {
             None
         };
 
+        self.parse_extern_fun(abi, start_span)
+    }
+
+    /// Parses an extern block: sex extern [abi] { [abi] fun name(...) -> Type; ... }
+    ///
+    /// Every function in the block inherits the block's ABI unless it names
+    /// its own ABI before `fun`. A bare `sex extern [abi] fun ...` is accepted
+    /// as a block containing a single function.
+    pub fn parse_sex_extern_block(&mut self) -> Result<Vec<ExternDecl>, ParseError> {
+        let start_span = self.current.span;
+        self.expect(TokenKind::Sex)?;
+        self.expect(TokenKind::Extern)?;
+
+        // Parse optional block ABI
+        let block_abi = if self.current.kind == TokenKind::String {
+            Some(self.expect_string()?)
+        } else {
+            None
+        };
+
+        if self.current.kind != TokenKind::LeftBrace {
+            return Ok(vec![self.parse_extern_fun(block_abi, start_span)?]);
+        }
+        self.advance(); // consume '{'
+
+        let mut decls = Vec::new();
+        while self.current.kind != TokenKind::RightBrace && self.current.kind != TokenKind::Eof {
+            let fun_start = self.current.span;
+
+            // Per-function ABI overrides the block ABI
+            let abi = if self.current.kind == TokenKind::String {
+                Some(self.expect_string()?)
+            } else {
+                block_abi.clone()
+            };
+
+            decls.push(self.parse_extern_fun(abi, fun_start)?);
+            self.consume_optional_semicolon();
+        }
+        self.expect(TokenKind::RightBrace)?;
+
+        Ok(decls)
+    }
+
+    /// Parses the `fun name(...) -> Type` part of an extern declaration.
+    fn parse_extern_fun(
+        &mut self,
+        abi: Option<String>,
+        start_span: Span,
+    ) -> Result<ExternDecl, ParseError> {
         self.expect(TokenKind::Function)?;
 
         let name = self.expect_identifier()?;
@@ -1766,14 +2111,10 @@ impl<'a> Parser<'a> {
                 Ok(Declaration::Function(Box::new(func)))
             }
             TokenKind::Extern => {
-                let extern_decl = self.parse_sex_extern()?;
-                // Keep extern functions as Gene placeholder (FFI stubs need special handling)
-                Ok(Declaration::Gene(Gene {
-                    name: extern_decl.name.clone(),
-                    extends: None,
-                    statements: vec![],
-                    exegesis: format!("sex extern {}", extern_decl.name),
-                    span: extern_decl.span,
+                let functions = self.parse_sex_extern_block()?;
+                Ok(Declaration::Extern(ExternBlock {
+                    functions,
+                    span: start.merge(&self.previous.span),
                 }))
             }
             _ => Err(ParseError::InvalidDeclaration {
@@ -1784,7 +2125,10 @@ impl<'a> Parser<'a> {
     }
 
     /// Parses the exegesis block.
-    fn parse_exegesis(&mut self) -> Result<String, ParseError> {
+    ///
+    /// The returned text is trimmed of surrounding whitespace, and its span
+    /// covers exactly that text in the source.
+    fn parse_exegesis(&mut self) -> Result<Exegesis, ParseError> {
         if self.current.kind != TokenKind::Exegesis {
             return Err(ParseError::MissingExegesis {
                 span: self.current.span,
@@ -1792,89 +2136,60 @@ impl<'a> Parser<'a> {
         }
 
         self.advance(); // consume 'exegesis'
+        let open = self.current.span;
         self.expect(TokenKind::LeftBrace)?;
 
-        // Collect all text until closing brace
-        // We need to handle nested braces
-        let mut content = String::new();
+        // Read the raw text up to the matching closing brace
+        let source_after_brace = &self.lexer_source()[open.end..];
         let mut brace_depth = 1;
-
-        // Get position after opening brace
-        let start_pos = self.current.span.start;
-
-        // Re-lex from the source to get raw text
-        let source_after_brace = &self.lexer_source()[start_pos..];
-
-        for ch in source_after_brace.chars() {
+        let mut end = None;
+        for (i, ch) in source_after_brace.char_indices() {
             if ch == '{' {
                 brace_depth += 1;
-                content.push(ch);
             } else if ch == '}' {
                 brace_depth -= 1;
                 if brace_depth == 0 {
+                    end = Some(i);
                     break;
                 }
-                content.push(ch);
-            } else {
-                content.push(ch);
             }
         }
+        let Some(end) = end else {
+            return Err(ParseError::UnterminatedExegesis { span: open });
+        };
+        let content = &source_after_brace[..end];
+        let leading = content.len() - content.trim_start().len();
+        let prefix = &content[..leading];
+        let (line, column) = match prefix.rfind('\n') {
+            Some(i) => (
+                open.line + prefix.matches('\n').count(),
+                prefix[i + 1..].chars().count() + 1,
+            ),
+            None => (open.line, open.column + 1 + prefix.chars().count()),
+        };
+        let text = content.trim();
+        let start = open.end + leading;
+        let span = Span::new(start, start + text.len(), line, column);
+        let text = text.to_string();
+
+        // The content need not lex cleanly, so resume lexing at the
+        // closing brace instead of stepping through its tokens
+        self.peeked = None;
+        self.peeked2 = None;
+        self.lexer.seek(open.end + end);
+        self.current = self.lexer.next_token();
+        self.expect(TokenKind::RightBrace)?;
 
-        // Skip past the exegesis content in the lexer
-        // We need to advance until we find the matching closing brace
-        while self.current.kind != TokenKind::RightBrace && self.current.kind != TokenKind::Eof {
-            self.advance();
-        }
-
-        if self.current.kind == TokenKind::RightBrace {
-            self.advance();
-        }
-
-        Ok(content.trim().to_string())
+        Ok(Exegesis::new(text, span))
     }
 
     /// Parses an optional inline exegesis block (DOL 2.0 style).
     /// Returns None if no exegesis is present.
-    fn parse_inline_exegesis(&mut self) -> Result<Option<String>, ParseError> {
+    fn parse_inline_exegesis(&mut self) -> Result<Option<Exegesis>, ParseError> {
         if self.current.kind != TokenKind::Exegesis {
             return Ok(None);
         }
-
-        self.advance(); // consume 'exegesis'
-        self.expect(TokenKind::LeftBrace)?;
-
-        // Collect all text until closing brace
-        let mut content = String::new();
-        let mut brace_depth = 1;
-
-        let start_pos = self.current.span.start;
-        let source_after_brace = &self.lexer_source()[start_pos..];
-
-        for ch in source_after_brace.chars() {
-            if ch == '{' {
-                brace_depth += 1;
-                content.push(ch);
-            } else if ch == '}' {
-                brace_depth -= 1;
-                if brace_depth == 0 {
-                    break;
-                }
-                content.push(ch);
-            } else {
-                content.push(ch);
-            }
-        }
-
-        // Skip past the exegesis content in the lexer
-        while self.current.kind != TokenKind::RightBrace && self.current.kind != TokenKind::Eof {
-            self.advance();
-        }
-
-        if self.current.kind == TokenKind::RightBrace {
-            self.advance();
-        }
-
-        Ok(Some(content.trim().to_string()))
+        self.parse_exegesis().map(Some)
     }
 
     // === DOL 2.0 Expression Parsing ===
@@ -1889,14 +2204,23 @@ impl<'a> Parser<'a> {
     ///
     /// The parsed expression on success, or a ParseError on failure.
     pub fn parse_expr(&mut self, min_bp: u8) -> Result<Expr, ParseError> {
+        self.nested(|parser| parser.parse_expr_bp(min_bp))
+    }
+
+    /// Parses an expression with the Pratt loop, without depth tracking.
+    fn parse_expr_bp(&mut self, min_bp: u8) -> Result<Expr, ParseError> {
+        let start = self.current.span;
+
         // Parse prefix or atom
         let mut lhs = self.parse_prefix_or_atom()?;
+        lhs = self.spanned(lhs, start);
 
         // Parse infix operators with binding power
         loop {
             // Special case: member access (.) should only consume an identifier
             if self.current.kind == TokenKind::Dot {
                 self.advance();
+                let field_span = self.current.span;
                 let field = self.expect_identifier()?;
 
                 // Check if this is a struct literal: Type.Variant { ... }
@@ -1916,16 +2240,9 @@ impl<'a> Parser<'a> {
                 if is_struct_literal {
                     // This is a struct literal like Type.Variant { field: value }
                     // Combine the lhs and field into a path name
-                    let path_name = match &lhs {
-                        Expr::Identifier(name) => format!("{}.{}", name, field),
-                        Expr::Member { object, field: f } => {
-                            if let Expr::Identifier(name) = object.as_ref() {
-                                format!("{}.{}.{}", name, f, field)
-                            } else {
-                                field.clone()
-                            }
-                        }
-                        _ => field.clone(),
+                    let path_name = match Self::member_path(&lhs) {
+                        Some(path) => format!("{}.{}", path, field),
+                        None => field.clone(),
                     };
 
                     self.advance(); // consume '{'
@@ -1954,20 +2271,14 @@ impl<'a> Parser<'a> {
                         fields,
                     };
                 } else {
-                    lhs = Expr::Member {
-                        object: Box::new(lhs),
-                        field,
-                    };
+                    lhs = Self::member_access(lhs, &field, field_span)?;
                 }
+                lhs = self.spanned(lhs, start);
                 continue;
             }
 
-            // Check for infix operators (excluding Dot which is handled above)
+            // Check for infix operators (Dot is handled above)
             if let Some((left_bp, _right_bp)) = infix_binding_power(&self.current.kind) {
-                if self.current.kind == TokenKind::Dot {
-                    // Already handled above
-                    break;
-                }
                 if left_bp < min_bp {
                     break;
                 }
@@ -2023,7 +2334,7 @@ impl<'a> Parser<'a> {
                 // Only treat as struct literal if:
                 // 1. The name starts with uppercase (type name convention)
                 // 2. The content looks like struct fields (identifier: value or empty)
-                if let Expr::Identifier(name) = &lhs {
+                if let Some(name) = Self::member_path(&lhs) {
                     let is_type_name = name.chars().next().is_some_and(|c| c.is_uppercase());
                     if !is_type_name {
                         break;
@@ -2073,11 +2384,26 @@ impl<'a> Parser<'a> {
             } else {
                 break;
             }
+            lhs = self.spanned(lhs, start);
         }
 
         Ok(lhs)
     }
 
+    /// Wraps an expression in an `Expr::Spanned` node covering everything
+    /// from `start` to the last consumed token, if expression spans are enabled.
+    fn spanned(&self, expr: Expr, start: Span) -> Expr {
+        let span = start.merge(&self.previous.span);
+        // Avoid stacking wrappers when nothing new was consumed
+        if !self.expr_spans || expr.span() == Some(span) {
+            return expr;
+        }
+        Expr::Spanned {
+            expr: Box::new(expr),
+            span,
+        }
+    }
+
     /// Parses prefix operators and atomic expressions.
     fn parse_prefix_or_atom(&mut self) -> Result<Expr, ParseError> {
         // Special case for Bang: check if it's eval (!{...}) or logical not (!expr)
@@ -2135,6 +2461,12 @@ impl<'a> Parser<'a> {
                 self.advance();
                 Ok(Expr::Literal(Literal::String(value)))
             }
+            TokenKind::FormatString => {
+                let value = self.current.lexeme.clone();
+                let span = self.current.span;
+                self.advance();
+                self.interpolate_string(value, span)
+            }
             TokenKind::Char => {
                 let value = self.current.lexeme.chars().next().unwrap_or('\0');
                 self.advance();
@@ -2171,7 +2503,15 @@ impl<'a> Parser<'a> {
                     }
                 }
 
-                Ok(Expr::Identifier(name))
+                // `a.b.c` lexes as one qualified identifier; it is member access
+                match name.split_once('.') {
+                    Some((object, fields)) if !name.contains("::") => Self::member_access(
+                        Expr::Identifier(object.to_string()),
+                        fields,
+                        self.previous.span,
+                    ),
+                    _ => Ok(Expr::Identifier(name)),
+                }
             }
 
             // Allow DOL keywords to be used as identifiers in expression context
@@ -2267,8 +2607,17 @@ impl<'a> Parser<'a> {
             // Syntax 2: forall x in iter { body } (iterator style)
             TokenKind::Forall => self.parse_forall_expr(),
 
-            // Block expression
-            TokenKind::LeftBrace => self.parse_block_expr(),
+            // Exists quantifier expression: exists x: T. expr
+            TokenKind::Exists => self.parse_exists_expr(),
+
+            // Map literal { key: value, ... } or block expression
+            TokenKind::LeftBrace => {
+                if self.is_map_literal_start() {
+                    self.parse_map_literal()
+                } else {
+                    self.parse_block_expr()
+                }
+            }
 
             // Sex block expression
             TokenKind::Sex => self.parse_sex_block(),
@@ -2393,8 +2742,18 @@ impl<'a> Parser<'a> {
             TokenKind::Compose => BinaryOp::Compose,
             TokenKind::At => BinaryOp::Apply,
             TokenKind::Bind => BinaryOp::Bind,
-            TokenKind::Dot => BinaryOp::Member,
             TokenKind::DotDot => BinaryOp::Range,
+            TokenKind::Implies => BinaryOp::Implies,
+            // `x in xs` asks the collection whether it contains the element
+            TokenKind::In => {
+                return Ok(Expr::Call {
+                    callee: Box::new(Expr::Member {
+                        object: Box::new(right),
+                        field: "contains".to_string(),
+                    }),
+                    args: vec![left],
+                })
+            }
             _ => {
                 return Err(ParseError::InvalidStatement {
                     message: format!("invalid binary operator: {:?}", op_token),
@@ -2410,6 +2769,39 @@ impl<'a> Parser<'a> {
         })
     }
 
+    /// Builds member access of the dot-separated `fields` on `object`.
+    ///
+    /// `fields` may hold several names, as in the tail of a qualified
+    /// identifier like `a.b.c`, giving nested [`Expr::Member`]s. Each field
+    /// must be a name; `a.1` is an error.
+    fn member_access(object: Expr, fields: &str, span: Span) -> Result<Expr, ParseError> {
+        fields.split('.').try_fold(object, |object, field| {
+            if !field.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+                return Err(ParseError::UnexpectedToken {
+                    expected: "field name after '.'".to_string(),
+                    found: format!("'{}'", field),
+                    span,
+                });
+            }
+            Ok(Expr::Member {
+                object: Box::new(object),
+                field: field.to_string(),
+            })
+        })
+    }
+
+    /// Returns the dotted path named by an identifier or a chain of member
+    /// accesses on one, such as `Type.Variant`.
+    fn member_path(expr: &Expr) -> Option<String> {
+        match expr.unspanned() {
+            Expr::Identifier(name) => Some(name.clone()),
+            Expr::Member { object, field } => {
+                Self::member_path(object).map(|path| format!("{}.{}", path, field))
+            }
+            _ => None,
+        }
+    }
+
     /// Creates a unary expression from operator token.
     fn make_unary_expr(&self, op_token: TokenKind, operand: Expr) -> Result<Expr, ParseError> {
         match op_token {
@@ -2576,6 +2968,25 @@ impl<'a> Parser<'a> {
         Ok(Expr::Match { scrutinee, arms })
     }
 
+    /// Parses an existential quantifier: `exists x: T. expr`.
+    fn parse_exists_expr(&mut self) -> Result<Expr, ParseError> {
+        let start_span = self.current.span;
+        self.expect(TokenKind::Exists)?;
+
+        let var = self.expect_identifier()?;
+        self.expect(TokenKind::Colon)?;
+        let type_ = self.parse_type()?;
+        self.expect(TokenKind::Dot)?;
+        let body = self.parse_expr(0)?;
+
+        Ok(Expr::Exists(ExistsExpr {
+            var,
+            type_,
+            body: Box::new(body),
+            span: start_span.merge(&self.previous.span),
+        }))
+    }
+
     /// Parses a forall quantifier expression (v0.3.0).
     ///
     /// Supports two syntaxes:
@@ -2601,6 +3012,7 @@ impl<'a> Parser<'a> {
             Ok(Expr::Forall(ForallExpr {
                 var,
                 type_,
+                iter: None,
                 body: Box::new(body),
                 span,
             }))
@@ -2634,23 +3046,17 @@ impl<'a> Parser<'a> {
             self.expect(TokenKind::RightBrace)?;
             let span = start_span.merge(&self.previous.span);
 
-            // For iterator-style forall, wrap in a Block that represents the comprehension
-            // Use a synthetic Inferred type since we don't know the element type statically
             let body = Expr::Block {
                 statements,
                 final_expr: final_expr.map(Box::new),
             };
 
-            // Create a ForallExpr using a placeholder type to indicate iterator-style
             // The underscore "_" indicates the type should be inferred from the iterator
             Ok(Expr::Forall(ForallExpr {
                 var,
                 type_: TypeExpr::Named("_".to_string()),
-                body: Box::new(Expr::Binary {
-                    left: Box::new(iter),
-                    op: BinaryOp::Member,
-                    right: Box::new(body),
-                }),
+                iter: Some(Box::new(iter)),
+                body: Box::new(body),
                 span,
             }))
         } else {
@@ -2662,8 +3068,104 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Expands `${expr}` interpolations in a format string.
+    ///
+    /// Only strings with an `f` prefix are format strings:
+    /// `f"Hello, ${name}!"` becomes `"Hello, " + to_string(name) + "!"`,
+    /// while `"Hello, ${name}!"` stays a plain literal. Format strings
+    /// without interpolations are returned as plain literals.
+    ///
+    /// Each interpolation is parsed in place in the source, with this
+    /// parser's options, so its spans and errors point into the string.
+    fn interpolate_string(&mut self, value: String, span: Span) -> Result<Expr, ParseError> {
+        if !value.contains("${") {
+            return Ok(Expr::Literal(Literal::String(value)));
+        }
+
+        let mut result = None;
+        let mut push = |part: Expr| {
+            result = Some(match result.take() {
+                None => part,
+                Some(left) => Expr::Binary {
+                    left: Box::new(left),
+                    op: BinaryOp::Add,
+                    right: Box::new(part),
+                },
+            });
+        };
+
+        // Walk the raw text between `f"` and the closing quote, so that
+        // interpolation offsets are offsets into the source
+        let start = span.start + 2;
+        let raw = &self.source[start..span.end - 1];
+        let mut text = String::new();
+        let mut first = true;
+        let mut i = 0;
+        while let Some(ch) = raw[i..].chars().next() {
+            if let Some(hole) = raw[i..].strip_prefix("${") {
+                let end = interpolation_end(hole).ok_or_else(|| ParseError::InvalidStatement {
+                    message: "unterminated '${' in string literal".to_string(),
+                    span,
+                })?;
+                // Always start with the leading text (even if empty) so the result is a string
+                if first || !text.is_empty() {
+                    push(Expr::Literal(Literal::String(std::mem::take(&mut text))));
+                }
+                first = false;
+                let expr = self.parse_interpolation(start + i + 2, start + i + 2 + end)?;
+                push(Expr::Call {
+                    callee: Box::new(Expr::Identifier("to_string".to_string())),
+                    args: vec![expr],
+                });
+                i += end + 3;
+            } else if ch == '\\' {
+                let escaped = raw[i + 1..].chars().next().unwrap_or('\\');
+                text.push(match escaped {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    other => other,
+                });
+                i += 1 + escaped.len_utf8();
+            } else {
+                text.push(ch);
+                i += ch.len_utf8();
+            }
+        }
+        if !text.is_empty() {
+            push(Expr::Literal(Literal::String(text)));
+        }
+
+        Ok(result.expect("interpolated string has at least one part"))
+    }
+
+    /// Parses the interpolated expression spanning `start..end` of the source.
+    fn parse_interpolation(&mut self, start: usize, end: usize) -> Result<Expr, ParseError> {
+        let mut lexer = Lexer::with_config(self.source, self.lexer.config());
+        lexer.seek(start);
+        let mut inner = Parser::with_lexer(self.source, lexer);
+        inner.expr_spans = self.expr_spans;
+        inner.depth = self.depth;
+        inner.max_depth = self.max_depth;
+
+        let expr = inner.parse_expr(0)?;
+        self.warnings.append(&mut inner.warnings);
+        if inner.current.span.start != end {
+            return Err(ParseError::UnexpectedToken {
+                expected: "'}' to close the interpolation".to_string(),
+                found: format!("'{}'", inner.current.lexeme),
+                span: inner.current.span,
+            });
+        }
+        Ok(expr)
+    }
+
     /// Parses a pattern for match expressions.
     pub fn parse_pattern(&mut self) -> Result<Pattern, ParseError> {
+        self.nested(Self::parse_pattern_unguarded)
+    }
+
+    fn parse_pattern_unguarded(&mut self) -> Result<Pattern, ParseError> {
         match self.current.kind {
             TokenKind::Underscore => {
                 self.advance();
@@ -2832,6 +3334,50 @@ impl<'a> Parser<'a> {
         Ok(expr)
     }
 
+    /// Returns true if the `{` at the current position opens a map literal.
+    ///
+    /// A map literal is recognized by a simple key followed by `:` (e.g.
+    /// `{ "a": 1 }`, `{ 1: "a" }` or `{ -1: "a" }`); anything else,
+    /// including `{}`, is a block. Numbers lex as identifiers.
+    fn is_map_literal_start(&mut self) -> bool {
+        if self.peek().kind == TokenKind::Minus {
+            // A negative number key needs a third token of lookahead
+            let number = self.peek2().clone();
+            if number.kind != TokenKind::Identifier || number.lexeme.parse::<f64>().is_err() {
+                return false;
+            }
+            let rest = self.source[number.span.end..].trim_start();
+            return rest.starts_with(':') && !rest.starts_with("::");
+        }
+        matches!(
+            self.peek().kind,
+            TokenKind::String
+                | TokenKind::Char
+                | TokenKind::Identifier
+                | TokenKind::True
+                | TokenKind::False
+        ) && self.peek2().kind == TokenKind::Colon
+    }
+
+    /// Parses a map literal: { key: value, ... }
+    fn parse_map_literal(&mut self) -> Result<Expr, ParseError> {
+        self.expect(TokenKind::LeftBrace)?;
+        let mut entries = Vec::new();
+        while self.current.kind != TokenKind::RightBrace && self.current.kind != TokenKind::Eof {
+            let key = self.parse_expr(0)?;
+            self.expect(TokenKind::Colon)?;
+            let value = self.parse_expr(0)?;
+            entries.push((key, value));
+            if self.current.kind == TokenKind::Comma {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        self.expect(TokenKind::RightBrace)?;
+        Ok(Expr::Map(entries))
+    }
+
     /// Parses a sex block expression: sex { statements }
     fn parse_sex_block(&mut self) -> Result<Expr, ParseError> {
         self.expect(TokenKind::Sex)?;
@@ -2998,11 +3544,34 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Returns true if a `let`/`val`/`var` binding starts with a destructuring
+    /// pattern: a tuple `(a, b)` or a record `Point { x, y }`.
+    fn at_destructuring_pattern(&mut self) -> bool {
+        match self.current.kind {
+            TokenKind::LeftParen => true,
+            TokenKind::Identifier => self.peek().kind == TokenKind::LeftBrace,
+            _ => false,
+        }
+    }
+
+    /// Parses the remainder of a destructuring binding: pattern = expr
+    fn parse_let_pattern(&mut self) -> Result<Stmt, ParseError> {
+        let pattern = self.parse_pattern()?;
+        self.expect(TokenKind::Equal)?;
+        let value = self.parse_expr(0)?;
+        self.consume_optional_semicolon();
+
+        Ok(Stmt::LetPattern { pattern, value })
+    }
+
     /// Parses a single statement.
     pub fn parse_stmt(&mut self) -> Result<Stmt, ParseError> {
         match self.current.kind {
             TokenKind::Let => {
                 self.advance();
+                if self.at_destructuring_pattern() {
+                    return self.parse_let_pattern();
+                }
                 // Support `let _ = ...` discard pattern
                 let name = if self.current.kind == TokenKind::Underscore {
                     self.advance();
@@ -3045,6 +3614,9 @@ impl<'a> Parser<'a> {
             // val x: Type = expr (immutable binding, v0.3.0)
             TokenKind::Val => {
                 self.advance();
+                if self.at_destructuring_pattern() {
+                    return self.parse_let_pattern();
+                }
                 // Support `val _ = ...` discard pattern
                 let name = if self.current.kind == TokenKind::Underscore {
                     self.advance();
@@ -3084,6 +3656,9 @@ impl<'a> Parser<'a> {
             }
             TokenKind::Var => {
                 self.advance();
+                if self.at_destructuring_pattern() {
+                    return self.parse_let_pattern();
+                }
                 let name = self.expect_identifier()?;
 
                 let type_ann = if self.current.kind == TokenKind::Colon {
@@ -3247,6 +3822,14 @@ impl<'a> Parser<'a> {
 
     /// Parses a type expression.
     pub fn parse_type(&mut self) -> Result<TypeExpr, ParseError> {
+        self.nested(Self::parse_type_unguarded)
+    }
+
+    fn parse_type_unguarded(&mut self) -> Result<TypeExpr, ParseError> {
+        // `enum` is only a keyword when a brace block follows
+        let inline_enum =
+            self.at_contextual_keyword(TokenKind::Enum) && self.peek().kind == TokenKind::LeftBrace;
+
         // Handle built-in type keywords
         let base_type = match self.current.kind {
             TokenKind::Int8 => {
@@ -3305,84 +3888,87 @@ impl<'a> Parser<'a> {
                 self.advance();
                 TypeExpr::Never
             }
-            TokenKind::Identifier => {
-                let name = self.expect_identifier()?;
+            TokenKind::Identifier if inline_enum => {
+                self.advance(); // consume 'enum'
 
-                // Check for inline enum type: enum { A, B, C } or enum { A { x: Int }, B }
-                if name == "enum" && self.current.kind == TokenKind::LeftBrace {
-                    self.advance(); // consume '{'
-                    let mut variants = Vec::new();
-                    while self.current.kind != TokenKind::RightBrace
-                        && self.current.kind != TokenKind::Eof
-                    {
-                        if self.current.kind == TokenKind::RightBrace {
-                            break;
-                        }
-                        // Parse variant name (allow keywords as variant names)
-                        let variant_name = self.expect_identifier_or_keyword()?;
-                        let mut fields = Vec::new();
-                        let mut tuple_types = Vec::new();
-                        let mut discriminant = None;
-
-                        // Check for tuple variant: Variant(T, U)
-                        if self.current.kind == TokenKind::LeftParen {
-                            self.advance(); // consume '('
-                            while self.current.kind != TokenKind::RightParen
-                                && self.current.kind != TokenKind::Eof
-                            {
-                                tuple_types.push(self.parse_type()?);
-                                if self.current.kind == TokenKind::Comma {
-                                    self.advance();
-                                } else {
-                                    break;
-                                }
+                // Inline enum type: enum { A, B, C } or enum { A { x: Int }, B }
+                self.expect(TokenKind::LeftBrace)?;
+                let mut variants = Vec::new();
+                while self.current.kind != TokenKind::RightBrace
+                    && self.current.kind != TokenKind::Eof
+                {
+                    if self.current.kind == TokenKind::RightBrace {
+                        break;
+                    }
+                    // Parse variant name (allow keywords as variant names)
+                    let variant_name = self.expect_identifier_or_keyword()?;
+                    let mut fields = Vec::new();
+                    let mut tuple_types = Vec::new();
+                    let mut discriminant = None;
+
+                    // Check for tuple variant: Variant(T, U)
+                    if self.current.kind == TokenKind::LeftParen {
+                        self.advance(); // consume '('
+                        while self.current.kind != TokenKind::RightParen
+                            && self.current.kind != TokenKind::Eof
+                        {
+                            tuple_types.push(self.parse_type()?);
+                            if self.current.kind == TokenKind::Comma {
+                                self.advance();
+                            } else {
+                                break;
                             }
-                            self.expect(TokenKind::RightParen)?;
                         }
-                        // Check for struct fields: Variant { field: Type, ... }
-                        else if self.current.kind == TokenKind::LeftBrace {
-                            self.advance(); // consume '{'
-                            while self.current.kind != TokenKind::RightBrace
-                                && self.current.kind != TokenKind::Eof
-                            {
-                                let field_name = self.expect_identifier_or_keyword()?;
-                                self.expect(TokenKind::Colon)?;
-                                let field_type = self.parse_type()?;
-                                fields.push((field_name, field_type));
-                                if self.current.kind == TokenKind::Comma {
-                                    self.advance();
-                                } else {
-                                    break;
-                                }
+                        self.expect(TokenKind::RightParen)?;
+                    }
+                    // Check for struct fields: Variant { field: Type, ... }
+                    else if self.current.kind == TokenKind::LeftBrace {
+                        self.advance(); // consume '{'
+                        while self.current.kind != TokenKind::RightBrace
+                            && self.current.kind != TokenKind::Eof
+                        {
+                            let field_name = self.expect_identifier_or_keyword()?;
+                            self.expect(TokenKind::Colon)?;
+                            let field_type = self.parse_type()?;
+                            fields.push((field_name, field_type));
+                            if self.current.kind == TokenKind::Comma {
+                                self.advance();
+                            } else {
+                                break;
                             }
-                            self.expect(TokenKind::RightBrace)?;
                         }
+                        self.expect(TokenKind::RightBrace)?;
+                    }
 
-                        // Check for discriminant value: Variant = 0
-                        if self.current.kind == TokenKind::Equal {
-                            self.advance(); // consume '='
-                                            // Numeric values are tokenized as Identifier
-                            if let Ok(val) = self.current.lexeme.parse::<i64>() {
-                                discriminant = Some(val);
-                            }
-                            self.advance();
+                    // Check for discriminant value: Variant = 0
+                    if self.current.kind == TokenKind::Equal {
+                        self.advance(); // consume '='
+                                        // Numeric values are tokenized as Identifier
+                        if let Ok(val) = self.current.lexeme.parse::<i64>() {
+                            discriminant = Some(val);
                         }
+                        self.advance();
+                    }
 
-                        variants.push(EnumVariant {
-                            name: variant_name,
-                            fields,
-                            tuple_types,
-                            discriminant,
-                        });
-                        // Skip comma if present
-                        if self.current.kind == TokenKind::Comma {
-                            self.advance();
-                        }
+                    variants.push(EnumVariant {
+                        name: variant_name,
+                        fields,
+                        tuple_types,
+                        discriminant,
+                    });
+                    // Skip comma if present
+                    if self.current.kind == TokenKind::Comma {
+                        self.advance();
                     }
-                    self.expect(TokenKind::RightBrace)?;
-                    TypeExpr::Enum { variants }
+                }
+                self.expect(TokenKind::RightBrace)?;
+                TypeExpr::Enum { variants }
+            }
+            TokenKind::Identifier => {
+                let name = self.expect_identifier()?;
+
                 // Check for generic type
-                } else if self.current.kind == TokenKind::Lt {
+                if self.current.kind == TokenKind::Lt {
                     self.advance();
                     let mut args = Vec::new();
                     // Also check for Compose (>>) which can occur in nested generics
@@ -3404,6 +3990,25 @@ impl<'a> Parser<'a> {
                     TypeExpr::Named(name)
                 }
             }
+            TokenKind::LeftBrace => {
+                // Inline record type: { cpu: Int64, memory: Int64 }
+                self.advance(); // consume '{'
+                let mut fields = Vec::new();
+                while self.current.kind != TokenKind::RightBrace
+                    && self.current.kind != TokenKind::Eof
+                {
+                    let field_name = self.expect_identifier_or_keyword()?;
+                    self.expect(TokenKind::Colon)?;
+                    fields.push((field_name, self.parse_type()?));
+                    if self.current.kind == TokenKind::Comma {
+                        self.advance();
+                    } else {
+                        break;
+                    }
+                }
+                self.expect(TokenKind::RightBrace)?;
+                TypeExpr::Record(fields)
+            }
             TokenKind::LeftParen => {
                 self.advance();
                 let mut types = Vec::new();
@@ -3440,9 +4045,36 @@ impl<'a> Parser<'a> {
             }
         };
 
+        // Nullable shorthand: T? is Option<T>
+        if self.current.kind == TokenKind::Reflect {
+            self.advance();
+            return Ok(TypeExpr::Generic {
+                name: "Option".to_string(),
+                args: vec![base_type],
+            });
+        }
+
         Ok(base_type)
     }
 
+    /// Parses an optional `= expr` field default.
+    fn parse_field_default(&mut self) -> Result<Option<Expr>, ParseError> {
+        if self.current.kind != TokenKind::Equal {
+            return Ok(None);
+        }
+        self.advance();
+        Ok(Some(self.parse_expr(0)?))
+    }
+
+    /// Parses an optional `where expr` field constraint.
+    fn parse_field_constraint(&mut self) -> Result<Option<Expr>, ParseError> {
+        if self.current.kind != TokenKind::Where {
+            return Ok(None);
+        }
+        self.advance();
+        Ok(Some(self.parse_expr(0)?))
+    }
+
     /// Parses a fun declaration (for DOL 2.0 gene/trait bodies).
     fn parse_function_decl(&mut self) -> Result<FunctionDecl, ParseError> {
         let start_span = self.current.span;
@@ -3503,7 +4135,7 @@ impl<'a> Parser<'a> {
             params,
             return_type,
             body,
-            exegesis: String::new(),
+            exegesis: Exegesis::default(),
             span,
         })
     }
@@ -3549,7 +4181,7 @@ impl<'a> Parser<'a> {
 
         // Parse optional exegesis
         let exegesis = if self.current.kind == TokenKind::Exegesis {
-            Some(self.parse_exegesis()?)
+            Some(self.parse_exegesis()?.text)
         } else {
             None
         };
@@ -3568,7 +4200,8 @@ impl<'a> Parser<'a> {
     /// Syntax: `migrate { statements }`
     ///
     /// Migrate blocks contain imperative migration code that transforms
-    /// data or state from the old version to the new version.
+    /// data or state from the old version, bound as `old`, to the new
+    /// version.
     pub fn parse_migrate_block(&mut self) -> Result<Vec<Stmt>, ParseError> {
         self.expect(TokenKind::Migrate)?;
         self.expect(TokenKind::LeftBrace)?;
@@ -3711,6 +4344,59 @@ impl<'a> Parser<'a> {
         Ok(MacroAttribute::new(name, args, span))
     }
 
+    /// Parses a `#[deprecated]` attribute.
+    ///
+    /// Accepts `#[deprecated]`, `#[deprecated("note")]` and
+    /// `#[deprecated(note = "note")]`, storing a note as a `note` key.
+    fn parse_deprecated_attribute(&mut self) -> Result<MacroAttribute, ParseError> {
+        let start_span = self.current.span;
+        self.expect(TokenKind::Macro)?; // consume #
+        self.expect(TokenKind::LeftBracket)?; // consume [
+        let name = self.expect_identifier()?; // consume 'deprecated'
+
+        let mut args = Vec::new();
+        if self.current.kind == TokenKind::LeftParen {
+            self.advance();
+            if self.at_contextual_keyword(TokenKind::Note) {
+                self.advance();
+                self.expect(TokenKind::Equal)?;
+            }
+            let note = self.expect_string()?;
+            self.expect(TokenKind::RightParen)?;
+            args.push(AttributeArg::KeyValue {
+                key: "note".to_string(),
+                value: Expr::Literal(Literal::String(note)),
+            });
+        }
+        self.expect(TokenKind::RightBracket)?; // consume ]
+
+        let span = start_span.merge(&self.previous.span);
+        Ok(MacroAttribute::new(name, args, span))
+    }
+
+    /// Parses a `#[since("version")]` attribute and the typed field it annotates.
+    fn parse_since_field(&mut self) -> Result<Statement, ParseError> {
+        let start_span = self.current.span;
+        self.expect(TokenKind::Macro)?; // consume #
+        self.expect(TokenKind::LeftBracket)?; // consume [
+        self.expect_identifier()?; // consume 'since'
+        self.expect(TokenKind::LeftParen)?;
+        let version = self.expect_string()?;
+        self.expect(TokenKind::RightParen)?;
+        self.expect(TokenKind::RightBracket)?; // consume ]
+
+        match self.parse_statement()? {
+            Statement::HasField(mut field) => {
+                field.since = Some(version);
+                Ok(Statement::HasField(field))
+            }
+            _ => Err(ParseError::InvalidStatement {
+                message: "#[since] is only supported on typed 'has' fields".to_string(),
+                span: start_span,
+            }),
+        }
+    }
+
     /// Parses an attribute argument.
     fn parse_attribute_arg(&mut self) -> Result<AttributeArg, ParseError> {
         let name = self.expect_identifier()?;
@@ -3852,6 +4538,29 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Expects a dotted declaration name such as `container.exists`.
+    ///
+    /// The lexer reads a dotted name as one identifier; its segments are
+    /// kept as written and the name is located at that token.
+    fn expect_qualified_name(&mut self) -> Result<QualifiedName, ParseError> {
+        let span = self.current.span;
+        let name = self.expect_identifier()?;
+        Ok(QualifiedName::verbatim(&name).with_span(span))
+    }
+
+    /// Returns true if the current token is the contextual keyword `kind`.
+    ///
+    /// Contextual keywords are lexed as identifiers, so they are matched by
+    /// spelling through the lexer's keyword table.
+    fn at_contextual_keyword(&self, kind: TokenKind) -> bool {
+        Self::is_contextual_keyword(&self.current, kind)
+    }
+
+    /// Returns true if `token` is the contextual keyword `kind`.
+    fn is_contextual_keyword(token: &Token, kind: TokenKind) -> bool {
+        token.kind == TokenKind::Identifier && TokenKind::from_keyword(&token.lexeme) == Some(kind)
+    }
+
     /// Expects an identifier or a DOL keyword that can be used as a variable/function name.
     /// This allows keywords like `gene`, `trait`, `test`, etc. to be used as names.
     fn expect_identifier_or_keyword(&mut self) -> Result<String, ParseError> {
@@ -3955,15 +4664,18 @@ impl<'a> Parser<'a> {
     }
 
     /// Checks if the next token is an identifier.
-    fn peek_is_identifier(&self) -> bool {
-        // Simple lookahead - would need proper implementation
-        true
+    fn peek_is_identifier(&mut self) -> bool {
+        self.peek().kind == TokenKind::Identifier
     }
 
-    /// Checks if a version constraint follows.
-    fn peek_is_version_constraint(&self) -> bool {
-        // Simple lookahead - would need proper implementation
-        true
+    /// Checks if the token after the next one starts a version constraint.
+    ///
+    /// A bare version counts, as `requires name 1.0.0` pins an exact version.
+    fn peek_is_version_constraint(&mut self) -> bool {
+        matches!(
+            self.peek2().kind,
+            TokenKind::GreaterEqual | TokenKind::Greater | TokenKind::Equal | TokenKind::Version
+        )
     }
 }
 
@@ -4032,4 +4744,18 @@ gene container.exists {
             panic!("Expected Gene declaration");
         }
     }
+
+    #[test]
+    fn test_exegesis_span() {
+        let input =
+            "gene counter.state {\n  counter has value\n}\n\nexegesis {\n  Counts {things}.\n}\n";
+        let decl = Parser::new(input).parse().unwrap();
+        let Declaration::Gene(gene) = decl else {
+            panic!("Expected Gene declaration");
+        };
+        assert_eq!(gene.exegesis.text(), "Counts {things}.");
+        let span = gene.exegesis.span();
+        assert_eq!(&input[span.start..span.end], "Counts {things}.");
+        assert_eq!((span.line, span.column), (6, 3));
+    }
 }
@@ -15,6 +15,7 @@
 //! # Precedence Table
 //!
 //! From lowest to highest:
+//! 0. Implication `implies` (3, 2) - right associative
 //! 1. Assignment `:=` (10, 9) - right associative
 //! 2. Pipe `|>` (21, 20) - left associative
 //! 3. Application `@` (31, 30) - left associative
@@ -23,7 +24,7 @@
 //! 6. Logical Or `||` (61, 60) - left associative
 //! 7. Logical And `&` (71, 70) - left associative
 //! 8. Equality `==`, `!=` (80, 80) - non-associative
-//! 9. Comparison `<`, `>`, `<=`, `>=` (90, 90) - non-associative
+//! 9. Comparison `<`, `>`, `<=`, `>=` and membership `in` (90, 90) - non-associative
 //! 10. Additive `+`, `-` (101, 100) - left associative
 //! 11. Multiplicative `*`, `/`, `%` (111, 110) - left associative
 //! 12. Power `^` (120, 121) - right associative
@@ -79,8 +80,12 @@ pub fn infix_binding_power(op: &TokenKind) -> Option<(u8, u8)> {
         // Equality (non-assoc)
         TokenKind::Eq | TokenKind::Ne => (80, 80),
 
-        // Comparison (non-assoc)
-        TokenKind::Lt | TokenKind::Le | TokenKind::Greater | TokenKind::GreaterEqual => (90, 90),
+        // Comparison and membership (non-assoc)
+        TokenKind::Lt
+        | TokenKind::Le
+        | TokenKind::Greater
+        | TokenKind::GreaterEqual
+        | TokenKind::In => (90, 90),
 
         // Additive (left-assoc)
         TokenKind::Plus | TokenKind::Minus => (101, 100),
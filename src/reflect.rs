@@ -674,6 +674,142 @@ pub fn reflect_type<'a>(registry: &'a TypeRegistry, type_name: &str) -> &'a Type
     registry.lookup(type_name).unwrap_or(&UNKNOWN)
 }
 
+/// Sample arguments used to render each built-in macro's example.
+///
+/// Arguments are DOL expression source, separated by commas. Macros not
+/// listed here (including `include` and `include_str`, whose expansions depend on the
+/// file system) are expanded with no arguments.
+const MACRO_EXAMPLES: &[(&str, &str)] = &[
+    ("assert", "x > 0"),
+    ("assert_eq", "len(items), 3"),
+    ("assert_ne", "len(items), 0"),
+    ("cfg", "debug"),
+    ("compile_error", "\"unsupported target\""),
+    ("concat", "\"hello\", \" \", \"world\""),
+    ("dbg", "x + 1"),
+    ("debug_assert", "x > 0"),
+    ("derive", "Debug, Clone"),
+    ("env", "\"DOL_HOME\""),
+    ("format", "\"{} + {}\", 1, 2"),
+    ("option_env", "\"DOL_HOME\""),
+    ("stringify", "a + b * c"),
+    ("todo", "\"parse headers\""),
+    ("vec", "1, 2, 3"),
+];
+
+/// Documentation for a built-in macro.
+///
+/// Produced by [`macro_catalog`] for tool listings and generated docs.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MacroDoc {
+    /// Macro name (without `#`)
+    pub name: String,
+    /// One-line description
+    pub description: String,
+    /// Minimum number of arguments
+    pub min_args: usize,
+    /// Maximum number of arguments, if bounded
+    pub max_args: Option<usize>,
+    /// Whether the macro can be used as an attribute
+    pub is_attribute: bool,
+    /// Whether the macro can be used in expression position
+    pub is_expr: bool,
+    /// Example invocation, e.g. `#concat("a", "b")`
+    pub example: String,
+    /// Rendered expansion of the example, or the error it produced
+    pub expansion: Result<String, String>,
+}
+
+/// Lists every built-in macro with an example expansion, sorted by name.
+///
+/// Each example is expanded one level, for real, in a fixed context (file
+/// `example.dol`, line 1, column 1, `DOL_HOME=/opt/dol`, `debug` enabled),
+/// so the rendered output tracks the macro implementations. A failing
+/// example is recorded in [`MacroDoc::expansion`] rather than failing the
+/// catalog.
+///
+/// # Example
+///
+/// ```rust
+/// use metadol::reflect::macro_catalog;
+///
+/// let catalog = macro_catalog();
+/// let concat = catalog.iter().find(|doc| doc.name == "concat").unwrap();
+/// assert_eq!(concat.example, r#"#concat("hello", " ", "world")"#);
+/// ```
+pub fn macro_catalog() -> Vec<MacroDoc> {
+    use crate::macros::{BuiltinMacros, MacroContext, MacroExpander, MacroInvocation};
+
+    let builtins = BuiltinMacros::new();
+    let mut expander = MacroExpander::with_builtins();
+    expander.set_recursive(false);
+    let mut ctx = MacroContext::with_location(Some("example.dol".to_string()), 1, 1);
+    ctx.env_vars = HashMap::from([("DOL_HOME".to_string(), "/opt/dol".to_string())]);
+    ctx.set_cfg("debug", true);
+
+    let mut names: Vec<&str> = builtins.names().collect();
+    names.sort_unstable();
+
+    names
+        .into_iter()
+        .filter_map(|name| builtins.get(name))
+        .map(|mac| {
+            let args_source = MACRO_EXAMPLES
+                .iter()
+                .find(|(name, _)| *name == mac.name())
+                .map_or("", |(_, args)| args);
+            let expansion = example_args(args_source)
+                .and_then(|args| {
+                    let invocation = MacroInvocation::new(mac.name(), args, Default::default());
+                    expander
+                        .expand(&invocation, &ctx)
+                        .map_err(|e| e.to_string())
+                })
+                .map(|output| render_macro_output(&output));
+
+            MacroDoc {
+                name: mac.name().to_string(),
+                description: mac.description().to_string(),
+                min_args: mac.min_args(),
+                max_args: mac.max_args(),
+                is_attribute: mac.is_attribute_macro(),
+                is_expr: mac.is_expr_macro(),
+                example: format!("#{}({})", mac.name(), args_source),
+                expansion,
+            }
+        })
+        .collect()
+}
+
+/// Parses comma-separated example arguments into expressions.
+fn example_args(source: &str) -> Result<Vec<crate::ast::Expr>, String> {
+    let list = crate::parser::Parser::new(&format!("[{}]", source))
+        .parse_expr(0)
+        .map_err(|e| e.to_string())?;
+    match list.unspanned() {
+        crate::ast::Expr::List(args) => Ok(args.clone()),
+        _ => Ok(vec![list]),
+    }
+}
+
+/// Renders macro output as DOL-like source text.
+fn render_macro_output(output: &crate::macros::MacroOutput) -> String {
+    use crate::macros::builtin::stringify_expr;
+    use crate::macros::MacroOutput;
+
+    match output {
+        MacroOutput::None => String::new(),
+        MacroOutput::Expr(expr) => stringify_expr(expr),
+        MacroOutput::ExprList(exprs) => exprs
+            .iter()
+            .map(stringify_expr)
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => format!("{:?}", other),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -875,4 +1011,28 @@ mod tests {
         let info = TypeInfo::record("InternalType").private();
         assert!(!info.is_public());
     }
+
+    #[test]
+    fn test_macro_catalog_concat_example() {
+        let catalog = macro_catalog();
+        let concat = catalog.iter().find(|doc| doc.name == "concat").unwrap();
+
+        assert!(concat.is_expr);
+        assert_eq!(concat.expansion.as_deref(), Ok("\"hello world\""));
+    }
+
+    #[test]
+    fn test_macro_catalog_records_failing_example() {
+        let catalog = macro_catalog();
+        let compile_error = catalog
+            .iter()
+            .find(|doc| doc.name == "compile_error")
+            .unwrap();
+
+        assert!(compile_error
+            .expansion
+            .as_ref()
+            .is_err_and(|e| e.contains("unsupported target")));
+        assert!(catalog.windows(2).all(|w| w[0].name < w[1].name));
+    }
 }
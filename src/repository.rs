@@ -0,0 +1,499 @@
+//! Declaration repository with evolution lineage.
+//!
+//! A [`Repository`] indexes a set of declarations by name so that the state
+//! of a declaration can be reconstructed at any version in its lineage. This
+//! lets evolutions be checked against the version they claim to evolve from.
+//!
+//! # Example
+//!
+//! ```rust
+//! use metadol::parser::Parser;
+//! use metadol::repository::Repository;
+//!
+//! let source = r#"
+//! gene container.exists { container has id container has image }
+//! evolves container.exists @ 0.0.2 > 0.0.1 { removes image }
+//! "#;
+//! let file = Parser::new(source).parse_file().unwrap();
+//! let repo = Repository::new(&file.declarations);
+//!
+//! assert!(repo.members_at("container.exists", "0.0.1").unwrap().contains("image"));
+//! assert!(!repo.members_at("container.exists", "0.0.2").unwrap().contains("image"));
+//! ```
+
+use crate::ast::{Constraint, Declaration, DeclarationKind, Evolution, Statement, TypeExpr};
+use crate::error::CycleError;
+use std::collections::{BTreeSet, HashMap};
+
+/// An index of declarations and their evolutions.
+///
+/// Base declarations carry no version of their own, so any version that is
+/// not produced by an evolution is treated as the base declaration.
+#[derive(Debug, Default)]
+pub struct Repository<'a> {
+    /// All declarations, in source order
+    all: &'a [Declaration],
+    /// Base (non-evolution) declarations by name
+    declarations: HashMap<&'a str, &'a Declaration>,
+    /// Evolutions by the name of the declaration they evolve, in source order
+    evolutions: HashMap<&'a str, Vec<&'a Evolution>>,
+}
+
+impl<'a> Repository<'a> {
+    /// Builds a repository from a list of declarations.
+    pub fn new(decls: &'a [Declaration]) -> Self {
+        let mut repo = Self {
+            all: decls,
+            ..Self::default()
+        };
+        for decl in decls {
+            match decl {
+                Declaration::Evolution(evolution) => repo
+                    .evolutions
+                    .entry(evolution.name.as_str())
+                    .or_default()
+                    .push(evolution),
+                // The first declaration of a name wins; later duplicates
+                // are reported by `validate_file`
+                other => {
+                    repo.declarations.entry(other.name()).or_insert(other);
+                }
+            }
+        }
+        repo
+    }
+
+    /// Returns the base declaration with the given name.
+    pub fn get(&self, name: &str) -> Option<&'a Declaration> {
+        self.declarations.get(name).copied()
+    }
+
+    /// Returns the top-level constraint declaration with the given name.
+    ///
+    /// This is how bodiless inline constraints inside genes resolve.
+    pub fn constraint(&self, name: &str) -> Option<&'a Constraint> {
+        match self.get(name)? {
+            Declaration::Constraint(constraint) => Some(constraint),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over all declarations, in source order.
+    pub fn iter(&self) -> std::slice::Iter<'a, Declaration> {
+        self.all.iter()
+    }
+
+    /// Returns an iterator over the declarations of one kind, in source order.
+    pub fn iter_kind(&self, kind: DeclarationKind) -> impl Iterator<Item = &'a Declaration> {
+        self.all.iter().filter(move |decl| decl.kind() == kind)
+    }
+
+    /// Returns the evolutions of a declaration, in source order.
+    pub fn lineage(&self, name: &str) -> &[&'a Evolution] {
+        self.evolutions.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Returns the names of the declarations affected by changes to
+    /// `changed`: the changed declarations themselves plus everything that
+    /// depends on them, directly or transitively, through [`dependencies`].
+    ///
+    /// Evolutions share the name of the declaration they evolve, so they
+    /// are affected along with it. Names not in the repository are ignored.
+    pub fn affected_by(&self, changed: &[&str]) -> BTreeSet<&'a str> {
+        let mut dependents: HashMap<&'a str, Vec<&'a str>> = HashMap::new();
+        for decl in self.all {
+            for dependency in dependencies(decl) {
+                dependents.entry(dependency).or_default().push(decl.name());
+            }
+        }
+
+        let mut affected: BTreeSet<&'a str> = self
+            .all
+            .iter()
+            .map(|decl| decl.name())
+            .filter(|name| changed.contains(name))
+            .collect();
+        let mut queue: Vec<&'a str> = affected.iter().copied().collect();
+        while let Some(name) = queue.pop() {
+            for &dependent in dependents.get(name).into_iter().flatten() {
+                if affected.insert(dependent) {
+                    queue.push(dependent);
+                }
+            }
+        }
+        affected
+    }
+
+    /// Returns the names of the declarations reachable from `roots`: the
+    /// roots themselves plus everything they depend on, directly or
+    /// transitively, through [`dependencies`].
+    ///
+    /// Names not in the repository are ignored.
+    pub fn reachable_from(&self, roots: &[&str]) -> BTreeSet<&'a str> {
+        let mut reachable: BTreeSet<&'a str> = self
+            .all
+            .iter()
+            .map(|decl| decl.name())
+            .filter(|name| roots.contains(name))
+            .collect();
+        let mut queue: Vec<&'a str> = reachable.iter().copied().collect();
+        while let Some(name) = queue.pop() {
+            let Some(decl) = self.get(name) else {
+                continue;
+            };
+            for dependency in dependencies(decl) {
+                if let Some(target) = self.get(dependency) {
+                    if reachable.insert(target.name()) {
+                        queue.push(target.name());
+                    }
+                }
+            }
+        }
+        reachable
+    }
+
+    /// Returns all declarations ordered so that each comes after the
+    /// declarations it depends on.
+    ///
+    /// Dependencies are those of [`dependencies`] plus the names used in
+    /// typed field types that match a declaration name exactly. Evolutions
+    /// follow the declaration they evolve. Independent declarations keep
+    /// their source order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CycleError`] naming the chain of declarations that
+    /// depend on each other.
+    pub fn topological_order(&self) -> Result<Vec<&'a Declaration>, CycleError> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            Unvisited,
+            InProgress,
+            Done,
+        }
+
+        let index_of = |decl: &Declaration| {
+            self.all
+                .iter()
+                .position(|candidate| std::ptr::eq(candidate, decl))
+        };
+        let edges: Vec<Vec<usize>> = self
+            .all
+            .iter()
+            .enumerate()
+            .map(|(i, decl)| {
+                let mut names = dependencies(decl);
+                names.extend(field_type_names(decl));
+                if let Declaration::Evolution(evolution) = decl {
+                    names.insert(evolution.name.as_str());
+                }
+                names
+                    .into_iter()
+                    .filter_map(|name| self.get(name).and_then(index_of))
+                    .filter(|&j| j != i)
+                    .collect()
+            })
+            .collect();
+
+        let mut marks = vec![Mark::Unvisited; self.all.len()];
+        let mut order = Vec::with_capacity(self.all.len());
+        for root in 0..self.all.len() {
+            if marks[root] != Mark::Unvisited {
+                continue;
+            }
+            // Iterative DFS: (node, index of next edge to follow)
+            let mut stack = vec![(root, 0)];
+            marks[root] = Mark::InProgress;
+            while let Some((node, next)) = stack.last_mut() {
+                let node = *node;
+                match edges[node].get(*next) {
+                    Some(&target) => {
+                        *next += 1;
+                        match marks[target] {
+                            Mark::Unvisited => {
+                                marks[target] = Mark::InProgress;
+                                stack.push((target, 0));
+                            }
+                            Mark::InProgress => {
+                                let start = stack.iter().position(|&(n, _)| n == target);
+                                let mut chain: Vec<String> = stack[start.unwrap_or(0)..]
+                                    .iter()
+                                    .map(|&(n, _)| self.all[n].name().to_string())
+                                    .collect();
+                                chain.push(self.all[target].name().to_string());
+                                return Err(CycleError { chain });
+                            }
+                            Mark::Done => {}
+                        }
+                    }
+                    None => {
+                        marks[node] = Mark::Done;
+                        order.push(&self.all[node]);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// Returns the names of the members (fields, states, functions, ...) of a
+    /// declaration at the given version.
+    ///
+    /// Returns `None` if the base declaration is not in the repository.
+    pub fn members_at(&self, name: &str, version: &str) -> Option<BTreeSet<String>> {
+        // Bound the walk by the lineage length so cyclic lineages terminate
+        let groups = self.member_groups_at(name, version, self.lineage(name).len())?;
+        Some(groups.into_iter().flatten().collect())
+    }
+
+    /// Returns the members at a version, grouped by the statement that
+    /// declares them so that removing one name removes all its aliases.
+    fn member_groups_at(
+        &self,
+        name: &str,
+        version: &str,
+        depth: usize,
+    ) -> Option<Vec<Vec<String>>> {
+        let evolution = self
+            .lineage(name)
+            .iter()
+            .find(|evolution| evolution.version == version);
+
+        match evolution {
+            Some(evolution) if depth > 0 => {
+                let mut groups =
+                    self.member_groups_at(name, &evolution.parent_version, depth - 1)?;
+                groups.extend(evolution.additions.iter().map(statement_members));
+                groups.retain(|group| !evolution.removals.iter().any(|r| group.contains(r)));
+                Some(groups)
+            }
+            _ => {
+                let statements = match self.get(name)? {
+                    Declaration::Gene(gene) => &gene.statements,
+                    Declaration::Trait(trait_decl) => &trait_decl.statements,
+                    Declaration::Constraint(constraint) => &constraint.statements,
+                    Declaration::System(system) => &system.statements,
+                    _ => return Some(Vec::new()),
+                };
+                Some(statements.iter().map(statement_members).collect())
+            }
+        }
+    }
+}
+
+impl<'a> IntoIterator for &Repository<'a> {
+    type Item = &'a Declaration;
+    type IntoIter = std::slice::Iter<'a, Declaration>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Returns the names of the declarations that `decl` refers to.
+///
+/// These are the targets of `uses`, `requires` and `derives from`
+/// statements, the gene a gene extends, referenced (bodiless) inline
+/// constraints, and a system's versioned requirements.
+pub fn dependencies(decl: &Declaration) -> BTreeSet<&str> {
+    let (statements, extra): (&[Statement], Vec<&str>) = match decl {
+        Declaration::Gene(gene) => (
+            &gene.statements,
+            gene.extends.iter().map(String::as_str).collect(),
+        ),
+        Declaration::Trait(trait_decl) => (&trait_decl.statements, Vec::new()),
+        Declaration::Constraint(constraint) => (&constraint.statements, Vec::new()),
+        Declaration::System(system) => (
+            &system.statements,
+            system
+                .requirements
+                .iter()
+                .map(|r| r.name.as_str())
+                .collect(),
+        ),
+        _ => return BTreeSet::new(),
+    };
+    statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::Uses { reference, .. } => Some(reference.as_str()),
+            Statement::Requires { requirement, .. } => Some(requirement.as_str()),
+            Statement::DerivesFrom { origin, .. } => Some(origin.as_str()),
+            Statement::Constraint(constraint) if constraint.is_reference() => {
+                Some(constraint.name.as_str())
+            }
+            _ => None,
+        })
+        .chain(extra)
+        .collect()
+}
+
+/// Returns the type names used by the typed fields of a declaration.
+fn field_type_names(decl: &Declaration) -> BTreeSet<&str> {
+    let statements = match decl {
+        Declaration::Gene(gene) => &gene.statements,
+        Declaration::Trait(trait_decl) => &trait_decl.statements,
+        _ => return BTreeSet::new(),
+    };
+    let mut names = BTreeSet::new();
+    for stmt in statements {
+        if let Statement::HasField(field) = stmt {
+            collect_type_names(&field.type_, &mut names);
+        }
+    }
+    names
+}
+
+/// Collects every type name mentioned in a type expression.
+fn collect_type_names<'t>(ty: &'t TypeExpr, names: &mut BTreeSet<&'t str>) {
+    match ty {
+        TypeExpr::Named(name) => {
+            names.insert(name);
+        }
+        TypeExpr::Generic { name, args } => {
+            names.insert(name);
+            for arg in args {
+                collect_type_names(arg, names);
+            }
+        }
+        TypeExpr::Function {
+            params,
+            return_type,
+        } => {
+            for param in params {
+                collect_type_names(param, names);
+            }
+            collect_type_names(return_type, names);
+        }
+        TypeExpr::Tuple(types) => {
+            for ty in types {
+                collect_type_names(ty, names);
+            }
+        }
+        TypeExpr::Record(fields) => {
+            for (_, ty) in fields {
+                collect_type_names(ty, names);
+            }
+        }
+        TypeExpr::Enum { variants } => {
+            for variant in variants {
+                for ty in variant
+                    .fields
+                    .iter()
+                    .map(|(_, ty)| ty)
+                    .chain(&variant.tuple_types)
+                {
+                    collect_type_names(ty, names);
+                }
+            }
+        }
+        TypeExpr::Never => {}
+    }
+}
+
+/// Returns the names by which a statement can be referenced from an evolution.
+///
+/// A `has` statement is known by its property and by `subject.property`;
+/// typed fields, functions and inline constraints by their names; every
+/// other predicate by its phrase.
+pub fn statement_members(stmt: &Statement) -> Vec<String> {
+    match stmt {
+        Statement::Has {
+            subject, property, ..
+        } => vec![property.clone(), format!("{}.{}", subject, property)],
+        Statement::HasField(field) => vec![field.name.clone()],
+        Statement::Function(func) => vec![func.name.clone()],
+        Statement::Constraint(constraint) => vec![constraint.name.clone()],
+        other => vec![other.phrase_parts().join(" ")],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_topological_order_puts_dependencies_first() {
+        let source = r#"
+gene Order { has customer: Customer has lines: List<Line> }
+gene Customer { has name: String }
+gene Line { has sku: String }
+"#;
+        let file = Parser::new(source).parse_file().unwrap();
+        let repo = Repository::new(&file.declarations);
+
+        let order: Vec<&str> = repo
+            .topological_order()
+            .unwrap()
+            .into_iter()
+            .map(Declaration::name)
+            .collect();
+        assert_eq!(order, ["Customer", "Line", "Order"]);
+    }
+
+    #[test]
+    fn test_topological_order_reports_cycle() {
+        let source = r#"
+trait a.first { uses b.second }
+trait b.second { uses a.first }
+"#;
+        let file = Parser::new(source).parse_file().unwrap();
+        let repo = Repository::new(&file.declarations);
+
+        let err = repo.topological_order().unwrap_err();
+        assert_eq!(err.chain, ["a.first", "b.second", "a.first"]);
+        assert_eq!(
+            err.to_string(),
+            "dependency cycle: a.first -> b.second -> a.first"
+        );
+    }
+
+    #[test]
+    fn test_members_follow_lineage() {
+        let source = r#"
+gene container.exists { container has id }
+evolves container.exists @ 0.0.2 > 0.0.1 { adds container has name }
+evolves container.exists @ 0.0.3 > 0.0.2 { removes id }
+"#;
+        let file = Parser::new(source).parse_file().unwrap();
+        let repo = Repository::new(&file.declarations);
+
+        let v2 = repo.members_at("container.exists", "0.0.2").unwrap();
+        assert!(v2.contains("id") && v2.contains("name"));
+
+        let v3 = repo.members_at("container.exists", "0.0.3").unwrap();
+        assert!(!v3.contains("id") && !v3.contains("container.id"));
+        assert!(v3.contains("container.name"));
+
+        assert_eq!(repo.lineage("container.exists").len(), 2);
+        assert!(repo.members_at("missing.gene", "0.0.1").is_none());
+    }
+
+    #[test]
+    fn test_iter_kind_filters_declarations() {
+        let source = r#"
+gene container.exists { container has id }
+trait container.lifecycle { uses container.exists }
+gene node.exists { node has address }
+system cluster @ 0.1.0 { requires container.exists >= 0.0.1 }
+"#;
+        let file = Parser::new(source).parse_file().unwrap();
+        let repo = Repository::new(&file.declarations);
+
+        let genes: Vec<&str> = repo
+            .iter_kind(DeclarationKind::Gene)
+            .map(Declaration::name)
+            .collect();
+        assert_eq!(genes, ["container.exists", "node.exists"]);
+        assert_eq!(repo.iter_kind(DeclarationKind::Trait).count(), 1);
+        assert_eq!(repo.iter_kind(DeclarationKind::Evolution).count(), 0);
+
+        let mut count = 0;
+        for _decl in &repo {
+            count += 1;
+        }
+        assert_eq!(count, 4);
+    }
+}
@@ -11,17 +11,25 @@
 //! - **E002**: Mutable global outside sex - Mutable global state accessed in pure context
 //! - **E003**: FFI outside sex - Foreign function interface call in pure context
 //! - **E004**: I/O outside sex - I/O operation in pure context
+//! - **E005**: Impure function - Function declared pure is inferred to perform effects
+//! - **E006**: Sex declaration in pure file - `sex extern` or `sex var` outside a sex file
 //!
 //! ## Warnings
 //!
 //! - **W001**: Large sex block - Sex block exceeds recommended size
 //! - **W002**: Sex function without documentation - Sex function lacks exegesis
 
+use crate::ast::{Declaration, Expr, FunctionDecl, Gene, Purity, Span, Trait};
 #[cfg(test)]
-use crate::ast::Statement;
-use crate::ast::{Declaration, Gene, Span, Trait};
-use crate::sex::context::SexContext;
-use crate::sex::tracking::{EffectKind, EffectTracker};
+use crate::ast::{Statement, Visibility};
+use crate::repository::Repository;
+use crate::sex::context::{FileContext, SexContext};
+use crate::sex::tracking::{Effect, EffectKind, EffectSet, EffectTracker};
+use crate::transform::visitor::{walk_expr, Visitor};
+use std::collections::{HashMap, HashSet};
+
+/// Builtins that perform I/O when called.
+pub(crate) const IO_BUILTINS: &[&str] = &["print", "println", "eprintln", "read_line"];
 
 /// A sex lint error.
 ///
@@ -69,6 +77,34 @@ pub enum SexLintError {
         /// Location of the operation
         span: Span,
     },
+
+    /// E005: Impure function.
+    ///
+    /// A function not declared `sex` performs effects, directly or through
+    /// the functions it calls.
+    ImpureFunction {
+        /// Name of the function
+        name: String,
+        /// Where the effect comes from, e.g. "call to extern 'c_write'"
+        cause: String,
+        /// Location of the effect
+        span: Span,
+    },
+
+    /// E006: Sex declaration in pure file.
+    ///
+    /// A `sex extern` function or top-level `sex var` was declared in a file
+    /// that is not in sex context.
+    SexDeclarationInPureFile {
+        /// Kind of declaration ("sex extern" or "sex var")
+        kind: String,
+        /// Name of the declaration
+        name: String,
+        /// Path of the pure file
+        path: String,
+        /// Location of the declaration
+        span: Span,
+    },
 }
 
 impl SexLintError {
@@ -94,6 +130,8 @@ impl SexLintError {
             SexLintError::MutableGlobalOutsideSex { .. } => "E002",
             SexLintError::FfiOutsideSex { .. } => "E003",
             SexLintError::IoOutsideSex { .. } => "E004",
+            SexLintError::ImpureFunction { .. } => "E005",
+            SexLintError::SexDeclarationInPureFile { .. } => "E006",
         }
     }
 
@@ -103,7 +141,9 @@ impl SexLintError {
             SexLintError::SexInPureContext { span, .. }
             | SexLintError::MutableGlobalOutsideSex { span, .. }
             | SexLintError::FfiOutsideSex { span, .. }
-            | SexLintError::IoOutsideSex { span, .. } => *span,
+            | SexLintError::IoOutsideSex { span, .. }
+            | SexLintError::ImpureFunction { span, .. }
+            | SexLintError::SexDeclarationInPureFile { span, .. } => *span,
         }
     }
 }
@@ -148,6 +188,31 @@ impl std::fmt::Display for SexLintError {
                 span.line,
                 span.column
             ),
+            SexLintError::ImpureFunction { name, cause, span } => write!(
+                f,
+                "[{}] function '{}' is not declared sex but performs effects ({}) at line {}, column {}",
+                self.code(),
+                name,
+                cause,
+                span.line,
+                span.column
+            ),
+            SexLintError::SexDeclarationInPureFile {
+                kind,
+                name,
+                path,
+                span,
+            } => write!(
+                f,
+                "[{}] {} '{}' declared in pure file '{}' at line {}, column {}; \
+                 rename the file to *.sex.dol or move it to a sex/ directory",
+                self.code(),
+                kind,
+                name,
+                path,
+                span.line,
+                span.column
+            ),
         }
     }
 }
@@ -284,6 +349,43 @@ impl LintResult {
     }
 }
 
+/// The inferred effects of a single function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionEffects {
+    /// Function name
+    pub name: String,
+    /// Declared purity of the function
+    pub purity: Purity,
+    /// Every kind of effect the function may perform
+    pub effects: EffectSet,
+    /// Where each effect comes from; the context names the call, extern,
+    /// or global responsible
+    pub sources: Vec<Effect>,
+}
+
+impl FunctionEffects {
+    /// Returns `true` if the function performs no effects.
+    pub fn is_pure(&self) -> bool {
+        self.effects.is_empty()
+    }
+}
+
+/// Effect summary for every function in a repository.
+///
+/// Produced by [`SexLinter::effect_report`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EffectReport {
+    /// One entry per function, in source order
+    pub functions: Vec<FunctionEffects>,
+}
+
+impl EffectReport {
+    /// Returns the entry for the named function.
+    pub fn get(&self, name: &str) -> Option<&FunctionEffects> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+}
+
 /// Sex linter for DOL code.
 ///
 /// Enforces purity constraints and detects improper use of side effects.
@@ -293,15 +395,17 @@ impl LintResult {
 /// ```rust
 /// use metadol::sex::lint::SexLinter;
 /// use metadol::sex::context::SexContext;
-/// use metadol::ast::{Declaration, Gene, Span};
+/// use metadol::ast::{Declaration, Gene, QualifiedName, Span, Visibility};
 ///
 /// let linter = SexLinter::new(SexContext::Pure);
 ///
 /// let gene = Gene {
-///     name: "test.gene".to_string(),
+///     name: QualifiedName::new("test.gene"),
 ///     extends: None,
 ///     statements: vec![],
-///     exegesis: "Test gene".to_string(),
+///     exegesis: "Test gene".into(),
+///     visibility: Visibility::Private,
+///     attributes: vec![],
 ///     span: Span::default(),
 /// };
 ///
@@ -420,6 +524,214 @@ impl SexLinter {
         result
     }
 
+    /// Lint every declaration in a repository.
+    ///
+    /// Runs [`lint_declaration`](Self::lint_declaration) on each declaration.
+    /// In a pure context it also reports each function that is not declared
+    /// `sex` but is inferred to perform effects (see
+    /// [`effect_report`](Self::effect_report)), pointing at the first effect.
+    pub fn lint_repository(&self, repo: &Repository) -> LintResult {
+        let mut result = LintResult::new();
+        for decl in repo {
+            let decl_result = self.lint_declaration(decl);
+            result.errors.extend(decl_result.errors);
+            result.warnings.extend(decl_result.warnings);
+        }
+
+        if self.context.is_pure() {
+            for func in self.effect_report(repo).functions {
+                if func.purity != Purity::Pure {
+                    continue;
+                }
+                if let Some(source) = func.sources.first() {
+                    result.add_error(SexLintError::ImpureFunction {
+                        name: func.name.clone(),
+                        cause: source
+                            .context
+                            .clone()
+                            .unwrap_or_else(|| source.kind.to_string()),
+                        span: source.span,
+                    });
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Lint where sex declarations are placed.
+    ///
+    /// `sex extern` functions and top-level `sex var` globals belong in sex
+    /// files. When `file` is in pure context, each one found in `decls` is
+    /// an error pointing at its declaration.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metadol::sex::{FileContext, SexContext, SexLinter};
+    /// use std::path::PathBuf;
+    ///
+    /// let decls = metadol::parse_file_all("sex var COUNTER: Int64 = 0").unwrap();
+    /// let linter = SexLinter::new(SexContext::Pure);
+    ///
+    /// let pure = FileContext::new(PathBuf::from("container.dol"));
+    /// assert_eq!(linter.lint_sex_declarations(&pure, &decls).errors[0].code(), "E006");
+    ///
+    /// let sex = FileContext::new(PathBuf::from("container.sex.dol"));
+    /// assert!(linter.lint_sex_declarations(&sex, &decls).is_clean());
+    /// ```
+    pub fn lint_sex_declarations(&self, file: &FileContext, decls: &[Declaration]) -> LintResult {
+        let mut result = LintResult::new();
+        if file.is_sex() {
+            return result;
+        }
+
+        let path = file.path.display().to_string();
+        let mut report = |kind: &str, name: &str, span: Span| {
+            result.add_error(SexLintError::SexDeclarationInPureFile {
+                kind: kind.to_string(),
+                name: name.to_string(),
+                path: path.clone(),
+                span,
+            });
+        };
+        for decl in decls {
+            match decl {
+                Declaration::Extern(block) => {
+                    for function in &block.functions {
+                        report("sex extern", &function.name, function.span);
+                    }
+                }
+                Declaration::SexVar(var) => report("sex var", &var.name, var.span),
+                _ => {}
+            }
+        }
+        result
+    }
+
+    /// Summarizes the effects of every function in a repository.
+    ///
+    /// Effects come from calls to `sex extern` functions (FFI), reads and
+    /// writes of `sex var` globals, I/O builtins such as `print`, and the
+    /// `sex fun` marker itself. A call to another function in the
+    /// repository inherits the callee's effects, attributed to that call.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metadol::repository::Repository;
+    /// use metadol::sex::context::SexContext;
+    /// use metadol::sex::lint::SexLinter;
+    ///
+    /// let decls = metadol::parse_file_all("fun double(x: Int64) -> Int64 { return x * 2 }").unwrap();
+    /// let report = SexLinter::new(SexContext::Pure).effect_report(&Repository::new(&decls));
+    /// assert!(report.get("double").unwrap().is_pure());
+    /// ```
+    pub fn effect_report(&self, repo: &Repository) -> EffectReport {
+        let mut externs = HashSet::new();
+        let mut globals = HashSet::new();
+        for decl in repo {
+            match decl {
+                Declaration::Extern(block) => {
+                    externs.extend(block.functions.iter().map(|f| f.name.clone()));
+                }
+                Declaration::SexVar(var) => {
+                    globals.insert(var.name.clone());
+                }
+                _ => {}
+            }
+        }
+
+        let functions: Vec<&FunctionDecl> = repo
+            .iter()
+            .filter_map(|decl| match decl {
+                Declaration::Function(func) => Some(func.as_ref()),
+                _ => None,
+            })
+            .collect();
+
+        // Direct effects, plus the calls that may inherit effects from
+        // other functions in the repository
+        let mut entries = Vec::with_capacity(functions.len());
+        let mut calls = Vec::with_capacity(functions.len());
+        for func in &functions {
+            let mut sites = CallSites::new(func.span);
+            sites.visit_function_decl(func);
+
+            let mut sources = Vec::new();
+            if func.purity == Purity::Sex {
+                sources.push(Effect::with_context(
+                    EffectKind::General,
+                    func.span,
+                    format!("sex fun '{}'", func.name),
+                ));
+            }
+            let mut local_calls = Vec::new();
+            for (name, span) in sites.calls {
+                if externs.contains(&name) {
+                    sources.push(Effect::with_context(
+                        EffectKind::Ffi,
+                        span,
+                        format!("call to extern '{}'", name),
+                    ));
+                } else if IO_BUILTINS.contains(&name.as_str()) {
+                    sources.push(Effect::with_context(
+                        EffectKind::Io,
+                        span,
+                        format!("call to '{}'", name),
+                    ));
+                } else if functions.iter().any(|f| f.name == name) {
+                    local_calls.push((name, span));
+                }
+            }
+            for (name, span) in sites.identifiers {
+                if globals.contains(&name) {
+                    sources.push(Effect::with_context(
+                        EffectKind::MutableGlobal,
+                        span,
+                        format!("access to global '{}'", name),
+                    ));
+                }
+            }
+
+            entries.push(FunctionEffects {
+                name: func.name.clone(),
+                purity: func.purity,
+                effects: sources.iter().map(|e| e.kind).collect(),
+                sources,
+            });
+            calls.push(local_calls);
+        }
+
+        // Propagate callee effects until nothing changes
+        let index: HashMap<String, usize> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, f)| (f.name.clone(), i))
+            .collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (caller, local_calls) in calls.iter().enumerate() {
+                for (callee, span) in local_calls {
+                    let inherited = entries[index[callee]].effects.clone();
+                    for kind in inherited {
+                        if entries[caller].effects.insert(kind) {
+                            entries[caller].sources.push(Effect::with_context(
+                                kind,
+                                *span,
+                                format!("call to '{}'", callee),
+                            ));
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        EffectReport { functions: entries }
+    }
+
     /// Check if a declaration's block size exceeds the maximum.
     fn check_block_size(&self, decl: &Declaration, result: &mut LintResult) {
         let (size, span) = match decl {
@@ -453,9 +765,51 @@ impl SexLinter {
     }
 }
 
+/// Collects the calls and identifier uses in a function body, each with the
+/// span of its nearest spanned expression.
+struct CallSites {
+    span: Span,
+    calls: Vec<(String, Span)>,
+    identifiers: Vec<(String, Span)>,
+}
+
+impl CallSites {
+    fn new(span: Span) -> Self {
+        Self {
+            span,
+            calls: Vec::new(),
+            identifiers: Vec::new(),
+        }
+    }
+}
+
+impl Visitor for CallSites {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Spanned { expr, span } => {
+                let outer = std::mem::replace(&mut self.span, *span);
+                self.visit_expr(expr);
+                self.span = outer;
+            }
+            Expr::Call { callee, .. } => {
+                if let Expr::Identifier(name) = callee.unspanned() {
+                    self.calls.push((name.clone(), self.span));
+                }
+                walk_expr(self, expr);
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+
+    fn visit_identifier(&mut self, name: &str) {
+        self.identifiers.push((name.to_string(), self.span));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::QualifiedName;
 
     #[test]
     fn test_lint_error_codes() {
@@ -528,14 +882,16 @@ mod tests {
         let linter = SexLinter::new(SexContext::Pure);
 
         let gene = Gene {
-            name: "test.gene".to_string(),
+            name: QualifiedName::new("test.gene"),
             extends: None,
             statements: vec![Statement::Has {
                 subject: "test".to_string(),
                 property: "property".to_string(),
                 span: Span::default(),
             }],
-            exegesis: "Test gene".to_string(),
+            exegesis: "Test gene".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -548,14 +904,16 @@ mod tests {
         let linter = SexLinter::new(SexContext::Pure);
 
         let gene = Gene {
-            name: "io.gene".to_string(),
+            name: QualifiedName::new("io.gene"),
             extends: None,
             statements: vec![Statement::Has {
                 subject: "io".to_string(),
                 property: "file_read".to_string(),
                 span: Span::default(),
             }],
-            exegesis: "Test gene".to_string(),
+            exegesis: "Test gene".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -578,10 +936,12 @@ mod tests {
             .collect();
 
         let gene = Gene {
-            name: "test.gene".to_string(),
+            name: QualifiedName::new("test.gene"),
             extends: None,
             statements,
-            exegesis: "Test gene".to_string(),
+            exegesis: "Test gene".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -596,10 +956,12 @@ mod tests {
         let linter = SexLinter::new(SexContext::Sex);
 
         let gene = Gene {
-            name: "test.gene".to_string(),
+            name: QualifiedName::new("test.gene"),
             extends: None,
             statements: vec![],
-            exegesis: "Short".to_string(), // Too short
+            exegesis: "Short".into(), // Too short
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -607,4 +969,59 @@ mod tests {
         assert!(result.has_warnings());
         assert_eq!(result.warnings[0].code(), "W002");
     }
+
+    #[test]
+    fn test_effect_report_attributes_extern_call() {
+        let source = r#"
+sex extern fun c_write(fd: Int64) -> Int64
+
+fun flush(fd: Int64) -> Int64 {
+    return c_write(fd)
+}
+
+fun sync() -> Int64 {
+    return flush(1)
+}
+
+fun double(x: Int64) -> Int64 {
+    return x * 2
+}
+"#;
+        let decls = crate::parse_file_all(source).unwrap();
+        let report = SexLinter::new(SexContext::Pure).effect_report(&Repository::new(&decls));
+
+        let flush = report.get("flush").unwrap();
+        assert!(flush.effects.contains(&EffectKind::Ffi));
+        assert_eq!(flush.sources.len(), 1);
+        assert_eq!(
+            flush.sources[0].context.as_deref(),
+            Some("call to extern 'c_write'")
+        );
+
+        let sync = report.get("sync").unwrap();
+        assert!(sync.effects.contains(&EffectKind::Ffi));
+        assert_eq!(sync.sources[0].context.as_deref(), Some("call to 'flush'"));
+
+        assert!(report.get("double").unwrap().is_pure());
+    }
+
+    #[test]
+    fn test_effect_report_ignores_gene_documented_as_extern() {
+        let source = r#"
+gene c_write {
+    c_write has fd
+}
+
+exegesis {
+    sex extern c_write
+}
+
+fun write_all(fd: Int64) -> Int64 {
+    return c_write(fd)
+}
+"#;
+        let decls = crate::parse_file_all(source).unwrap();
+        let report = SexLinter::new(SexContext::Pure).effect_report(&Repository::new(&decls));
+        assert!(report.get("write_all").unwrap().is_pure());
+    }
 }
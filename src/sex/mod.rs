@@ -38,7 +38,9 @@ pub mod lint;
 pub mod tracking;
 
 pub use context::{FileContext, SexContext};
-pub use lint::{LintResult, SexLintError, SexLintWarning, SexLinter};
+pub use lint::{
+    EffectReport, FunctionEffects, LintResult, SexLintError, SexLintWarning, SexLinter,
+};
 pub use tracking::EffectTracker;
 
 use std::path::Path;
@@ -4,13 +4,16 @@
 //! to detect purity violations and enforce sex context rules.
 
 use crate::ast::{Declaration, Expr, Gene, Purity, Span, Statement, Stmt, Trait};
-use std::collections::{HashMap, HashSet};
+use crate::repository::Repository;
+use crate::sex::context::SexContext;
+use crate::sex::lint::SexLinter;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 /// The kind of side effect being tracked.
 ///
 /// Different kinds of effects may have different severity levels
 /// and linting rules.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum EffectKind {
     /// I/O operations (file, network, console)
     Io,
@@ -46,6 +49,9 @@ impl std::fmt::Display for EffectKind {
     }
 }
 
+/// The set of effect kinds a function may perform.
+pub type EffectSet = BTreeSet<EffectKind>;
+
 /// A tracked side effect occurrence.
 ///
 /// Records where an effect occurs in the source code and what kind of effect it is.
@@ -110,15 +116,17 @@ impl Effect {
 ///
 /// ```rust
 /// use metadol::sex::tracking::EffectTracker;
-/// use metadol::ast::{Declaration, Gene, Span};
+/// use metadol::ast::{Declaration, Gene, QualifiedName, Span, Visibility};
 ///
 /// let mut tracker = EffectTracker::new();
 ///
 /// let gene = Gene {
-///     name: "test.gene".to_string(),
+///     name: QualifiedName::new("test.gene"),
 ///     extends: None,
 ///     statements: vec![],
-///     exegesis: "Test".to_string(),
+///     exegesis: "Test".into(),
+///     visibility: Visibility::Private,
+///     attributes: vec![],
 ///     span: Span::default(),
 /// };
 ///
@@ -288,6 +296,7 @@ impl EffectTracker {
                     self.track_expr(expr, effects);
                 }
             }
+            Expr::Spanned { expr, .. } => self.track_expr(expr, effects),
             _ => {}
         }
     }
@@ -295,7 +304,7 @@ impl EffectTracker {
     /// Track effects in a statement.
     fn track_stmt(&mut self, stmt: &Stmt, effects: &mut Vec<Effect>) {
         match stmt {
-            Stmt::Let { value, .. } => {
+            Stmt::Let { value, .. } | Stmt::LetPattern { value, .. } => {
                 self.track_expr(value, effects);
             }
             Stmt::Assign { target, value } => {
@@ -358,6 +367,44 @@ impl EffectTracker {
             .unwrap_or(&[])
     }
 
+    /// Infer the sex context of every function in `decls`.
+    ///
+    /// A function is [`SexContext::Sex`] if it performs an effect directly
+    /// (a `sex extern` call, a `sex var` access, an I/O builtin, or being
+    /// declared `sex fun`) or calls, directly or transitively, a function
+    /// that is. The call graph is iterated to a fixpoint, so recursive and
+    /// mutually recursive functions terminate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use metadol::sex::context::SexContext;
+    /// use metadol::sex::tracking::EffectTracker;
+    ///
+    /// let decls = metadol::parse_file_all(
+    ///     "sex extern fun c_write(fd: Int64) -> Int64\n\
+    ///      fun flush(fd: Int64) -> Int64 { return c_write(fd) }",
+    /// )
+    /// .unwrap();
+    /// let contexts = EffectTracker::infer_effects(&decls);
+    /// assert_eq!(contexts["flush"], SexContext::Sex);
+    /// ```
+    pub fn infer_effects(decls: &[Declaration]) -> HashMap<String, SexContext> {
+        let report = SexLinter::new(SexContext::Pure).effect_report(&Repository::new(decls));
+        report
+            .functions
+            .into_iter()
+            .map(|func| {
+                let context = if func.is_pure() {
+                    SexContext::Pure
+                } else {
+                    SexContext::Sex
+                };
+                (func.name, context)
+            })
+            .collect()
+    }
+
     /// Set the purity annotation for a declaration.
     ///
     /// # Arguments
@@ -385,6 +432,7 @@ impl EffectTracker {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::{QualifiedName, Visibility};
 
     #[test]
     fn test_effect_kind_display() {
@@ -432,14 +480,16 @@ mod tests {
         let mut tracker = EffectTracker::new();
 
         let gene = Gene {
-            name: "io.gene".to_string(),
+            name: QualifiedName::new("io.gene"),
             extends: None,
             statements: vec![Statement::Has {
                 subject: "io".to_string(),
                 property: "file_read".to_string(),
                 span: Span::default(),
             }],
-            exegesis: "Test".to_string(),
+            exegesis: "Test".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -0,0 +1,170 @@
+//! Detection of shared `uses` sets that could become a common base trait.
+//!
+//! When several declarations compose the same references, the shared set is
+//! a candidate for extraction into a base trait that each of them `uses`
+//! instead. This module only detects the opportunity; the suggestions can be
+//! reported as diagnostics and applied by hand.
+
+use crate::ast::{Declaration, Span, Statement};
+use crate::lower::{DiagnosticKind, LowerDiagnostic};
+use crate::repository::Repository;
+use std::collections::BTreeSet;
+
+/// Smallest shared `uses` set worth extracting into a base.
+const MIN_SHARED_USES: usize = 2;
+
+/// A set of `uses` references shared by several declarations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseSuggestion {
+    /// The shared references, sorted
+    pub uses: Vec<String>,
+    /// Names of the declarations that use every shared reference, in
+    /// source order
+    pub declarations: Vec<String>,
+    /// Location of the first declaration in the group
+    pub span: Span,
+}
+
+impl BaseSuggestion {
+    /// Reports the suggestion as a warning diagnostic.
+    pub fn to_diagnostic(&self) -> LowerDiagnostic {
+        LowerDiagnostic {
+            kind: DiagnosticKind::Warning,
+            message: format!(
+                "{} share uses {}",
+                self.declarations.join(", "),
+                self.uses.join(", ")
+            ),
+            span: Some(self.span),
+            suggestion: Some(format!(
+                "extract a base trait that uses {} and use it instead",
+                self.uses.join(", ")
+            )),
+        }
+    }
+}
+
+/// Finds sets of `uses` references shared by two or more declarations.
+///
+/// Candidates are the pairwise intersections of the declarations' `uses`
+/// sets with at least two references. A candidate is dropped when a larger
+/// candidate is shared by exactly the same declarations, so each group of
+/// declarations is reported once with its largest common set. Suggestions
+/// are ordered by group size, largest first.
+pub fn suggest_common_bases(repo: &Repository<'_>) -> Vec<BaseSuggestion> {
+    let users: Vec<(&Declaration, BTreeSet<&str>)> = repo
+        .iter()
+        .filter_map(|decl| {
+            let uses = uses_of(decl);
+            (uses.len() >= MIN_SHARED_USES).then_some((decl, uses))
+        })
+        .collect();
+
+    let mut candidates: BTreeSet<BTreeSet<&str>> = BTreeSet::new();
+    for (i, (_, a)) in users.iter().enumerate() {
+        for (_, b) in &users[i + 1..] {
+            let shared: BTreeSet<&str> = a.intersection(b).copied().collect();
+            if shared.len() >= MIN_SHARED_USES {
+                candidates.insert(shared);
+            }
+        }
+    }
+
+    let groups: Vec<(&BTreeSet<&str>, Vec<&Declaration>)> = candidates
+        .iter()
+        .map(|shared| {
+            let members = users
+                .iter()
+                .filter(|(_, uses)| shared.is_subset(uses))
+                .map(|(decl, _)| *decl)
+                .collect();
+            (shared, members)
+        })
+        .collect();
+
+    let mut suggestions: Vec<BaseSuggestion> = groups
+        .iter()
+        .filter(|(shared, members)| {
+            !groups.iter().any(|(other, other_members)| {
+                other.len() > shared.len()
+                    && shared.is_subset(other)
+                    && other_members.len() == members.len()
+            })
+        })
+        .map(|(shared, members)| BaseSuggestion {
+            uses: shared.iter().map(|s| s.to_string()).collect(),
+            declarations: members.iter().map(|d| d.name().to_string()).collect(),
+            span: members[0].span(),
+        })
+        .collect();
+
+    suggestions.sort_by_key(|s| std::cmp::Reverse(s.declarations.len()));
+    suggestions
+}
+
+/// Returns the references a declaration `uses`.
+fn uses_of(decl: &Declaration) -> BTreeSet<&str> {
+    let statements = match decl {
+        Declaration::Gene(gene) => &gene.statements,
+        Declaration::Trait(trait_decl) => &trait_decl.statements,
+        Declaration::System(system) => &system.statements,
+        _ => return BTreeSet::new(),
+    };
+    statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::Uses { reference, .. } => Some(reference.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_three_traits_share_two_uses() {
+        let source = r#"
+trait container.lifecycle { uses container.exists uses identity.cryptographic uses clock.monotonic }
+trait container.networking { uses container.exists uses identity.cryptographic uses network.core }
+trait container.storage { uses identity.cryptographic uses container.exists }
+trait node.lifecycle { uses node.exists }
+"#;
+        let file = crate::parser::Parser::new(source).parse_file().unwrap();
+        let repo = Repository::new(&file.declarations);
+
+        let suggestions = suggest_common_bases(&repo);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(
+            suggestions[0].uses,
+            ["container.exists", "identity.cryptographic"]
+        );
+        assert_eq!(
+            suggestions[0].declarations,
+            [
+                "container.lifecycle",
+                "container.networking",
+                "container.storage"
+            ]
+        );
+
+        let diagnostic = suggestions[0].to_diagnostic();
+        assert_eq!(diagnostic.kind, DiagnosticKind::Warning);
+        assert!(diagnostic
+            .message
+            .contains("container.exists, identity.cryptographic"));
+    }
+
+    #[test]
+    fn test_single_shared_use_is_not_suggested() {
+        let source = r#"
+trait a.one { uses base.x uses base.y }
+trait b.two { uses base.x uses base.z }
+"#;
+        let file = crate::parser::Parser::new(source).parse_file().unwrap();
+        let repo = Repository::new(&file.declarations);
+
+        assert!(suggest_common_bases(&repo).is_empty());
+    }
+}
@@ -56,6 +56,10 @@ impl IdiomDesugar {
     #[allow(clippy::only_used_in_recursion)]
     pub fn desugar_expr(&self, expr: Expr) -> Expr {
         match expr {
+            Expr::Spanned { expr, span } => Expr::Spanned {
+                expr: Box::new(self.desugar_expr(*expr)),
+                span,
+            },
             Expr::IdiomBracket { func, args } => {
                 // First, recursively desugar the function and arguments
                 let func = self.desugar_expr(*func);
@@ -176,6 +180,10 @@ impl IdiomDesugar {
                             type_ann,
                             value: self.desugar_expr(value),
                         },
+                        Stmt::LetPattern { pattern, value } => Stmt::LetPattern {
+                            pattern,
+                            value: self.desugar_expr(value),
+                        },
                         Stmt::Assign { target, value } => Stmt::Assign {
                             target: self.desugar_expr(target),
                             value: self.desugar_expr(value),
@@ -216,6 +224,9 @@ impl IdiomDesugar {
                 Expr::Forall(ForallExpr {
                     var: forall_expr.var,
                     type_: forall_expr.type_,
+                    iter: forall_expr
+                        .iter
+                        .map(|iter| Box::new(self.desugar_expr(*iter))),
                     body: Box::new(self.desugar_expr(*forall_expr.body)),
                     span: forall_expr.span,
                 })
@@ -255,6 +266,10 @@ impl IdiomDesugar {
                             type_ann,
                             value: self.desugar_expr(value),
                         },
+                        Stmt::LetPattern { pattern, value } => Stmt::LetPattern {
+                            pattern,
+                            value: self.desugar_expr(value),
+                        },
                         Stmt::Assign { target, value } => Stmt::Assign {
                             target: self.desugar_expr(target),
                             value: self.desugar_expr(value),
@@ -297,6 +312,14 @@ impl IdiomDesugar {
                 Expr::Tuple(elements.into_iter().map(|e| self.desugar_expr(e)).collect())
             }
 
+            // Map - transform keys and values
+            Expr::Map(entries) => Expr::Map(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (self.desugar_expr(k), self.desugar_expr(v)))
+                    .collect(),
+            ),
+
             // Cast - transform inner expression
             Expr::Cast { expr, target_type } => Expr::Cast {
                 expr: Box::new(self.desugar_expr(*expr)),
@@ -153,6 +153,10 @@ pub trait Fold {
     /// Fold any expression by dispatching to specific methods.
     fn fold_expr(&mut self, expr: Expr) -> Expr {
         match expr {
+            Expr::Spanned { expr, span } => Expr::Spanned {
+                expr: Box::new(self.fold_expr(*expr)),
+                span,
+            },
             Expr::Literal(lit) => Expr::Literal(self.fold_literal(lit)),
             Expr::Identifier(name) => self.fold_identifier(name),
             Expr::Binary { left, op, right } => self.fold_binary(*left, op, *right),
@@ -185,6 +189,7 @@ pub trait Fold {
                 Expr::Forall(ForallExpr {
                     var: forall_expr.var.clone(),
                     type_: forall_expr.type_.clone(),
+                    iter: forall_expr.iter.map(|iter| Box::new(self.fold_expr(*iter))),
                     body: Box::new(self.fold_expr(*forall_expr.body)),
                     span: forall_expr.span,
                 })
@@ -213,6 +218,12 @@ pub trait Fold {
             Expr::Tuple(elements) => {
                 Expr::Tuple(elements.into_iter().map(|e| self.fold_expr(e)).collect())
             }
+            Expr::Map(entries) => Expr::Map(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (self.fold_expr(k), self.fold_expr(v)))
+                    .collect(),
+            ),
             Expr::Cast { expr, target_type } => Expr::Cast {
                 expr: Box::new(self.fold_expr(*expr)),
                 target_type,
@@ -251,6 +262,10 @@ pub trait Fold {
                 type_ann,
                 value: self.fold_expr(value),
             },
+            Stmt::LetPattern { pattern, value } => Stmt::LetPattern {
+                pattern,
+                value: self.fold_expr(value),
+            },
             Stmt::Assign { target, value } => Stmt::Assign {
                 target: self.fold_expr(target),
                 value: self.fold_expr(value),
@@ -294,6 +309,8 @@ pub trait Fold {
                 .map(|s| self.fold_statement(s))
                 .collect(),
             exegesis: gene.exegesis,
+            visibility: gene.visibility,
+            attributes: gene.attributes,
             span: gene.span,
         }
     }
@@ -11,15 +11,18 @@
 //! - **Visitor**: Immutable traversal of AST nodes
 //! - **MutVisitor**: Mutable transformation of AST nodes
 //! - **Fold**: Expression-level transformation
+//! - **common_bases**: Refactoring analysis over the `uses` graph
 
+pub mod common_bases;
 pub mod desugar_idiom;
 pub mod fold;
 pub mod passes;
 pub mod visitor;
 
+pub use common_bases::{suggest_common_bases, BaseSuggestion};
 pub use desugar_idiom::IdiomDesugar;
 pub use fold::Fold;
-pub use passes::{ConstantFolding, DeadCodeElimination};
+pub use passes::{CanonicalQuantifiers, ConstantFolding, DeadCodeElimination, Normalize};
 pub use visitor::{MutVisitor, Visitor};
 
 use crate::ast::Declaration;
@@ -78,8 +81,21 @@ pub trait Pass {
         true
     }
 
+    /// Sees every declaration of a file before any of them is run.
+    ///
+    /// Called by [`PassPipeline::run_all`], so a pass can gather facts that
+    /// depend on declarations appearing later in the file.
+    fn prepare(&mut self, _decls: &[Declaration]) {}
+
     /// Runs the pass on a declaration, potentially transforming it.
     fn run(&mut self, decl: Declaration) -> PassResult<Declaration>;
+
+    /// Statistics collected by the most recent [`run`](Pass::run).
+    ///
+    /// Passes that don't track anything report empty stats.
+    fn stats(&self) -> PassStats {
+        PassStats::new()
+    }
 }
 
 /// A pipeline of passes to run in sequence.
@@ -95,10 +111,27 @@ impl PassPipeline {
 
     /// Adds a pass to the pipeline.
     pub fn add<P: Pass + 'static>(&mut self, pass: P) -> &mut Self {
-        self.passes.push(Box::new(pass));
+        self.add_boxed(Box::new(pass))
+    }
+
+    /// Adds an already boxed pass, e.g. one chosen from configuration.
+    pub fn add_boxed(&mut self, pass: Box<dyn Pass>) -> &mut Self {
+        self.passes.push(pass);
         self
     }
 
+    /// Returns the names of the passes in the order they run.
+    pub fn pass_names(&self) -> Vec<&str> {
+        self.passes.iter().map(|pass| pass.name()).collect()
+    }
+
+    /// Removes every pass named `name`, returning whether any was removed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.passes.len();
+        self.passes.retain(|pass| pass.name() != name);
+        self.passes.len() != before
+    }
+
     /// Runs all passes on a declaration.
     pub fn run(&mut self, decl: Declaration) -> PassResult<Declaration> {
         let mut current = decl;
@@ -110,8 +143,27 @@ impl PassPipeline {
         Ok(current)
     }
 
+    /// Runs all passes on a declaration, returning the merged stats of
+    /// every pass that ran.
+    pub fn run_with_stats(&mut self, decl: Declaration) -> PassResult<(Declaration, PassStats)> {
+        let mut current = decl;
+        let mut stats = PassStats::new();
+        for pass in &mut self.passes {
+            if pass.should_run(&current) {
+                current = pass.run(current)?;
+                stats.merge(&pass.stats());
+            }
+        }
+        Ok((current, stats))
+    }
+
     /// Runs all passes on a list of declarations.
+    ///
+    /// Every pass is [prepared](Pass::prepare) with the whole list first.
     pub fn run_all(&mut self, decls: Vec<Declaration>) -> PassResult<Vec<Declaration>> {
+        for pass in &mut self.passes {
+            pass.prepare(&decls);
+        }
         decls.into_iter().map(|d| self.run(d)).collect()
     }
 }
@@ -168,6 +220,7 @@ impl PassStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::QualifiedName;
 
     struct CountingPass {
         count: usize,
@@ -186,13 +239,15 @@ mod tests {
 
     #[test]
     fn test_pipeline_runs_passes() {
-        use crate::ast::{Gene, Span};
+        use crate::ast::{Gene, Span, Visibility};
 
         let gene = Gene {
-            name: "test".to_string(),
+            name: QualifiedName::new("test"),
             extends: None,
             statements: vec![],
-            exegesis: "Test gene".to_string(),
+            exegesis: "Test gene".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::new(0, 0, 1, 1),
         };
         let decl = Declaration::Gene(gene);
@@ -206,6 +261,55 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_pipeline_names_and_remove() {
+        let mut pipeline = PassPipeline::new();
+        pipeline
+            .add(ConstantFolding::new())
+            .add_boxed(Box::new(DeadCodeElimination::new()))
+            .add(Normalize::new());
+
+        assert_eq!(
+            pipeline.pass_names(),
+            ["constant_folding", "dead_code_elimination", "normalize"]
+        );
+
+        assert!(pipeline.remove("dead_code_elimination"));
+        assert!(!pipeline.remove("dead_code_elimination"));
+        assert_eq!(pipeline.pass_names(), ["constant_folding", "normalize"]);
+    }
+
+    #[test]
+    fn test_pipeline_run_with_stats() {
+        let decl = crate::parse_file(
+            r#"
+fun pick() -> Int64 {
+  return if true && false { 1 + 2 } else { 3 * 4 }
+}
+
+exegesis {
+  Folds to a constant.
+}
+"#,
+        )
+        .unwrap();
+
+        let mut pipeline = PassPipeline::new();
+        pipeline
+            .add(CountingPass { count: 0 })
+            .add(ConstantFolding::new())
+            .add(DeadCodeElimination::new());
+
+        let (_, stats) = pipeline.run_with_stats(decl.clone()).unwrap();
+        // true && false, the if, and 3 * 4
+        assert_eq!(stats.expressions_folded, 3);
+        assert_eq!(stats.nodes_visited, 2);
+
+        // Stats are per run, not cumulative
+        let (_, stats) = pipeline.run_with_stats(decl).unwrap();
+        assert_eq!(stats.expressions_folded, 3);
+    }
+
     #[test]
     fn test_pass_error_display() {
         let err = PassError::new("test_pass", "something went wrong");
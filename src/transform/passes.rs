@@ -4,123 +4,55 @@
 //! - Constant folding
 //! - Dead code elimination
 //! - Expression simplification
+//! - Phrase normalization
+//! - Canonical quantifiers
 
-use crate::ast::{BinaryOp, Declaration, Expr, Literal, UnaryOp};
-use crate::transform::{Pass, PassResult};
+use crate::ast::{
+    BinaryOp, Declaration, Evolution, Expr, Literal, Purity, Quantifier, Statement, Stmt, UnaryOp,
+};
+use crate::lower::LowerDiagnostic;
+use crate::sex::lint::IO_BUILTINS;
+use crate::transform::visitor::walk_expr;
+use crate::transform::{Fold, MutVisitor, Pass, PassResult, PassStats, Visitor};
+use std::collections::HashSet;
 
 /// Constant folding pass.
 ///
 /// Evaluates constant expressions at compile time:
 /// - Arithmetic on literals: 1 + 2 => 3
 /// - Boolean logic: true && false => false
-/// - String concatenation: "a" + "b" => "ab"
-pub struct ConstantFolding;
+/// - Short-circuits: true && e => e, false || e => e,
+///   false && e => false, true || e => true
+/// - String concatenation: "a" + "b" => "ab", #concat("a", "b") => "ab"
+///
+/// Function bodies, trait laws and constant values are folded. `sex`
+/// blocks are left untouched, since their effects must run as written.
+#[derive(Debug, Default)]
+pub struct ConstantFolding {
+    stats: PassStats,
+}
 
 impl ConstantFolding {
     /// Creates a new constant folding pass.
     pub fn new() -> Self {
-        Self
+        Self::default()
     }
 
-    /// Fold an expression, evaluating constant subexpressions.
-    #[allow(dead_code)]
-    #[allow(clippy::only_used_in_recursion)]
-    pub fn fold_expr(&self, expr: Expr) -> Expr {
-        match expr {
-            Expr::Binary { left, op, right } => {
-                let left = self.fold_expr(*left);
-                let right = self.fold_expr(*right);
-
-                // Try to evaluate constant binary operations
-                if let (Expr::Literal(l), Expr::Literal(r)) = (&left, &right) {
-                    if let Some(result) = Self::eval_binary(l, &op, r) {
-                        return Expr::Literal(result);
-                    }
-                }
-
-                Expr::Binary {
-                    left: Box::new(left),
-                    op,
-                    right: Box::new(right),
-                }
-            }
-            Expr::Unary { op, operand } => {
-                let operand = self.fold_expr(*operand);
-
-                // Try to evaluate constant unary operations
-                if let Expr::Literal(lit) = &operand {
-                    if let Some(result) = Self::eval_unary(&op, lit) {
-                        return Expr::Literal(result);
-                    }
-                }
-
-                Expr::Unary {
-                    op,
-                    operand: Box::new(operand),
-                }
-            }
-            Expr::If {
-                condition,
-                then_branch,
-                else_branch,
-            } => {
-                let condition = self.fold_expr(*condition);
-
-                // If condition is constant, select the appropriate branch
-                if let Expr::Literal(Literal::Bool(b)) = &condition {
-                    return if *b {
-                        self.fold_expr(*then_branch)
-                    } else if let Some(else_expr) = else_branch {
-                        self.fold_expr(*else_expr)
-                    } else {
-                        // No else branch, return void-like
-                        Expr::Block {
-                            statements: vec![],
-                            final_expr: None,
-                        }
-                    };
-                }
-
-                Expr::If {
-                    condition: Box::new(condition),
-                    then_branch: Box::new(self.fold_expr(*then_branch)),
-                    else_branch: else_branch.map(|e| Box::new(self.fold_expr(*e))),
-                }
-            }
-            Expr::Call { callee, args } => Expr::Call {
-                callee: Box::new(self.fold_expr(*callee)),
-                args: args.into_iter().map(|a| self.fold_expr(a)).collect(),
-            },
-            Expr::Lambda {
-                params,
-                return_type,
-                body,
-            } => Expr::Lambda {
-                params,
-                return_type,
-                body: Box::new(self.fold_expr(*body)),
-            },
-            Expr::Block {
-                statements,
-                final_expr,
-            } => Expr::Block {
-                statements,
-                final_expr: final_expr.map(|e| Box::new(self.fold_expr(*e))),
-            },
-            other => other,
-        }
+    /// Records one folded expression.
+    fn folded(&mut self, expr: Expr) -> Expr {
+        self.stats.expressions_folded += 1;
+        expr
     }
 
     /// Evaluate a binary operation on literals.
-    #[allow(dead_code)]
     fn eval_binary(left: &Literal, op: &BinaryOp, right: &Literal) -> Option<Literal> {
         match (left, right) {
             (Literal::Int(a), Literal::Int(b)) => match op {
                 BinaryOp::Add => Some(Literal::Int(a.wrapping_add(*b))),
                 BinaryOp::Sub => Some(Literal::Int(a.wrapping_sub(*b))),
                 BinaryOp::Mul => Some(Literal::Int(a.wrapping_mul(*b))),
-                BinaryOp::Div if *b != 0 => Some(Literal::Int(a / b)),
-                BinaryOp::Mod if *b != 0 => Some(Literal::Int(a % b)),
+                BinaryOp::Div => a.checked_div(*b).map(Literal::Int),
+                BinaryOp::Mod => a.checked_rem(*b).map(Literal::Int),
                 BinaryOp::Eq => Some(Literal::Bool(a == b)),
                 BinaryOp::Ne => Some(Literal::Bool(a != b)),
                 BinaryOp::Lt => Some(Literal::Bool(a < b)),
@@ -160,10 +92,9 @@ impl ConstantFolding {
     }
 
     /// Evaluate a unary operation on a literal.
-    #[allow(dead_code)]
     fn eval_unary(op: &UnaryOp, operand: &Literal) -> Option<Literal> {
         match (op, operand) {
-            (UnaryOp::Neg, Literal::Int(n)) => Some(Literal::Int(-n)),
+            (UnaryOp::Neg, Literal::Int(n)) => n.checked_neg().map(Literal::Int),
             (UnaryOp::Neg, Literal::Float(f)) => Some(Literal::Float(-f)),
             (UnaryOp::Not, Literal::Bool(b)) => Some(Literal::Bool(!b)),
             _ => None,
@@ -171,9 +102,107 @@ impl ConstantFolding {
     }
 }
 
-impl Default for ConstantFolding {
-    fn default() -> Self {
-        Self::new()
+impl Fold for ConstantFolding {
+    fn fold_binary(&mut self, left: Expr, op: BinaryOp, right: Expr) -> Expr {
+        let left = self.fold_expr(left);
+        let right = self.fold_expr(right);
+
+        // Try to evaluate constant binary operations
+        if let (Expr::Literal(l), Expr::Literal(r)) = (left.unspanned(), right.unspanned()) {
+            if let Some(result) = Self::eval_binary(l, &op, r) {
+                return self.folded(Expr::Literal(result));
+            }
+        }
+
+        // Short-circuit on a constant left operand
+        match (left.unspanned(), &op) {
+            (Expr::Literal(Literal::Bool(true)), BinaryOp::And)
+            | (Expr::Literal(Literal::Bool(false)), BinaryOp::Or) => self.folded(right),
+            (Expr::Literal(Literal::Bool(b)), BinaryOp::And | BinaryOp::Or) => {
+                let b = *b;
+                self.folded(Expr::Literal(Literal::Bool(b)))
+            }
+            _ => Expr::Binary {
+                left: Box::new(left),
+                op,
+                right: Box::new(right),
+            },
+        }
+    }
+
+    fn fold_unary(&mut self, op: UnaryOp, operand: Expr) -> Expr {
+        let operand = self.fold_expr(operand);
+
+        // Try to evaluate constant unary operations
+        if let Expr::Literal(lit) = operand.unspanned() {
+            if let Some(result) = Self::eval_unary(&op, lit) {
+                return self.folded(Expr::Literal(result));
+            }
+        }
+
+        Expr::Unary {
+            op,
+            operand: Box::new(operand),
+        }
+    }
+
+    fn fold_if(&mut self, condition: Expr, then_branch: Expr, else_branch: Option<Expr>) -> Expr {
+        let condition = self.fold_expr(condition);
+
+        // If condition is constant, select the appropriate branch
+        if let Expr::Literal(Literal::Bool(b)) = condition.unspanned() {
+            let branch = if *b {
+                self.fold_expr(then_branch)
+            } else if let Some(else_expr) = else_branch {
+                self.fold_expr(else_expr)
+            } else {
+                // No else branch, return void-like
+                Expr::Block {
+                    statements: vec![],
+                    final_expr: None,
+                }
+            };
+            return self.folded(branch);
+        }
+
+        Expr::If {
+            condition: Box::new(condition),
+            then_branch: Box::new(self.fold_expr(then_branch)),
+            else_branch: else_branch.map(|e| Box::new(self.fold_expr(e))),
+        }
+    }
+
+    fn fold_call(&mut self, callee: Expr, args: Vec<Expr>) -> Expr {
+        let callee = self.fold_expr(callee);
+        let args: Vec<Expr> = args.into_iter().map(|a| self.fold_expr(a)).collect();
+
+        // #concat of string literals
+        if matches!(callee.unspanned(), Expr::Identifier(name) if name == "#concat") {
+            let strings: Option<Vec<&str>> = args
+                .iter()
+                .map(|arg| match arg.unspanned() {
+                    Expr::Literal(Literal::String(s)) => Some(s.as_str()),
+                    _ => None,
+                })
+                .collect();
+            if let Some(strings) = strings {
+                let joined = strings.concat();
+                return self.folded(Expr::Literal(Literal::String(joined)));
+            }
+        }
+
+        Expr::Call {
+            callee: Box::new(callee),
+            args,
+        }
+    }
+
+    fn fold_sex_block(&mut self, statements: Vec<Stmt>, final_expr: Option<Expr>) -> Expr {
+        // Effects run as written, so nothing inside is folded
+        Expr::SexBlock {
+            statements,
+            final_expr: final_expr.map(Box::new),
+        }
     }
 }
 
@@ -183,28 +212,143 @@ impl Pass for ConstantFolding {
     }
 
     fn run(&mut self, decl: Declaration) -> PassResult<Declaration> {
-        // DOL 1.0 declarations (Gene, Trait, Constraint, System) don't contain
-        // DOL 2.0 expressions directly - they use Statement predicates.
-        // This pass is primarily for future DOL 2.0 expression contexts.
-        Ok(decl)
+        self.stats = PassStats::new();
+        self.stats.nodes_visited += 1;
+
+        // DOL 1.0 statements carry no expressions; fold the DOL 2.0
+        // expressions in functions, trait laws and constants
+        Ok(match decl {
+            Declaration::Function(mut func) => {
+                let body = std::mem::take(&mut func.body);
+                func.body = body.into_iter().map(|s| self.fold_stmt(s)).collect();
+                Declaration::Function(func)
+            }
+            Declaration::Trait(mut trait_decl) => {
+                for law in &mut trait_decl.laws {
+                    let body = std::mem::replace(&mut law.body, Expr::Literal(Literal::Null));
+                    law.body = self.fold_expr(body);
+                }
+                Declaration::Trait(trait_decl)
+            }
+            Declaration::Const(mut constant) => {
+                let value = std::mem::replace(&mut constant.value, Expr::Literal(Literal::Null));
+                constant.value = self.fold_expr(value);
+                Declaration::Const(constant)
+            }
+            other => other,
+        })
+    }
+
+    fn stats(&self) -> PassStats {
+        self.stats.clone()
     }
 }
 
 /// Dead code elimination pass.
 ///
-/// Removes unreachable code and unused bindings.
-pub struct DeadCodeElimination;
+/// Removes `let` bindings that are never referenced later in their block.
+/// A binding whose value may have side effects (a `sex` block, or a call to
+/// a `sex extern`, `sex fun` or I/O builtin) keeps its value as a bare
+/// expression statement.
+///
+/// `sex extern` and `sex fun` declarations passed to
+/// [`prepare`](Pass::prepare) or seen by [`run`](Pass::run) are remembered,
+/// so a function may be declared after its callers when the pass runs
+/// through [`PassPipeline::run_all`](crate::transform::PassPipeline::run_all).
+/// Functions declared elsewhere can be registered with
+/// [`with_effectful`](Self::with_effectful).
+#[derive(Debug, Default)]
+pub struct DeadCodeElimination {
+    stats: PassStats,
+    effectful: HashSet<String>,
+}
 
 impl DeadCodeElimination {
     /// Creates a new dead code elimination pass.
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Treats calls to the named functions as side-effecting.
+    pub fn with_effectful<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.effectful.extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Remembers the side-effecting functions a declaration introduces.
+    fn learn(&mut self, decl: &Declaration) {
+        match decl {
+            Declaration::Extern(block) => self
+                .effectful
+                .extend(block.functions.iter().map(|f| f.name.clone())),
+            Declaration::Function(func) if func.purity == Purity::Sex => {
+                self.effectful.insert(func.name.clone());
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns `true` if evaluating `expr` may have side effects.
+    fn has_effects(&self, expr: &Expr) -> bool {
+        let mut finder = EffectFinder {
+            effectful: &self.effectful,
+            found: false,
+        };
+        finder.visit_expr(expr);
+        finder.found
+    }
+
+    /// Drops unreferenced `let` bindings from a statement list.
+    ///
+    /// Walks backwards so a binding only used by another dead binding is
+    /// removed as well.
+    fn prune(&mut self, statements: Vec<Stmt>, final_expr: Option<&Expr>) -> Vec<Stmt> {
+        let mut used = IdentifierUses::default();
+        if let Some(expr) = final_expr {
+            used.visit_expr(expr);
+        }
+
+        let mut kept = Vec::with_capacity(statements.len());
+        for stmt in statements.into_iter().rev() {
+            let stmt = match stmt {
+                Stmt::Let { name, value, .. } if !used.names.contains(&name) => {
+                    self.stats.nodes_transformed += 1;
+                    if !self.has_effects(&value) {
+                        continue;
+                    }
+                    Stmt::Expr(value)
+                }
+                stmt => stmt,
+            };
+            used.visit_stmt(&stmt);
+            kept.push(stmt);
+        }
+        kept.reverse();
+        kept
     }
 }
 
-impl Default for DeadCodeElimination {
-    fn default() -> Self {
-        Self::new()
+impl Fold for DeadCodeElimination {
+    fn fold_block(&mut self, statements: Vec<Stmt>, final_expr: Option<Expr>) -> Expr {
+        let statements: Vec<Stmt> = statements.into_iter().map(|s| self.fold_stmt(s)).collect();
+        let final_expr = final_expr.map(|e| self.fold_expr(e));
+        Expr::Block {
+            statements: self.prune(statements, final_expr.as_ref()),
+            final_expr: final_expr.map(Box::new),
+        }
+    }
+
+    fn fold_sex_block(&mut self, statements: Vec<Stmt>, final_expr: Option<Expr>) -> Expr {
+        let statements: Vec<Stmt> = statements.into_iter().map(|s| self.fold_stmt(s)).collect();
+        let final_expr = final_expr.map(|e| self.fold_expr(e));
+        Expr::SexBlock {
+            statements: self.prune(statements, final_expr.as_ref()),
+            final_expr: final_expr.map(Box::new),
+        }
     }
 }
 
@@ -213,10 +357,77 @@ impl Pass for DeadCodeElimination {
         "dead_code_elimination"
     }
 
+    fn prepare(&mut self, decls: &[Declaration]) {
+        for decl in decls {
+            self.learn(decl);
+        }
+    }
+
     fn run(&mut self, decl: Declaration) -> PassResult<Declaration> {
-        // For now, just return the declaration unchanged
-        // Full DCE requires use-def analysis
-        Ok(decl)
+        self.stats = PassStats::new();
+        self.stats.nodes_visited += 1;
+        self.learn(&decl);
+
+        Ok(match decl {
+            Declaration::Function(mut func) => {
+                let body = std::mem::take(&mut func.body);
+                let body = body.into_iter().map(|s| self.fold_stmt(s)).collect();
+                func.body = self.prune(body, None);
+                Declaration::Function(func)
+            }
+            Declaration::Trait(mut trait_decl) => {
+                for law in &mut trait_decl.laws {
+                    let body = std::mem::replace(&mut law.body, Expr::Literal(Literal::Null));
+                    law.body = self.fold_expr(body);
+                }
+                Declaration::Trait(trait_decl)
+            }
+            Declaration::Const(mut constant) => {
+                let value = std::mem::replace(&mut constant.value, Expr::Literal(Literal::Null));
+                constant.value = self.fold_expr(value);
+                Declaration::Const(constant)
+            }
+            other => other,
+        })
+    }
+
+    fn stats(&self) -> PassStats {
+        self.stats.clone()
+    }
+}
+
+/// Collects every identifier referenced in the visited nodes.
+#[derive(Default)]
+struct IdentifierUses {
+    names: HashSet<String>,
+}
+
+impl Visitor for IdentifierUses {
+    fn visit_identifier(&mut self, name: &str) {
+        self.names.insert(name.to_string());
+    }
+}
+
+/// Looks for a `sex` block or a call to a side-effecting function.
+struct EffectFinder<'a> {
+    effectful: &'a HashSet<String>,
+    found: bool,
+}
+
+impl Visitor for EffectFinder<'_> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::SexBlock { .. } => self.found = true,
+            Expr::Call { callee, .. } => {
+                if let Expr::Identifier(name) = callee.unspanned() {
+                    if self.effectful.contains(name) || IO_BUILTINS.contains(&name.as_str()) {
+                        self.found = true;
+                    }
+                }
+                walk_expr(self, expr);
+            }
+            _ => walk_expr(self, expr),
+        }
     }
 }
 
@@ -242,12 +453,16 @@ impl Simplify {
     #[allow(clippy::only_used_in_recursion)]
     pub fn simplify_expr(&self, expr: Expr) -> Expr {
         match expr {
+            Expr::Spanned { expr, span } => Expr::Spanned {
+                expr: Box::new(self.simplify_expr(*expr)),
+                span,
+            },
             Expr::Binary { left, op, right } => {
                 let left = self.simplify_expr(*left);
                 let right = self.simplify_expr(*right);
 
                 // Apply simplification rules
-                match (&left, &op, &right) {
+                match (left.unspanned(), &op, right.unspanned()) {
                     // x + 0 => x
                     (x, BinaryOp::Add, Expr::Literal(Literal::Int(0))) => return x.clone(),
                     (Expr::Literal(Literal::Int(0)), BinaryOp::Add, x) => return x.clone(),
@@ -307,9 +522,9 @@ impl Simplify {
                     if let Expr::Unary {
                         op: UnaryOp::Not,
                         operand: inner,
-                    } = operand
+                    } = operand.unspanned()
                     {
-                        return *inner;
+                        return (**inner).clone();
                     }
                 }
 
@@ -318,9 +533,9 @@ impl Simplify {
                     if let Expr::Unary {
                         op: UnaryOp::Neg,
                         operand: inner,
-                    } = operand
+                    } = operand.unspanned()
                     {
-                        return *inner;
+                        return (**inner).clone();
                     }
                 }
 
@@ -351,13 +566,224 @@ impl Pass for Simplify {
     }
 }
 
+/// Phrase normalization pass.
+///
+/// Canonicalizes the textual parts of DOL 1.0 statements so that two
+/// semantically identical declarations compare equal:
+/// - Internal whitespace in phrases is collapsed to a single space
+/// - Leading and trailing whitespace is trimmed
+/// - State keywords in `is` statements are lowercased
+///
+/// The pass is idempotent: running it on its own output is a no-op.
+pub struct Normalize;
+
+impl Normalize {
+    /// Creates a new normalization pass.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Collapse runs of whitespace into single spaces and trim the ends.
+    pub fn normalize_phrase(phrase: &str) -> String {
+        phrase.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Normalize the phrases of a single statement.
+    pub fn normalize_statement(&self, stmt: Statement) -> Statement {
+        let norm = |s: String| Self::normalize_phrase(&s);
+        match stmt {
+            Statement::Has {
+                subject,
+                property,
+                span,
+            } => Statement::Has {
+                subject: norm(subject),
+                property: norm(property),
+                span,
+            },
+            Statement::Is {
+                subject,
+                state,
+                span,
+            } => Statement::Is {
+                subject: norm(subject),
+                state: norm(state).to_lowercase(),
+                span,
+            },
+            Statement::DerivesFrom {
+                subject,
+                origin,
+                span,
+            } => Statement::DerivesFrom {
+                subject: norm(subject),
+                origin: norm(origin),
+                span,
+            },
+            Statement::Requires {
+                subject,
+                requirement,
+                span,
+            } => Statement::Requires {
+                subject: norm(subject),
+                requirement: norm(requirement),
+                span,
+            },
+            Statement::Uses { reference, span } => Statement::Uses {
+                reference: norm(reference),
+                span,
+            },
+            Statement::Emits {
+                action,
+                event,
+                span,
+            } => Statement::Emits {
+                action: norm(action),
+                event: norm(event),
+                span,
+            },
+            Statement::Matches {
+                subject,
+                target,
+                span,
+            } => Statement::Matches {
+                subject: norm(subject),
+                target: norm(target),
+                span,
+            },
+            Statement::Never {
+                subject,
+                action,
+                span,
+            } => Statement::Never {
+                subject: norm(subject),
+                action: norm(action),
+                span,
+            },
+            Statement::Quantified {
+                quantifier,
+                phrase,
+                span,
+            } => Statement::Quantified {
+                quantifier,
+                phrase: norm(phrase),
+                span,
+            },
+            other => other,
+        }
+    }
+
+    fn normalize_statements(&self, stmts: Vec<Statement>) -> Vec<Statement> {
+        stmts
+            .into_iter()
+            .map(|s| self.normalize_statement(s))
+            .collect()
+    }
+}
+
+impl Default for Normalize {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pass for Normalize {
+    fn name(&self) -> &str {
+        "normalize"
+    }
+
+    fn run(&mut self, decl: Declaration) -> PassResult<Declaration> {
+        Ok(match decl {
+            Declaration::Gene(mut gene) => {
+                gene.statements = self.normalize_statements(gene.statements);
+                Declaration::Gene(gene)
+            }
+            Declaration::Trait(mut trait_decl) => {
+                trait_decl.statements = self.normalize_statements(trait_decl.statements);
+                Declaration::Trait(trait_decl)
+            }
+            Declaration::Constraint(mut constraint) => {
+                constraint.statements = self.normalize_statements(constraint.statements);
+                Declaration::Constraint(constraint)
+            }
+            Declaration::System(mut system) => {
+                system.statements = self.normalize_statements(system.statements);
+                Declaration::System(system)
+            }
+            Declaration::Evolution(mut evolution) => {
+                evolution.additions = self.normalize_statements(evolution.additions);
+                evolution.deprecations = self.normalize_statements(evolution.deprecations);
+                Declaration::Evolution(evolution)
+            }
+            other => other,
+        })
+    }
+}
+
+/// Quantifier canonicalization pass.
+///
+/// Rewrites quantified statements using the deprecated `each` and `all`
+/// quantifiers to `forall`, recording the same deprecation diagnostic that
+/// HIR lowering emits for them.
+#[derive(Debug, Default)]
+pub struct CanonicalQuantifiers {
+    diagnostics: Vec<LowerDiagnostic>,
+}
+
+impl CanonicalQuantifiers {
+    /// Creates a new quantifier canonicalization pass.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the deprecation diagnostics recorded so far.
+    pub fn diagnostics(&self) -> &[LowerDiagnostic] {
+        &self.diagnostics
+    }
+}
+
+impl MutVisitor for CanonicalQuantifiers {
+    fn visit_evolution(&mut self, evo: &mut Evolution) {
+        for stmt in evo.additions.iter_mut().chain(&mut evo.deprecations) {
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &mut Statement) {
+        if let Statement::Quantified {
+            quantifier, span, ..
+        } = stmt
+        {
+            if quantifier.is_deprecated() {
+                self.diagnostics.push(LowerDiagnostic::deprecation(
+                    &quantifier.to_string(),
+                    "forall",
+                    *span,
+                ));
+                *quantifier = Quantifier::Forall;
+            }
+        }
+    }
+}
+
+impl Pass for CanonicalQuantifiers {
+    fn name(&self) -> &str {
+        "canonical_quantifiers"
+    }
+
+    fn run(&mut self, mut decl: Declaration) -> PassResult<Declaration> {
+        self.visit_declaration(&mut decl);
+        Ok(decl)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::QualifiedName;
 
     #[test]
     fn test_constant_folding_arithmetic() {
-        let pass = ConstantFolding::new();
+        let mut pass = ConstantFolding::new();
 
         // 1 + 2 => 3
         let expr = Expr::Binary {
@@ -380,7 +806,7 @@ mod tests {
 
     #[test]
     fn test_constant_folding_nested() {
-        let pass = ConstantFolding::new();
+        let mut pass = ConstantFolding::new();
 
         // (1 + 2) * 3 => 9
         let expr = Expr::Binary {
@@ -398,7 +824,7 @@ mod tests {
 
     #[test]
     fn test_constant_folding_boolean() {
-        let pass = ConstantFolding::new();
+        let mut pass = ConstantFolding::new();
 
         // true && false => false
         let expr = Expr::Binary {
@@ -412,7 +838,7 @@ mod tests {
 
     #[test]
     fn test_constant_folding_unary() {
-        let pass = ConstantFolding::new();
+        let mut pass = ConstantFolding::new();
 
         // -5 => -5
         let expr = Expr::Unary {
@@ -431,9 +857,63 @@ mod tests {
         assert_eq!(result, Expr::Literal(Literal::Bool(false)));
     }
 
+    #[test]
+    fn test_constant_folding_leaves_overflow_unfolded() {
+        let mut pass = ConstantFolding::new();
+        let int = |n: i64| Box::new(Expr::Literal(Literal::Int(n)));
+        let neg = |n: i64| {
+            Box::new(Expr::Unary {
+                op: UnaryOp::Neg,
+                operand: int(n),
+            })
+        };
+        // (-9223372036854775807 - 1) folds to i64::MIN
+        let min = || {
+            Box::new(Expr::Binary {
+                left: neg(i64::MAX),
+                op: BinaryOp::Sub,
+                right: int(1),
+            })
+        };
+
+        // (-9223372036854775807 - 1) / -1 and % -1 overflow
+        for op in [BinaryOp::Div, BinaryOp::Mod] {
+            let expr = Expr::Binary {
+                left: min(),
+                op,
+                right: neg(1),
+            };
+            let expected = Expr::Binary {
+                left: int(i64::MIN),
+                op,
+                right: int(-1),
+            };
+            assert_eq!(pass.fold_expr(expr), expected);
+        }
+
+        // -(-9223372036854775807 - 1) overflows
+        let expr = Expr::Unary {
+            op: UnaryOp::Neg,
+            operand: min(),
+        };
+        let expected = Expr::Unary {
+            op: UnaryOp::Neg,
+            operand: int(i64::MIN),
+        };
+        assert_eq!(pass.fold_expr(expr), expected);
+
+        // Division by zero is still left alone
+        let expr = Expr::Binary {
+            left: int(1),
+            op: BinaryOp::Div,
+            right: int(0),
+        };
+        assert_eq!(pass.fold_expr(expr.clone()), expr);
+    }
+
     #[test]
     fn test_constant_folding_if() {
-        let pass = ConstantFolding::new();
+        let mut pass = ConstantFolding::new();
 
         // if true { 1 } else { 2 } => 1
         let expr = Expr::If {
@@ -454,6 +934,242 @@ mod tests {
         assert_eq!(result, Expr::Literal(Literal::Int(2)));
     }
 
+    #[test]
+    fn test_constant_folding_string_concat() {
+        let mut pass = ConstantFolding::new();
+
+        // "foo" + "bar" => "foobar"
+        let expr = Expr::Binary {
+            left: Box::new(Expr::Literal(Literal::String("foo".to_string()))),
+            op: BinaryOp::Add,
+            right: Box::new(Expr::Literal(Literal::String("bar".to_string()))),
+        };
+        let result = pass.fold_expr(expr);
+        assert_eq!(result, Expr::Literal(Literal::String("foobar".to_string())));
+
+        // #concat("a", "b", "c") => "abc"
+        let expr = Expr::Call {
+            callee: Box::new(Expr::Identifier("#concat".to_string())),
+            args: vec![
+                Expr::Literal(Literal::String("a".to_string())),
+                Expr::Literal(Literal::String("b".to_string())),
+                Expr::Literal(Literal::String("c".to_string())),
+            ],
+        };
+        let result = pass.fold_expr(expr);
+        assert_eq!(result, Expr::Literal(Literal::String("abc".to_string())));
+        assert_eq!(pass.stats().expressions_folded, 2);
+    }
+
+    #[test]
+    fn test_constant_folding_short_circuit() {
+        let mut pass = ConstantFolding::new();
+        let x = || Expr::Identifier("x".to_string());
+        let bool_op = |b: bool, op: BinaryOp| Expr::Binary {
+            left: Box::new(Expr::Literal(Literal::Bool(b))),
+            op,
+            right: Box::new(x()),
+        };
+
+        assert_eq!(pass.fold_expr(bool_op(true, BinaryOp::And)), x());
+        assert_eq!(
+            pass.fold_expr(bool_op(false, BinaryOp::And)),
+            Expr::Literal(Literal::Bool(false))
+        );
+        assert_eq!(
+            pass.fold_expr(bool_op(true, BinaryOp::Or)),
+            Expr::Literal(Literal::Bool(true))
+        );
+        assert_eq!(pass.fold_expr(bool_op(false, BinaryOp::Or)), x());
+        assert_eq!(pass.stats().expressions_folded, 4);
+
+        // x && true is left alone
+        let expr = Expr::Binary {
+            left: Box::new(x()),
+            op: BinaryOp::And,
+            right: Box::new(Expr::Literal(Literal::Bool(true))),
+        };
+        assert_eq!(pass.fold_expr(expr.clone()), expr);
+        assert_eq!(pass.stats().expressions_folded, 4);
+    }
+
+    #[test]
+    fn test_constant_folding_skips_sex_blocks() {
+        let mut pass = ConstantFolding::new();
+
+        let expr = Expr::SexBlock {
+            statements: vec![],
+            final_expr: Some(Box::new(Expr::Binary {
+                left: Box::new(Expr::Literal(Literal::Bool(false))),
+                op: BinaryOp::And,
+                right: Box::new(Expr::Identifier("x".to_string())),
+            })),
+        };
+        assert_eq!(pass.fold_expr(expr.clone()), expr);
+        assert_eq!(pass.stats().expressions_folded, 0);
+    }
+
+    #[test]
+    fn test_constant_folding_pass_folds_laws() {
+        let mut pass = ConstantFolding::new();
+        let decl = crate::parse_file(
+            r#"
+trait test.folding {
+  law identity(x: Bool) {
+    true && x
+  }
+}
+
+exegesis {
+  Folding inside trait laws.
+}
+"#,
+        )
+        .unwrap();
+
+        let Declaration::Trait(trait_decl) = pass.run(decl).unwrap() else {
+            panic!("expected trait");
+        };
+        assert_eq!(
+            trait_decl.laws[0].body.unspanned(),
+            &Expr::Identifier("x".to_string())
+        );
+        assert_eq!(pass.stats().expressions_folded, 1);
+    }
+
+    #[test]
+    fn test_dce_drops_unused_pure_let() {
+        let mut pass = DeadCodeElimination::new();
+        let decl = crate::parse_file(
+            r#"
+fun area(w: Int64, h: Int64) -> Int64 {
+  let perimeter = 2 * (w + h)
+  let doubled = perimeter * 2
+  let result = w * h
+  return result
+}
+
+exegesis {
+  Only the area is returned.
+}
+"#,
+        )
+        .unwrap();
+
+        let Declaration::Function(func) = pass.run(decl).unwrap() else {
+            panic!("expected function");
+        };
+        assert_eq!(func.body.len(), 2);
+        assert!(matches!(&func.body[0], Stmt::Let { name, .. } if name == "result"));
+        assert_eq!(pass.stats().nodes_transformed, 2);
+    }
+
+    #[test]
+    fn test_dce_preserves_side_effects() {
+        let mut pass = DeadCodeElimination::new().with_effectful(["getpid"]);
+        let unused = |value: Expr| Stmt::Let {
+            name: "unused".to_string(),
+            type_ann: None,
+            value,
+        };
+        let call = Expr::Call {
+            callee: Box::new(Expr::Identifier("getpid".to_string())),
+            args: vec![],
+        };
+        let sex_block = Expr::SexBlock {
+            statements: vec![],
+            final_expr: Some(Box::new(Expr::Literal(Literal::Int(1)))),
+        };
+
+        let expr = Expr::Block {
+            statements: vec![unused(call.clone()), unused(sex_block.clone())],
+            final_expr: Some(Box::new(Expr::Literal(Literal::Int(0)))),
+        };
+        let result = pass.fold_expr(expr);
+        assert_eq!(
+            result,
+            Expr::Block {
+                statements: vec![Stmt::Expr(call), Stmt::Expr(sex_block)],
+                final_expr: Some(Box::new(Expr::Literal(Literal::Int(0)))),
+            }
+        );
+    }
+
+    #[test]
+    fn test_dce_learns_sex_externs() {
+        let decls = crate::parse_file_all(
+            r#"
+sex extern fun getpid() -> Int32
+
+fun check() -> Int64 {
+  let pid = getpid()
+  return 0
+}
+"#,
+        )
+        .unwrap();
+
+        let mut pass = DeadCodeElimination::new();
+        let decls: Vec<Declaration> = decls.into_iter().map(|d| pass.run(d).unwrap()).collect();
+        let Some(Declaration::Function(func)) = decls.last() else {
+            panic!("expected function");
+        };
+        assert!(matches!(&func.body[0], Stmt::Expr(_)));
+        assert_eq!(func.body.len(), 2);
+    }
+
+    #[test]
+    fn test_dce_learns_sex_externs_declared_after_caller() {
+        let decls = crate::parse_file_all(
+            r#"
+fun check() -> Int64 {
+  let pid = getpid()
+  return 0
+}
+
+sex extern fun getpid() -> Int32
+"#,
+        )
+        .unwrap();
+
+        let mut pipeline = crate::transform::PassPipeline::new();
+        pipeline.add(DeadCodeElimination::new());
+        let decls = pipeline.run_all(decls).unwrap();
+        let Declaration::Function(func) = &decls[0] else {
+            panic!("expected function");
+        };
+        assert!(matches!(&func.body[0], Stmt::Expr(_)));
+        assert_eq!(func.body.len(), 2);
+    }
+
+    #[test]
+    fn test_dce_ignores_gene_documented_as_extern() {
+        let decls = crate::parse_file_all(
+            r#"
+gene getpid {
+  getpid has value
+}
+
+exegesis {
+  sex extern getpid
+}
+
+fun check() -> Int64 {
+  let pid = getpid()
+  return 0
+}
+"#,
+        )
+        .unwrap();
+
+        let mut pass = DeadCodeElimination::new();
+        let decls: Vec<Declaration> = decls.into_iter().map(|d| pass.run(d).unwrap()).collect();
+        let Some(Declaration::Function(func)) = decls.last() else {
+            panic!("expected function");
+        };
+        assert_eq!(func.body.len(), 1);
+    }
+
     #[test]
     fn test_simplify_identity() {
         let pass = Simplify::new();
@@ -506,4 +1222,82 @@ mod tests {
         let result = pass.simplify_expr(expr);
         assert_eq!(result, Expr::Identifier("x".to_string()));
     }
+
+    fn spaced_gene(subject: &str, state: &str) -> Declaration {
+        use crate::ast::{Gene, Span, Visibility};
+
+        Declaration::Gene(Gene {
+            name: QualifiedName::new("container.exists"),
+            extends: None,
+            statements: vec![Statement::Is {
+                subject: subject.to_string(),
+                state: state.to_string(),
+                span: Span::new(0, 0, 1, 1),
+            }],
+            exegesis: "A container exists.".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
+            span: Span::new(0, 0, 1, 1),
+        })
+    }
+
+    #[test]
+    fn test_normalize_collapses_whitespace() {
+        let mut pass = Normalize::new();
+
+        let a = pass.run(spaced_gene("container", "created")).unwrap();
+        let b = pass
+            .run(spaced_gene("  container  ", "  Created\t"))
+            .unwrap();
+        assert_eq!(a, b);
+
+        let a = pass.run(spaced_gene("running container", "ok")).unwrap();
+        let b = pass
+            .run(spaced_gene("running \n  container", "ok"))
+            .unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_normalize_is_idempotent() {
+        let mut pass = Normalize::new();
+
+        let once = pass.run(spaced_gene(" a   b ", " STATE ")).unwrap();
+        let twice = pass.run(once.clone()).unwrap();
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn test_canonical_quantifiers_rewrites_each() {
+        use crate::lower::DiagnosticKind;
+
+        let decl = crate::parse_file("gene container.exists { each container has id }").unwrap();
+        let mut pass = CanonicalQuantifiers::new();
+        let decl = pass.run(decl).unwrap();
+
+        let Declaration::Gene(gene) = decl else {
+            panic!("expected gene");
+        };
+        assert!(matches!(
+            gene.statements[0],
+            Statement::Quantified {
+                quantifier: Quantifier::Forall,
+                ..
+            }
+        ));
+
+        let diagnostics = pass.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::Deprecation);
+        assert_eq!(diagnostics[0].message, "'each' is deprecated");
+        assert_eq!(
+            diagnostics[0].suggestion.as_deref(),
+            Some("use 'forall' instead")
+        );
+
+        // Canonical statements are left alone
+        let decl = pass.run(Declaration::Gene(gene)).unwrap();
+        assert_eq!(pass.diagnostics().len(), 1);
+        assert!(matches!(decl, Declaration::Gene(_)));
+    }
 }
@@ -156,6 +156,26 @@ pub trait MutVisitor {
     }
 }
 
+/// Removes every [`Expr::Spanned`] wrapper from an expression tree.
+///
+/// Passes that match on the shape of their input, such as macro expansion,
+/// use this to see the tree a parser without expression spans produces.
+pub fn strip_spans(expr: &mut Expr) {
+    StripSpans.visit_expr(expr);
+}
+
+struct StripSpans;
+
+impl MutVisitor for StripSpans {
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        while let Expr::Spanned { expr: inner, .. } = expr {
+            let inner = std::mem::replace(&mut **inner, Expr::Literal(Literal::Bool(false)));
+            *expr = inner;
+        }
+        walk_expr_mut(self, expr);
+    }
+}
+
 // Walk functions for immutable visitor
 
 fn walk_declaration<V: Visitor + ?Sized>(v: &mut V, decl: &Declaration) {
@@ -166,7 +186,8 @@ fn walk_declaration<V: Visitor + ?Sized>(v: &mut V, decl: &Declaration) {
         Declaration::System(sys) => v.visit_system(sys),
         Declaration::Evolution(evo) => v.visit_evolution(evo),
         Declaration::Function(func) => v.visit_function_decl(func),
-        Declaration::Const(_) | Declaration::SexVar(_) => {} // Constants and SexVars don't need walking
+        // Constants, SexVars and extern signatures don't need walking
+        Declaration::Const(_) | Declaration::SexVar(_) | Declaration::Extern(_) => {}
     }
 }
 
@@ -201,9 +222,9 @@ fn walk_system<V: Visitor + ?Sized>(v: &mut V, sys: &System) {
     }
 }
 
-fn walk_stmt<V: Visitor + ?Sized>(v: &mut V, stmt: &Stmt) {
+pub(crate) fn walk_stmt<V: Visitor + ?Sized>(v: &mut V, stmt: &Stmt) {
     match stmt {
-        Stmt::Let { value, .. } => {
+        Stmt::Let { value, .. } | Stmt::LetPattern { value, .. } => {
             v.visit_expr(value);
         }
         Stmt::Assign { target, value } => {
@@ -233,8 +254,9 @@ fn walk_stmt<V: Visitor + ?Sized>(v: &mut V, stmt: &Stmt) {
     }
 }
 
-fn walk_expr<V: Visitor + ?Sized>(v: &mut V, expr: &Expr) {
+pub(crate) fn walk_expr<V: Visitor + ?Sized>(v: &mut V, expr: &Expr) {
     match expr {
+        Expr::Spanned { expr, .. } => v.visit_expr(expr),
         Expr::Literal(lit) => v.visit_literal(lit),
         Expr::Identifier(name) => v.visit_identifier(name),
         Expr::Binary { left, op, right } => {
@@ -299,6 +321,9 @@ fn walk_expr<V: Visitor + ?Sized>(v: &mut V, expr: &Expr) {
         }
         Expr::Forall(forall_expr) => {
             v.visit_type_expr(&forall_expr.type_);
+            if let Some(iter) = &forall_expr.iter {
+                v.visit_expr(iter);
+            }
             v.visit_expr(&forall_expr.body);
         }
         Expr::Exists(exists_expr) => {
@@ -330,6 +355,12 @@ fn walk_expr<V: Visitor + ?Sized>(v: &mut V, expr: &Expr) {
                 v.visit_expr(elem);
             }
         }
+        Expr::Map(entries) => {
+            for (key, value) in entries {
+                v.visit_expr(key);
+                v.visit_expr(value);
+            }
+        }
         Expr::Cast { expr, .. } => {
             v.visit_expr(expr);
         }
@@ -377,7 +408,8 @@ fn walk_declaration_mut<V: MutVisitor + ?Sized>(v: &mut V, decl: &mut Declaratio
         Declaration::System(sys) => v.visit_system(sys),
         Declaration::Evolution(evo) => v.visit_evolution(evo),
         Declaration::Function(func) => v.visit_function_decl(func),
-        Declaration::Const(_) | Declaration::SexVar(_) => {} // Constants and SexVars don't need walking
+        // Constants, SexVars and extern signatures don't need walking
+        Declaration::Const(_) | Declaration::SexVar(_) | Declaration::Extern(_) => {}
     }
 }
 
@@ -414,7 +446,7 @@ fn walk_system_mut<V: MutVisitor + ?Sized>(v: &mut V, sys: &mut System) {
 
 fn walk_stmt_mut<V: MutVisitor + ?Sized>(v: &mut V, stmt: &mut Stmt) {
     match stmt {
-        Stmt::Let { value, .. } => {
+        Stmt::Let { value, .. } | Stmt::LetPattern { value, .. } => {
             v.visit_expr(value);
         }
         Stmt::Assign { target, value } => {
@@ -446,6 +478,7 @@ fn walk_stmt_mut<V: MutVisitor + ?Sized>(v: &mut V, stmt: &mut Stmt) {
 
 fn walk_expr_mut<V: MutVisitor + ?Sized>(v: &mut V, expr: &mut Expr) {
     match expr {
+        Expr::Spanned { expr, .. } => v.visit_expr(expr),
         Expr::Binary { left, right, .. } => {
             v.visit_expr(left);
             v.visit_expr(right);
@@ -506,6 +539,9 @@ fn walk_expr_mut<V: MutVisitor + ?Sized>(v: &mut V, expr: &mut Expr) {
         }
         Expr::Forall(forall_expr) => {
             v.visit_type_expr(&mut forall_expr.type_);
+            if let Some(iter) = &mut forall_expr.iter {
+                v.visit_expr(iter);
+            }
             v.visit_expr(&mut forall_expr.body);
         }
         Expr::Exists(exists_expr) => {
@@ -538,6 +574,12 @@ fn walk_expr_mut<V: MutVisitor + ?Sized>(v: &mut V, expr: &mut Expr) {
                 v.visit_expr(elem);
             }
         }
+        Expr::Map(entries) => {
+            for (key, value) in entries {
+                v.visit_expr(key);
+                v.visit_expr(value);
+            }
+        }
         Expr::Cast { expr, .. } => {
             v.visit_expr(expr);
         }
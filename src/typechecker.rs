@@ -22,7 +22,15 @@
 //! assert_eq!(ty, Type::Int64);
 //! ```
 
-use crate::ast::{BinaryOp, Expr, Literal, Pattern, Stmt, TypeExpr, UnaryOp};
+// `TypeError` carries the expected and actual types inline so callers can
+// match on them directly; the larger `Err` variant is accepted here.
+#![allow(clippy::result_large_err)]
+
+use crate::ast::{
+    BinaryOp, Declaration, Expr, FunctionDecl, Gene, HasField, Literal, Pattern, Purity, Span,
+    Statement, Stmt, TypeExpr, UnaryOp,
+};
+use crate::naming::to_pascal_case;
 use std::collections::HashMap;
 
 /// Semantic types used during type checking.
@@ -74,6 +82,12 @@ pub enum Type {
     /// Tuple type
     Tuple(Vec<Type>),
 
+    /// Anonymous record type with named fields (`{ cpu: Int64 }`)
+    Record(Vec<(String, Type)>),
+
+    /// Optional type (`Option<T>` or `T?`), the only type `null` belongs to
+    Option(Box<Type>),
+
     /// Generic/parametric type
     Generic {
         /// Type constructor name
@@ -155,6 +169,21 @@ impl Type {
         }
     }
 
+    /// Returns the inclusive range of values an integer type can hold.
+    pub fn integer_range(&self) -> Option<(i128, i128)> {
+        match self {
+            Type::Int8 => Some((i8::MIN.into(), i8::MAX.into())),
+            Type::Int16 => Some((i16::MIN.into(), i16::MAX.into())),
+            Type::Int32 => Some((i32::MIN.into(), i32::MAX.into())),
+            Type::Int64 => Some((i64::MIN.into(), i64::MAX.into())),
+            Type::UInt8 => Some((0, u8::MAX.into())),
+            Type::UInt16 => Some((0, u16::MAX.into())),
+            Type::UInt32 => Some((0, u32::MAX.into())),
+            Type::UInt64 => Some((0, u64::MAX.into())),
+            _ => None,
+        }
+    }
+
     /// Creates a type from a TypeExpr.
     pub fn from_type_expr(expr: &TypeExpr) -> Type {
         match expr {
@@ -177,6 +206,9 @@ impl Type {
                     args: vec![],
                 },
             },
+            TypeExpr::Generic { name, args } if name == "Option" && args.len() == 1 => {
+                Type::Option(Box::new(Type::from_type_expr(&args[0])))
+            }
             TypeExpr::Generic { name, args } => Type::Generic {
                 name: name.clone(),
                 args: args.iter().map(Type::from_type_expr).collect(),
@@ -200,6 +232,12 @@ impl Type {
                     })
                     .collect(),
             },
+            TypeExpr::Record(fields) => Type::Record(
+                fields
+                    .iter()
+                    .map(|(name, t)| (name.clone(), Type::from_type_expr(t)))
+                    .collect(),
+            ),
         }
     }
 }
@@ -243,6 +281,17 @@ impl std::fmt::Display for Type {
                 }
                 write!(f, ")")
             }
+            Type::Record(fields) => {
+                write!(f, "{{ ")?;
+                for (i, (name, t)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", name, t)?;
+                }
+                write!(f, " }}")
+            }
+            Type::Option(inner) => write!(f, "Option<{}>", inner),
             Type::Generic { name, args } => {
                 write!(f, "{}", name)?;
                 if !args.is_empty() {
@@ -285,6 +334,8 @@ pub struct TypeError {
     pub expected: Option<Type>,
     /// Actual type (if applicable)
     pub actual: Option<Type>,
+    /// Source location (if known)
+    pub span: Option<Span>,
 }
 
 impl TypeError {
@@ -294,6 +345,7 @@ impl TypeError {
             message: message.into(),
             expected: None,
             actual: None,
+            span: None,
         }
     }
 
@@ -303,6 +355,7 @@ impl TypeError {
             message: format!("type mismatch: expected {}, found {}", expected, actual),
             expected: Some(expected),
             actual: Some(actual),
+            span: None,
         }
     }
 
@@ -312,13 +365,39 @@ impl TypeError {
             message: format!("undefined variable: {}", name),
             expected: None,
             actual: None,
+            span: None,
+        }
+    }
+
+    /// Attaches a source location to the error.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Returns the stable error code for this error.
+    ///
+    /// Mismatches between an expected and actual type are `E0302`; every
+    /// other type error is `E0301`.
+    pub fn code(&self) -> &'static str {
+        if self.expected.is_some() && self.actual.is_some() {
+            "E0302"
+        } else {
+            "E0301"
         }
     }
 }
 
 impl std::fmt::Display for TypeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.message)
+        match self.span {
+            Some(span) => write!(
+                f,
+                "{} at line {}, column {}",
+                self.message, span.line, span.column
+            ),
+            None => write!(f, "{}", self.message),
+        }
     }
 }
 
@@ -329,6 +408,8 @@ impl std::error::Error for TypeError {}
 pub struct TypeEnv {
     /// Variable bindings in current scope
     bindings: HashMap<String, Type>,
+    /// Named type definitions in current scope
+    types: HashMap<String, Type>,
     /// Parent scope (for nested scopes)
     parent: Option<Box<TypeEnv>>,
 }
@@ -339,14 +420,117 @@ impl TypeEnv {
         Self::default()
     }
 
+    /// Creates an environment seeded with the DOL prelude.
+    ///
+    /// The prelude provides the built-in types and standard functions that
+    /// every program can use without declaring them:
+    ///
+    /// | Name     | Kind     | Type                                     |
+    /// |----------|----------|------------------------------------------|
+    /// | `Bool`   | type     | `Bool`                                   |
+    /// | `Int64`  | type     | `Int64`                                  |
+    /// | `String` | type     | `String`                                 |
+    /// | `List`   | type     | `List<Any>`                              |
+    /// | `Option` | type     | `Option<Any>`                            |
+    /// | `len`    | function | `(List<Any>) -> Int64`                   |
+    /// | `map`    | function | `(List<Any>, (Any) -> Any) -> List<Any>` |
+    /// | `print`  | function | `(Any) -> Void`                          |
+    /// | `to_string` | function | `(Any) -> String`                     |
+    pub fn with_prelude() -> Self {
+        let list = |elem: Type| Type::Generic {
+            name: "List".to_string(),
+            args: vec![elem],
+        };
+        let func = |params: Vec<Type>, ret: Type| Type::Function {
+            params,
+            return_type: Box::new(ret),
+        };
+
+        let mut env = Self::new();
+
+        env.bind_type("Bool", Type::Bool);
+        env.bind_type("Int64", Type::Int64);
+        env.bind_type("String", Type::String);
+        env.bind_type("List", list(Type::Any));
+        env.bind_type("Option", Type::Option(Box::new(Type::Any)));
+
+        env.bind("len", func(vec![list(Type::Any)], Type::Int64));
+        env.bind(
+            "map",
+            func(
+                vec![list(Type::Any), func(vec![Type::Any], Type::Any)],
+                list(Type::Any),
+            ),
+        );
+        env.bind("print", func(vec![Type::Any], Type::Void));
+        env.bind("to_string", func(vec![Type::Any], Type::String));
+
+        env
+    }
+
     /// Creates a child environment with this as parent.
     pub fn child(&self) -> Self {
         Self {
             bindings: HashMap::new(),
+            types: HashMap::new(),
             parent: Some(Box::new(self.clone())),
         }
     }
 
+    /// Binds a type name to a type.
+    pub fn bind_type(&mut self, name: impl Into<String>, ty: Type) {
+        self.types.insert(name.into(), ty);
+    }
+
+    /// Looks up a named type.
+    pub fn lookup_type(&self, name: &str) -> Option<&Type> {
+        self.types
+            .get(name)
+            .or_else(|| self.parent.as_ref().and_then(|p| p.lookup_type(name)))
+    }
+
+    /// Converts a type expression to a type, resolving names bound with
+    /// [`bind_type`](Self::bind_type).
+    ///
+    /// In the prelude a bare `List` resolves to `List<Any>` and a bare
+    /// `Option` to `Option<Any>`. Unbound names are left as they are.
+    pub fn resolve(&self, expr: &TypeExpr) -> Type {
+        self.resolve_names(Type::from_type_expr(expr))
+    }
+
+    fn resolve_names(&self, ty: Type) -> Type {
+        match ty {
+            // Enum variants are names, not types
+            Type::Generic { name, args } if name == "Enum" => Type::Generic { name, args },
+            Type::Generic { name, args } if args.is_empty() => match self.lookup_type(&name) {
+                Some(bound) => bound.clone(),
+                None => Type::Generic { name, args },
+            },
+            Type::Generic { name, args } => Type::Generic {
+                name,
+                args: args.into_iter().map(|a| self.resolve_names(a)).collect(),
+            },
+            Type::Option(inner) => Type::Option(Box::new(self.resolve_names(*inner))),
+            Type::Function {
+                params,
+                return_type,
+            } => Type::Function {
+                params: params.into_iter().map(|p| self.resolve_names(p)).collect(),
+                return_type: Box::new(self.resolve_names(*return_type)),
+            },
+            Type::Tuple(types) => {
+                Type::Tuple(types.into_iter().map(|t| self.resolve_names(t)).collect())
+            }
+            Type::Record(fields) => Type::Record(
+                fields
+                    .into_iter()
+                    .map(|(name, t)| (name, self.resolve_names(t)))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
     /// Binds a variable to a type.
     pub fn bind(&mut self, name: impl Into<String>, ty: Type) {
         self.bindings.insert(name.into(), ty);
@@ -365,14 +549,14 @@ impl TypeEnv {
 pub struct TypeChecker {
     /// Current type environment
     env: TypeEnv,
-    /// Counter for generating fresh type variables
-    var_counter: usize,
     /// Collected type errors
     errors: Vec<TypeError>,
     /// Current effect context
     effect_context: EffectContext,
     /// Effect context stack for nested contexts
     effect_stack: Vec<EffectContext>,
+    /// Types of spanned subexpressions, recorded during [`TypeChecker::infer_all`]
+    span_types: Option<HashMap<Span, Type>>,
 }
 
 impl Default for TypeChecker {
@@ -386,13 +570,18 @@ impl TypeChecker {
     pub fn new() -> Self {
         Self {
             env: TypeEnv::new(),
-            var_counter: 0,
             errors: Vec::new(),
             effect_context: EffectContext::Pure,
             effect_stack: Vec::new(),
+            span_types: None,
         }
     }
 
+    /// Creates a type checker that resolves names in the given environment.
+    pub fn with_env(env: TypeEnv) -> Self {
+        Self { env, ..Self::new() }
+    }
+
     /// Returns collected errors.
     pub fn errors(&self) -> &[TypeError] {
         &self.errors
@@ -408,13 +597,6 @@ impl TypeChecker {
         self.errors.clear();
     }
 
-    /// Generates a fresh type variable.
-    fn fresh_var(&mut self) -> Type {
-        let id = self.var_counter;
-        self.var_counter += 1;
-        Type::Var(id)
-    }
-
     /// Adds a type error.
     fn error(&mut self, err: TypeError) {
         self.errors.push(err);
@@ -441,9 +623,204 @@ impl TypeChecker {
         self.effect_context
     }
 
+    /// Infers the type of an expression that originates at `span`.
+    ///
+    /// Used for synthesized code such as macro expansions, whose nodes carry
+    /// no location of their own: any error raised while inferring `expr`
+    /// that lacks a span is attributed to `span`.
+    pub fn infer_spanned(&mut self, expr: &Expr, span: Span) -> Result<Type, TypeError> {
+        self.with_span(span, |checker| checker.infer(expr))
+    }
+
+    /// Runs `infer`, attributing its span-less errors and its result to `span`.
+    fn with_span(
+        &mut self,
+        span: Span,
+        infer: impl FnOnce(&mut Self) -> Result<Type, TypeError>,
+    ) -> Result<Type, TypeError> {
+        let first_new = self.errors.len();
+        let result = infer(self);
+        for err in &mut self.errors[first_new..] {
+            err.span.get_or_insert(span);
+        }
+        if let Some(span_types) = &mut self.span_types {
+            let ty = result.as_ref().cloned().unwrap_or(Type::Error);
+            span_types.insert(span, ty);
+        }
+        result.map_err(|err| match err.span {
+            Some(_) => err,
+            None => err.with_span(span),
+        })
+    }
+
+    /// Infers the type of every spanned subexpression in a declaration.
+    ///
+    /// Expressions only carry spans when parsed with
+    /// [`Parser::with_expr_spans`](crate::parser::Parser::with_expr_spans).
+    /// Checking continues past failures: an expression whose type cannot be
+    /// inferred maps to [`Type::Error`], and the failure is recorded in
+    /// [`TypeChecker::errors`].
+    pub fn infer_all(&mut self, decl: &Declaration) -> HashMap<Span, Type> {
+        self.span_types = Some(HashMap::new());
+        match decl {
+            Declaration::Function(func) => self.infer_function_body(func),
+            Declaration::Gene(gene) => self.infer_statements(&gene.statements),
+            Declaration::Trait(trait_decl) => self.infer_statements(&trait_decl.statements),
+            Declaration::Const(c) => self.infer_recovering(&c.value),
+            Declaration::SexVar(var) => {
+                if let Some(value) = &var.value {
+                    self.infer_recovering(value);
+                }
+            }
+            Declaration::Constraint(_)
+            | Declaration::System(_)
+            | Declaration::Evolution(_)
+            | Declaration::Extern(_) => {}
+        }
+        self.span_types.take().unwrap_or_default()
+    }
+
+    /// Infers the field defaults, constraints and methods of a gene or trait body.
+    ///
+    /// The body refers to its instance as `this`, whose fields are not
+    /// typed yet.
+    fn infer_statements(&mut self, statements: &[Statement]) {
+        let old_env = std::mem::take(&mut self.env);
+        self.env = old_env.child();
+        self.env.bind("this", Type::Unknown);
+        for stmt in statements {
+            match stmt {
+                Statement::HasField(field) => {
+                    self.check_null_default(field);
+                    for expr in field.default.iter().chain(&field.constraint) {
+                        self.infer_recovering(expr);
+                    }
+                }
+                Statement::Constraint(constraint) => {
+                    if let Some(body) = &constraint.body {
+                        let first_new = self.errors.len();
+                        if let Err(err) = self.check(body, &Type::Bool) {
+                            self.error(err);
+                        }
+                        for err in &mut self.errors[first_new..] {
+                            err.span.get_or_insert(constraint.span);
+                        }
+                    }
+                }
+                Statement::Function(func) => self.infer_function_body(func),
+                _ => {}
+            }
+        }
+        self.env = old_env;
+    }
+
+    /// Reports genes that contain themselves by value.
+    ///
+    /// A field typed as its own gene, directly or through a chain of other
+    /// genes, would give the generated struct infinite size. Recursion is
+    /// allowed once it passes through `Box`, `List`, `Map` or `Set`.
+    /// `Option` stores its value inline, so it only breaks a cycle when it
+    /// wraps the gene itself, which code generation boxes. Each cycle is
+    /// reported once, at the first of its genes in declaration order that
+    /// the search reaches.
+    pub fn check_recursive_types(&mut self, decls: &[Declaration]) {
+        let genes: Vec<&Gene> = decls
+            .iter()
+            .filter_map(|decl| match decl {
+                Declaration::Gene(gene) => Some(gene),
+                _ => None,
+            })
+            .collect();
+        let index_of = |name: &str| {
+            genes
+                .iter()
+                .position(|g| g.name == name || to_pascal_case(g.name.as_str()) == name)
+        };
+        let edges: Vec<Vec<usize>> = genes
+            .iter()
+            .map(|gene| {
+                let mut names = Vec::new();
+                for stmt in &gene.statements {
+                    if let Statement::HasField(field) = stmt {
+                        by_value_type_names(&field.type_, gene.name.as_str(), &mut names);
+                    }
+                }
+                let mut targets: Vec<usize> = names.iter().filter_map(|n| index_of(n)).collect();
+                targets.sort_unstable();
+                targets.dedup();
+                targets
+            })
+            .collect();
+
+        for cycle in find_cycles(&edges) {
+            let start = genes[cycle[0]];
+            let chain: Vec<&str> = cycle.iter().map(|&i| genes[i].name.as_str()).collect();
+            self.error(
+                TypeError::new(format!(
+                    "recursive type '{}' has infinite size: {}; \
+                     wrap the recursive field in Box",
+                    start.name,
+                    chain.join(" -> ")
+                ))
+                .with_span(start.span),
+            );
+        }
+    }
+
+    /// Reports a `null` default on a field whose type is not nullable.
+    pub(crate) fn check_null_default(&mut self, field: &HasField) {
+        let is_null = matches!(
+            field.default.as_ref().map(Expr::unspanned),
+            Some(Expr::Literal(Literal::Null))
+        );
+        let ty = self.env.resolve(&field.type_);
+        let nullable = matches!(ty, Type::Option(_) | Type::Any | Type::Unknown);
+        if is_null && !nullable {
+            self.error(
+                TypeError::new(format!(
+                    "field '{}' of non-nullable type {} cannot default to null",
+                    field.name, ty
+                ))
+                .with_span(field.span),
+            );
+        }
+    }
+
+    /// Checks a function body with its parameters in scope, continuing
+    /// after statements that fail.
+    fn infer_function_body(&mut self, func: &FunctionDecl) {
+        let old_env = std::mem::take(&mut self.env);
+        self.env = old_env.child();
+        for param in &func.params {
+            let ty = self.env.resolve(&param.type_ann);
+            self.env.bind(param.name.clone(), ty);
+        }
+        let sex = func.purity == Purity::Sex;
+        if sex {
+            self.enter_sex_context();
+        }
+        for stmt in &func.body {
+            if let Err(err) = self.check_stmt(stmt) {
+                self.error(err);
+            }
+        }
+        if sex {
+            self.exit_sex_context();
+        }
+        self.env = old_env;
+    }
+
+    /// Infers an expression, recording any failure instead of returning it.
+    fn infer_recovering(&mut self, expr: &Expr) {
+        if let Err(err) = self.infer(expr) {
+            self.error(err);
+        }
+    }
+
     /// Infers the type of an expression.
     pub fn infer(&mut self, expr: &Expr) -> Result<Type, TypeError> {
         match expr {
+            Expr::Spanned { expr, span } => self.infer_spanned(expr, *span),
             // Literals
             Expr::Literal(lit) => match lit {
                 Literal::Int(_) => Ok(Type::Int64),
@@ -451,7 +828,7 @@ impl TypeChecker {
                 Literal::Bool(_) => Ok(Type::Bool),
                 Literal::String(_) => Ok(Type::String),
                 Literal::Char(_) => Ok(Type::String), // Char treated as String
-                Literal::Null => Ok(Type::Unknown),   // Null is polymorphic
+                Literal::Null => Ok(Type::Option(Box::new(Type::Unknown))),
             },
 
             // Identifiers
@@ -475,7 +852,7 @@ impl TypeChecker {
                 params,
                 body,
                 return_type,
-            } => self.infer_lambda(params, body, return_type.as_ref()),
+            } => self.infer_lambda(params, body, return_type.as_ref(), None),
 
             // If expressions
             Expr::If {
@@ -495,9 +872,15 @@ impl TypeChecker {
 
             // Member/Field access
             Expr::Member { object, field } => {
-                let _obj_type = self.infer(object)?;
+                let obj_type = self.infer(object)?;
+                if let Type::Option(_) = obj_type {
+                    self.error(TypeError::new(format!(
+                        "cannot access field '{}' on {}; unwrap the option first",
+                        field, obj_type
+                    )));
+                    return Ok(Type::Error);
+                }
                 // For now, field access returns Unknown (structural typing TBD)
-                let _ = field;
                 Ok(Type::Unknown)
             }
 
@@ -571,7 +954,21 @@ impl TypeChecker {
             // Logic expressions
             Expr::Forall(forall_expr) => {
                 // Forall expressions have type Bool (they are propositions)
-                self.infer(&forall_expr.body)?;
+                match &forall_expr.iter {
+                    Some(iter) => {
+                        let iter_type = self.infer(iter)?;
+                        let elem = list_element(&iter_type).cloned().unwrap_or(Type::Unknown);
+                        let old_env = std::mem::take(&mut self.env);
+                        self.env = old_env.child();
+                        self.env.bind(forall_expr.var.clone(), elem);
+                        let result = self.infer(&forall_expr.body);
+                        self.env = old_env;
+                        result?;
+                    }
+                    None => {
+                        self.infer(&forall_expr.body)?;
+                    }
+                }
                 Ok(Type::Bool)
             }
             Expr::Exists(exists_expr) => {
@@ -581,8 +978,7 @@ impl TypeChecker {
             }
             Expr::Implies { left, right, .. } => {
                 // Implication requires both sides to be Bool
-                let left_type = self.infer(left)?;
-                let right_type = self.infer(right)?;
+                let (left_type, right_type) = self.infer_operands(left, right)?;
                 if left_type != Type::Bool || right_type != Type::Bool {
                     self.error(TypeError::new(format!(
                         "implication requires Bool, found {} and {}",
@@ -628,6 +1024,32 @@ impl TypeChecker {
                     })
                 }
             }
+            // Map literal
+            Expr::Map(entries) => {
+                let Some((first_key, first_value)) = entries.first() else {
+                    // Empty map: Map<Unknown, Unknown>
+                    return Ok(Type::Generic {
+                        name: "Map".to_string(),
+                        args: vec![Type::Unknown, Type::Unknown],
+                    });
+                };
+                let key_type = self.infer(first_key)?;
+                let value_type = self.infer(first_value)?;
+                for (key, value) in entries.iter().skip(1) {
+                    let k = self.infer(key)?;
+                    let v = self.infer(value)?;
+                    if k != key_type || v != value_type {
+                        self.error(TypeError::new(format!(
+                            "map entries have inconsistent types: {}: {} vs {}: {}",
+                            key_type, value_type, k, v
+                        )));
+                    }
+                }
+                Ok(Type::Generic {
+                    name: "Map".to_string(),
+                    args: vec![key_type, value_type],
+                })
+            }
             // Tuple literal
             Expr::Tuple(elements) => {
                 let mut elem_types = Vec::new();
@@ -642,7 +1064,7 @@ impl TypeChecker {
                 // Type-check the expression being cast
                 let _expr_type = self.infer(expr)?;
                 // The result type is the target type
-                Ok(Type::from_type_expr(target_type))
+                Ok(self.env.resolve(target_type))
             }
 
             // Struct literal - the result is the struct type
@@ -714,6 +1136,22 @@ impl TypeChecker {
         }
     }
 
+    /// Infers both operands of a binary expression, so the right one is
+    /// still checked when the left one fails. If both fail, the right
+    /// error is recorded and the left one returned.
+    fn infer_operands(&mut self, left: &Expr, right: &Expr) -> Result<(Type, Type), TypeError> {
+        let left = self.infer(left);
+        let right = self.infer(right);
+        match (left, right) {
+            (Ok(left), Ok(right)) => Ok((left, right)),
+            (Err(err), Ok(_)) | (Ok(_), Err(err)) => Err(err),
+            (Err(err), Err(second)) => {
+                self.error(second);
+                Err(err)
+            }
+        }
+    }
+
     /// Infers type for binary expressions.
     fn infer_binary(
         &mut self,
@@ -721,12 +1159,21 @@ impl TypeChecker {
         left: &Expr,
         right: &Expr,
     ) -> Result<Type, TypeError> {
-        let left_type = self.infer(left)?;
-        let right_type = self.infer(right)?;
+        let (left_type, right_type) = self.infer_operands(left, right)?;
+
+        // An operand that already failed has been reported
+        if left_type == Type::Error || right_type == Type::Error {
+            return Ok(Type::Error);
+        }
 
         match op {
             // Arithmetic operators
             BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => {
+                // An unconstrained operand, such as a lambda parameter
+                // typed `Any`, takes whatever the other side needs
+                if left_type == Type::Unknown || right_type == Type::Unknown {
+                    return Ok(Type::Unknown);
+                }
                 if !left_type.is_numeric() || !right_type.is_numeric() {
                     self.error(TypeError::new(format!(
                         "arithmetic requires numeric types, found {} and {}",
@@ -734,8 +1181,7 @@ impl TypeChecker {
                     )));
                     return Ok(Type::Error);
                 }
-                // Promote to larger type
-                Ok(self.promote_numeric(&left_type, &right_type))
+                Ok(self.unify_numeric(left, &left_type, right, &right_type))
             }
 
             // Comparison operators
@@ -751,11 +1197,16 @@ impl TypeChecker {
             }
 
             BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+                if left_type == Type::Unknown || right_type == Type::Unknown {
+                    return Ok(Type::Bool);
+                }
                 if !left_type.is_numeric() || !right_type.is_numeric() {
                     self.error(TypeError::new(format!(
                         "comparison requires numeric types, found {} and {}",
                         left_type, right_type
                     )));
+                } else {
+                    self.unify_numeric(left, &left_type, right, &right_type);
                 }
                 Ok(Type::Bool)
             }
@@ -844,7 +1295,7 @@ impl TypeChecker {
                     )));
                     return Ok(Type::Error);
                 }
-                Ok(self.promote_numeric(&left_type, &right_type))
+                Ok(self.unify_numeric(left, &left_type, right, &right_type))
             }
 
             // Function application
@@ -878,12 +1329,6 @@ impl TypeChecker {
                 Ok(right_type)
             }
 
-            // Member access operator
-            BinaryOp::Member => {
-                // Member access returns unknown (structural typing TBD)
-                Ok(Type::Unknown)
-            }
-
             // Functor map operator <$>
             BinaryOp::Map => {
                 // Map applies a function to a value inside a functor
@@ -927,6 +1372,11 @@ impl TypeChecker {
 
     /// Infers type for function calls.
     fn infer_call(&mut self, function: &Expr, args: &[Expr]) -> Result<Type, TypeError> {
+        if let Expr::Member { object, field } = function.unspanned() {
+            if let Some(result) = self.infer_list_method(object, field, args) {
+                return result;
+            }
+        }
         let func_type = self.infer(function)?;
 
         match func_type {
@@ -944,10 +1394,41 @@ impl TypeChecker {
                     return Ok(*return_type);
                 }
 
-                // Check argument types
+                // Check argument types. A `List<Any>` parameter passed a
+                // typed list fixes the element type for the parameters of the
+                // function arguments that follow, as in `map(xs, |x| x + 1)`.
+                let mut elem: Option<Type> = None;
                 for (i, (arg, param)) in args.iter().zip(params.iter()).enumerate() {
-                    let arg_type = self.infer(arg)?;
-                    if !self.types_compatible(&arg_type, param) {
+                    let param = match (&elem, param) {
+                        (
+                            Some(elem),
+                            Type::Function {
+                                params: fn_params,
+                                return_type,
+                            },
+                        ) => Type::Function {
+                            params: fn_params
+                                .iter()
+                                .map(|p| {
+                                    if *p == Type::Any {
+                                        elem.clone()
+                                    } else {
+                                        p.clone()
+                                    }
+                                })
+                                .collect(),
+                            return_type: return_type.clone(),
+                        },
+                        _ => param.clone(),
+                    };
+                    let param = &param;
+                    let arg_type = self.infer_expected(arg, param)?;
+                    if let Some(arg_elem) = list_element(&arg_type) {
+                        if list_element(param) == Some(&Type::Any) && *arg_elem != Type::Any {
+                            elem = Some(arg_elem.clone());
+                        }
+                    }
+                    if !self.coerces_to(&arg_type, param) {
                         self.error(TypeError::new(format!(
                             "argument {} has type {}, expected {}",
                             i, arg_type, param
@@ -957,6 +1438,20 @@ impl TypeChecker {
 
                 Ok(*return_type)
             }
+            // Indexing `map[key]` parses as a call with a single argument
+            Type::Generic {
+                name,
+                args: type_args,
+            } if name == "Map" && type_args.len() == 2 && args.len() == 1 => {
+                let key_type = self.infer(&args[0])?;
+                if !self.types_compatible(&key_type, &type_args[0]) {
+                    self.error(TypeError::new(format!(
+                        "map key has type {}, expected {}",
+                        key_type, type_args[0]
+                    )));
+                }
+                Ok(type_args[1].clone())
+            }
             Type::Unknown | Type::Any => {
                 // Infer all arguments for side effects, return unknown
                 for arg in args {
@@ -974,49 +1469,139 @@ impl TypeChecker {
         }
     }
 
+    /// Infers a `map` or `filter` method call on a list, passing the element
+    /// type to the lambda argument.
+    ///
+    /// Returns `None` when `object` is not a list or the method is not one
+    /// of these, leaving the call to be inferred as usual.
+    fn infer_list_method(
+        &mut self,
+        object: &Expr,
+        method: &str,
+        args: &[Expr],
+    ) -> Option<Result<Type, TypeError>> {
+        if !matches!(method, "map" | "filter") || args.len() != 1 {
+            return None;
+        }
+        // Inferring the object twice would report its errors twice
+        let first_new = self.errors.len();
+        let object_type = match self.infer(object) {
+            Ok(ty) => ty,
+            Err(err) => return Some(Err(err)),
+        };
+        let Some(elem) = list_element(&object_type).cloned() else {
+            self.errors.truncate(first_new);
+            return None;
+        };
+        let (return_type, result) = match method {
+            "map" => (Type::Any, None),
+            _ => (Type::Bool, Some(object_type.clone())),
+        };
+        let expected = Type::Function {
+            params: vec![elem],
+            return_type: Box::new(return_type),
+        };
+        Some(self.infer_expected(&args[0], &expected).map(|arg_type| {
+            if !self.coerces_to(&arg_type, &expected) {
+                self.error(TypeError::new(format!(
+                    "argument 0 has type {}, expected {}",
+                    arg_type, expected
+                )));
+            }
+            result.unwrap_or_else(|| match arg_type {
+                Type::Function { return_type, .. } => Type::Generic {
+                    name: "List".to_string(),
+                    args: vec![*return_type],
+                },
+                _ => Type::Error,
+            })
+        }))
+    }
+
+    /// Infers the type of an expression checked against `expected`.
+    ///
+    /// A lambda takes the types of its unannotated parameters, and its
+    /// return type, from an expected function type. Other expressions are
+    /// inferred on their own.
+    fn infer_expected(&mut self, expr: &Expr, expected: &Type) -> Result<Type, TypeError> {
+        match expr {
+            Expr::Spanned { expr, span } => {
+                self.with_span(*span, |checker| checker.infer_expected(expr, expected))
+            }
+            Expr::Lambda {
+                params,
+                body,
+                return_type,
+            } => self.infer_lambda(params, body, return_type.as_ref(), Some(expected)),
+            _ => self.infer(expr),
+        }
+    }
+
     /// Infers type for lambda expressions.
+    ///
+    /// Unannotated parameters need an `expected` function type of the same
+    /// arity to take their types from; without one they are reported and
+    /// typed as [`Type::Error`].
     fn infer_lambda(
         &mut self,
         params: &[(String, Option<TypeExpr>)],
         body: &Expr,
         return_type: Option<&TypeExpr>,
+        expected: Option<&Type>,
     ) -> Result<Type, TypeError> {
+        let (expected_params, expected_return) = match expected {
+            Some(Type::Function {
+                params: expected_params,
+                return_type,
+            }) if expected_params.len() == params.len() => (
+                Some(expected_params),
+                Some(return_type.as_ref()).filter(|ret| **ret != Type::Any),
+            ),
+            _ => (None, None),
+        };
+
         // Create child environment with parameters
         let old_env = std::mem::take(&mut self.env);
         self.env = old_env.child();
 
-        let param_types: Vec<Type> = params
-            .iter()
-            .map(|(name, ty_expr)| {
-                let ty = ty_expr
-                    .as_ref()
-                    .map(Type::from_type_expr)
-                    .unwrap_or_else(|| self.fresh_var());
-                self.env.bind(name.clone(), ty.clone());
-                ty
-            })
-            .collect();
+        let mut param_types = Vec::with_capacity(params.len());
+        for (i, (name, ty_expr)) in params.iter().enumerate() {
+            let ty = match (ty_expr, expected_params) {
+                (Some(ty_expr), _) => self.env.resolve(ty_expr),
+                // `Any` leaves the parameter unconstrained
+                (None, Some(expected_params)) => match &expected_params[i] {
+                    Type::Any => Type::Unknown,
+                    ty => ty.clone(),
+                },
+                (None, None) => {
+                    self.error(TypeError::new(format!(
+                        "cannot infer parameter type for '{}'; annotate it or pass the lambda \
+                         where a function type is expected",
+                        name
+                    )));
+                    Type::Error
+                }
+            };
+            self.env.bind(name.clone(), ty.clone());
+            param_types.push(ty);
+        }
 
-        // Infer body type
-        let body_type = self.infer(body)?;
+        // Infer body type, against the declared or expected return type
+        let declared_return = return_type.map(|ty| self.env.resolve(ty));
+        let result = match declared_return.as_ref().or(expected_return) {
+            Some(ret) => self.infer_expected(body, ret).map(|body_type| {
+                self.check_assignable(body, &body_type, ret);
+                ret.clone()
+            }),
+            None => self.infer(body),
+        };
 
         // Restore environment
         self.env = old_env;
 
-        // Check return type annotation if present
-        let ret_type = if let Some(ret_expr) = return_type {
-            let expected = Type::from_type_expr(ret_expr);
-            if !self.types_compatible(&body_type, &expected) {
-                self.error(TypeError::mismatch(expected.clone(), body_type));
-            }
-            expected
-        } else {
-            body_type
-        };
-
         Ok(Type::Function {
             params: param_types,
-            return_type: Box::new(ret_type),
+            return_type: Box::new(result?),
         })
     }
 
@@ -1135,6 +1720,45 @@ impl TypeChecker {
         }
     }
 
+    /// Binds pattern variables against the type of a destructured value.
+    ///
+    /// Tuple patterns take their element types from the value; a pattern whose
+    /// shape cannot match the value (such as a tuple arity mismatch) is an error.
+    fn bind_pattern_typed(&mut self, pattern: &Pattern, ty: &Type) -> Result<(), TypeError> {
+        match (pattern, ty) {
+            (Pattern::Identifier(name), _) => {
+                self.env.bind(name.clone(), ty.clone());
+                Ok(())
+            }
+            (Pattern::Tuple(patterns), Type::Tuple(types)) => {
+                if patterns.len() != types.len() {
+                    return Err(TypeError::new(format!(
+                        "tuple pattern has {} elements, but value {} has {}",
+                        patterns.len(),
+                        ty,
+                        types.len()
+                    )));
+                }
+                for (p, t) in patterns.iter().zip(types) {
+                    self.bind_pattern_typed(p, t)?;
+                }
+                Ok(())
+            }
+            (Pattern::Tuple(_), Type::Unknown | Type::Any | Type::Var(_) | Type::Error) => {
+                self.bind_pattern(pattern);
+                Ok(())
+            }
+            (Pattern::Tuple(_), _) => Err(TypeError::new(format!(
+                "tuple pattern cannot destructure value of type {}",
+                ty
+            ))),
+            _ => {
+                self.bind_pattern(pattern);
+                Ok(())
+            }
+        }
+    }
+
     /// Infers type for block expressions.
     fn infer_block(
         &mut self,
@@ -1163,10 +1787,26 @@ impl TypeChecker {
     /// Type checks a statement.
     fn check_stmt(&mut self, stmt: &Stmt) -> Result<(), TypeError> {
         match stmt {
-            Stmt::Let { name, value, .. } => {
-                let ty = self.infer(value)?;
+            Stmt::Let {
+                name,
+                type_ann,
+                value,
+            } => {
+                let ty = match type_ann {
+                    Some(ann) => {
+                        let declared = self.env.resolve(ann);
+                        let ty = self.infer_expected(value, &declared)?;
+                        self.check_assignable(value, &ty, &declared);
+                        declared
+                    }
+                    None => self.infer(value)?,
+                };
                 self.env.bind(name.clone(), ty);
             }
+            Stmt::LetPattern { pattern, value } => {
+                let ty = self.infer(value)?;
+                self.bind_pattern_typed(pattern, &ty)?;
+            }
             Stmt::Expr(expr) => {
                 let _ = self.infer(expr)?;
             }
@@ -1223,31 +1863,91 @@ impl TypeChecker {
         Ok(())
     }
 
-    /// Promotes two numeric types to their common supertype.
-    fn promote_numeric(&self, left: &Type, right: &Type) -> Type {
-        // Float > Int, larger width wins, signed > unsigned at same width
-        let left_width = left.bit_width().unwrap_or(64);
-        let right_width = right.bit_width().unwrap_or(64);
+    /// Unifies the operand types of a numeric binary operator.
+    ///
+    /// Widths are never converted implicitly: both operands must have the
+    /// same type, except that a numeric literal adopts the type of the other
+    /// operand as long as its value fits.
+    fn unify_numeric(
+        &mut self,
+        left: &Expr,
+        left_type: &Type,
+        right: &Expr,
+        right_type: &Type,
+    ) -> Type {
+        if left_type == right_type {
+            return left_type.clone();
+        }
+        if let Some(ty) = self.coerce_literal(left, right_type) {
+            return ty;
+        }
+        if let Some(ty) = self.coerce_literal(right, left_type) {
+            return ty;
+        }
+        self.error(TypeError::new(format!(
+            "mismatched numeric types {} and {}; convert one explicitly with `as`",
+            left_type, right_type
+        )));
+        Type::Error
+    }
+
+    /// Checks that a value of type `actual` can initialize a binding
+    /// declared as `declared`.
+    fn check_assignable(&mut self, value: &Expr, actual: &Type, declared: &Type) {
+        if !self.is_assignable(value, actual, declared) {
+            self.error(TypeError::mismatch(declared.clone(), actual.clone()));
+        }
+    }
 
-        if left.is_float() || right.is_float() {
-            if left_width >= 64 || right_width >= 64 {
-                Type::Float64
-            } else {
-                Type::Float32
+    /// Returns true if a value of type `actual` can initialize a binding
+    /// declared as `declared`.
+    ///
+    /// A bare `T` is wrapped into `Option<T>` implicitly.
+    fn is_assignable(&mut self, value: &Expr, actual: &Type, declared: &Type) -> bool {
+        if actual == declared || self.coerce_literal(value, declared).is_some() {
+            return true;
+        }
+        if let Type::Option(inner) = declared {
+            if !matches!(actual, Type::Option(_)) {
+                return self.is_assignable(value, actual, inner);
             }
-        } else {
-            let max_width = left_width.max(right_width);
-            let signed = left.is_signed() || right.is_signed();
-            match (max_width, signed) {
-                (8, true) => Type::Int8,
-                (8, false) => Type::UInt8,
-                (16, true) => Type::Int16,
-                (16, false) => Type::UInt16,
-                (32, true) => Type::Int32,
-                (32, false) => Type::UInt32,
-                (_, true) => Type::Int64,
-                (_, false) => Type::UInt64,
+        }
+        let width_mismatch = actual.is_numeric() && declared.is_numeric();
+        !width_mismatch && self.types_compatible(declared, actual)
+    }
+
+    /// Returns true if `actual` is compatible with `expected`, or can be
+    /// wrapped into it when `expected` is an `Option`.
+    fn coerces_to(&self, actual: &Type, expected: &Type) -> bool {
+        match expected {
+            Type::Option(inner) if !matches!(actual, Type::Option(_)) => {
+                self.types_compatible(actual, inner)
             }
+            _ => self.types_compatible(actual, expected),
+        }
+    }
+
+    /// Gives a numeric literal the type `target`, reporting integer literals
+    /// outside the target's range.
+    ///
+    /// Returns `None` if `expr` is not a literal that can take that type.
+    fn coerce_literal(&mut self, expr: &Expr, target: &Type) -> Option<Type> {
+        match numeric_literal(expr)? {
+            NumericLiteral::Int(value) if target.is_integer() => {
+                if let Some((min, max)) = target.integer_range() {
+                    if value < min || value > max {
+                        self.error(TypeError::new(format!(
+                            "integer literal {} is out of range for {} ({}..={})",
+                            value, target, min, max
+                        )));
+                    }
+                }
+                Some(target.clone())
+            }
+            NumericLiteral::Int(_) | NumericLiteral::Float if target.is_float() => {
+                Some(target.clone())
+            }
+            _ => None,
         }
     }
 
@@ -1283,24 +1983,157 @@ impl TypeChecker {
                     && p1.iter().zip(p2).all(|(x, y)| self.types_compatible(x, y))
                     && self.types_compatible(r1, r2)
             }
+            (Type::Option(a), Type::Option(b)) => self.types_compatible(a, b),
             // Tuples
             (Type::Tuple(t1), Type::Tuple(t2)) => {
                 t1.len() == t2.len() && t1.iter().zip(t2).all(|(x, y)| self.types_compatible(x, y))
             }
+            // Records match field by field, in order
+            (Type::Record(f1), Type::Record(f2)) => {
+                f1.len() == f2.len()
+                    && f1
+                        .iter()
+                        .zip(f2)
+                        .all(|((n1, x), (n2, y))| n1 == n2 && self.types_compatible(x, y))
+            }
             _ => false,
         }
     }
 
     /// Checks an expression against an expected type.
     pub fn check(&mut self, expr: &Expr, expected: &Type) -> Result<(), TypeError> {
-        let actual = self.infer(expr)?;
-        if !self.types_compatible(&actual, expected) {
+        let actual = self.infer_expected(expr, expected)?;
+        if !self.coerces_to(&actual, expected) {
             self.error(TypeError::mismatch(expected.clone(), actual));
         }
         Ok(())
     }
 }
 
+/// A numeric literal, possibly negated.
+enum NumericLiteral {
+    Int(i128),
+    Float,
+}
+
+/// Returns the numeric literal an expression consists of, if any.
+fn numeric_literal(expr: &Expr) -> Option<NumericLiteral> {
+    match expr.unspanned() {
+        Expr::Literal(Literal::Int(value)) => Some(NumericLiteral::Int((*value).into())),
+        Expr::Literal(Literal::Float(_)) => Some(NumericLiteral::Float),
+        Expr::Unary {
+            op: UnaryOp::Neg,
+            operand,
+        } => match numeric_literal(operand)? {
+            NumericLiteral::Int(value) => Some(NumericLiteral::Int(-value)),
+            NumericLiteral::Float => Some(NumericLiteral::Float),
+        },
+        _ => None,
+    }
+}
+
+/// Returns the element type of a `List<T>`.
+fn list_element(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Generic { name, args } if name == "List" && args.len() == 1 => Some(&args[0]),
+        _ => None,
+    }
+}
+
+/// Collects the type names a field of gene `owner` stores by value,
+/// stopping at containers that introduce indirection.
+///
+/// `Option<owner>` counts as indirection because code generation boxes a
+/// field of exactly that type; any other `Option` stores its value inline.
+fn by_value_type_names(ty: &TypeExpr, owner: &str, names: &mut Vec<String>) {
+    match ty {
+        TypeExpr::Named(name) => names.push(name.clone()),
+        TypeExpr::Generic { name, args } => {
+            let boxed_self = name == "Option"
+                && matches!(args.as_slice(), [TypeExpr::Named(inner)]
+                    if inner == owner || *inner == to_pascal_case(owner));
+            if !boxed_self && !matches!(name.as_str(), "Box" | "List" | "Vec" | "Map" | "Set") {
+                for arg in args {
+                    by_value_type_names(arg, owner, names);
+                }
+            }
+        }
+        TypeExpr::Tuple(types) => {
+            for ty in types {
+                by_value_type_names(ty, owner, names);
+            }
+        }
+        TypeExpr::Record(fields) => {
+            for (_, ty) in fields {
+                by_value_type_names(ty, owner, names);
+            }
+        }
+        TypeExpr::Enum { variants } => {
+            for variant in variants {
+                for (_, ty) in &variant.fields {
+                    by_value_type_names(ty, owner, names);
+                }
+                for ty in &variant.tuple_types {
+                    by_value_type_names(ty, owner, names);
+                }
+            }
+        }
+        TypeExpr::Function { .. } | TypeExpr::Never => {}
+    }
+}
+
+/// Finds cycles in a directed graph given as adjacency lists.
+///
+/// A depth-first search marks each node white (unvisited), grey (on the
+/// current path) or black (finished). Every edge back to a grey node closes
+/// one cycle, returned as the path from that node round to itself, so each
+/// node and edge is visited once.
+fn find_cycles(edges: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Colour {
+        White,
+        Grey,
+        Black,
+    }
+
+    let mut colour = vec![Colour::White; edges.len()];
+    let mut cycles = Vec::new();
+    for root in 0..edges.len() {
+        if colour[root] != Colour::White {
+            continue;
+        }
+        colour[root] = Colour::Grey;
+        // The current path, with the index of the next edge to follow from each node
+        let mut path = vec![(root, 0)];
+        while let Some((node, next)) = path.last_mut() {
+            let node = *node;
+            let Some(&succ) = edges[node].get(*next) else {
+                colour[node] = Colour::Black;
+                path.pop();
+                continue;
+            };
+            *next += 1;
+            match colour[succ] {
+                Colour::White => {
+                    colour[succ] = Colour::Grey;
+                    path.push((succ, 0));
+                }
+                Colour::Grey => {
+                    let from = path
+                        .iter()
+                        .position(|&(n, _)| n == succ)
+                        .expect("grey nodes are on the path");
+                    let mut cycle: Vec<usize> = path[from..].iter().map(|&(n, _)| n).collect();
+                    cycle.push(succ);
+                    cycles.push(cycle);
+                }
+                Colour::Black => {}
+            }
+        }
+    }
+    cycles
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1375,6 +2208,84 @@ mod tests {
         }
     }
 
+    fn add_one_lambda() -> Expr {
+        Expr::Lambda {
+            params: vec![("x".to_string(), None)],
+            body: Box::new(Expr::Binary {
+                left: Box::new(Expr::Identifier("x".to_string())),
+                op: BinaryOp::Add,
+                right: Box::new(int_lit(1)),
+            }),
+            return_type: None,
+        }
+    }
+
+    #[test]
+    fn test_lambda_parameters_inferred_from_expected_type() {
+        let int_fn = Type::Function {
+            params: vec![Type::Int32],
+            return_type: Box::new(Type::Int32),
+        };
+        let mut env = TypeEnv::new();
+        env.bind(
+            "apply",
+            Type::Function {
+                params: vec![int_fn.clone(), Type::Int32],
+                return_type: Box::new(Type::Int32),
+            },
+        );
+        let mut checker = TypeChecker::with_env(env);
+
+        let call = Expr::Call {
+            callee: Box::new(Expr::Identifier("apply".to_string())),
+            args: vec![add_one_lambda(), int_lit(5)],
+        };
+        assert_eq!(checker.infer(&call), Ok(Type::Int32));
+        assert!(checker.is_ok(), "{:?}", checker.errors());
+
+        checker.check(&add_one_lambda(), &int_fn).unwrap();
+        assert!(checker.is_ok(), "{:?}", checker.errors());
+
+        // The body must still produce the expected return type
+        let to_string = Expr::Lambda {
+            params: vec![("x".to_string(), None)],
+            body: Box::new(string_lit("one")),
+            return_type: None,
+        };
+        checker.check(&to_string, &int_fn).unwrap();
+        assert_eq!(checker.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_lambda_expected_type_from_let_annotation() {
+        let source = "fun main() -> Int64 {\n  \
+                      let inc: (Int32) -> Int32 = |x| x + 1\n  \
+                      return 0\n}";
+        let file = crate::parser::Parser::new(source).parse_file().unwrap();
+
+        let mut checker = TypeChecker::new();
+        checker.infer_all(&file.declarations[0]);
+        assert!(checker.is_ok(), "{:?}", checker.errors());
+    }
+
+    #[test]
+    fn test_lambda_parameter_without_context_is_ambiguous() {
+        let mut checker = TypeChecker::new();
+
+        let ty = checker.infer(&add_one_lambda()).unwrap();
+        assert_eq!(
+            ty,
+            Type::Function {
+                params: vec![Type::Error],
+                return_type: Box::new(Type::Error),
+            }
+        );
+        assert_eq!(checker.errors().len(), 1);
+        assert!(checker.errors()[0]
+            .message
+            .starts_with("cannot infer parameter type for 'x'"));
+    }
+
     #[test]
     fn test_infer_if() {
         let mut checker = TypeChecker::new();
@@ -1420,6 +2331,33 @@ mod tests {
         assert!(checker.infer(&expr).is_err());
     }
 
+    #[test]
+    fn test_prelude_resolves_builtins() {
+        let env = TypeEnv::with_prelude();
+        assert!(env.lookup("len").is_some());
+        assert_eq!(env.lookup_type("Int64"), Some(&Type::Int64));
+
+        let mut env = env.child();
+        env.bind(
+            "xs",
+            Type::Generic {
+                name: "List".to_string(),
+                args: vec![Type::Int64],
+            },
+        );
+        let mut checker = TypeChecker::with_env(env);
+
+        let call = Expr::Call {
+            callee: Box::new(Expr::Identifier("len".to_string())),
+            args: vec![Expr::Identifier("xs".to_string())],
+        };
+        assert_eq!(checker.infer(&call), Ok(Type::Int64));
+        assert!(checker.is_ok());
+
+        let unknown = Expr::Identifier("frobnicate".to_string());
+        assert!(checker.infer(&unknown).is_err());
+    }
+
     #[test]
     fn test_type_from_type_expr() {
         let type_expr = TypeExpr::Function {
@@ -1441,6 +2379,304 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_infer_all_records_subexpression_types() {
+        let source = "fun add(a: Int64, b: Int64) -> Int64 { let c = a + b\n return missing + c }";
+        let file = crate::parser::Parser::new(source)
+            .with_expr_spans()
+            .parse_file()
+            .unwrap();
+
+        let mut checker = TypeChecker::new();
+        let types = checker.infer_all(&file.declarations[0]);
+
+        let sum_start = source.find("a + b").unwrap();
+        let sum = types
+            .iter()
+            .find(|(span, _)| span.start == sum_start && span.end == sum_start + 5)
+            .map(|(_, ty)| ty);
+        assert_eq!(sum, Some(&Type::Int64));
+
+        // The unresolved `missing + c` still gets an entry
+        let bad_start = source.find("missing + c").unwrap();
+        assert!(types
+            .iter()
+            .any(|(span, ty)| span.start == bad_start && *ty == Type::Error));
+        assert!(!checker.is_ok());
+
+        // Inference continues to the operand after the failed one
+        let c_start = bad_start + "missing + ".len();
+        assert!(types
+            .iter()
+            .any(|(span, ty)| span.start == c_start && *ty == Type::Int64));
+    }
+
+    #[test]
+    fn test_direct_type_recursion_is_reported() {
+        let source = "gene Node { Node has value: Int64\n Node has child: Node }";
+        let file = crate::parser::Parser::new(source).parse_file().unwrap();
+
+        let mut checker = TypeChecker::new();
+        checker.check_recursive_types(&file.declarations);
+        assert_eq!(checker.errors().len(), 1);
+        assert!(checker.errors()[0].message.contains("Node -> Node"));
+    }
+
+    #[test]
+    fn test_mutual_type_recursion_is_reported() {
+        let source = "gene Left { Left has right: Right }\ngene Right { Right has left: Left }";
+        let file = crate::parser::Parser::new(source).parse_file().unwrap();
+
+        let mut checker = TypeChecker::new();
+        checker.check_recursive_types(&file.declarations);
+        assert_eq!(checker.errors().len(), 1);
+        assert!(checker.errors()[0]
+            .message
+            .contains("Left -> Right -> Left"));
+    }
+
+    #[test]
+    fn test_mutual_recursion_through_option_is_reported() {
+        // Option stores its value inline, so A and B would contain each other
+        let source = "gene A { A has b: Option<B> }\ngene B { B has a: Option<A> }";
+        let file = crate::parser::Parser::new(source).parse_file().unwrap();
+
+        let mut checker = TypeChecker::new();
+        checker.check_recursive_types(&file.declarations);
+        assert_eq!(checker.errors().len(), 1);
+        assert!(checker.errors()[0].message.contains("A -> B -> A"));
+    }
+
+    #[test]
+    fn test_indirect_type_recursion_is_allowed() {
+        let source = "gene Tree { Tree has parent: Option<Tree>\n Tree has children: List<Tree> }";
+        let file = crate::parser::Parser::new(source).parse_file().unwrap();
+
+        let mut checker = TypeChecker::new();
+        checker.check_recursive_types(&file.declarations);
+        assert!(checker.is_ok());
+    }
+
+    #[test]
+    fn test_null_default_requires_nullable_field() {
+        let source =
+            "gene session { session has token: String? = null\n session has user: String = null }";
+        let file = crate::parser::Parser::new(source).parse_file().unwrap();
+
+        let mut checker = TypeChecker::new();
+        checker.infer_all(&file.declarations[0]);
+        assert_eq!(checker.errors().len(), 1);
+        assert!(checker.errors()[0].message.contains("'user'"));
+        assert!(checker.errors()[0].span.is_some());
+    }
+
+    #[test]
+    fn test_inline_constraint_must_be_bool() {
+        let source = "gene money {\n  has amount: Int64\n  \
+                      constraint non_negative { this.amount >= 0 }\n  \
+                      constraint labelled { \"money\" }\n}";
+        let file = crate::parser::Parser::new(source).parse_file().unwrap();
+
+        let mut checker = TypeChecker::new();
+        checker.infer_all(&file.declarations[0]);
+        assert_eq!(checker.errors().len(), 1, "{:?}", checker.errors());
+        assert_eq!(checker.errors()[0].actual, Some(Type::String));
+        let span = checker.errors()[0].span.unwrap();
+        assert!(source[span.start..span.end].starts_with("constraint labelled"));
+    }
+
+    #[test]
+    fn test_option_type_from_type_expr() {
+        let optional = TypeExpr::Generic {
+            name: "Option".to_string(),
+            args: vec![TypeExpr::Named("Int32".to_string())],
+        };
+        let ty = Type::from_type_expr(&optional);
+        assert_eq!(ty, Type::Option(Box::new(Type::Int32)));
+        assert_eq!(ty.to_string(), "Option<Int32>");
+    }
+
+    /// Type-checks the body of the first function in `source`.
+    fn function_errors(source: &str) -> Vec<String> {
+        let file = crate::parser::Parser::new(source).parse_file().unwrap();
+        let mut checker = TypeChecker::with_env(TypeEnv::with_prelude());
+        checker.infer_all(&file.declarations[0]);
+        checker.errors().iter().map(|e| e.message.clone()).collect()
+    }
+
+    #[test]
+    fn test_prelude_resolves_bare_type_names() {
+        let env = TypeEnv::with_prelude();
+        let list_any = Type::Generic {
+            name: "List".to_string(),
+            args: vec![Type::Any],
+        };
+        assert_eq!(env.resolve(&TypeExpr::Named("List".to_string())), list_any);
+
+        let optional = TypeExpr::Generic {
+            name: "Option".to_string(),
+            args: vec![TypeExpr::Named("List".to_string())],
+        };
+        assert_eq!(env.resolve(&optional), Type::Option(Box::new(list_any)));
+
+        let unbound = TypeExpr::Named("Container".to_string());
+        assert_eq!(env.resolve(&unbound), Type::from_type_expr(&unbound));
+    }
+
+    #[test]
+    fn test_map_lambda_takes_list_element_type() {
+        let errors = function_errors(
+            "fun bump(xs: List<Int64>) -> Int64 {\n  \
+               let ys = map(xs, |x| x + 1)\n  \
+               return 0\n}",
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let errors = function_errors(
+            "fun bump(names: List<String>) -> Int64 {\n  \
+               let ys = map(names, |n| n * 2)\n  \
+               return 0\n}",
+        );
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(errors[0].contains("String"), "{errors:?}");
+    }
+
+    #[test]
+    fn test_lambda_with_any_parameter_is_unconstrained() {
+        let mut env = TypeEnv::with_prelude();
+        env.bind(
+            "apply",
+            Type::Function {
+                params: vec![Type::Function {
+                    params: vec![Type::Any],
+                    return_type: Box::new(Type::Any),
+                }],
+                return_type: Box::new(Type::Any),
+            },
+        );
+        let mut checker = TypeChecker::with_env(env);
+        let call = Expr::Call {
+            callee: Box::new(Expr::Identifier("apply".to_string())),
+            args: vec![add_one_lambda()],
+        };
+        checker.infer(&call).unwrap();
+        assert!(checker.is_ok(), "{:?}", checker.errors());
+    }
+
+    #[test]
+    fn test_list_methods_give_lambdas_the_element_type() {
+        let errors = function_errors(
+            "fun bump(xs: List<Int64>) -> Int64 {\n  \
+               let ys: List<Int64> = xs.map(|x| x + 1)\n  \
+               let big: List<Int64> = xs.filter(|x| x > 1)\n  \
+               return 0\n}",
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let errors = function_errors(
+            "fun bump(xs: List<Int64>) -> Int64 {\n  \
+               let names: List<String> = xs.map(|x| x + 1)\n  \
+               return 0\n}",
+        );
+        assert_eq!(errors.len(), 1, "{errors:?}");
+    }
+
+    #[test]
+    fn test_null_and_bare_values_assign_to_option() {
+        let errors = function_errors(
+            "fun config() -> Int64 {\n  \
+               let timeout: Option<Int32> = null\n  \
+               let retries: Option<Int32> = 3\n  \
+               let name: String? = \"main\"\n  \
+               let fallback: Option<String> = name\n  \
+               return 0\n}",
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn test_null_to_non_optional_is_rejected() {
+        let errors =
+            function_errors("fun config() -> Int64 {\n  let timeout: Int32 = null\n  return 0\n}");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("Int32"), "{errors:?}");
+
+        // The element type of the option still has to match
+        let errors = function_errors(
+            "fun config() -> Int64 {\n  let timeout: Option<Int32> = \"soon\"\n  return 0\n}",
+        );
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_option_fields_accept_null_defaults() {
+        let source =
+            "gene Config {\n  has timeout: Option<Int32> = null\n  has retries: Int32 = null\n}";
+        let file = crate::parser::Parser::new(source).parse_file().unwrap();
+
+        let mut checker = TypeChecker::new();
+        checker.infer_all(&file.declarations[0]);
+        assert_eq!(checker.errors().len(), 1);
+        assert!(checker.errors()[0].message.contains("'retries'"));
+    }
+
+    #[test]
+    fn test_member_access_on_option_is_rejected() {
+        let errors =
+            function_errors("fun port(config: Option<Config>) -> Int64 {\n  return config.port\n}");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("unwrap the option"), "{errors:?}");
+
+        let errors =
+            function_errors("fun port(config: Config) -> Int64 {\n  return config.port\n}");
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn test_bare_argument_coerces_to_option_parameter() {
+        let mut env = TypeEnv::with_prelude();
+        env.bind(
+            "connect",
+            Type::Function {
+                params: vec![Type::Option(Box::new(Type::Int64))],
+                return_type: Box::new(Type::Bool),
+            },
+        );
+        let mut checker = TypeChecker::with_env(env);
+        let call = |arg: Expr| Expr::Call {
+            callee: Box::new(Expr::Identifier("connect".to_string())),
+            args: vec![arg],
+        };
+
+        assert_eq!(checker.infer(&call(int_lit(30))), Ok(Type::Bool));
+        assert_eq!(
+            checker.infer(&call(Expr::Literal(Literal::Null))),
+            Ok(Type::Bool)
+        );
+        assert!(checker.is_ok());
+
+        checker.infer(&call(string_lit("30"))).unwrap();
+        assert_eq!(checker.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_type_from_record_type_expr() {
+        let type_expr = TypeExpr::Record(vec![
+            ("cpu".to_string(), TypeExpr::Named("Int64".to_string())),
+            ("label".to_string(), TypeExpr::Named("String".to_string())),
+        ]);
+
+        let ty = Type::from_type_expr(&type_expr);
+        assert_eq!(
+            ty,
+            Type::Record(vec![
+                ("cpu".to_string(), Type::Int64),
+                ("label".to_string(), Type::String),
+            ])
+        );
+        assert_eq!(ty.to_string(), "{ cpu: Int64, label: String }");
+    }
+
     #[test]
     fn test_quote_preserves_inner_type() {
         let mut checker = TypeChecker::new();
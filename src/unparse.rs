@@ -0,0 +1,165 @@
+//! Re-emitting DOL source from edited declarations.
+//!
+//! Tooling that edits declarations programmatically (for example to improve
+//! documentation) needs to write the result back to source. Rather than
+//! pretty-printing the whole declaration, the functions here splice the
+//! changed parts into the original text so comments and layout survive.
+//!
+//! # Example
+//!
+//! ```rust
+//! use metadol::parser::Parser;
+//! use metadol::unparse::rewrite_exegesis;
+//!
+//! let source = "gene container.exists {\n  container has id\n}\n\nexegesis {\n  Old text.\n}\n";
+//! let decl = Parser::new(source).parse().unwrap();
+//! let decl = decl.with_exegesis("A container is the fundamental unit.".to_string());
+//!
+//! let updated = rewrite_exegesis(source, &decl).unwrap();
+//! let reparsed = Parser::new(&updated).parse().unwrap();
+//! assert_eq!(reparsed.exegesis(), "A container is the fundamental unit.");
+//! ```
+
+use crate::ast::Declaration;
+use crate::format::Printer;
+use crate::lexer::{Lexer, TokenKind};
+
+/// Re-emits `source` with the exegesis block of `decl` replaced by the
+/// declaration's current exegesis.
+///
+/// `decl` must have been parsed from `source`, since its span is used to
+/// locate the block. A declaration without an exegesis block gets a
+/// trailing one appended after it.
+///
+/// Returns `None` if the declaration kind has no exegesis to emit into
+/// (constants, SexVars, and functions without an exegesis block).
+pub fn rewrite_exegesis(source: &str, decl: &Declaration) -> Option<String> {
+    let span = decl.span();
+    let start = span.start.min(source.len());
+    let end = span.end.min(source.len());
+
+    if let Some((keyword, close)) = find_exegesis_block(source, start, end) {
+        let mut printer = Printer::continuing(line_indent(source, keyword), 2);
+        printer.exegesis(decl.exegesis(), None);
+        return Some(format!(
+            "{}{}{}",
+            &source[..keyword],
+            printer.into_string(),
+            &source[close + 1..]
+        ));
+    }
+
+    match decl {
+        Declaration::Gene(_)
+        | Declaration::Trait(_)
+        | Declaration::Constraint(_)
+        | Declaration::System(_)
+        | Declaration::Evolution(_) => {
+            let mut printer = Printer::new(2);
+            printer.exegesis(decl.exegesis(), None);
+            Some(format!(
+                "{}\n\n{}{}",
+                &source[..end],
+                printer.into_string(),
+                &source[end..]
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Finds the declaration-level exegesis block in `source[start..end]`,
+/// returning the byte offsets of the `exegesis` keyword and the closing `}`.
+///
+/// Exegesis blocks nested deeper than the declaration body (for example on
+/// functions inside a gene) are skipped.
+fn find_exegesis_block(source: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let mut lexer = Lexer::new(&source[start..end]);
+    let mut depth = 0usize;
+
+    loop {
+        let token = lexer.next_token();
+        match token.kind {
+            TokenKind::Eof => return None,
+            TokenKind::LeftBrace => depth += 1,
+            TokenKind::RightBrace => depth = depth.saturating_sub(1),
+            TokenKind::Exegesis if depth <= 1 => {
+                let keyword = start + token.span.start;
+                let after = start + token.span.end;
+                let open = after + source[after..].find('{')?;
+                let close = matching_brace(source, open)?;
+                return Some((keyword, close));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the offset of the `}` matching the `{` at `open`.
+pub(crate) fn matching_brace(source: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (offset, ch) in source[open..].char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + offset);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the leading whitespace of the line containing `offset`.
+fn line_indent(source: &str, offset: usize) -> &str {
+    let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = &source[line_start..offset];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn roundtrip(source: &str, text: &str) -> String {
+        let file = Parser::new(source).parse_file().unwrap();
+        let decl = file.declarations[0].clone().with_exegesis(text.to_string());
+        let updated = rewrite_exegesis(source, &decl).unwrap();
+        let reparsed = Parser::new(&updated).parse_file().unwrap();
+        assert_eq!(reparsed.declarations[0].exegesis(), text);
+        updated
+    }
+
+    #[test]
+    fn test_rewrite_trailing_exegesis() {
+        let source = "gene container.exists {\n  container has id\n}\n\nexegesis {\n  Old.\n}\n\ngene node.exists {\n  node has address\n}\n";
+        let updated = roundtrip(source, "New first line.\n  New second line.");
+        assert!(updated.contains("exegesis {\n  New first line.\n  New second line.\n}"));
+        assert!(updated.ends_with("gene node.exists {\n  node has address\n}\n"));
+    }
+
+    #[test]
+    fn test_rewrite_inline_exegesis() {
+        let source =
+            "system univrs.node @ 0.1.0 {\n  requires network\n\n  exegesis {\n    Old.\n  }\n}\n";
+        let updated = roundtrip(source, "Updated.");
+        assert!(updated.contains("  exegesis {\n    Updated.\n  }\n}"));
+    }
+
+    #[test]
+    fn test_rewrite_appends_missing_exegesis() {
+        roundtrip("gene container.exists {\n  container has id\n}\n", "Added.");
+    }
+
+    #[test]
+    fn test_replace_exegesis_returns_previous() {
+        let source = "gene container.exists {\n  container has id\n}\n\nexegesis {\n  Old.\n}\n";
+        let mut decl = Parser::new(source).parse().unwrap();
+        assert_eq!(decl.replace_exegesis("New.".to_string()), "Old.");
+        assert_eq!(decl.exegesis(), "New.");
+    }
+}
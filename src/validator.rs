@@ -42,13 +42,19 @@
 //! "#;
 //!
 //! let decl = parse_file(source).unwrap();
-//! let options = ValidationOptions { typecheck: true };
+//! let options = ValidationOptions {
+//!     typecheck: true,
+//!     ..Default::default()
+//! };
 //! let result = validate_with_options(&decl, &options);
 //! ```
 
 use crate::ast::*;
+use crate::diagnostic::{Diagnostic, DiagnosticCollector};
 use crate::error::{ValidationError, ValidationWarning};
+use crate::repository::{statement_members, Repository};
 use crate::typechecker::{Type, TypeChecker, TypeError};
+use std::collections::HashMap;
 
 /// The result of validating a declaration.
 #[derive(Debug, Clone)]
@@ -104,16 +110,30 @@ impl ValidationResult {
             message: error.message.clone(),
             expected: error.expected.as_ref().map(|t| t.to_string()),
             actual: error.actual.as_ref().map(|t| t.to_string()),
-            span,
+            span: error.span.unwrap_or(span),
         });
     }
 }
 
+/// Default minimum length, in characters, of a descriptive exegesis.
+pub const DEFAULT_MIN_EXEGESIS_LENGTH: usize = 20;
+
 /// Options for validation.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct ValidationOptions {
     /// Enable type checking for DOL 2.0 expressions.
     pub typecheck: bool,
+    /// Exegesis shorter than this (after trimming) is flagged as short.
+    pub min_exegesis_length: usize,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            typecheck: false,
+            min_exegesis_length: DEFAULT_MIN_EXEGESIS_LENGTH,
+        }
+    }
 }
 
 /// Validates a declaration with options.
@@ -130,7 +150,7 @@ pub fn validate_with_options(decl: &Declaration, options: &ValidationOptions) ->
     let mut result = ValidationResult::new(decl.name());
 
     // Validate exegesis
-    validate_exegesis(decl, &mut result);
+    validate_exegesis(decl, options.min_exegesis_length, &mut result);
 
     // Validate naming conventions
     validate_naming(decl, &mut result);
@@ -147,6 +167,7 @@ pub fn validate_with_options(decl: &Declaration, options: &ValidationOptions) ->
         Declaration::Evolution(evolution) => validate_evolution(evolution, &mut result),
         Declaration::Function(_) => {} // Top-level functions don't need special validation yet
         Declaration::Const(_) | Declaration::SexVar(_) => {} // Constants and SexVars are validated by type checking
+        Declaration::Extern(_) => {} // Extern signatures are checked by the SEX linter
     }
 
     // DOL 2.0 Type checking (if enabled)
@@ -174,13 +195,21 @@ pub fn validate(decl: &Declaration) -> ValidationResult {
 }
 
 /// Validates the exegesis block.
-fn validate_exegesis(decl: &Declaration, result: &mut ValidationResult) {
-    let exegesis = decl.exegesis();
-    let span = decl.span();
+///
+/// A declaration without an exegesis block and one whose block has no
+/// content get separate warnings; content shorter than `min_length` is
+/// flagged as short.
+fn validate_exegesis(decl: &Declaration, min_length: usize, result: &mut ValidationResult) {
+    let Some(exegesis) = decl.exegesis_block() else {
+        result.add_warning(ValidationWarning::MissingExegesis { span: decl.span() });
+        return;
+    };
+    let span = exegesis.span();
+    let trimmed_len = exegesis.trim().chars().count();
 
-    // Warn about very short exegesis
-    let trimmed_len = exegesis.trim().len();
-    if trimmed_len < 20 {
+    if trimmed_len == 0 {
+        result.add_warning(ValidationWarning::EmptyExegesis { span });
+    } else if trimmed_len < min_length {
         result.add_warning(ValidationWarning::ShortExegesis {
             length: trimmed_len,
             span,
@@ -206,10 +235,10 @@ fn validate_naming(decl: &Declaration, result: &mut ValidationResult) {
         return;
     }
 
-    // If it contains a dot, it's qualified notation - validate each part
-    if name.contains('.') {
-        // Validate qualified identifier format
-        if !is_valid_qualified_identifier(name) {
+    // A dotted name is qualified notation - validate each segment
+    let qualified = decl.qualified_name();
+    if qualified.segments().len() >= 2 {
+        if !is_valid_qualified_identifier(&qualified) {
             result.add_error(ValidationError::InvalidIdentifier {
                 name: name.to_string(),
                 reason: "must be a valid qualified identifier (domain.property)".to_string(),
@@ -249,6 +278,9 @@ fn validate_naming(decl: &Declaration, result: &mut ValidationResult) {
 
         // SexVars should be SCREAMING_SNAKE_CASE like constants
         Declaration::SexVar(_) => {}
+
+        // Extern functions follow the foreign library's naming
+        Declaration::Extern(_) => {}
     }
 }
 
@@ -285,7 +317,8 @@ fn validate_statements(decl: &Declaration, result: &mut ValidationResult) {
         Declaration::Evolution(_)
         | Declaration::Function(_)
         | Declaration::Const(_)
-        | Declaration::SexVar(_) => return, // Different structure
+        | Declaration::SexVar(_)
+        | Declaration::Extern(_) => return, // Different structure
     };
 
     // Check for duplicate statements
@@ -304,6 +337,405 @@ fn validate_statements(decl: &Declaration, result: &mut ValidationResult) {
     }
 }
 
+/// Validates every declaration in a file.
+///
+/// In addition to the per-declaration checks of [`validate`], this reports
+/// declarations that reuse an earlier qualified name, warns when a `uses`
+/// statement references a declaration marked `#[deprecated]`, reports genes
+/// that contain themselves by value, and checks each evolution against its
+/// lineage in a [`Repository`].
+pub fn validate_file(file: &DolFile) -> Vec<ValidationResult> {
+    let context = FileContext::new(file);
+    file.declarations
+        .iter()
+        .map(|decl| context.validate(decl))
+        .collect()
+}
+
+/// Re-validates only the declarations affected by a change.
+///
+/// `changed` names the declarations that were edited. They are validated
+/// again together with every declaration that depends on them through
+/// `uses`, `requires`, `derives from`, `extends` or constraint references (see
+/// [`Repository::affected_by`]); everything else is skipped. Returns the
+/// errors [`validate_file`] reports for the affected declarations, in source
+/// order. A repository has no module header, so `#[since]` versions are not
+/// checked against a module version.
+pub fn validate_incremental(
+    repo: &Repository<'_>,
+    changed: &[QualifiedName],
+) -> Vec<ValidationError> {
+    let context = FileContext::from_declarations(repo.iter().as_slice(), None);
+    let changed: Vec<String> = changed.iter().map(ToString::to_string).collect();
+    let changed: Vec<&str> = changed.iter().map(String::as_str).collect();
+    let affected = repo.affected_by(&changed);
+    repo.iter()
+        .filter(|decl| affected.contains(decl.name()))
+        .flat_map(|decl| context.validate(decl).errors)
+        .collect()
+}
+
+/// Roots of the reachability analysis run by [`validate_orphans`].
+#[derive(Debug, Clone)]
+pub struct OrphanOptions {
+    /// Names of additional declarations to treat as roots.
+    pub roots: Vec<String>,
+    /// Treat every `pub` declaration as a root.
+    pub public_roots: bool,
+}
+
+impl Default for OrphanOptions {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            public_roots: true,
+        }
+    }
+}
+
+/// Reports genes, traits and constraints that nothing references.
+///
+/// Every system is a root, as are the declarations named in
+/// `options.roots` and, unless disabled, every `pub` declaration. A
+/// declaration is referenced if it can be reached from a root through
+/// `uses`, `requires`, `derives from`, `extends` or constraint references
+/// (see [`Repository::reachable_from`]). Returns one result per unreferenced
+/// declaration, each holding an [`ValidationWarning::OrphanDeclaration`].
+pub fn validate_orphans(file: &DolFile, options: &OrphanOptions) -> Vec<ValidationResult> {
+    let repo = Repository::new(&file.declarations);
+    let roots: Vec<&str> = file
+        .declarations
+        .iter()
+        .filter(|decl| {
+            matches!(decl, Declaration::System(_))
+                || (options.public_roots && decl.visibility() != Visibility::Private)
+        })
+        .map(|decl| decl.name())
+        .chain(options.roots.iter().map(String::as_str))
+        .collect();
+    let reachable = repo.reachable_from(&roots);
+
+    file.declarations
+        .iter()
+        .filter(|decl| {
+            matches!(
+                decl,
+                Declaration::Gene(_) | Declaration::Trait(_) | Declaration::Constraint(_)
+            ) && !reachable.contains(decl.name())
+        })
+        .map(|decl| {
+            let mut result = ValidationResult::new(decl.name());
+            result.add_warning(ValidationWarning::OrphanDeclaration {
+                name: decl.name().to_string(),
+                span: decl.span(),
+            });
+            result
+        })
+        .collect()
+}
+
+/// Reports `uses` statements that name no declaration in the repository.
+///
+/// The repository may span several files, so a reference resolves if any
+/// declaration in it has that name, wherever it appears. Each unresolved
+/// reference is an error; when a known name is within a small edit distance
+/// of it, the message suggests that name.
+///
+/// # Example
+///
+/// ```rust
+/// use metadol::parser::Parser;
+/// use metadol::repository::Repository;
+/// use metadol::validator::validate_repository;
+///
+/// let source = "gene container.exists { container has id }\n\
+///               trait container.lifecycle { uses container.exist }";
+/// let file = Parser::new(source).parse_file().unwrap();
+///
+/// let diagnostics = validate_repository(&Repository::new(&file.declarations));
+/// assert!(diagnostics.has_errors());
+/// assert!(diagnostics.diagnostics()[0]
+///     .message
+///     .ends_with("did you mean 'container.exists'?"));
+/// ```
+pub fn validate_repository(repo: &Repository<'_>) -> DiagnosticCollector {
+    let mut collector = DiagnosticCollector::new();
+    for decl in repo {
+        let statements = match decl {
+            Declaration::Gene(g) => &g.statements,
+            Declaration::Trait(t) => &t.statements,
+            Declaration::Constraint(c) => &c.statements,
+            Declaration::System(s) => &s.statements,
+            _ => continue,
+        };
+        for stmt in statements {
+            let Statement::Uses { reference, span } = stmt else {
+                continue;
+            };
+            if repo.get(reference).is_some() {
+                continue;
+            }
+            let error = ValidationError::UnresolvedReference {
+                reference: reference.clone(),
+                span: *span,
+            };
+            let message = match closest_name(reference, repo) {
+                Some(name) => format!("{}; did you mean '{}'?", error, name),
+                None => error.to_string(),
+            };
+            collector.push(Diagnostic::error(message, error.span()).with_code(error.code()));
+        }
+    }
+    collector
+}
+
+/// Returns the declared name closest to `name`, if any is close enough to
+/// be a plausible typo.
+fn closest_name<'a>(name: &str, repo: &Repository<'a>) -> Option<&'a str> {
+    let limit = (name.chars().count() / 3).max(1);
+    repo.iter()
+        .filter(|decl| !matches!(decl, Declaration::Evolution(_)))
+        .map(|decl| (edit_distance(name, decl.name()), decl.name()))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// File-wide information needed to validate a single declaration.
+struct FileContext<'a> {
+    repo: Repository<'a>,
+    module_version: Option<&'a Version>,
+    deprecated: HashMap<&'a str, &'a str>,
+    /// Genes that contain themselves by value, reported at the gene's span.
+    recursive: Vec<TypeError>,
+}
+
+impl<'a> FileContext<'a> {
+    fn new(file: &'a DolFile) -> Self {
+        let module_version = file
+            .module
+            .as_ref()
+            .and_then(|module| module.version.as_ref());
+        Self::from_declarations(&file.declarations, module_version)
+    }
+
+    fn from_declarations(
+        declarations: &'a [Declaration],
+        module_version: Option<&'a Version>,
+    ) -> Self {
+        let mut checker = TypeChecker::new();
+        checker.check_recursive_types(declarations);
+        Self {
+            repo: Repository::new(declarations),
+            module_version,
+            deprecated: declarations
+                .iter()
+                .filter_map(|decl| decl.deprecated().map(|note| (decl.name(), note)))
+                .collect(),
+            recursive: checker.errors().to_vec(),
+        }
+    }
+
+    /// Runs the per-declaration and file-level checks for one declaration.
+    fn validate(&self, decl: &Declaration) -> ValidationResult {
+        let mut result = validate(decl);
+        validate_unique_name(decl, &self.repo, &mut result);
+        validate_deprecated_uses(decl, &self.deprecated, &mut result);
+        match decl {
+            Declaration::Evolution(evolution) => {
+                validate_evolution_targets(evolution, &self.repo, &mut result)
+            }
+            Declaration::Gene(gene) => {
+                validate_constraint_references(gene, &self.repo, &mut result);
+                validate_field_versions(gene, self.module_version, &mut result);
+                for error in &self.recursive {
+                    if error.span == Some(gene.span) {
+                        result.add_type_error(error, gene.span);
+                    }
+                }
+            }
+            Declaration::System(system) => validate_capabilities(system, &self.repo, &mut result),
+            _ => {}
+        }
+        result
+    }
+}
+
+/// Reports a declaration whose qualified name was already declared.
+///
+/// Evolutions are exempt, since they share the name of the declaration they
+/// evolve, and so are extern blocks, which are named after their first
+/// function or not at all. The error points at the repeated declaration and
+/// notes the first, which is the one the repository resolves the name to.
+fn validate_unique_name(decl: &Declaration, repo: &Repository<'_>, result: &mut ValidationResult) {
+    if matches!(decl, Declaration::Evolution(_) | Declaration::Extern(_)) {
+        return;
+    }
+    if let Some(first) = repo.get(decl.name()) {
+        if !std::ptr::eq(first, decl) {
+            result.add_error(ValidationError::DuplicateDeclaration {
+                name: decl.name().to_string(),
+                span: decl.span(),
+                first: first.span(),
+            });
+        }
+    }
+}
+
+/// Checks that every capability a system requires is known.
+///
+/// A capability is known if it names a declaration in the same file or one
+/// of the system's versioned requirements.
+fn validate_capabilities(system: &System, repo: &Repository<'_>, result: &mut ValidationResult) {
+    for requirement in &system.capabilities {
+        for name in requirement.expr.capabilities() {
+            let known =
+                repo.get(name).is_some() || system.requirements.iter().any(|req| req.name == name);
+            if !known {
+                result.add_error(ValidationError::UnresolvedReference {
+                    reference: name.to_string(),
+                    span: requirement.span,
+                });
+            }
+        }
+    }
+}
+
+/// Checks that every bodiless inline constraint in a gene names a top-level
+/// constraint declaration.
+fn validate_constraint_references(
+    gene: &Gene,
+    repo: &Repository<'_>,
+    result: &mut ValidationResult,
+) {
+    for stmt in &gene.statements {
+        if let Statement::Constraint(constraint) = stmt {
+            if constraint.is_reference() && repo.constraint(&constraint.name).is_none() {
+                result.add_error(ValidationError::UnresolvedReference {
+                    reference: constraint.name.clone(),
+                    span: constraint.span,
+                });
+            }
+        }
+    }
+}
+
+/// Checks `#[since]` versions on a gene's fields.
+///
+/// Each version must be valid semver and, when the file declares a module
+/// version, must not be later than it: a field cannot be introduced in a
+/// version that does not exist yet.
+fn validate_field_versions(
+    gene: &Gene,
+    module_version: Option<&Version>,
+    result: &mut ValidationResult,
+) {
+    for stmt in &gene.statements {
+        let Statement::HasField(field) = stmt else {
+            continue;
+        };
+        let Some(since) = &field.since else {
+            continue;
+        };
+        let Some(since_version) = parse_valid_version(since) else {
+            result.add_error(ValidationError::InvalidVersion {
+                version: since.clone(),
+                reason: format!("#[since] on field '{}' must be X.Y.Z", field.name),
+            });
+            continue;
+        };
+        if let Some(module_version) = module_version {
+            if since_version > *module_version {
+                result.add_error(ValidationError::InvalidVersion {
+                    version: since.clone(),
+                    reason: format!(
+                        "field '{}' cannot be since a version after its module version {}",
+                        field.name, module_version
+                    ),
+                });
+            }
+        }
+    }
+}
+
+/// Warns about `uses` references to deprecated declarations.
+fn validate_deprecated_uses(
+    decl: &Declaration,
+    deprecated: &HashMap<&str, &str>,
+    result: &mut ValidationResult,
+) {
+    let statements = match decl {
+        Declaration::Gene(gene) => &gene.statements,
+        Declaration::Trait(trait_decl) => &trait_decl.statements,
+        Declaration::Constraint(constraint) => &constraint.statements,
+        Declaration::System(system) => &system.statements,
+        _ => return,
+    };
+
+    for stmt in statements {
+        if let Statement::Uses { reference, span } = stmt {
+            if let Some(note) = deprecated.get(reference.as_str()) {
+                result.add_warning(ValidationWarning::DeprecatedReference {
+                    reference: reference.clone(),
+                    note: note.to_string(),
+                    span: *span,
+                });
+            }
+        }
+    }
+}
+
+/// Checks that an evolution only removes or deprecates members that exist
+/// at its parent version.
+///
+/// Evolutions of declarations outside the repository are not checked.
+fn validate_evolution_targets(
+    evolution: &Evolution,
+    repo: &Repository<'_>,
+    result: &mut ValidationResult,
+) {
+    let Some(members) = repo.members_at(&evolution.name, &evolution.parent_version) else {
+        return;
+    };
+
+    let mut missing = |action: &str, item: &str| {
+        result.add_error(ValidationError::MissingEvolutionTarget {
+            name: evolution.name.clone(),
+            action: action.to_string(),
+            item: item.to_string(),
+            version: evolution.parent_version.clone(),
+        });
+    };
+
+    for removed in &evolution.removals {
+        if !members.contains(removed) {
+            missing("remove", removed);
+        }
+    }
+    for deprecated in &evolution.deprecations {
+        let names = statement_members(deprecated);
+        if !names.iter().any(|name| members.contains(name)) {
+            missing("deprecate", &names[0]);
+        }
+    }
+}
+
 /// Validates gene-specific rules.
 fn validate_gene(gene: &Gene, result: &mut ValidationResult) {
     // Genes should only contain has, is, derives from, requires statements
@@ -323,6 +755,43 @@ fn validate_gene(gene: &Gene, result: &mut ValidationResult) {
             _ => {}
         }
     }
+
+    validate_gene_properties(gene, result);
+}
+
+/// Reports properties declared more than once in a gene.
+///
+/// `has` properties and typed fields share one namespace, since both become
+/// fields of the generated struct. Names that differ only in case are
+/// warned about.
+fn validate_gene_properties(gene: &Gene, result: &mut ValidationResult) {
+    let mut seen: HashMap<&str, Span> = HashMap::new();
+    let mut folded: HashMap<String, &str> = HashMap::new();
+    for stmt in &gene.statements {
+        let (name, span) = match stmt {
+            Statement::Has { property, span, .. } => (property.as_str(), *span),
+            Statement::HasField(field) => (field.name.as_str(), field.span),
+            _ => continue,
+        };
+
+        if let Some(first) = seen.get(name) {
+            result.add_error(ValidationError::DuplicateProperty {
+                name: name.to_string(),
+                span,
+                first: *first,
+            });
+            continue;
+        }
+        if let Some(similar) = folded.get(&name.to_lowercase()) {
+            result.add_warning(ValidationWarning::SimilarProperty {
+                name: name.to_string(),
+                similar: similar.to_string(),
+                span,
+            });
+        }
+        seen.insert(name, span);
+        folded.entry(name.to_lowercase()).or_insert(name);
+    }
 }
 
 /// Validates trait-specific rules.
@@ -340,10 +809,207 @@ fn validate_trait(trait_decl: &Trait, result: &mut ValidationResult) {
 
     if !has_uses && !has_behavior {
         result.add_warning(ValidationWarning::NamingConvention {
-            name: trait_decl.name.clone(),
+            name: trait_decl.name.to_string(),
             suggestion: "traits typically include 'uses' or behavior statements".to_string(),
         });
     }
+
+    for law in &trait_decl.laws {
+        validate_law_bindings(law, result);
+    }
+}
+
+/// Warns about names in a law body that none of its parameters bind.
+///
+/// Names bound inside the body (quantifiers, lambdas, `let`, `for` and match
+/// patterns) are in scope where they are bound. `self`, called functions,
+/// member names and capitalized names such as types and constructors are not
+/// parameters and are never reported.
+fn validate_law_bindings(law: &LawDecl, result: &mut ValidationResult) {
+    let mut bound: Vec<&str> = law.params.iter().map(|p| p.name.as_str()).collect();
+    let mut unbound = Vec::new();
+    collect_unbound(&law.body, &mut bound, &mut unbound);
+    for name in unbound {
+        result.add_warning(ValidationWarning::UnboundLawParameter {
+            law: law.name.clone(),
+            name,
+            span: law.span,
+        });
+    }
+}
+
+/// Appends each free name in `expr` not in `bound` to `unbound`, once.
+fn collect_unbound<'a>(expr: &'a Expr, bound: &mut Vec<&'a str>, unbound: &mut Vec<String>) {
+    match expr {
+        Expr::Identifier(name) => {
+            let root = name.split('.').next().unwrap_or(name);
+            let is_param = root != "self"
+                && !root.starts_with(|c: char| c.is_uppercase())
+                && !bound.contains(&root);
+            if is_param && !unbound.iter().any(|n| n == root) {
+                unbound.push(root.to_string());
+            }
+        }
+        Expr::Literal(_) | Expr::Reflect(_) => {}
+        Expr::List(items) | Expr::Tuple(items) => {
+            for item in items {
+                collect_unbound(item, bound, unbound);
+            }
+        }
+        Expr::Map(entries) => {
+            for (key, value) in entries {
+                collect_unbound(key, bound, unbound);
+                collect_unbound(value, bound, unbound);
+            }
+        }
+        Expr::Binary { left, right, .. } | Expr::Implies { left, right, .. } => {
+            collect_unbound(left, bound, unbound);
+            collect_unbound(right, bound, unbound);
+        }
+        Expr::Call { callee, args } | Expr::IdiomBracket { func: callee, args } => {
+            if !matches!(callee.unspanned(), Expr::Identifier(_)) {
+                collect_unbound(callee, bound, unbound);
+            }
+            for arg in args {
+                collect_unbound(arg, bound, unbound);
+            }
+        }
+        Expr::StructLiteral { fields, .. } => {
+            for (_, value) in fields {
+                collect_unbound(value, bound, unbound);
+            }
+        }
+        Expr::Unary { operand: inner, .. }
+        | Expr::Member { object: inner, .. }
+        | Expr::Quote(inner)
+        | Expr::Unquote(inner)
+        | Expr::QuasiQuote(inner)
+        | Expr::Eval(inner)
+        | Expr::Cast { expr: inner, .. }
+        | Expr::Try(inner)
+        | Expr::Spanned { expr: inner, .. } => collect_unbound(inner, bound, unbound),
+        Expr::Lambda { params, body, .. } => {
+            let depth = bound.len();
+            bound.extend(params.iter().map(|(name, _)| name.as_str()));
+            collect_unbound(body, bound, unbound);
+            bound.truncate(depth);
+        }
+        Expr::Forall(ForallExpr {
+            var, iter, body, ..
+        }) => {
+            if let Some(iter) = iter {
+                collect_unbound(iter, bound, unbound);
+            }
+            bound.push(var);
+            collect_unbound(body, bound, unbound);
+            bound.pop();
+        }
+        Expr::Exists(ExistsExpr { var, body, .. }) => {
+            bound.push(var);
+            collect_unbound(body, bound, unbound);
+            bound.pop();
+        }
+        Expr::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            collect_unbound(condition, bound, unbound);
+            collect_unbound(then_branch, bound, unbound);
+            if let Some(else_branch) = else_branch {
+                collect_unbound(else_branch, bound, unbound);
+            }
+        }
+        Expr::Match { scrutinee, arms } => {
+            collect_unbound(scrutinee, bound, unbound);
+            for arm in arms {
+                let depth = bound.len();
+                bind_pattern(&arm.pattern, bound);
+                if let Some(guard) = &arm.guard {
+                    collect_unbound(guard, bound, unbound);
+                }
+                collect_unbound(&arm.body, bound, unbound);
+                bound.truncate(depth);
+            }
+        }
+        Expr::Block {
+            statements,
+            final_expr,
+        }
+        | Expr::SexBlock {
+            statements,
+            final_expr,
+        } => {
+            let depth = bound.len();
+            collect_unbound_stmts(statements, bound, unbound);
+            if let Some(final_expr) = final_expr {
+                collect_unbound(final_expr, bound, unbound);
+            }
+            bound.truncate(depth);
+        }
+    }
+}
+
+/// Runs [`collect_unbound`] over statements, binding each `let` for the
+/// statements after it. The caller restores `bound`.
+fn collect_unbound_stmts<'a>(
+    statements: &'a [Stmt],
+    bound: &mut Vec<&'a str>,
+    unbound: &mut Vec<String>,
+) {
+    for stmt in statements {
+        match stmt {
+            Stmt::Let { name, value, .. } => {
+                collect_unbound(value, bound, unbound);
+                bound.push(name);
+            }
+            Stmt::LetPattern { pattern, value } => {
+                collect_unbound(value, bound, unbound);
+                bind_pattern(pattern, bound);
+            }
+            Stmt::Assign { target, value } => {
+                collect_unbound(target, bound, unbound);
+                collect_unbound(value, bound, unbound);
+            }
+            Stmt::For {
+                binding,
+                iterable,
+                body,
+            } => {
+                collect_unbound(iterable, bound, unbound);
+                let depth = bound.len();
+                bound.push(binding);
+                collect_unbound_stmts(body, bound, unbound);
+                bound.truncate(depth);
+            }
+            Stmt::While { condition, body } => {
+                collect_unbound(condition, bound, unbound);
+                let depth = bound.len();
+                collect_unbound_stmts(body, bound, unbound);
+                bound.truncate(depth);
+            }
+            Stmt::Loop { body } => {
+                let depth = bound.len();
+                collect_unbound_stmts(body, bound, unbound);
+                bound.truncate(depth);
+            }
+            Stmt::Return(Some(value)) | Stmt::Expr(value) => collect_unbound(value, bound, unbound),
+            Stmt::Return(None) | Stmt::Break | Stmt::Continue => {}
+        }
+    }
+}
+
+/// Adds the names a pattern binds to `bound`.
+fn bind_pattern<'a>(pattern: &'a Pattern, bound: &mut Vec<&'a str>) {
+    match pattern {
+        Pattern::Identifier(name) => bound.push(name),
+        Pattern::Constructor { fields, .. } | Pattern::Tuple(fields) | Pattern::Or(fields) => {
+            for field in fields {
+                bind_pattern(field, bound);
+            }
+        }
+        Pattern::Wildcard | Pattern::Literal(_) => {}
+    }
 }
 
 /// Validates constraint-specific rules.
@@ -356,7 +1022,7 @@ fn validate_constraint(constraint: &Constraint, result: &mut ValidationResult) {
 
     if !has_constraint_stmts {
         result.add_warning(ValidationWarning::NamingConvention {
-            name: constraint.name.clone(),
+            name: constraint.name.to_string(),
             suggestion: "constraints typically include 'matches' or 'never' statements".to_string(),
         });
     }
@@ -437,15 +1103,18 @@ fn validate_types(decl: &Declaration, result: &mut ValidationResult) {
     let mut checker = TypeChecker::new();
     let span = decl.span();
 
-    // Currently, DOL 2.0 expressions can appear in evolution additions
-    // and potentially in future extended statement types
-    if let Declaration::Evolution(evolution) = decl {
-        for stmt in &evolution.additions {
-            validate_statement_types(stmt, &mut checker, result, span);
-        }
-        for stmt in &evolution.deprecations {
-            validate_statement_types(stmt, &mut checker, result, span);
-        }
+    let statements: Vec<&Statement> = match decl {
+        Declaration::Gene(gene) => gene.statements.iter().collect(),
+        Declaration::Trait(trait_decl) => trait_decl.statements.iter().collect(),
+        Declaration::Evolution(evolution) => evolution
+            .additions
+            .iter()
+            .chain(&evolution.deprecations)
+            .collect(),
+        _ => Vec::new(),
+    };
+    for stmt in statements {
+        validate_statement_types(stmt, &mut checker);
     }
 
     // Convert any accumulated type errors to validation errors
@@ -454,16 +1123,12 @@ fn validate_types(decl: &Declaration, result: &mut ValidationResult) {
     }
 }
 
-/// Type-checks a statement for DOL 2.0 expressions.
-fn validate_statement_types(
-    _stmt: &Statement,
-    _checker: &mut TypeChecker,
-    _result: &mut ValidationResult,
-    _span: Span,
-) {
-    // Current Statement enum doesn't embed DOL 2.0 expressions directly.
-    // This is a placeholder for when statements can contain typed expressions.
-    // For now, type checking happens when parsing DOL 2.0 expression blocks.
+/// Type-checks the parts of a statement that carry types, such as a
+/// field's default value.
+fn validate_statement_types(stmt: &Statement, checker: &mut TypeChecker) {
+    if let Statement::HasField(field) = stmt {
+        checker.check_null_default(field);
+    }
 }
 
 /// Type-checks an expression and reports any errors.
@@ -514,6 +1179,9 @@ fn validate_stmt_types(
                 }
             }
         }
+        Stmt::LetPattern { value, .. } => {
+            validate_expr_types(value, checker, result, span);
+        }
         Stmt::Expr(expr) => {
             validate_expr_types(expr, checker, result, span);
         }
@@ -567,20 +1235,18 @@ fn types_match(ty1: &Type, ty2: &Type) -> bool {
 
 // === Helper Functions ===
 
-/// Checks if an identifier is valid.
-fn is_valid_qualified_identifier(name: &str) -> bool {
+/// Checks if every segment of a qualified name is a valid identifier.
+fn is_valid_qualified_identifier(name: &QualifiedName) -> bool {
     if name.is_empty() {
         return false;
     }
 
-    // Split by dots and validate each part
-    for part in name.split('.') {
-        if part.is_empty() {
-            return false;
-        }
-
+    // Validate each segment
+    for part in name.segments() {
         let mut chars = part.chars();
-        let first = chars.next().unwrap();
+        let Some(first) = chars.next() else {
+            return false;
+        };
 
         // First char must be alphabetic
         if !first.is_alphabetic() {
@@ -600,35 +1266,22 @@ fn is_valid_qualified_identifier(name: &str) -> bool {
 
 /// Checks if a version string is valid semver.
 fn is_valid_version(version: &str) -> bool {
-    let parts: Vec<&str> = version.split('.').collect();
-    if parts.len() != 3 {
-        return false;
-    }
-
-    for part in parts {
-        if part.parse::<u64>().is_err() {
-            return false;
-        }
-    }
+    parse_valid_version(version).is_some()
+}
 
-    true
+/// Parses a version string that is valid semver.
+fn parse_valid_version(version: &str) -> Option<Version> {
+    Version::parse(version)
+        .ok()
+        .filter(|v| v.suffix.as_deref() != Some(""))
 }
 
-/// Compares two version strings.
+/// Compares two version strings by semver precedence.
 fn is_version_greater(version: &str, other: &str) -> bool {
-    let parse_version = |v: &str| -> (u64, u64, u64) {
-        let parts: Vec<&str> = v.split('.').collect();
-        (
-            parts[0].parse().unwrap_or(0),
-            parts[1].parse().unwrap_or(0),
-            parts[2].parse().unwrap_or(0),
-        )
-    };
-
-    let v1 = parse_version(version);
-    let v2 = parse_version(other);
-
-    v1 > v2
+    match (Version::parse(version), Version::parse(other)) {
+        (Ok(v1), Ok(v2)) => v1 > v2,
+        _ => false,
+    }
 }
 
 #[cfg(test)]
@@ -637,14 +1290,16 @@ mod tests {
 
     fn make_gene(name: &str, exegesis: &str) -> Declaration {
         Declaration::Gene(Gene {
-            name: name.to_string(),
+            name: QualifiedName::verbatim(name),
             extends: None,
             statements: vec![Statement::Has {
                 subject: "test".to_string(),
                 property: "property".to_string(),
                 span: Span::default(),
             }],
-            exegesis: exegesis.to_string(),
+            exegesis: exegesis.into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         })
     }
@@ -677,12 +1332,16 @@ mod tests {
 
     #[test]
     fn test_valid_identifier() {
-        assert!(is_valid_qualified_identifier("container.exists"));
-        assert!(is_valid_qualified_identifier("identity.cryptographic"));
-        assert!(is_valid_qualified_identifier("simple"));
-        assert!(!is_valid_qualified_identifier(""));
-        assert!(!is_valid_qualified_identifier(".starts.with.dot"));
-        assert!(!is_valid_qualified_identifier("123invalid"));
+        let is_valid =
+            |name| QualifiedName::parse(name).is_some_and(|q| is_valid_qualified_identifier(&q));
+        assert!(is_valid("container.exists"));
+        assert!(is_valid("identity.cryptographic"));
+        assert!(is_valid("simple"));
+        assert!(!is_valid(""));
+        assert!(!is_valid(".starts.with.dot"));
+        assert!(!is_valid("container..exists"));
+        assert!(!is_valid("123invalid"));
+        assert!(!is_valid_qualified_identifier(&QualifiedName::default()));
     }
 
     #[test]
@@ -693,6 +1352,8 @@ mod tests {
         assert!(!is_valid_version("1.2"));
         assert!(!is_valid_version("1.2.3.4"));
         assert!(!is_valid_version("a.b.c"));
+        assert!(is_valid_version("1.2.0-alpha"));
+        assert!(!is_valid_version("1.2.0-"));
     }
 
     #[test]
@@ -702,6 +1363,8 @@ mod tests {
         assert!(is_version_greater("1.0.0", "0.9.9"));
         assert!(!is_version_greater("0.0.1", "0.0.2"));
         assert!(!is_version_greater("0.0.1", "0.0.1"));
+        assert!(is_version_greater("1.2.0", "1.2.0-alpha"));
+        assert!(is_version_greater("1.2.0-rc.1", "1.2.0-beta"));
     }
 
     // === DOL 2.0 Type-Aware Validation Tests ===
@@ -718,12 +1381,34 @@ mod tests {
     #[test]
     fn test_validate_with_typecheck_enabled() {
         let decl = make_gene("test.gene", "A test gene for type checking validation.");
-        let options = ValidationOptions { typecheck: true };
+        let options = ValidationOptions {
+            typecheck: true,
+            ..Default::default()
+        };
         let result = validate_with_options(&decl, &options);
         // Should still be valid (no DOL 2.0 expressions with errors)
         assert!(result.is_valid());
     }
 
+    #[test]
+    fn test_validate_with_typecheck_reports_null_default() {
+        let source = "gene session {\n  session has token: String? = null\n  \
+                      session has user: String = null\n}\n\n\
+                      exegesis {\n  A session with an optional token.\n}";
+        let decl = crate::parse_file(source).unwrap();
+        let options = ValidationOptions {
+            typecheck: true,
+            ..Default::default()
+        };
+        let result = validate_with_options(&decl, &options);
+        assert_eq!(result.errors.len(), 1);
+        assert!(
+            result.errors[0].to_string().contains("'user'"),
+            "{}",
+            result.errors[0]
+        );
+    }
+
     #[test]
     fn test_types_match_any() {
         assert!(types_match(&Type::Any, &Type::Int32));
@@ -784,10 +1469,24 @@ mod tests {
 
     #[test]
     fn test_validation_options_typecheck_flag() {
-        let options = ValidationOptions { typecheck: true };
+        let options = ValidationOptions {
+            typecheck: true,
+            ..Default::default()
+        };
         assert!(options.typecheck);
 
-        let options = ValidationOptions { typecheck: false };
+        let options = ValidationOptions {
+            typecheck: false,
+            ..Default::default()
+        };
         assert!(!options.typecheck);
     }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("container.exist", "container.exists"), 1);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
 }
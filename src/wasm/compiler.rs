@@ -675,7 +675,7 @@ impl WasmCompiler {
 
             if has_fields {
                 // Don't re-register if already registered
-                if !self.gene_layouts.contains(&gene.name) {
+                if !self.gene_layouts.contains(gene.name.as_str()) {
                     // Compute the layout using the registry (for nested types)
                     if let Ok(layout) = compute_gene_layout(gene, &self.gene_layouts) {
                         self.gene_layouts.register(layout);
@@ -1292,7 +1292,7 @@ impl WasmCompiler {
 
             for gene in remaining {
                 // Skip if already registered
-                if self.gene_layouts.contains(&gene.name) {
+                if self.gene_layouts.contains(gene.name.as_str()) {
                     continue;
                 }
 
@@ -1386,7 +1386,7 @@ impl WasmCompiler {
                     None
                 } else {
                     Some(GeneContext {
-                        gene_name: gene.name.clone(),
+                        gene_name: gene.name.to_string(),
                         field_names,
                     })
                 };
@@ -1669,6 +1669,9 @@ impl WasmCompiler {
             TypeExpr::Tuple(_) => Err(WasmError::new(
                 "Tuple types not yet supported in WASM compilation",
             )),
+            TypeExpr::Record(_) => Err(WasmError::new(
+                "Record types not yet supported in WASM compilation",
+            )),
             TypeExpr::Enum { .. } => {
                 // Inline enum types are represented as i32 discriminants
                 Ok(ValType::I32)
@@ -1703,7 +1706,7 @@ impl WasmCompiler {
                         self.dol_type_to_wasm(ty)?
                     } else {
                         // For struct literals, use I32 (pointer)
-                        if matches!(value, crate::ast::Expr::StructLiteral { .. }) {
+                        if matches!(value.unspanned(), crate::ast::Expr::StructLiteral { .. }) {
                             ValType::I32
                         } else {
                             // Default to i64 for untyped locals
@@ -1713,7 +1716,7 @@ impl WasmCompiler {
                     locals.declare(name, val_type);
 
                     // Track DOL type for struct literals to enable member access
-                    if let crate::ast::Expr::StructLiteral { type_name, .. } = value {
+                    if let crate::ast::Expr::StructLiteral { type_name, .. } = value.unspanned() {
                         locals.set_dol_type(name, type_name);
                     }
                 }
@@ -1754,7 +1757,7 @@ impl WasmCompiler {
         use crate::ast::Expr;
         use wasm_encoder::ValType;
 
-        match expr {
+        match expr.unspanned() {
             Expr::Match { arms, scrutinee } => {
                 // Declare the match temp local if not already declared
                 if locals.lookup("__match_temp").is_none() {
@@ -1844,7 +1847,7 @@ impl WasmCompiler {
             Stmt::Expr(expr) | Stmt::Return(Some(expr)) => {
                 self.collect_strings_from_expr(expr, pool);
             }
-            Stmt::Let { value, .. } => {
+            Stmt::Let { value, .. } | Stmt::LetPattern { value, .. } => {
                 self.collect_strings_from_expr(value, pool);
             }
             Stmt::Assign { value, .. } => {
@@ -1875,7 +1878,7 @@ impl WasmCompiler {
     fn collect_strings_from_expr(&self, expr: &crate::ast::Expr, pool: &mut StringPool) {
         use crate::ast::{Expr, Literal};
 
-        match expr {
+        match expr.unspanned() {
             Expr::Literal(Literal::String(s)) => {
                 pool.add(s);
             }
@@ -1930,6 +1933,12 @@ impl WasmCompiler {
                     self.collect_strings_from_expr(item, pool);
                 }
             }
+            Expr::Map(entries) => {
+                for (key, value) in entries {
+                    self.collect_strings_from_expr(key, pool);
+                    self.collect_strings_from_expr(value, pool);
+                }
+            }
             Expr::Member { object, .. } => {
                 self.collect_strings_from_expr(object, pool);
             }
@@ -2032,9 +2041,14 @@ impl WasmCompiler {
                 // Store the value in the local
                 function.instruction(&Instruction::LocalSet(local_idx));
             }
+            Stmt::LetPattern { .. } => {
+                return Err(WasmError::new(
+                    "Destructuring bindings not yet supported in WASM compilation",
+                ));
+            }
             Stmt::Assign { target, value } => {
                 // Handle assignment to different target types
-                match target {
+                match target.unspanned() {
                     Expr::Identifier(name) => {
                         // Emit the value expression
                         self.emit_expression(function, value, locals, loop_ctx, string_pool)?;
@@ -2061,7 +2075,7 @@ impl WasmCompiler {
                         // WASM store expects [address, value] on stack
 
                         // Infer gene type from object expression
-                        let gene_type = match object.as_ref() {
+                        let gene_type = match object.unspanned() {
                             Expr::Identifier(var_name) => {
                                 // Look up DOL type from locals table (works for 'self' and other vars)
                                 locals.lookup_dol_type(var_name).map(|s| s.to_string())
@@ -2183,7 +2197,7 @@ impl WasmCompiler {
                     left,
                     op: crate::ast::BinaryOp::Range,
                     right,
-                } = iterable
+                } = iterable.unspanned()
                 {
                     // Look up the loop variable (declared in collect_locals)
                     let loop_var = locals.lookup(binding).ok_or_else(|| {
@@ -2301,6 +2315,9 @@ impl WasmCompiler {
         use wasm_encoder::Instruction;
 
         match expr {
+            Expr::Spanned { expr, .. } => {
+                self.emit_expression(function, expr, locals, loop_ctx, string_pool)?;
+            }
             Expr::Literal(lit) => match lit {
                 Literal::Int(i) => {
                     function.instruction(&Instruction::I64Const(*i));
@@ -2526,7 +2543,7 @@ impl WasmCompiler {
                 self.emit_binary_op(function, *op, operand_type)?;
             }
             Expr::Call { callee, args } => {
-                match callee.as_ref() {
+                match callee.unspanned() {
                     // Direct function call: func(args)
                     Expr::Identifier(func_name) => {
                         // Emit arguments
@@ -2777,8 +2794,18 @@ impl WasmCompiler {
                 ))
             }
             Expr::Member { object, field } => {
+                // Enum variant access (e.g., AccountType.Node) emits its discriminant
+                if let Expr::Identifier(enum_name) = object.unspanned() {
+                    if let Some(variant_index) =
+                        self.enum_registry.get_variant_index(enum_name, field)
+                    {
+                        function.instruction(&Instruction::I32Const(variant_index));
+                        return Ok(());
+                    }
+                }
+
                 // Try to infer the gene type from the object expression
-                let gene_type = match object.as_ref() {
+                let gene_type = match object.unspanned() {
                     Expr::Identifier(var_name) => {
                         // Look up DOL type from locals table
                         locals.lookup_dol_type(var_name).map(|s| s.to_string())
@@ -2876,9 +2903,9 @@ impl WasmCompiler {
                     }
                 }
             }
-            Expr::List(_) | Expr::Tuple(_) => {
+            Expr::List(_) | Expr::Tuple(_) | Expr::Map(_) => {
                 return Err(WasmError::new(
-                    "List/tuple literals not yet supported in WASM compilation",
+                    "List/tuple/map literals not yet supported in WASM compilation",
                 ))
             }
             Expr::Forall { .. } | Expr::Exists { .. } => {
@@ -3164,7 +3191,7 @@ impl WasmCompiler {
         use crate::ast::Expr;
         use wasm_encoder::ValType;
 
-        match expr {
+        match expr.unspanned() {
             Expr::Literal(lit) => match lit {
                 crate::ast::Literal::Int(_) => ValType::I64,
                 crate::ast::Literal::Float(_) => ValType::F64,
@@ -3195,7 +3222,7 @@ impl WasmCompiler {
                 }
             }
             Expr::Call { callee, .. } => {
-                if let Expr::Identifier(func_name) = callee.as_ref() {
+                if let Expr::Identifier(func_name) = callee.unspanned() {
                     locals
                         .lookup_function_return_type(func_name)
                         .unwrap_or(ValType::I64)
@@ -3398,7 +3425,6 @@ impl WasmCompiler {
                 | BinaryOp::Compose
                 | BinaryOp::Apply
                 | BinaryOp::Bind
-                | BinaryOp::Member
                 | BinaryOp::Map
                 | BinaryOp::Ap
                 | BinaryOp::Implies
@@ -3430,7 +3456,7 @@ impl WasmCompiler {
     fn expression_produces_value(&self, expr: &crate::ast::Expr) -> bool {
         use crate::ast::Expr;
 
-        match expr {
+        match expr.unspanned() {
             // If without else produces no value
             Expr::If {
                 else_branch: None, ..
@@ -3516,7 +3542,7 @@ impl WasmCompiler {
         use crate::ast::{Declaration, Expr, Literal, Statement, Stmt};
 
         fn check_expr(expr: &Expr) -> bool {
-            match expr {
+            match expr.unspanned() {
                 Expr::Literal(Literal::String(_)) => true,
                 Expr::Binary { left, right, .. } => check_expr(left) || check_expr(right),
                 Expr::Unary { operand, .. } => check_expr(operand),
@@ -3550,6 +3576,7 @@ impl WasmCompiler {
                 }
                 Expr::Lambda { body, .. } => check_expr(body),
                 Expr::List(exprs) | Expr::Tuple(exprs) => exprs.iter().any(check_expr),
+                Expr::Map(entries) => entries.iter().any(|(k, v)| check_expr(k) || check_expr(v)),
                 Expr::IdiomBracket { func, args } => {
                     check_expr(func) || args.iter().any(check_expr)
                 }
@@ -3565,7 +3592,7 @@ impl WasmCompiler {
         fn check_stmt(stmt: &Stmt) -> bool {
             match stmt {
                 Stmt::Expr(e) | Stmt::Return(Some(e)) => check_expr(e),
-                Stmt::Let { value, .. } => check_expr(value),
+                Stmt::Let { value, .. } | Stmt::LetPattern { value, .. } => check_expr(value),
                 Stmt::Assign { target, value } => check_expr(target) || check_expr(value),
                 Stmt::For { iterable, body, .. } => {
                     check_expr(iterable) || body.iter().any(check_stmt)
@@ -3618,8 +3645,8 @@ impl Default for WasmCompiler {
 mod tests {
     use super::*;
     use crate::ast::{
-        BinaryOp, Expr, FunctionDecl, FunctionParam, Literal, Purity, Span, Stmt, TypeExpr,
-        Visibility,
+        BinaryOp, Expr, FunctionDecl, FunctionParam, Literal, Purity, QualifiedName, Span, Stmt,
+        TypeExpr, Visibility,
     };
 
     #[test]
@@ -3681,7 +3708,7 @@ mod tests {
                 op: BinaryOp::Add,
                 right: Box::new(Expr::Identifier("b".to_string())),
             }))],
-            exegesis: "Adds two numbers".to_string(),
+            exegesis: "Adds two numbers".into(),
             span: Span::default(),
         };
 
@@ -3714,7 +3741,7 @@ mod tests {
             params: vec![],
             return_type: Some(TypeExpr::Named("i64".to_string())),
             body: vec![Stmt::Return(Some(Expr::Literal(Literal::Int(42))))],
-            exegesis: "Returns the answer to everything".to_string(),
+            exegesis: "Returns the answer to everything".into(),
             span: Span::default(),
         };
 
@@ -3734,10 +3761,12 @@ mod tests {
 
         // Try to compile a Gene (not supported)
         let gene = Gene {
-            name: "test.gene".to_string(),
+            name: QualifiedName::new("test.gene"),
             extends: None,
             statements: vec![],
-            exegesis: "Test gene".to_string(),
+            exegesis: "Test gene".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
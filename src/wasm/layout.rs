@@ -757,6 +757,7 @@ fn type_to_wasm_info(
                 Err(WasmError::new("Tuple types not yet supported".to_string()))
             }
         }
+        TypeExpr::Record(_) => Err(WasmError::new("Record types not yet supported".to_string())),
         TypeExpr::Never => {
             // Never type has no runtime representation
             Err(WasmError::new(
@@ -887,7 +888,7 @@ pub fn compute_gene_layout(
     };
 
     Ok(GeneLayout {
-        name: gene.name.clone(),
+        name: gene.name.to_string(),
         fields,
         total_size,
         alignment: max_alignment,
@@ -928,7 +929,7 @@ impl GeneLayout {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ast::{Gene, HasField, Span, Statement, TypeExpr};
+    use crate::ast::{Gene, HasField, QualifiedName, Span, Statement, TypeExpr, Visibility};
 
     fn make_field(name: &str, type_name: &str) -> Statement {
         Statement::HasField(Box::new(HasField {
@@ -936,16 +937,19 @@ mod tests {
             type_: TypeExpr::Named(type_name.to_string()),
             default: None,
             constraint: None,
+            since: None,
             span: Span::default(),
         }))
     }
 
     fn make_gene(name: &str, statements: Vec<Statement>) -> Gene {
         Gene {
-            name: name.to_string(),
+            name: QualifiedName::new(name),
             extends: None,
             statements,
-            exegesis: "Test gene".to_string(),
+            exegesis: "Test gene".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         }
     }
@@ -1111,7 +1115,7 @@ mod tests {
         //   has next: &Node  (reference)
         // }
         let gene = Gene {
-            name: "Node".to_string(),
+            name: QualifiedName::new("Node"),
             extends: None,
             statements: vec![
                 make_field("value", "Int64"),
@@ -1120,10 +1124,13 @@ mod tests {
                     type_: TypeExpr::Named("&Node".to_string()),
                     default: None,
                     constraint: None,
+                    since: None,
                     span: Span::default(),
                 })),
             ],
-            exegesis: "Test".to_string(),
+            exegesis: "Test".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -1229,10 +1236,12 @@ mod tests {
 
         // gene Dog extends Animal { has breed_id: Int64 }
         let dog_gene = Gene {
-            name: "Dog".to_string(),
+            name: QualifiedName::new("Dog"),
             extends: Some("Animal".to_string()),
             statements: vec![make_field("breed_id", "Int64")],
-            exegesis: "Test gene".to_string(),
+            exegesis: "Test gene".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -1257,10 +1266,12 @@ mod tests {
         // gene Dog extends Animal { has breed_id: Int64 }
         // But Animal is not in the registry
         let dog_gene = Gene {
-            name: "Dog".to_string(),
+            name: QualifiedName::new("Dog"),
             extends: Some("Animal".to_string()),
             statements: vec![make_field("breed_id", "Int64")],
-            exegesis: "Test gene".to_string(),
+            exegesis: "Test gene".into(),
+            visibility: Visibility::Private,
+            attributes: vec![],
             span: Span::default(),
         };
 
@@ -220,6 +220,82 @@ fn declaration_is_system() {
     ));
 }
 
+#[test]
+fn statement_phrase_parts_split_words() {
+    let file = Parser::new("gene net.node { container requires stable network connectivity }")
+        .parse_file()
+        .unwrap();
+    let Some(Declaration::Gene(gene)) = file.declarations.first() else {
+        panic!("expected gene");
+    };
+
+    let parts = gene.statements[0].phrase_parts();
+    assert_eq!(parts, vec!["stable", "network", "connectivity"]);
+
+    let phrase = parts.join(" ");
+    let roles = classify_phrase(&phrase).unwrap();
+    assert_eq!(roles.head, "connectivity");
+    assert_eq!(roles.modifiers, vec!["stable", "network"]);
+}
+
+#[test]
+fn statement_phrase_parts_single_word() {
+    let file = Parser::new("gene container.exists { container has id }")
+        .parse_file()
+        .unwrap();
+    let Some(Declaration::Gene(gene)) = file.declarations.first() else {
+        panic!("expected gene");
+    };
+
+    assert_eq!(gene.statements[0].phrase_parts(), vec!["id"]);
+    let roles = classify_phrase("id").unwrap();
+    assert_eq!(roles.head, "id");
+    assert!(roles.modifiers.is_empty());
+}
+
+#[test]
+fn classify_phrase_stops_at_preposition() {
+    let roles = classify_phrase("rate of change").unwrap();
+    assert_eq!(roles.head, "rate");
+    assert_eq!(roles.modifiers, vec!["of", "change"]);
+    assert!(classify_phrase("   ").is_none());
+}
+
+#[test]
+fn declaration_name_and_kind() {
+    let source = r#"
+gene container.exists { container has id }
+trait container.lifecycle { uses container.exists }
+constraint container.integrity { id never changes }
+system univrs.orchestrator @ 0.1.0 { requires container.exists >= 0.0.1 }
+evolves container.exists @ 0.0.2 > 0.0.1 { adds container has name }
+fun double(x: Int64) -> Int64 { return x * 2 }
+const MAX_SIZE: Int64 = 100
+sex var COUNTER: Int64 = 0
+"#;
+    let file = Parser::new(source).parse_file().unwrap();
+    let summary: Vec<(&str, DeclarationKind)> = file
+        .declarations
+        .iter()
+        .map(|d| (d.name(), d.kind()))
+        .collect();
+
+    assert_eq!(
+        summary,
+        vec![
+            ("container.exists", DeclarationKind::Gene),
+            ("container.lifecycle", DeclarationKind::Trait),
+            ("container.integrity", DeclarationKind::Constraint),
+            ("univrs.orchestrator", DeclarationKind::System),
+            ("container.exists", DeclarationKind::Evolution),
+            ("double", DeclarationKind::Function),
+            ("MAX_SIZE", DeclarationKind::Const),
+            ("COUNTER", DeclarationKind::SexVar),
+        ]
+    );
+    assert_eq!(DeclarationKind::Evolution.to_string(), "evolves");
+}
+
 // ============================================================================
 // STATEMENT VARIANTS
 // ============================================================================
@@ -273,20 +349,47 @@ fn statement_uses() {
 }
 
 #[test]
-fn statement_requires() {
-    // DOL 2.0: 'constraint' inside a gene generates Requires statement
-    // Direct 'requires' keyword isn't valid as a statement - use inline constraint blocks
-    let file = Parser::new("gene Test { constraint Valid { } }")
+fn statement_inline_constraint() {
+    // DOL 2.0: 'constraint' inside a gene keeps its name and body
+    let file = Parser::new("gene Test { constraint Valid { true } }")
         .parse_file()
         .unwrap();
     if let Some(Declaration::Gene(gene)) = file.declarations.first() {
         assert!(matches!(
             gene.statements.first(),
-            Some(Statement::Requires { .. })
+            Some(Statement::Constraint(c)) if c.name == "Valid"
+                && c.body == Some(Expr::Literal(Literal::Bool(true)))
         ));
     }
 }
 
+#[test]
+fn statement_inline_constraint_rejects_malformed_body() {
+    for source in [
+        "gene Test { constraint Valid { } }",
+        "gene Test { constraint Valid { this.x >= } }",
+        "gene Test { constraint Valid { amount never overflows } }",
+    ] {
+        assert!(Parser::new(source).parse_file().is_err(), "{source}");
+    }
+}
+
+#[test]
+fn statement_span_accessor() {
+    let source = "gene Test {\n  has x: Int64\n  entity has identity\n  entity is active\n}";
+    let file = Parser::new(source).parse_file().unwrap();
+    if let Some(Declaration::Gene(gene)) = file.declarations.first() {
+        let spans: Vec<Span> = gene.statements.iter().map(Statement::span).collect();
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[0].line, 2);
+        assert_eq!(spans[1].line, 3);
+        assert_eq!(spans[2].line, 4);
+        assert!(spans.iter().all(|s| s.start < s.end));
+    } else {
+        panic!("Expected Gene");
+    }
+}
+
 // ============================================================================
 // EXPRESSION AST
 // ============================================================================
@@ -5,6 +5,7 @@
 
 use metadol::ast::*;
 use metadol::codegen::{RustCodegen, TypeMapper};
+use metadol::parser::Parser;
 
 // ============================================
 // 1. Gene → Struct Generation Tests
@@ -14,13 +15,14 @@ use metadol::codegen::{RustCodegen, TypeMapper};
 fn test_codegen_simple_gene() {
     let gene = Gene {
         extends: None,
-        name: "Point".to_string(),
+        name: QualifiedName::new("Point"),
         statements: vec![
             Statement::HasField(Box::new(HasField {
                 name: "x".to_string(),
                 type_: TypeExpr::Named("Float64".to_string()),
                 default: Some(Expr::Literal(Literal::Float(0.0))),
                 constraint: None,
+                since: None,
                 span: Span::default(),
             })),
             Statement::HasField(Box::new(HasField {
@@ -28,10 +30,13 @@ fn test_codegen_simple_gene() {
                 type_: TypeExpr::Named("Float64".to_string()),
                 default: Some(Expr::Literal(Literal::Float(0.0))),
                 constraint: None,
+                since: None,
                 span: Span::default(),
             })),
         ],
-        exegesis: "A 2D point in Cartesian coordinates".to_string(),
+        exegesis: "A 2D point in Cartesian coordinates".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -55,7 +60,7 @@ fn test_codegen_simple_gene() {
 fn test_codegen_gene_with_constraint() {
     let gene = Gene {
         extends: None,
-        name: "PositiveNumber".to_string(),
+        name: QualifiedName::new("PositiveNumber"),
         statements: vec![Statement::HasField(Box::new(HasField {
             name: "value".to_string(),
             type_: TypeExpr::Named("Int64".to_string()),
@@ -65,9 +70,12 @@ fn test_codegen_gene_with_constraint() {
                 op: BinaryOp::Gt,
                 right: Box::new(Expr::Literal(Literal::Int(0))),
             }),
+            since: None,
             span: Span::default(),
         }))],
-        exegesis: "A number that must be positive".to_string(),
+        exegesis: "A number that must be positive".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -95,13 +103,14 @@ fn test_codegen_gene_with_constraint() {
 fn test_codegen_gene_with_multiple_types() {
     let gene = Gene {
         extends: None,
-        name: "User".to_string(),
+        name: QualifiedName::new("User"),
         statements: vec![
             Statement::HasField(Box::new(HasField {
                 name: "id".to_string(),
                 type_: TypeExpr::Named("UInt64".to_string()),
                 default: None,
                 constraint: None,
+                since: None,
                 span: Span::default(),
             })),
             Statement::HasField(Box::new(HasField {
@@ -109,6 +118,7 @@ fn test_codegen_gene_with_multiple_types() {
                 type_: TypeExpr::Named("String".to_string()),
                 default: None,
                 constraint: None,
+                since: None,
                 span: Span::default(),
             })),
             Statement::HasField(Box::new(HasField {
@@ -119,6 +129,7 @@ fn test_codegen_gene_with_multiple_types() {
                 },
                 default: Some(Expr::Literal(Literal::Null)),
                 constraint: None,
+                since: None,
                 span: Span::default(),
             })),
             Statement::HasField(Box::new(HasField {
@@ -129,10 +140,13 @@ fn test_codegen_gene_with_multiple_types() {
                 },
                 default: None,
                 constraint: None,
+                since: None,
                 span: Span::default(),
             })),
         ],
-        exegesis: "A user entity with various field types".to_string(),
+        exegesis: "A user entity with various field types".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -157,7 +171,7 @@ fn test_codegen_gene_with_multiple_types() {
 fn test_codegen_gene_with_legacy_has_statement() {
     let gene = Gene {
         extends: None,
-        name: "Legacy".to_string(),
+        name: QualifiedName::new("Legacy"),
         statements: vec![
             Statement::Has {
                 subject: "legacy".to_string(),
@@ -170,7 +184,9 @@ fn test_codegen_gene_with_legacy_has_statement() {
                 span: Span::default(),
             },
         ],
-        exegesis: "Legacy gene using old syntax".to_string(),
+        exegesis: "Legacy gene using old syntax".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -194,7 +210,7 @@ fn test_codegen_gene_with_legacy_has_statement() {
 #[test]
 fn test_codegen_simple_trait() {
     let trait_decl = Trait {
-        name: "Lifecycle".to_string(),
+        name: QualifiedName::new("Lifecycle"),
         statements: vec![
             Statement::Is {
                 subject: "entity".to_string(),
@@ -212,7 +228,10 @@ fn test_codegen_simple_trait() {
                 span: Span::default(),
             },
         ],
-        exegesis: "Lifecycle state machine".to_string(),
+        laws: vec![],
+        exegesis: "Lifecycle state machine".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -243,7 +262,7 @@ fn test_codegen_simple_trait() {
 #[test]
 fn test_codegen_trait_with_supertraits() {
     let trait_decl = Trait {
-        name: "Advanced".to_string(),
+        name: QualifiedName::new("Advanced"),
         statements: vec![
             Statement::Uses {
                 reference: "Basic".to_string(),
@@ -259,7 +278,10 @@ fn test_codegen_trait_with_supertraits() {
                 span: Span::default(),
             },
         ],
-        exegesis: "Advanced trait with multiple supertraits".to_string(),
+        laws: vec![],
+        exegesis: "Advanced trait with multiple supertraits".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -278,13 +300,16 @@ fn test_codegen_trait_with_supertraits() {
 #[test]
 fn test_codegen_trait_no_supertraits() {
     let trait_decl = Trait {
-        name: "Simple".to_string(),
+        name: QualifiedName::new("Simple"),
         statements: vec![Statement::Is {
             subject: "entity".to_string(),
             state: "active".to_string(),
             span: Span::default(),
         }],
-        exegesis: "Simple trait".to_string(),
+        laws: vec![],
+        exegesis: "Simple trait".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -304,7 +329,7 @@ fn test_codegen_trait_no_supertraits() {
 #[test]
 fn test_codegen_system_module() {
     let system = System {
-        name: "orchestrator.core".to_string(),
+        name: QualifiedName::new("orchestrator.core"),
         version: "1.0.0".to_string(),
         requirements: vec![
             Requirement {
@@ -321,7 +346,10 @@ fn test_codegen_system_module() {
             },
         ],
         statements: vec![],
-        exegesis: "Core orchestration system".to_string(),
+        states: vec![],
+        exegesis: "Core orchestration system".into(),
+        capabilities: vec![],
+        visibility: Visibility::Private,
         span: Span::default(),
     };
 
@@ -356,11 +384,14 @@ fn test_codegen_system_module() {
 #[test]
 fn test_codegen_system_no_requirements() {
     let system = System {
-        name: "simple".to_string(),
+        name: QualifiedName::new("simple"),
         version: "1.0.0".to_string(),
         requirements: vec![],
         statements: vec![],
-        exegesis: "Simple system".to_string(),
+        states: vec![],
+        exegesis: "Simple system".into(),
+        capabilities: vec![],
+        visibility: Visibility::Private,
         span: Span::default(),
     };
 
@@ -373,6 +404,48 @@ fn test_codegen_system_no_requirements() {
     );
 }
 
+#[test]
+fn test_codegen_system_states() {
+    let system = System {
+        name: QualifiedName::new("scheduler"),
+        version: "1.0.0".to_string(),
+        requirements: vec![],
+        statements: vec![],
+        states: vec![
+            StateDecl {
+                name: "queue".to_string(),
+                type_: TypeExpr::Generic {
+                    name: "List".to_string(),
+                    args: vec![TypeExpr::Named("String".to_string())],
+                },
+                default: None,
+                span: Span::default(),
+            },
+            StateDecl {
+                name: "ticks".to_string(),
+                type_: TypeExpr::Named("Int64".to_string()),
+                default: Some(Expr::Literal(Literal::Int(0))),
+                span: Span::default(),
+            },
+        ],
+        exegesis: "Scheduler system".into(),
+        capabilities: vec![],
+        visibility: Visibility::Private,
+        span: Span::default(),
+    };
+
+    let code = RustCodegen::generate(&Declaration::System(system));
+
+    assert!(
+        code.contains("    pub struct State {\n        pub queue: Vec<String>,\n        pub ticks: i64,\n    }"),
+        "Should emit states as struct fields: {code}"
+    );
+    assert!(
+        code.contains("            ticks: 0,"),
+        "Should apply state defaults: {code}"
+    );
+}
+
 // ============================================
 // 4. Constraint → Validation Generation Tests
 // ============================================
@@ -380,7 +453,7 @@ fn test_codegen_system_no_requirements() {
 #[test]
 fn test_codegen_constraint() {
     let constraint = Constraint {
-        name: "data.integrity".to_string(),
+        name: QualifiedName::new("data.integrity"),
         statements: vec![
             Statement::Matches {
                 subject: "checksum".to_string(),
@@ -393,7 +466,8 @@ fn test_codegen_constraint() {
                 span: Span::default(),
             },
         ],
-        exegesis: "Ensures data integrity through checksums".to_string(),
+        exegesis: "Ensures data integrity through checksums".into(),
+        visibility: Visibility::Private,
         span: Span::default(),
     };
 
@@ -420,9 +494,10 @@ fn test_codegen_constraint() {
 #[test]
 fn test_codegen_constraint_empty() {
     let constraint = Constraint {
-        name: "empty".to_string(),
+        name: QualifiedName::new("empty"),
         statements: vec![],
-        exegesis: "Empty constraint".to_string(),
+        exegesis: "Empty constraint".into(),
+        visibility: Visibility::Private,
         span: Span::default(),
     };
 
@@ -457,7 +532,8 @@ fn test_codegen_evolution() {
         }],
         removals: vec!["old_field".to_string()],
         rationale: Some("GPU support is now standard".to_string()),
-        exegesis: "Version 2.0 adds GPU capabilities".to_string(),
+        migrate: None,
+        exegesis: "Version 2.0 adds GPU capabilities".into(),
         span: Span::default(),
     };
 
@@ -493,7 +569,8 @@ fn test_codegen_evolution_no_rationale() {
         deprecations: vec![],
         removals: vec![],
         rationale: None,
-        exegesis: "Minor update".to_string(),
+        migrate: None,
+        exegesis: "Minor update".into(),
         span: Span::default(),
     };
 
@@ -506,6 +583,33 @@ fn test_codegen_evolution_no_rationale() {
     assert!(!code.contains("// Rationale:"), "Should not have rationale");
 }
 
+#[test]
+fn test_codegen_evolution_migrate() {
+    let source = "evolves container.lifecycle from 0.0.1 to 0.0.2 {\n  \
+                  adds container is paused\n  migrate {\n    let paused = false\n    \
+                  return old\n  }\n}";
+    let decl = Parser::new(source).parse().unwrap();
+
+    let code = RustCodegen::generate(&decl);
+
+    assert!(code.contains("impl ContainerLifecycle {"), "{code}");
+    assert!(
+        code.contains("fn migrate_container_lifecycle_to_0_0_2(old: ContainerLifecycle) -> Self {"),
+        "Should generate migration function: {code}"
+    );
+    assert!(code.contains("let mut paused = false;"), "{code}");
+    assert!(code.contains("return old;"), "{code}");
+    assert!(
+        !RustCodegen::generate(
+            &Parser::new("evolves a from 1.0.0 to 1.1.0 {}")
+                .parse()
+                .unwrap()
+        )
+        .contains("fn migrate_"),
+        "No migrate block, no migration function"
+    );
+}
+
 // ============================================
 // 6. SEX (Side Effect eXecution) Tests
 // ============================================
@@ -528,7 +632,7 @@ fn test_codegen_sex_function() {
             callee: Box::new(Expr::Identifier("println".to_string())),
             args: vec![Expr::Identifier("msg".to_string())],
         })],
-        exegesis: String::new(),
+        exegesis: Default::default(),
         span: Span::default(),
     };
 
@@ -557,7 +661,7 @@ fn test_codegen_sex_function_with_return() {
             op: BinaryOp::Add,
             right: Box::new(Expr::Literal(Literal::Int(1))),
         }))],
-        exegesis: String::new(),
+        exegesis: Default::default(),
         span: Span::default(),
     };
 
@@ -801,26 +905,32 @@ fn test_codegen_type_mapping_nested_generics() {
 fn test_codegen_generate_all() {
     let gene = Gene {
         extends: None,
-        name: "Point".to_string(),
+        name: QualifiedName::new("Point"),
         statements: vec![Statement::HasField(Box::new(HasField {
             name: "x".to_string(),
             type_: TypeExpr::Named("Int32".to_string()),
             default: None,
             constraint: None,
+            since: None,
             span: Span::default(),
         }))],
-        exegesis: "A point".to_string(),
+        exegesis: "A point".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
     let trait_decl = Trait {
-        name: "Drawable".to_string(),
+        name: QualifiedName::new("Drawable"),
         statements: vec![Statement::Is {
             subject: "entity".to_string(),
             state: "visible".to_string(),
             span: Span::default(),
         }],
-        exegesis: "Can be drawn".to_string(),
+        laws: vec![],
+        exegesis: "Can be drawn".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -854,7 +964,7 @@ fn test_codegen_type_info() {
     let gen = RustCodegen::new();
     let gene = Gene {
         extends: None,
-        name: "User".to_string(),
+        name: QualifiedName::new("User"),
         statements: vec![
             Statement::Has {
                 subject: "user".to_string(),
@@ -867,7 +977,9 @@ fn test_codegen_type_info() {
                 span: Span::default(),
             },
         ],
-        exegesis: "A user entity".to_string(),
+        exegesis: "A user entity".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -883,17 +995,21 @@ fn test_codegen_type_registry() {
     let gen = RustCodegen::new();
     let gene1 = Gene {
         extends: None,
-        name: "Point".to_string(),
+        name: QualifiedName::new("Point"),
         statements: vec![],
-        exegesis: "A point".to_string(),
+        exegesis: "A point".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
     let gene2 = Gene {
         extends: None,
-        name: "Line".to_string(),
+        name: QualifiedName::new("Line"),
         statements: vec![],
-        exegesis: "A line".to_string(),
+        exegesis: "A line".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -948,9 +1064,11 @@ fn test_codegen_naming_snake_to_pascal() {
     // Test that gene names are converted to PascalCase
     let gene = Gene {
         extends: None,
-        name: "my.special.gene".to_string(),
+        name: QualifiedName::new("my.special.gene"),
         statements: vec![],
-        exegesis: "Test".to_string(),
+        exegesis: "Test".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -966,15 +1084,18 @@ fn test_codegen_naming_field_snake_case() {
     // Test that field names are converted to snake_case
     let gene = Gene {
         extends: None,
-        name: "Test".to_string(),
+        name: QualifiedName::new("Test"),
         statements: vec![Statement::HasField(Box::new(HasField {
             name: "MyField".to_string(),
             type_: TypeExpr::Named("Int32".to_string()),
             default: None,
             constraint: None,
+            since: None,
             span: Span::default(),
         }))],
-        exegesis: "Test".to_string(),
+        exegesis: "Test".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -993,9 +1114,11 @@ fn test_codegen_naming_field_snake_case() {
 fn test_codegen_empty_gene() {
     let gene = Gene {
         extends: None,
-        name: "Empty".to_string(),
+        name: QualifiedName::new("Empty"),
         statements: vec![],
-        exegesis: "Empty gene".to_string(),
+        exegesis: "Empty gene".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -1007,9 +1130,12 @@ fn test_codegen_empty_gene() {
 #[test]
 fn test_codegen_empty_trait() {
     let trait_decl = Trait {
-        name: "Empty".to_string(),
+        name: QualifiedName::new("Empty"),
         statements: vec![],
-        exegesis: "Empty trait".to_string(),
+        laws: vec![],
+        exegesis: "Empty trait".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -1021,9 +1147,11 @@ fn test_codegen_empty_trait() {
 fn test_codegen_multiline_exegesis() {
     let gene = Gene {
         extends: None,
-        name: "Test".to_string(),
+        name: QualifiedName::new("Test"),
         statements: vec![],
-        exegesis: "Line 1\nLine 2\nLine 3".to_string(),
+        exegesis: "Line 1\nLine 2\nLine 3".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -1032,3 +1160,384 @@ fn test_codegen_multiline_exegesis() {
     assert!(code.contains("/// Line 2"));
     assert!(code.contains("/// Line 3"));
 }
+
+// ============================================
+// 14. Deprecation Tests
+// ============================================
+
+#[test]
+fn test_codegen_deprecated_attribute_round_trip() {
+    let source = r#"
+#[deprecated("use container.identity instead")]
+gene container.exists { container has id }
+"#;
+    let file = Parser::new(source).parse_file().unwrap();
+    let decl = &file.declarations[0];
+    assert_eq!(decl.deprecated(), Some("use container.identity instead"));
+
+    let code = RustCodegen::generate(decl);
+    assert!(code.contains("#[deprecated(note = \"use container.identity instead\")]\n#[derive("));
+}
+
+#[test]
+fn test_codegen_deprecated_trait_without_note() {
+    let source = "#[deprecated]\ntrait container.lifecycle { uses container.exists }";
+    let file = Parser::new(source).parse_file().unwrap();
+
+    let code = RustCodegen::generate(&file.declarations[0]);
+    assert!(code.contains("#[deprecated]\npub trait ContainerLifecycle"));
+}
+
+// ============================================
+// 15. Parallel Generation Tests
+// ============================================
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_codegen_parallel_matches_serial() {
+    use metadol::codegen::{Codegen, CodegenOptions, Visibility};
+
+    let source = r#"
+gene container.exists { container has id }
+trait container.lifecycle { uses container.exists }
+constraint container.integrity { id never changes }
+fun double(x: Int64) -> Int64 { return x * 2 }
+const MAX_SIZE: Int64 = 100
+"#;
+    let file = Parser::new(source).parse_file().unwrap();
+    let opts = CodegenOptions {
+        visibility: Visibility::Crate,
+        ..Default::default()
+    };
+
+    let serial = file
+        .declarations
+        .iter()
+        .map(|d| <RustCodegen as Codegen>::generate_with(d, &opts))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let parallel = RustCodegen::generate_all_parallel(&file.declarations, &opts);
+
+    assert_eq!(parallel, serial);
+}
+
+// ============================================
+// 16. Namespace Module Tests
+// ============================================
+
+#[test]
+fn test_codegen_module_from_namespace_nests_genes() {
+    use metadol::codegen::CodegenOptions;
+
+    let source = r#"
+gene univrs.container.exists { container has id }
+gene univrs.container.image { image has digest }
+gene univrs.node.exists { node has address }
+"#;
+    let file = Parser::new(source).parse_file().unwrap();
+    let opts = CodegenOptions {
+        module_from_namespace: true,
+        ..Default::default()
+    };
+
+    let code = RustCodegen::with_options(opts).gen_file(&file.declarations);
+
+    assert_eq!(code.matches("pub mod univrs {").count(), 1);
+    assert_eq!(code.matches("    pub mod container {").count(), 1);
+    assert!(code.contains("    pub mod node {"));
+
+    let container = code.find("pub mod container {").unwrap();
+    let node = code.find("pub mod node {").unwrap();
+    let exists = code.find("struct UnivrsContainerExists").unwrap();
+    let image = code.find("struct UnivrsContainerImage").unwrap();
+    assert!(container < exists && exists < node);
+    assert!(container < image && image < node);
+}
+
+// ============================================
+// 17. Field Version Tests
+// ============================================
+
+#[test]
+fn test_codegen_field_since_doc_note() {
+    let source = "gene Invoice { has id: String #[since(\"1.2.0\")] has currency: String }";
+    let file = Parser::new(source).parse_file().unwrap();
+
+    let code = RustCodegen::generate(&file.declarations[0]);
+    assert!(code.contains("    /// Since version 1.2.0.\n    pub currency: String,"));
+    assert!(!code.contains("Since version 1.2.0.\n    pub id"));
+}
+
+// ============================================
+// 18. Function Type Tests
+// ============================================
+
+#[test]
+fn test_codegen_function_typed_fields() {
+    let source = "gene Button { has on_click: (Int64, String) -> Bool has on_hover: () -> Void }";
+    let file = Parser::new(source).parse_file().unwrap();
+
+    let code = RustCodegen::generate(&file.declarations[0]);
+    assert!(code.contains("pub on_click: fn(i64, String) -> bool,"));
+    assert!(code.contains("pub on_hover: fn(),"));
+}
+
+// ============================================
+// 19. Default Impl Tests
+// ============================================
+
+#[test]
+fn test_codegen_default_impl_uses_field_defaults() {
+    let source = "gene Deployment { has name: String has replicas: Int64 = 3 }";
+    let file = Parser::new(source).parse_file().unwrap();
+
+    let code = RustCodegen::generate(&file.declarations[0]);
+    assert!(code.contains("impl Default for Deployment {"));
+    assert!(code.contains("            name: Default::default(),\n"));
+    assert!(code.contains("            replicas: 3,\n"));
+}
+
+#[test]
+fn test_codegen_default_impl_skipped_without_defaults() {
+    let plain = Parser::new("gene Deployment { has replicas: Int64 }")
+        .parse_file()
+        .unwrap();
+    assert!(!RustCodegen::generate(&plain.declarations[0]).contains("impl Default"));
+
+    let source = "gene Deployment { has owner: Account has replicas: Int64 = 3 }";
+    let file = Parser::new(source).parse_file().unwrap();
+    assert!(!RustCodegen::generate(&file.declarations[0]).contains("impl Default"));
+}
+
+#[test]
+fn test_codegen_default_impl_skipped_when_gene_derives_default() {
+    let source = "#[derive(Default)]\ngene X { has n: Int64 = 3 }";
+    let file = Parser::new(source).parse_file().unwrap();
+
+    let code = RustCodegen::generate(&file.declarations[0]);
+    assert!(code.contains("#[derive(Debug, Clone, PartialEq, Default)]"));
+    assert!(!code.contains("impl Default for X"));
+}
+
+// ============================================
+// 20. Test Scaffold Tests
+// ============================================
+
+#[test]
+fn test_codegen_test_gene_emits_scaffold() {
+    let source =
+        "#[test]\ngene Account { has balance: Int64 where balance >= 0 has owner: String }";
+    let file = Parser::new(source).parse_file().unwrap();
+    let Declaration::Gene(gene) = &file.declarations[0] else {
+        panic!("expected gene");
+    };
+    assert!(gene.is_test());
+
+    let code = RustCodegen::generate(&file.declarations[0]);
+    assert!(code.contains("#[cfg(test)]\nmod account_tests {"));
+    assert!(code.contains("    fn account_satisfies_constraints() {"));
+    assert!(code.contains("let instance = Account::new(Default::default(), Default::default());"));
+    assert!(code.contains("assert!(instance.validate_all());"));
+    assert!(code.contains("if !self.validate_balance() { return false; }"));
+}
+
+#[test]
+fn test_codegen_plain_gene_has_no_scaffold() {
+    let file = Parser::new("gene Account { has balance: Int64 }")
+        .parse_file()
+        .unwrap();
+    assert!(!RustCodegen::generate(&file.declarations[0]).contains("#[cfg(test)]"));
+}
+
+// ============================================
+// 21. Inline Record Type Tests
+// ============================================
+
+#[test]
+fn test_codegen_inline_record_struct() {
+    let source = "gene container { container has resources: { cpu: Int64, memory: Int64 } }";
+    let file = Parser::new(source).parse_file().unwrap();
+
+    let code = RustCodegen::generate(&file.declarations[0]);
+    assert!(code.contains("pub struct ContainerResources {"));
+    assert!(code.contains("    pub cpu: i64,\n    pub memory: i64,\n"));
+    assert!(code.contains("    pub resources: ContainerResources,"));
+}
+
+// ============================================
+// 22. Nullable Field Tests
+// ============================================
+
+#[test]
+fn test_codegen_nullable_field_null_default() {
+    let source =
+        "gene session { session has token: String? = null\n session has retries: Int64 = 3 }";
+    let file = Parser::new(source).parse_file().unwrap();
+
+    let code = RustCodegen::generate(&file.declarations[0]);
+    assert!(code.contains("pub token: Option<String>,"));
+    assert!(code.contains("token: None,"));
+}
+
+// ============================================
+// 23. Implicit Subject Tests
+// ============================================
+
+#[test]
+fn test_codegen_bare_has_fields_in_order() {
+    let source = "gene user.account {\n    has name: String\n    has age: Int64\n    has active\n}";
+    let file = Parser::new(source).parse_file().unwrap();
+
+    let code = RustCodegen::generate(&file.declarations[0]);
+    assert!(code.contains("    pub name: String,\n    pub age: i64,\n    pub active: String,\n"));
+    assert!(!code.contains("self:"));
+    assert!(!code.contains("pub self"));
+
+    let hir_code = metadol::codegen::compile_to_rust_via_hir(source).unwrap();
+    assert!(hir_code.contains("pub active: String"));
+    assert!(!hir_code.contains("self"));
+}
+
+// ============================================
+// 24. Recursive Type Tests
+// ============================================
+
+#[test]
+fn test_codegen_boxes_optional_self_reference() {
+    let source = "gene Tree { Tree has parent: Option<Tree>\n Tree has children: List<Tree> }";
+    let file = Parser::new(source).parse_file().unwrap();
+
+    let code = RustCodegen::generate(&file.declarations[0]);
+    assert!(code.contains("pub parent: Option<Box<Tree>>,"));
+    assert!(code.contains("pub children: Vec<Tree>,"));
+}
+
+// ============================================
+// 25. Attribute Macro Tests
+// ============================================
+
+#[test]
+fn test_codegen_emits_expanded_derives() {
+    use metadol::macros::{MacroContext, MacroExpander};
+
+    let source = "#[derive(Hash, Debug)]\ngene container.exists { container has id }";
+    let decl = Parser::new(source).parse().unwrap();
+    let Declaration::Gene(gene) = &decl else {
+        panic!("expected gene");
+    };
+    let attrs = gene.attributes.clone();
+
+    let decl = MacroExpander::with_builtins()
+        .expand_declaration(decl, &attrs, &MacroContext::new())
+        .unwrap();
+    let code = RustCodegen::generate(&decl);
+    assert!(code.contains("#[derive(Debug, Clone, PartialEq, Hash)]"));
+}
+
+// ============================================
+// 26. Builder Tests
+// ============================================
+
+#[test]
+fn test_codegen_builder_required_and_optional_fields() {
+    use metadol::codegen::CodegenOptions;
+
+    let source = "gene server.config {\n  has host: String\n  has port: Int32 = 8080\n  \
+                  has label: Option<String>\n  has timeout: Option<Int32> = 30\n  \
+                  has proxy: Option<String> = null\n}";
+    let decls = Parser::new(source).parse_file().unwrap().declarations;
+    let options = CodegenOptions {
+        generate_builders: true,
+        ..Default::default()
+    };
+    let code = RustCodegen::with_options(options).gen_file(&decls);
+
+    assert_eq!(code.matches("pub struct BuildError").count(), 1);
+    assert!(code.contains("pub struct ServerConfigBuilder {\n    host: Option<String>,\n    port: Option<i32>,\n    label: Option<String>,\n    timeout: Option<i32>,\n    proxy: Option<String>,\n}"));
+    assert!(code.contains("pub fn builder() -> ServerConfigBuilder"));
+    assert!(code.contains("pub fn with_label(mut self, label: String) -> Self"));
+    assert!(code.contains("host: self.host.ok_or(BuildError { field: \"host\" })?,"));
+    assert!(code.contains("port: self.port.unwrap_or_else(|| 8080),"));
+    assert!(code.contains("label: self.label,"));
+    assert!(code.contains("timeout: self.timeout.or_else(|| Some(30)),"));
+    assert!(code.contains("proxy: self.proxy,"));
+}
+
+#[test]
+fn test_codegen_builder_single_declaration_includes_build_error() {
+    use metadol::codegen::CodegenOptions;
+
+    let decl = Parser::new("gene server.config { has host: String }")
+        .parse()
+        .unwrap();
+    let options = CodegenOptions {
+        generate_builders: true,
+        ..Default::default()
+    };
+    let code = RustCodegen::generate_with(&decl, &options);
+    assert_eq!(code.matches("pub struct BuildError").count(), 1);
+    assert!(code.contains("pub fn build(self) -> Result<ServerConfig, BuildError>"));
+}
+
+#[test]
+fn test_codegen_no_builder_by_default() {
+    let source = "gene server.config { has host: String }";
+    let decls = Parser::new(source).parse_file().unwrap().declarations;
+
+    let code = RustCodegen::new().gen_file(&decls);
+    assert!(!code.contains("Builder"));
+    assert!(!code.contains("BuildError"));
+}
+
+// ============================================
+// 27. Codegen Options Tests
+// ============================================
+
+#[test]
+fn test_codegen_generate_with_options() {
+    use metadol::codegen::{CodegenOptions, Visibility};
+
+    let source = "gene container.exists { has id: String }\n\nexegesis {\n  A container.\n}";
+    let decl = Parser::new(source).parse().unwrap();
+    let opts = CodegenOptions {
+        visibility: Visibility::Crate,
+        derive_macros: vec!["Serialize".to_string(), "Debug".to_string()],
+        ..Default::default()
+    };
+
+    let code = RustCodegen::generate_with(&decl, &opts);
+    assert!(code.starts_with(
+        "/// A container.\n#[derive(Debug, Clone, PartialEq, Serialize)]\n\
+         pub(crate) struct ContainerExists {\n    pub(crate) id: String,\n}"
+    ));
+
+    let without_docs = CodegenOptions {
+        include_docs: false,
+        ..opts
+    };
+    let code = RustCodegen::generate_with(&decl, &without_docs);
+    assert!(code.starts_with("#[derive("));
+    assert!(!code.contains("A container."));
+}
+
+#[test]
+fn test_codegen_default_options_match_generate() {
+    use metadol::codegen::{Codegen, CodegenOptions};
+
+    let source = "gene container.exists { has id: String }\n\nexegesis {\n  A container.\n}";
+    let decl = Parser::new(source).parse().unwrap();
+
+    let code = RustCodegen::generate_with(&decl, &CodegenOptions::default());
+    assert_eq!(code, <RustCodegen as Codegen>::generate(&decl));
+    assert!(code.contains("/// A container."));
+}
+
+#[test]
+fn test_codegen_inline_record_outside_gene_field_is_rejected() {
+    let source = "fun area(size: { w: Int64, h: Int64 }) -> Int64 { return 0 }";
+    let file = Parser::new(source).parse_file().unwrap();
+
+    let code = RustCodegen::generate(&file.declarations[0]);
+    assert!(code.contains("compile_error!(\"inline record types are only supported"));
+    assert!(!code.contains("(i64, i64)"));
+}
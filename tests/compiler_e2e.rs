@@ -646,6 +646,54 @@ fun increment(c: Counter) -> Counter {
 // 6. Real-World Examples
 // ============================================
 
+/// Collects every `.dol` file under `dir`, recursively.
+fn collect_dol_files(dir: &std::path::Path, files: &mut Vec<std::path::PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_dol_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "dol") {
+            files.push(path);
+        }
+    }
+}
+
+/// Stdlib examples that use syntax the parser does not accept yet, such as
+/// generic `uses Transport<Energy>` in systems.
+const UNPARSED_EXAMPLES: &[&str] = &[
+    "stdlib/biology/ecosystem.dol",
+    "stdlib/biology/evolution.dol",
+    "stdlib/biology/hyphal.dol",
+    "stdlib/biology/mycelium.dol",
+    "stdlib/biology/transport.dol",
+    "stdlib/network/hyphal_network.dol",
+];
+
+#[test]
+fn test_lower_all_examples() {
+    let examples = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    let mut files = Vec::new();
+    collect_dol_files(&examples, &mut files);
+    files.sort();
+    assert!(!files.is_empty(), "no example files found");
+
+    for path in files {
+        let relative = path.strip_prefix(&examples).unwrap().to_string_lossy();
+        if UNPARSED_EXAMPLES.contains(&relative.as_ref()) {
+            continue;
+        }
+        let source = std::fs::read_to_string(&path).unwrap();
+        let (_hir, ctx) = metadol::lower::lower_file(&source)
+            .unwrap_or_else(|e| panic!("{} failed to parse: {:?}", relative, e));
+        assert!(
+            !ctx.has_errors(),
+            "{} produced lowering errors: {:?}",
+            relative,
+            ctx.diagnostics()
+        );
+    }
+}
+
 #[test]
 fn test_parse_container_example() {
     let source = r#"
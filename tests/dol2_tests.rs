@@ -3,7 +3,7 @@
 //! End-to-end tests for new DOL 2.0 syntax including functional programming
 //! features, control flow, pattern matching, and meta-programming constructs.
 
-use metadol::ast::{BinaryOp, Declaration, Expr, Pattern, Stmt, TypeExpr};
+use metadol::ast::{BinaryOp, Declaration, Expr, Literal, Pattern, Stmt, TypeExpr};
 use metadol::parser::Parser;
 
 // ============================================
@@ -743,6 +743,175 @@ fn test_let_with_lambda() {
     }
 }
 
+#[test]
+fn test_val_tuple_destructuring() {
+    let input = "val (a, b) = pair;";
+    let mut parser = Parser::new(input);
+    let stmt = parser.parse_stmt().unwrap();
+
+    match stmt {
+        Stmt::LetPattern { pattern, value } => {
+            assert_eq!(
+                pattern,
+                Pattern::Tuple(vec![
+                    Pattern::Identifier("a".to_string()),
+                    Pattern::Identifier("b".to_string()),
+                ])
+            );
+            assert_eq!(value, Expr::Identifier("pair".to_string()));
+        }
+        _ => panic!("Expected destructuring let statement"),
+    }
+}
+
+#[test]
+fn test_val_record_destructuring() {
+    let input = "val Point { x, y } = origin";
+    let mut parser = Parser::new(input);
+    let stmt = parser.parse_stmt().unwrap();
+
+    match stmt {
+        Stmt::LetPattern {
+            pattern: Pattern::Constructor { name, fields },
+            ..
+        } => {
+            assert_eq!(name, "Point");
+            assert_eq!(fields.len(), 2);
+        }
+        _ => panic!("Expected record destructuring"),
+    }
+}
+
+#[test]
+fn test_val_tuple_destructuring_binds_types() {
+    use metadol::typechecker::{Type, TypeChecker};
+
+    let input = r#"{ val (a, b) = (1, "one"); b }"#;
+    let expr = Parser::new(input).parse_expr(0).unwrap();
+
+    let mut checker = TypeChecker::new();
+    assert_eq!(checker.infer(&expr), Ok(Type::String));
+}
+
+#[test]
+fn test_val_tuple_destructuring_arity_mismatch() {
+    use metadol::typechecker::TypeChecker;
+
+    let input = "{ val (a, b, c) = (1, 2); a }";
+    let expr = Parser::new(input).parse_expr(0).unwrap();
+
+    let mut checker = TypeChecker::new();
+    let err = checker.infer(&expr).unwrap_err();
+    assert!(err.message.contains("3 elements"), "{}", err);
+}
+
+#[test]
+fn test_val_tuple_destructuring_evaluates() {
+    use metadol::eval::{Interpreter, Value};
+
+    let input = "{ val (a, b) = (40, 2); a + b }";
+    let expr = Parser::new(input).parse_expr(0).unwrap();
+
+    let mut interp = Interpreter::new();
+    assert_eq!(interp.eval(&expr).unwrap(), Value::Int(42));
+}
+
+// ============================================
+// String Method and Interpolation Tests
+// ============================================
+
+#[test]
+fn test_string_len_method_evaluates() {
+    use metadol::eval::{Interpreter, Value};
+
+    let expr = Parser::new(r#""abc".len()"#).parse_expr(0).unwrap();
+
+    let mut interp = Interpreter::new();
+    assert_eq!(interp.eval(&expr).unwrap(), Value::Int(3));
+}
+
+#[test]
+fn test_string_methods_chain() {
+    use metadol::eval::{Interpreter, Value};
+
+    let input = r#"{ val s = " Hello "; s.trim().to_uppercase().contains("HELLO") }"#;
+    let expr = Parser::new(input).parse_expr(0).unwrap();
+
+    let mut interp = Interpreter::new();
+    assert_eq!(interp.eval(&expr).unwrap(), Value::Bool(true));
+}
+
+#[test]
+fn test_string_interpolation_splices_variables() {
+    use metadol::eval::{Interpreter, Value};
+
+    let input = r#"{ val name = "DOL"; val version = 2; f"Hello, ${name} ${version}!" }"#;
+    let expr = Parser::new(input).parse_expr(0).unwrap();
+
+    let mut interp = Interpreter::new();
+    assert_eq!(
+        interp.eval(&expr).unwrap(),
+        Value::String("Hello, DOL 2!".to_string())
+    );
+}
+
+#[test]
+fn test_plain_strings_are_not_interpolated() {
+    use metadol::eval::{Interpreter, Value};
+
+    let input = r#"{ val name = "DOL"; "${name} is literal" }"#;
+    let expr = Parser::new(input).parse_expr(0).unwrap();
+
+    let mut interp = Interpreter::new();
+    assert_eq!(
+        interp.eval(&expr).unwrap(),
+        Value::String("${name} is literal".to_string())
+    );
+}
+
+#[test]
+fn test_string_interpolation_rejects_unterminated() {
+    let result = Parser::new(r#"f"Hello, ${name""#).parse_expr(0);
+    assert!(result.is_err());
+    assert!(Parser::new(r#""Hello, ${name""#).parse_expr(0).is_ok());
+}
+
+#[test]
+fn test_string_interpolation_skips_nested_braces_and_strings() {
+    use metadol::eval::{Interpreter, Value};
+
+    let input = r#"{ val name = "DOL"; f"[${name + "}"}] ${ { val n = 2; n } }" }"#;
+    let expr = Parser::new(input).parse_expr(0).unwrap();
+
+    let mut interp = Interpreter::new();
+    assert_eq!(
+        interp.eval(&expr).unwrap(),
+        Value::String("[DOL}] 2".to_string())
+    );
+}
+
+#[test]
+fn test_string_interpolation_spans_point_into_the_string() {
+    let input = r#"f"Hello, ${name}!""#;
+    let expr = Parser::new(input).with_expr_spans().parse_expr(0).unwrap();
+    let Expr::Binary { left, .. } = expr.unspanned() else {
+        panic!("expected a concatenation, got {:?}", expr);
+    };
+    let Expr::Binary { right, .. } = left.unspanned() else {
+        panic!("expected a concatenation, got {:?}", left);
+    };
+    let Expr::Call { args, .. } = right.unspanned() else {
+        panic!("expected a to_string call, got {:?}", right);
+    };
+    let span = args[0].span().unwrap();
+    assert_eq!(&input[span.start..span.end], "name");
+    assert_eq!((span.line, span.column), (1, 12));
+
+    let input = r#"f"Hello, ${name +}""#;
+    let err = Parser::new(input).parse_expr(0).unwrap_err();
+    assert_eq!(err.span().start, input.find('}').unwrap());
+}
+
 // ============================================
 // Return Statement Tests
 // ============================================
@@ -788,6 +957,22 @@ fn test_member_access_with_call() {
     assert!(result.is_ok(), "Failed to parse member access with call");
 }
 
+#[test]
+fn test_eval_iterator_forall() {
+    use metadol::eval::{Interpreter, Value};
+
+    let mut interp = Interpreter::new();
+    let holds = Parser::new("forall x in [1, 2, 3] { x > 0 }")
+        .parse_expr(0)
+        .unwrap();
+    assert_eq!(interp.eval(&holds).unwrap(), Value::Bool(true));
+
+    let fails = Parser::new("forall x in [1, -2, 3] { x > 0 }")
+        .parse_expr(0)
+        .unwrap();
+    assert_eq!(interp.eval(&fails).unwrap(), Value::Bool(false));
+}
+
 // ============================================
 // Boolean Literal Tests
 // ============================================
@@ -821,3 +1006,328 @@ fn test_boolean_in_if() {
         _ => panic!("Expected if expression"),
     }
 }
+
+// ============================================
+// Block Evaluation Tests
+// ============================================
+
+#[test]
+fn test_block_returns_final_expression() {
+    use metadol::eval::{Interpreter, Value};
+
+    let expr = Parser::new("{ val x = 1; x + 1 }").parse_expr(0).unwrap();
+
+    let mut interp = Interpreter::new();
+    assert_eq!(interp.eval(&expr).unwrap(), Value::Int(2));
+}
+
+#[test]
+fn test_block_ending_in_statement_is_unit() {
+    use metadol::eval::{Interpreter, Value};
+
+    let expr = Parser::new(r#"{ print("hi"); }"#).parse_expr(0).unwrap();
+    match &expr {
+        Expr::Block { final_expr, .. } => assert!(final_expr.is_none()),
+        other => panic!("Expected Block, got {:?}", other),
+    }
+
+    let mut interp = Interpreter::new();
+    assert_eq!(interp.eval(&expr).unwrap(), Value::Void);
+}
+
+#[test]
+fn test_block_ending_in_binding_is_unit() {
+    use metadol::eval::{Interpreter, Value};
+
+    let expr = Parser::new("{ val x = 1 }").parse_expr(0).unwrap();
+    match &expr {
+        Expr::Block {
+            statements,
+            final_expr,
+        } => {
+            assert_eq!(statements.len(), 1);
+            assert!(final_expr.is_none());
+        }
+        other => panic!("Expected Block, got {:?}", other),
+    }
+
+    let mut interp = Interpreter::new();
+    assert_eq!(interp.eval(&expr).unwrap(), Value::Void);
+}
+
+#[test]
+fn test_empty_block_is_unit() {
+    use metadol::eval::{Interpreter, Value};
+
+    let expr = Parser::new("{ }").parse_expr(0).unwrap();
+
+    let mut interp = Interpreter::new();
+    assert_eq!(interp.eval(&expr).unwrap(), Value::Void);
+}
+
+// ============================================
+// Map Literal Tests
+// ============================================
+
+#[test]
+fn test_parse_map_literal() {
+    let expr = Parser::new(r#"{ "a": 1, "b": 2 }"#).parse_expr(0).unwrap();
+    match expr {
+        Expr::Map(entries) => {
+            assert_eq!(entries.len(), 2);
+            assert!(matches!(&entries[0].0, Expr::Literal(Literal::String(k)) if k == "a"));
+            assert!(matches!(&entries[1].1, Expr::Literal(Literal::Int(2))));
+        }
+        other => panic!("Expected Map, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_map_literal_with_integer_keys() {
+    let expr = Parser::new(r#"{ 1: "one", 2: "two" }"#)
+        .parse_expr(0)
+        .unwrap();
+    match expr {
+        Expr::Map(entries) => {
+            assert_eq!(entries.len(), 2);
+            assert!(matches!(&entries[0].0, Expr::Literal(Literal::Int(1))));
+            assert!(matches!(&entries[1].1, Expr::Literal(Literal::String(v)) if v == "two"));
+        }
+        other => panic!("Expected Map, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_eval_map_with_negative_integer_key() {
+    use metadol::eval::{Interpreter, Value};
+
+    let expr = Parser::new(r#"{ val m = { -1: "low", 1: "high" }; m[-1] }"#)
+        .parse_expr(0)
+        .unwrap();
+
+    let mut interp = Interpreter::new();
+    assert_eq!(
+        interp.eval(&expr).unwrap(),
+        Value::String("low".to_string())
+    );
+}
+
+#[test]
+fn test_brace_without_key_colon_is_block() {
+    let expr = Parser::new("{ x }").parse_expr(0).unwrap();
+    assert!(matches!(expr, Expr::Block { .. }));
+
+    let expr = Parser::new("{}").parse_expr(0).unwrap();
+    assert!(matches!(expr, Expr::Block { .. }));
+}
+
+#[test]
+fn test_eval_map_key_access() {
+    use metadol::eval::{Interpreter, Value};
+
+    let expr = Parser::new(r#"{ val m = { "a": 1, "b": 2 }; m["b"] }"#)
+        .parse_expr(0)
+        .unwrap();
+
+    let mut interp = Interpreter::new();
+    assert_eq!(interp.eval(&expr).unwrap(), Value::Int(2));
+}
+
+#[test]
+fn test_eval_map_construction_and_missing_key() {
+    use metadol::eval::{Interpreter, Value};
+
+    let mut interp = Interpreter::new();
+    let map = Parser::new(r#"{ "a": 1, "a": 3 }"#).parse_expr(0).unwrap();
+    assert_eq!(
+        interp.eval(&map).unwrap(),
+        Value::Map(vec![(Value::String("a".to_string()), Value::Int(3))])
+    );
+
+    let lookup = Parser::new(r#"{ val m = { "a": 1 }; m["z"] }"#)
+        .parse_expr(0)
+        .unwrap();
+    let err = interp.eval(&lookup).unwrap_err();
+    assert!(err.message.contains("key not found"));
+}
+
+// ============================================
+// Result Value Tests
+// ============================================
+
+#[test]
+fn test_eval_try_unwraps_ok() {
+    use metadol::eval::{Interpreter, Value};
+
+    let expr = Parser::new("ok(5)?").parse_expr(0).unwrap();
+    let mut interp = Interpreter::new();
+    assert_eq!(interp.eval(&expr).unwrap(), Value::Int(5));
+}
+
+#[test]
+fn test_eval_try_propagates_err_from_function() {
+    use metadol::eval::{Interpreter, Value};
+
+    let expr =
+        Parser::new(r#"{ val check = |x| { val y = err("bad input")?; ok(y + x) }; check(1) }"#)
+            .parse_expr(0)
+            .unwrap();
+    let mut interp = Interpreter::new();
+    assert_eq!(
+        interp.eval(&expr).unwrap(),
+        Value::Result(Err(Box::new(Value::String("bad input".to_string()))))
+    );
+}
+
+#[test]
+fn test_eval_match_over_result() {
+    use metadol::eval::{Interpreter, Value};
+
+    let mut interp = Interpreter::new();
+    let expr = Parser::new("match ok(2) { Ok(v) => v + 1, Err(e) => 0 }")
+        .parse_expr(0)
+        .unwrap();
+    assert_eq!(interp.eval(&expr).unwrap(), Value::Int(3));
+
+    let expr = Parser::new(r#"match err("boom") { Ok(v) => v, Err(e) => e }"#)
+        .parse_expr(0)
+        .unwrap();
+    assert_eq!(
+        interp.eval(&expr).unwrap(),
+        Value::String("boom".to_string())
+    );
+}
+
+// ============================================
+// Host Function Call Tests
+// ============================================
+
+#[test]
+fn test_call_function_from_host() {
+    use metadol::eval::{Interpreter, Value};
+
+    let file = Parser::new("fun add(a: Int64, b: Int64) -> Int64 { a + b }")
+        .parse_file()
+        .unwrap();
+
+    let mut interp = Interpreter::new();
+    interp.load(&file.declarations);
+    assert_eq!(
+        interp
+            .call_function("add", vec![Value::Int(2), Value::Int(3)])
+            .unwrap(),
+        Value::Int(5)
+    );
+}
+
+#[test]
+fn test_call_function_checks_arity_and_name() {
+    use metadol::eval::{Interpreter, Value};
+
+    let file = Parser::new("fun add(a: Int64, b: Int64) -> Int64 { a + b }")
+        .parse_file()
+        .unwrap();
+
+    let mut interp = Interpreter::new();
+    interp.load(&file.declarations);
+    assert!(interp.call_function("add", vec![Value::Int(2)]).is_err());
+    assert!(interp.call_function("missing", Vec::new()).is_err());
+}
+
+#[test]
+fn test_call_recursive_function() {
+    use metadol::eval::{Interpreter, Value};
+
+    let file =
+        Parser::new("fun fact(n: Int64) -> Int64 { if n <= 1 { 1 } else { n * fact(n - 1) } }")
+            .parse_file()
+            .unwrap();
+
+    let mut interp = Interpreter::new();
+    interp.load(&file.declarations);
+    assert_eq!(
+        interp.call_function("fact", vec![Value::Int(5)]).unwrap(),
+        Value::Int(120)
+    );
+}
+
+#[test]
+fn test_call_mutually_recursive_functions() {
+    use metadol::eval::{Interpreter, Value};
+
+    let file = Parser::new(
+        "fun is_even(n: Int64) -> Bool { if n == 0 { true } else { is_odd(n - 1) } }\n\
+         fun is_odd(n: Int64) -> Bool { if n == 0 { false } else { is_even(n - 1) } }",
+    )
+    .parse_file()
+    .unwrap();
+
+    let mut interp = Interpreter::new();
+    interp.load(&file.declarations);
+    assert_eq!(
+        interp
+            .call_function("is_even", vec![Value::Int(10)])
+            .unwrap(),
+        Value::Bool(true)
+    );
+    assert_eq!(
+        interp.call_function("is_odd", vec![Value::Int(7)]).unwrap(),
+        Value::Bool(true)
+    );
+    assert_eq!(
+        interp
+            .call_function("is_even", vec![Value::Int(3)])
+            .unwrap(),
+        Value::Bool(false)
+    );
+}
+
+#[test]
+fn test_call_function_with_early_return() {
+    use metadol::eval::{Interpreter, Value};
+
+    let file = Parser::new(
+        "fun clamp(n: Int64) -> Int64 { if n > 10 { return 10 } let doubled = n * 2 doubled }",
+    )
+    .parse_file()
+    .unwrap();
+
+    let mut interp = Interpreter::new();
+    interp.load(&file.declarations);
+    assert_eq!(
+        interp.call_function("clamp", vec![Value::Int(42)]).unwrap(),
+        Value::Int(10)
+    );
+    assert_eq!(
+        interp.call_function("clamp", vec![Value::Int(3)]).unwrap(),
+        Value::Int(6)
+    );
+}
+
+#[test]
+fn test_restore_snapshot_undoes_definitions() {
+    use metadol::eval::{Interpreter, Value};
+
+    let first = Parser::new("fun one() -> Int64 { 1 }")
+        .parse_file()
+        .unwrap();
+    let second = Parser::new("fun two() -> Int64 { 2 }")
+        .parse_file()
+        .unwrap();
+
+    let mut interp = Interpreter::new();
+    interp.load(&first.declarations);
+    let snapshot = interp.snapshot();
+    interp.load(&second.declarations);
+    assert_eq!(
+        interp.call_function("two", Vec::new()).unwrap(),
+        Value::Int(2)
+    );
+
+    interp.restore(snapshot);
+    assert!(interp.call_function("two", Vec::new()).is_err());
+    assert_eq!(
+        interp.call_function("one", Vec::new()).unwrap(),
+        Value::Int(1)
+    );
+}
@@ -7,14 +7,20 @@ use metadol::ast::Expr;
 use metadol::lexer::{Lexer, TokenKind};
 use metadol::parser::Parser;
 
-/// Helper to collect all tokens from input
+/// Helper to collect all tokens from input, without the final `Eof`
 fn tokenize(input: &str) -> Vec<(TokenKind, String)> {
-    Lexer::new(input).map(|t| (t.kind, t.lexeme)).collect()
+    Lexer::new(input)
+        .take_while(|t| t.kind != TokenKind::Eof)
+        .map(|t| (t.kind, t.lexeme))
+        .collect()
 }
 
-/// Helper to get just token kinds
+/// Helper to get just token kinds, without the final `Eof`
 fn token_kinds(input: &str) -> Vec<TokenKind> {
-    Lexer::new(input).map(|t| t.kind).collect()
+    Lexer::new(input)
+        .take_while(|t| t.kind != TokenKind::Eof)
+        .map(|t| t.kind)
+        .collect()
 }
 
 // ============================================
@@ -174,12 +180,20 @@ fn test_parse_idiom_bracket_with_qualified_identifier() {
     let mut parser = Parser::new(input);
     let expr = parser.parse_expr(0).unwrap();
 
+    // Qualified identifiers are member accesses
+    let is_member = |expr: &Expr, object: &str, member: &str| {
+        matches!(
+            expr,
+            Expr::Member { object: o, field } if field == member
+                && matches!(o.as_ref(), Expr::Identifier(name) if name == object)
+        )
+    };
     match expr {
         Expr::IdiomBracket { func, args } => {
-            assert!(matches!(*func, Expr::Identifier(ref name) if name == "map.async"));
+            assert!(is_member(&func, "map", "async"));
             assert_eq!(args.len(), 2);
-            assert!(matches!(args[0], Expr::Identifier(ref name) if name == "transform.data"));
-            assert!(matches!(args[1], Expr::Identifier(ref name) if name == "list.items"));
+            assert!(is_member(&args[0], "transform", "data"));
+            assert!(is_member(&args[1], "list", "items"));
         }
         _ => panic!("Expected IdiomBracket expression, got: {:?}", expr),
     }
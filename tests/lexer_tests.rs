@@ -2,16 +2,22 @@
 //!
 //! These tests verify correct tokenization of all DOL language constructs.
 
-use metadol::lexer::{Lexer, TokenKind};
+use metadol::lexer::{tokenize as tokenize_all, Lexer, TokenKind};
 
-/// Helper to collect all tokens from input
+/// Helper to collect all tokens from input, without the final `Eof`
 fn tokenize(input: &str) -> Vec<(TokenKind, String)> {
-    Lexer::new(input).map(|t| (t.kind, t.lexeme)).collect()
+    Lexer::new(input)
+        .take_while(|t| t.kind != TokenKind::Eof)
+        .map(|t| (t.kind, t.lexeme))
+        .collect()
 }
 
-/// Helper to get just token kinds
+/// Helper to get just token kinds, without the final `Eof`
 fn token_kinds(input: &str) -> Vec<TokenKind> {
-    Lexer::new(input).map(|t| t.kind).collect()
+    Lexer::new(input)
+        .take_while(|t| t.kind != TokenKind::Eof)
+        .map(|t| t.kind)
+        .collect()
 }
 
 // ============================================
@@ -243,6 +249,19 @@ fn test_empty_string() {
     assert_eq!(tokens[0].1, "");
 }
 
+#[test]
+fn test_format_string() {
+    let tokens = tokenize(r#"f"hi ${name}""#);
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].0, TokenKind::FormatString);
+    assert_eq!(tokens[0].1, "hi ${name}");
+
+    // A spaced `f` is an identifier followed by a plain string
+    let tokens = tokenize(r#"f "hi""#);
+    assert_eq!(tokens[0].0, TokenKind::Identifier);
+    assert_eq!(tokens[1].0, TokenKind::String);
+}
+
 // ============================================
 // 6. Whitespace and Comment Tests
 // ============================================
@@ -415,7 +434,37 @@ fn test_errors_collected() {
 #[test]
 fn test_lexer_iterator() {
     let tokens: Vec<_> = Lexer::new("gene trait").collect();
-    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens.len(), 3);
+    assert_eq!(tokens[2].kind, TokenKind::Eof);
+}
+
+#[test]
+fn test_lexer_iterator_is_lazy_and_fused() {
+    let mut lexer = Lexer::new("gene trait");
+    assert_eq!(lexer.next().map(|t| t.kind), Some(TokenKind::Gene));
+    assert_eq!(lexer.next().map(|t| t.kind), Some(TokenKind::Trait));
+    assert_eq!(lexer.next().map(|t| t.kind), Some(TokenKind::Eof));
+    assert_eq!(lexer.next(), None);
+    assert_eq!(lexer.next(), None);
+}
+
+#[test]
+fn test_tokenize_spans_are_ordered_and_end_at_eof() {
+    let source = "gene x {\n  x has id // note\n}\n";
+    let tokens = tokenize_all(source);
+    for pair in tokens.windows(2) {
+        assert!(pair[0].span.end <= pair[1].span.start);
+    }
+    let eof = tokens.last().unwrap();
+    assert_eq!(eof.kind, TokenKind::Eof);
+    assert_eq!((eof.span.start, eof.span.end), (source.len(), source.len()));
+    assert_eq!((eof.span.line, eof.span.column), (4, 1));
+
+    let trivia: Vec<_> = Lexer::with_trivia(source).collect();
+    for pair in trivia.windows(2) {
+        assert_eq!(pair[0].span.end, pair[1].span.start);
+    }
+    assert_eq!(trivia.last().unwrap().span.end, source.len());
 }
 
 #[test]
@@ -23,6 +23,69 @@ fn deeply_nested_binary() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn nesting_within_default_limit() {
+    let input = format!("{}a{}", "(".repeat(100), ")".repeat(100));
+    let result = Parser::new(&input).parse_expr(0);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn nesting_too_deep_fails_gracefully() {
+    use metadol::error::ParseError;
+
+    let input = format!("{}a{}", "(".repeat(100_000), ")".repeat(100_000));
+    let result = Parser::new(&input).parse_expr(0);
+    assert!(matches!(result, Err(ParseError::NestingTooDeep { .. })));
+}
+
+#[test]
+fn nesting_respects_custom_max_depth() {
+    use metadol::error::ParseError;
+
+    let input = "((((a))))";
+    let result = Parser::new(input).with_max_depth(3).parse_expr(0);
+    match result {
+        Err(ParseError::NestingTooDeep { limit, .. }) => assert_eq!(limit, 3),
+        other => panic!("expected NestingTooDeep, got {:?}", other),
+    }
+    assert!(Parser::new(input).with_max_depth(8).parse_expr(0).is_ok());
+}
+
+#[test]
+fn deeply_nested_generic_type_fails_gracefully() {
+    use metadol::error::ParseError;
+
+    let ty = format!("{}Int32{}", "List<".repeat(100_000), ">".repeat(100_000));
+    let result = Parser::new(&ty).parse_type();
+    assert!(matches!(result, Err(ParseError::NestingTooDeep { .. })));
+}
+
+#[test]
+fn nested_generic_type_within_default_limit() {
+    let ty = format!("{}Int32{}", "List<".repeat(50), ">".repeat(50));
+    assert!(Parser::new(&ty).parse_type().is_ok());
+}
+
+#[test]
+fn deeply_nested_pattern_fails_gracefully() {
+    use metadol::error::ParseError;
+
+    let input = format!(
+        "match x {{ {}a{} => 1 }}",
+        "(".repeat(200_000),
+        ")".repeat(200_000)
+    );
+    let result = Parser::new(&input).parse_expr(0);
+    assert!(matches!(result, Err(ParseError::NestingTooDeep { .. })));
+}
+
+#[test]
+fn nested_pattern_within_default_limit() {
+    let input = format!("match x {{ {}a{} => 1 }}", "(".repeat(50), ")".repeat(50));
+    assert!(Parser::new(&input).parse_expr(0).is_ok());
+}
+
 #[test]
 fn chained_method_calls() {
     let input = "a.b.c.d.e";
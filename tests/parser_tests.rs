@@ -2,7 +2,9 @@
 //!
 //! These tests verify correct parsing of all DOL language constructs.
 
-use metadol::ast::{Declaration, Quantifier, Statement};
+use metadol::ast::{
+    CapabilityExpr, Declaration, Expr, Literal, Quantifier, Statement, Stmt, TypeExpr,
+};
 use metadol::error::ParseError;
 use metadol::parser::Parser;
 
@@ -231,6 +233,72 @@ exegesis {
     }
 }
 
+#[test]
+fn test_parse_attribute_macros_on_gene_and_trait() {
+    let file = Parser::new(
+        "#[derive(Debug, Clone)]\n#[deprecated]\ngene container.exists { container has id }\n\
+         #[cache]\ntrait container.lifecycle { uses container.exists }",
+    )
+    .parse_file()
+    .unwrap();
+
+    let Declaration::Gene(gene) = &file.declarations[0] else {
+        panic!("expected gene");
+    };
+    assert_eq!(gene.deprecated(), Some(""));
+    let names: Vec<&str> = gene.attributes.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["derive", "deprecated"]);
+    assert_eq!(gene.attributes[0].args.len(), 2);
+    assert_eq!(gene.derives(), ["Debug", "Clone"]);
+
+    let Declaration::Trait(trait_decl) = &file.declarations[1] else {
+        panic!("expected trait");
+    };
+    assert_eq!(trait_decl.attributes[0].name, "cache");
+}
+
+#[test]
+fn test_parse_attribute_on_constraint_is_ignored_with_warning() {
+    let mut parser = Parser::new(
+        "#[derive(Debug)]
+constraint container.valid { container has id }",
+    );
+    let decl = parser.parse().unwrap();
+
+    assert!(matches!(decl, Declaration::Constraint(_)));
+    assert_eq!(parser.warnings().len(), 1);
+    assert!(parser.warnings()[0]
+        .message
+        .contains("#[derive] is ignored"));
+}
+
+#[test]
+fn test_parse_trait_laws() {
+    let input = r#"
+trait container.lifecycle {
+  uses container.exists
+  law idempotent(c: Container) { start(start(c)) == start(c) }
+  exegesis { Starting twice is starting once. }
+  law stops(c: Container) { stopped(stop(c)) }
+}
+
+exegesis {
+  Lifecycle laws.
+}
+"#;
+    let Declaration::Trait(trait_decl) = parse(input).unwrap() else {
+        panic!("Expected Trait");
+    };
+    let names: Vec<&str> = trait_decl.laws.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(names, ["idempotent", "stops"]);
+    assert_eq!(trait_decl.laws[0].params[0].name, "c");
+    assert_eq!(
+        trait_decl.laws[0].exegesis.as_deref().map(str::trim),
+        Some("Starting twice is starting once.")
+    );
+    assert!(trait_decl.laws[1].exegesis.is_none());
+}
+
 #[test]
 fn test_parse_trait_emits() {
     let input = r#"
@@ -415,6 +483,133 @@ exegesis {
     }
 }
 
+#[test]
+fn test_parse_system_with_states() {
+    let input = r#"
+system univrs.scheduler @ 1.0.0 {
+  state queue: List<String>
+  state ticks: Int64 = 0
+  scheduler is running
+}
+
+exegesis {
+  Scheduler with state.
+}
+"#;
+    let Declaration::System(system) = parse(input).unwrap() else {
+        panic!("Expected System");
+    };
+    let names: Vec<&str> = system.states.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["queue", "ticks"]);
+    assert_eq!(
+        system.states[1].default,
+        Some(Expr::Literal(Literal::Int(0)))
+    );
+    assert_eq!(system.statements.len(), 1);
+}
+
+#[test]
+fn test_parse_system_requirement_and_requires_statement() {
+    let input = r#"
+system univrs.api @ 1.0.0 {
+  requires container.lifecycle >= 0.0.2
+  requires foo.bar emits event
+  requires network
+}
+
+exegesis {
+  API system with an event.
+}
+"#;
+    let Declaration::System(system) = parse(input).unwrap() else {
+        panic!("Expected System");
+    };
+    assert_eq!(system.requirements.len(), 1);
+    assert_eq!(system.requirements[0].name, "container.lifecycle");
+    assert_eq!(system.capabilities.len(), 1);
+    assert!(matches!(
+        &system.statements[..],
+        [Statement::Emits { action, event, .. }]
+            if action == "requires foo.bar" && event == "event"
+    ));
+}
+
+#[test]
+fn test_parse_system_requirement_bare_version_is_exact() {
+    let input = r#"
+system univrs.api @ 1.0.0 {
+  requires container.lifecycle 0.0.2
+}
+
+exegesis {
+  API system pinned to one lifecycle version.
+}
+"#;
+    let Declaration::System(system) = parse(input).unwrap() else {
+        panic!("Expected System");
+    };
+    assert_eq!(system.requirements.len(), 1);
+    assert_eq!(system.requirements[0].name, "container.lifecycle");
+    assert_eq!(system.requirements[0].constraint, "=");
+    assert_eq!(system.requirements[0].version, "0.0.2");
+}
+
+#[test]
+fn test_parse_system_requirement_bad_operator() {
+    let input = r#"
+system univrs.api @ 1.0.0 {
+  requires container.lifecycle < 0.0.2
+}
+"#;
+    assert!(parse(input).is_err());
+}
+
+#[test]
+fn test_parse_system_capability_conjunction() {
+    let input = r#"
+system univrs.node @ 0.1.0 {
+  requires container.lifecycle >= 0.0.2
+  requires network and storage
+}
+
+exegesis {
+  A node needs both network and storage.
+}
+"#;
+    let Declaration::System(system) = parse(input).unwrap() else {
+        panic!("Expected System");
+    };
+    assert_eq!(system.requirements.len(), 1);
+    assert_eq!(system.capabilities.len(), 1);
+    assert_eq!(
+        system.capabilities[0].expr,
+        CapabilityExpr::And(
+            Box::new(CapabilityExpr::Capability("network".to_string())),
+            Box::new(CapabilityExpr::Capability("storage".to_string())),
+        )
+    );
+}
+
+#[test]
+fn test_parse_system_capability_disjunction() {
+    let input = r#"
+system univrs.node @ 0.1.0 {
+  requires (network and storage) or local_disk
+}
+
+exegesis {
+  A node needs networked storage or a local disk.
+}
+"#;
+    let Declaration::System(system) = parse(input).unwrap() else {
+        panic!("Expected System");
+    };
+    let expr = &system.capabilities[0].expr;
+    assert!(matches!(expr, CapabilityExpr::Or(..)));
+    assert_eq!(expr.to_string(), "((network and storage) or local_disk)");
+    assert_eq!(expr.capabilities(), ["network", "storage", "local_disk"]);
+}
+
 // ============================================
 // 5. Evolution Declaration Tests
 // ============================================
@@ -443,6 +638,35 @@ exegesis {
     }
 }
 
+#[test]
+fn test_parse_evolution_from_to_matches_legacy_syntax() {
+    let body = "{\n  adds container is paused\n}\n\nexegesis {\n  Adding pause state.\n}\n";
+    let modern_src = format!("evolves container.lifecycle from 0.0.1 to 0.0.2 {body}");
+    let legacy_src = format!("evolves container.lifecycle @ 0.0.2 > 0.0.1 {body}");
+
+    let mut modern = Parser::new(&modern_src);
+    let modern_decl = modern.parse().unwrap();
+    assert!(modern.warnings().is_empty());
+
+    let mut legacy = Parser::new(&legacy_src);
+    let legacy_decl = legacy.parse().unwrap();
+    assert_eq!(legacy.warnings().len(), 1);
+    assert!(legacy.warnings()[0].message.contains("deprecated"));
+
+    let (Declaration::Evolution(modern), Declaration::Evolution(legacy)) =
+        (modern_decl, legacy_decl)
+    else {
+        panic!("Expected Evolution");
+    };
+    assert_eq!(modern.name, legacy.name);
+    assert_eq!(modern.version, "0.0.2");
+    assert_eq!(modern.parent_version, "0.0.1");
+    assert_eq!(modern.version, legacy.version);
+    assert_eq!(modern.parent_version, legacy.parent_version);
+    assert_eq!(modern.additions.len(), legacy.additions.len());
+    assert_eq!(modern.exegesis.text(), legacy.exegesis.text());
+}
+
 #[test]
 fn test_parse_evolution_with_deprecates() {
     let input = r#"
@@ -511,6 +735,39 @@ exegesis {
     }
 }
 
+#[test]
+fn test_parse_evolution_with_migrate() {
+    let input = r#"
+evolves container.lifecycle from 0.0.1 to 0.0.2 {
+  adds container is paused
+  migrate {
+    let paused = false
+    state = "running"
+  }
+}
+
+exegesis {
+  Pause for migration.
+}
+"#;
+    let Declaration::Evolution(evolution) = parse(input).unwrap() else {
+        panic!("Expected Evolution");
+    };
+    let migrate = evolution.migrate.expect("migrate block should be kept");
+    assert_eq!(migrate.len(), 2);
+    assert!(matches!(&migrate[0], Stmt::Let { name, .. } if name == "paused"));
+    assert!(matches!(&migrate[1], Stmt::Assign { .. }));
+}
+
+#[test]
+fn test_parse_evolution_without_migrate() {
+    let input = "evolves container.lifecycle from 0.0.1 to 0.0.2 {\n  adds container is paused\n}";
+    let Declaration::Evolution(evolution) = parse(input).unwrap() else {
+        panic!("Expected Evolution");
+    };
+    assert!(evolution.migrate.is_none());
+}
+
 // ============================================
 // 6. Statement Type Tests
 // ============================================
@@ -590,6 +847,46 @@ exegesis {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_parse_statements_with_trailing_semicolons() {
+    let with_semicolons = r#"
+gene container.exists {
+  container has identity;
+  container is persistent;
+  container derives from image;
+}
+"#;
+    // Blank out the semicolons so both sources share the same spans.
+    let plain = with_semicolons.replace(';', " ");
+    let (Declaration::Gene(plain), Declaration::Gene(with_semicolons)) =
+        (parse(&plain).unwrap(), parse(with_semicolons).unwrap())
+    else {
+        panic!("Expected genes");
+    };
+    assert_eq!(plain.statements.len(), 3);
+    assert_eq!(plain.statements, with_semicolons.statements);
+}
+
+#[test]
+fn test_parse_system_body_with_trailing_semicolons() {
+    let with_semicolons = r#"
+system s @ 1.0.0 {
+  requires a >= 1.0.0;
+  requires b > 0.1.0;
+  s has uptime;
+}
+"#;
+    let plain = with_semicolons.replace(';', " ");
+    let (Declaration::System(plain), Declaration::System(with_semicolons)) =
+        (parse(&plain).unwrap(), parse(with_semicolons).unwrap())
+    else {
+        panic!("Expected systems");
+    };
+    assert_eq!(plain.requirements.len(), 2);
+    assert_eq!(plain.requirements, with_semicolons.requirements);
+    assert_eq!(plain.statements, with_semicolons.statements);
+}
+
 // ============================================
 // 7. Error Case Tests
 // ============================================
@@ -635,6 +932,25 @@ exegesis {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_error_unterminated_exegesis() {
+    let input =
+        "gene container.exists {\n  container has identity\n}\n\nexegesis {\n  Never closed.\n";
+    let err = parse(input).unwrap_err();
+    assert!(matches!(err, ParseError::UnterminatedExegesis { .. }));
+    assert_eq!((err.span().line, err.span().column), (5, 10));
+    assert!(err.to_string().contains("line 5, column 10"));
+}
+
+#[test]
+fn test_error_unterminated_inline_exegesis() {
+    let input =
+        "gene container.exists {\n  container has identity\n  exegesis {\n    Never closed.\n";
+    let err = parse(input).unwrap_err();
+    assert!(matches!(err, ParseError::UnterminatedExegesis { .. }));
+    assert_eq!((err.span().line, err.span().column), (3, 12));
+}
+
 #[test]
 fn test_error_missing_name() {
     let input = r#"
@@ -654,6 +970,30 @@ exegesis {
 // 8. Edge Case Tests
 // ============================================
 
+#[test]
+fn test_parse_dol1_dialect_treats_dol2_keywords_as_words() {
+    use metadol::lexer::{Dialect, LexerConfig};
+
+    let input = r#"
+gene lease.terms {
+  lease has let
+}
+
+exegesis {
+  In DOL 1.x `let` is an ordinary word.
+}
+"#;
+    let mut parser = Parser::with_lexer_config(input, LexerConfig::dialect(Dialect::Dol1));
+    let decl = parser.parse().unwrap();
+    match decl {
+        Declaration::Gene(gene) => match &gene.statements[0] {
+            Statement::Has { property, .. } => assert_eq!(property, "let"),
+            other => panic!("Expected Has statement, got {:?}", other),
+        },
+        other => panic!("Expected Gene, got {:?}", other),
+    }
+}
+
 #[test]
 fn test_parse_empty_body() {
     let input = r#"
@@ -1575,6 +1915,23 @@ fn test_parse_type_function() {
     }
 }
 
+#[test]
+fn test_parse_type_record() {
+    use metadol::ast::TypeExpr;
+
+    let mut parser = Parser::new("{ cpu: Int64, memory: Int64 }");
+    let type_expr = parser.parse_type().unwrap();
+
+    match type_expr {
+        TypeExpr::Record(fields) => {
+            let names: Vec<_> = fields.iter().map(|(name, _)| name.as_str()).collect();
+            assert_eq!(names, ["cpu", "memory"]);
+            assert_eq!(fields[0].1, TypeExpr::Named("Int64".to_string()));
+        }
+        _ => panic!("Expected record type"),
+    }
+}
+
 #[test]
 fn test_parse_complex_nested_expression() {
     use metadol::ast::Expr;
@@ -1689,3 +2046,225 @@ fn test_parse_idiom_bracket_simple_literals() {
         _ => panic!("Expected IdiomBracket expression"),
     }
 }
+
+#[test]
+fn test_parse_member_access_nests() {
+    use metadol::ast::Expr;
+
+    let expr = Parser::new("a.b.c").parse_expr(0).unwrap();
+    let Expr::Member { object, field } = expr else {
+        panic!("Expected member access, got {:?}", expr);
+    };
+    assert_eq!(field, "c");
+    let Expr::Member { object, field } = *object else {
+        panic!("Expected nested member access, got {:?}", object);
+    };
+    assert_eq!(field, "b");
+    assert_eq!(*object, Expr::Identifier("a".to_string()));
+
+    // Spaced dots produce the same tree
+    assert_eq!(
+        Parser::new("a . b . c").parse_expr(0).unwrap(),
+        Parser::new("a.b.c").parse_expr(0).unwrap()
+    );
+}
+
+#[test]
+fn test_parse_iterator_forall_keeps_iter_apart_from_body() {
+    use metadol::ast::Expr;
+
+    let expr = Parser::new("forall x in xs { x > 0 }")
+        .parse_expr(0)
+        .unwrap();
+    let Expr::Forall(forall) = expr else {
+        panic!("Expected forall, got {:?}", expr);
+    };
+    assert_eq!(forall.var, "x");
+    assert_eq!(
+        forall.iter.as_deref(),
+        Some(&Expr::Identifier("xs".to_string()))
+    );
+    assert!(matches!(*forall.body, Expr::Block { .. }));
+
+    let expr = Parser::new("forall x: Int64. x > 0").parse_expr(0).unwrap();
+    assert!(matches!(expr, Expr::Forall(forall) if forall.iter.is_none()));
+}
+
+#[test]
+fn test_parse_exists_implies_and_membership() {
+    use metadol::ast::{BinaryOp, Expr};
+
+    let expr = Parser::new("exists x: Int64. x in items implies x > 0")
+        .parse_expr(0)
+        .unwrap();
+    let Expr::Exists(exists) = expr else {
+        panic!("Expected exists, got {:?}", expr);
+    };
+    assert_eq!(exists.var, "x");
+    let Expr::Binary {
+        left,
+        op: BinaryOp::Implies,
+        ..
+    } = *exists.body
+    else {
+        panic!("Expected implication, got {:?}", exists.body);
+    };
+    // `x in items` is a call to the collection's `contains`
+    assert_eq!(
+        *left,
+        Parser::new("items.contains(x)").parse_expr(0).unwrap()
+    );
+}
+
+#[test]
+fn test_parse_member_access_requires_field_name() {
+    let err = Parser::new("a.1").parse_expr(0).unwrap_err();
+    assert!(matches!(err, ParseError::UnexpectedToken { ref found, .. } if found == "'1'"));
+    assert!(Parser::new("a . 1").parse_expr(0).is_err());
+}
+
+#[test]
+fn test_parse_all_multiple_declarations() {
+    let source = r#"gene a.first {
+  a has x
+}
+exegesis {
+  First.
+}
+
+exegesis {
+  A stray note about the file.
+}
+
+trait b.second {
+  uses a.first
+}
+exegesis {
+  Second.
+}
+
+constraint c.third {
+  c matches d
+}
+exegesis {
+  Third.
+}
+"#;
+    let decls = Parser::new(source).parse_all().unwrap();
+    let names: Vec<&str> = decls.iter().map(|d| d.name()).collect();
+    assert_eq!(names, ["a.first", "b.second", "c.third"]);
+    assert_eq!(decls[1].exegesis(), "Second.");
+    assert_eq!(decls[2].exegesis(), "Third.");
+    assert_eq!(decls[1].span().line, 12);
+    assert_eq!(decls[2].span().line, 19);
+
+    // parse() still returns only the first declaration
+    assert_eq!(Parser::new(source).parse().unwrap().name(), "a.first");
+}
+
+#[test]
+fn test_parse_all_reports_error_line_in_later_declaration() {
+    let source = "gene a.first {\n  a has x\n}\nexegesis { First. }\n\ntrait b.second {\n  uses a.first\n  ???\n}\n";
+    let err = Parser::new(source).parse_all().unwrap_err();
+    assert_eq!(err.span().line, 8);
+}
+
+#[test]
+fn test_parse_all_exegesis_with_glob_keeps_following_declarations() {
+    let source = r#"gene files.glob {
+  files has pattern
+}
+exegesis {
+  Matches paths such as src/*.rs.
+}
+
+gene b.c {
+  b has d
+}
+"#;
+    let decls = Parser::new(source).parse_all().unwrap();
+    let names: Vec<&str> = decls.iter().map(|d| d.name()).collect();
+    assert_eq!(names, ["files.glob", "b.c"]);
+}
+
+#[test]
+fn test_parse_file_exegesis_with_nested_braces_keeps_following_declarations() {
+    let source = r#"gene counter.state {
+  counter has count
+}
+exegesis {
+  Counts {things}, including {nested {ones}}.
+  It's "quoted" text, not tokens.
+}
+
+gene b.c {
+  b has d
+}
+"#;
+    let file = Parser::new(source).parse_file().unwrap();
+    let names: Vec<&str> = file.declarations.iter().map(|d| d.name()).collect();
+    assert_eq!(names, ["counter.state", "b.c"]);
+    let exegesis = file.declarations[0].exegesis();
+    assert!(exegesis.starts_with("Counts {things}"), "{exegesis}");
+    assert!(exegesis.ends_with("not tokens."), "{exegesis}");
+}
+
+#[test]
+fn test_parse_enum_as_identifier() {
+    let decl = parse("gene a.b { a has enum }").unwrap();
+    let Declaration::Gene(gene) = decl else {
+        panic!("Expected gene");
+    };
+    assert!(matches!(&gene.statements[0], Statement::Has { property, .. } if property == "enum"));
+
+    let decl = parse("gene a.b { has enum: String has kind: enum { On, Off } }").unwrap();
+    let Declaration::Gene(gene) = decl else {
+        panic!("Expected gene");
+    };
+    let types: Vec<_> = gene
+        .statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::HasField(field) => Some((field.name.as_str(), &field.type_)),
+            _ => None,
+        })
+        .collect();
+    assert!(matches!(types[0], ("enum", TypeExpr::Named(name)) if name == "String"));
+    assert!(matches!(types[1], ("kind", TypeExpr::Enum { variants }) if variants.len() == 2));
+
+    let expr = Parser::new("{ val enum = 1; enum }").parse_expr(0).unwrap();
+    assert!(matches!(expr, Expr::Block { .. }));
+}
+
+#[test]
+fn test_parse_declaration_names_are_qualified() {
+    let source = "gene container.exists {\n  container has id\n}\n\n\
+                  trait container.lifecycle {\n  uses container.exists\n}\n\n\
+                  constraint container.integrity {\n  id never changes\n}\n\n\
+                  system container.runtime {\n  uses container.lifecycle\n}\n";
+    let decls = Parser::new(source).parse_all().unwrap();
+
+    let Declaration::Gene(gene) = &decls[0] else {
+        panic!("Expected gene");
+    };
+    assert_eq!(gene.name.segments(), ["container", "exists"]);
+    assert_eq!(gene.name.domain().to_string(), "container");
+    assert_eq!(gene.name.property(), Some("exists"));
+    assert_eq!((gene.name.span.line, gene.name.span.column), (1, 6));
+    assert_eq!(
+        &source[gene.name.span.start..gene.name.span.end],
+        "container.exists"
+    );
+
+    let names: Vec<_> = decls.iter().map(|d| d.qualified_name()).collect();
+    assert_eq!(
+        names,
+        [
+            "container.exists",
+            "container.lifecycle",
+            "container.integrity",
+            "container.runtime"
+        ]
+    );
+    assert_eq!(names[3].span.line, 13);
+}
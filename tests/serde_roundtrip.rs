@@ -0,0 +1,100 @@
+//! JSON round-trip tests for the AST
+//! Requires the `serde` feature
+
+#![cfg(feature = "serde")]
+
+use metadol::ast::*;
+use metadol::parser::Parser;
+
+const SOURCE: &str = r#"
+module univrs.core @ 1.0.0
+
+pub gene container.exists {
+  container has identity
+  has count: Int64 = 0
+  constraint bounded { count <= 10 }
+}
+
+exegesis {
+  A container is the fundamental unit.
+}
+
+trait container.lifecycle {
+  uses container.exists
+  each transition emits event;
+  law idempotent(c: Container) { start(start(c)) == start(c) }
+}
+
+exegesis {
+  Lifecycle.
+}
+
+system univrs.scheduler @ 0.1.0 {
+  requires container.lifecycle >= 0.0.2
+  requires network and storage
+  state ticks: Int64 = 0
+  scheduler is running
+}
+
+exegesis {
+  Scheduler.
+}
+
+evolves container.lifecycle from 0.0.1 to 0.0.2 {
+  adds container is paused
+  because "pausing"
+  migrate {
+    let paused = false
+  }
+}
+
+exegesis {
+  Adds pausing.
+}
+
+pub fun classify(x: Int64, f: (Int64) -> Bool) -> String {
+  let xs = [1, 2.5, 'c', "s"]
+  let g = |a: Int64| -> Int64 a + 1
+  for i in 0..10 { x = x + i }
+  return match (x, xs) { (a, _) if a > 1 => "big", Some(_) | None => "opt", _ => "other" }
+}
+
+exegesis {
+  Classifies.
+}
+"#;
+
+#[test]
+fn file_roundtrips_through_json() {
+    let file = Parser::new(SOURCE).parse_file().unwrap();
+    let json = serde_json::to_string(&file).unwrap();
+    let back: DolFile = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, file);
+}
+
+#[test]
+fn enums_serialize_tagged() {
+    let stmt = Statement::Is {
+        subject: "container".to_string(),
+        state: "created".to_string(),
+        span: Span::new(0, 20, 1, 1),
+    };
+    let value = serde_json::to_value(&stmt).unwrap();
+    assert_eq!(value["kind"], "Is");
+    assert_eq!(value["value"]["state"], "created");
+
+    let expr = Expr::Binary {
+        left: Box::new(Expr::Literal(Literal::Int(1))),
+        op: BinaryOp::Add,
+        right: Box::new(Expr::Identifier("x".to_string())),
+    };
+    let value = serde_json::to_value(&expr).unwrap();
+    assert_eq!(value["kind"], "Binary");
+    assert_eq!(value["value"]["op"], "Add");
+    assert_eq!(value["value"]["left"]["kind"], "Literal");
+    assert_eq!(value["value"]["left"]["value"]["kind"], "Int");
+    assert_eq!(value["value"]["right"]["value"], "x");
+
+    let back: Expr = serde_json::from_value(value).unwrap();
+    assert_eq!(back, expr);
+}
@@ -9,14 +9,18 @@
 
 use metadol::ast::{
     Declaration, Expr, ExternDecl, FunctionDecl, FunctionParam, Gene, Literal, Mutability, Purity,
-    Span, Statement, Stmt, TypeExpr, VarDecl, Visibility,
+    QualifiedName, Span, Statement, Stmt, TypeExpr, VarDecl, Visibility,
 };
 use metadol::codegen::RustCodegen;
+use metadol::parser::Parser;
+use metadol::repository::Repository;
 use metadol::sex::context::SexContext;
 use metadol::sex::tracking::{Effect, EffectKind, EffectTracker};
-use metadol::sex::{file_sex_context, is_sex_file, LintResult, SexLinter};
+use metadol::sex::{
+    file_sex_context, is_sex_file, FileContext, LintResult, SexLintError, SexLinter,
+};
 use metadol::typechecker::{EffectContext, TypeChecker};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 // ═══════════════════════════════════════════════════════════════════
 // File Context Detection Tests
@@ -154,13 +158,15 @@ fn test_effect_tracker_gene_with_io() {
 
     let gene = Gene {
         extends: None,
-        name: "io.gene".to_string(),
+        name: QualifiedName::new("io.gene"),
         statements: vec![Statement::Has {
             subject: "io".to_string(),
             property: "file_read".to_string(),
             span: Span::default(),
         }],
-        exegesis: "Test".to_string(),
+        exegesis: "Test".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -178,13 +184,15 @@ fn test_effect_tracker_gene_with_ffi() {
 
     let gene = Gene {
         extends: None,
-        name: "ffi.gene".to_string(),
+        name: QualifiedName::new("ffi.gene"),
         statements: vec![Statement::Has {
             subject: "ffi".to_string(),
             property: "extern_call".to_string(),
             span: Span::default(),
         }],
-        exegesis: "Test".to_string(),
+        exegesis: "Test".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -202,13 +210,15 @@ fn test_effect_tracker_gene_with_global() {
 
     let gene = Gene {
         extends: None,
-        name: "global.gene".to_string(),
+        name: QualifiedName::new("global.gene"),
         statements: vec![Statement::Has {
             subject: "state".to_string(),
             property: "global_counter".to_string(),
             span: Span::default(),
         }],
-        exegesis: "Test".to_string(),
+        exegesis: "Test".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -226,13 +236,15 @@ fn test_effect_tracker_pure_gene() {
 
     let gene = Gene {
         extends: None,
-        name: "pure.gene".to_string(),
+        name: QualifiedName::new("pure.gene"),
         statements: vec![Statement::Has {
             subject: "thing".to_string(),
             property: "property".to_string(),
             span: Span::default(),
         }],
-        exegesis: "Test".to_string(),
+        exegesis: "Test".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -287,13 +299,15 @@ fn test_linter_pure_gene_no_effects() {
 
     let gene = Gene {
         extends: None,
-        name: "test.gene".to_string(),
+        name: QualifiedName::new("test.gene"),
         statements: vec![Statement::Has {
             subject: "test".to_string(),
             property: "property".to_string(),
             span: Span::default(),
         }],
-        exegesis: "Test gene with sufficient documentation for linting".to_string(),
+        exegesis: "Test gene with sufficient documentation for linting".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -307,13 +321,15 @@ fn test_linter_io_in_pure_context() {
 
     let gene = Gene {
         extends: None,
-        name: "io.gene".to_string(),
+        name: QualifiedName::new("io.gene"),
         statements: vec![Statement::Has {
             subject: "io".to_string(),
             property: "file_read".to_string(),
             span: Span::default(),
         }],
-        exegesis: "Test gene with I/O operations in pure context".to_string(),
+        exegesis: "Test gene with I/O operations in pure context".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -329,13 +345,15 @@ fn test_linter_ffi_in_pure_context() {
 
     let gene = Gene {
         extends: None,
-        name: "ffi.gene".to_string(),
+        name: QualifiedName::new("ffi.gene"),
         statements: vec![Statement::Has {
             subject: "ffi".to_string(),
             property: "extern_func".to_string(),
             span: Span::default(),
         }],
-        exegesis: "Test gene with FFI in pure context".to_string(),
+        exegesis: "Test gene with FFI in pure context".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -351,13 +369,15 @@ fn test_linter_mutable_global_in_pure_context() {
 
     let gene = Gene {
         extends: None,
-        name: "global.gene".to_string(),
+        name: QualifiedName::new("global.gene"),
         statements: vec![Statement::Has {
             subject: "state".to_string(),
             property: "global_var".to_string(),
             span: Span::default(),
         }],
-        exegesis: "Test gene with global state in pure context".to_string(),
+        exegesis: "Test gene with global state in pure context".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -381,9 +401,11 @@ fn test_linter_large_block_warning() {
 
     let gene = Gene {
         extends: None,
-        name: "test.gene".to_string(),
+        name: QualifiedName::new("test.gene"),
         statements,
-        exegesis: "Test gene with many statements".to_string(),
+        exegesis: "Test gene with many statements".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -399,9 +421,11 @@ fn test_linter_sex_without_documentation() {
 
     let gene = Gene {
         extends: None,
-        name: "test.gene".to_string(),
+        name: QualifiedName::new("test.gene"),
         statements: vec![],
-        exegesis: "Short".to_string(), // Too short
+        exegesis: "Short".into(), // Too short
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -416,13 +440,15 @@ fn test_linter_sex_context_allows_effects() {
 
     let gene = Gene {
         extends: None,
-        name: "io.gene".to_string(),
+        name: QualifiedName::new("io.gene"),
         statements: vec![Statement::Has {
             subject: "io".to_string(),
             property: "file_read".to_string(),
             span: Span::default(),
         }],
-        exegesis: "Test gene with I/O operations in sex context - this is allowed".to_string(),
+        exegesis: "Test gene with I/O operations in sex context - this is allowed".into(),
+        visibility: Visibility::Private,
+        attributes: vec![],
         span: Span::default(),
     };
 
@@ -431,6 +457,170 @@ fn test_linter_sex_context_allows_effects() {
     assert!(!result.has_errors());
 }
 
+// ═══════════════════════════════════════════════════════════════════
+// Effect Inference Tests
+// ═══════════════════════════════════════════════════════════════════
+
+const CALL_GRAPH: &str = r#"
+sex extern fun c_write(fd: Int64) -> Int64
+
+fun flush(fd: Int64) -> Int64 {
+    return c_write(fd)
+}
+
+fun sync(n: Int64) -> Int64 {
+    return if n > 0 { retry(n - 1) } else { flush(1) }
+}
+
+fun retry(n: Int64) -> Int64 {
+    return sync(n)
+}
+
+fun countdown(n: Int64) -> Int64 {
+    return if n > 0 { countdown(n - 1) } else { 0 }
+}
+
+sex fun log_line(fd: Int64) -> Int64 {
+    return c_write(fd)
+}
+"#;
+
+#[test]
+fn test_infer_effects_propagates_through_calls() {
+    let decls = metadol::parse_file_all(CALL_GRAPH).unwrap();
+    let contexts = EffectTracker::infer_effects(&decls);
+
+    assert_eq!(contexts["flush"], SexContext::Sex);
+    // Mutual recursion reaches the fixpoint without looping
+    assert_eq!(contexts["sync"], SexContext::Sex);
+    assert_eq!(contexts["retry"], SexContext::Sex);
+    assert_eq!(contexts["countdown"], SexContext::Pure);
+    assert_eq!(contexts["log_line"], SexContext::Sex);
+}
+
+#[test]
+fn test_linter_flags_pure_function_inferred_sex() {
+    let decls = metadol::parse_file_all(CALL_GRAPH).unwrap();
+    let repo = Repository::new(&decls);
+
+    let result = SexLinter::new(SexContext::Pure).lint_repository(&repo);
+    let impure: Vec<_> = result
+        .errors
+        .iter()
+        .filter_map(|e| match e {
+            SexLintError::ImpureFunction { name, cause, .. } => {
+                Some((name.as_str(), cause.as_str()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        impure,
+        [
+            ("flush", "call to extern 'c_write'"),
+            ("sync", "call to 'flush'"),
+            ("retry", "call to 'sync'"),
+        ]
+    );
+
+    // A sex file may call effectful functions freely
+    let result = SexLinter::new(SexContext::Sex).lint_repository(&repo);
+    assert!(!result.has_errors());
+}
+
+#[test]
+fn test_linter_points_impure_function_at_call_with_expr_spans() {
+    let decls = Parser::new(CALL_GRAPH)
+        .with_expr_spans()
+        .parse_all()
+        .unwrap();
+    let repo = Repository::new(&decls);
+
+    let result = SexLinter::new(SexContext::Pure).lint_repository(&repo);
+    let span = result
+        .errors
+        .iter()
+        .find_map(|e| match e {
+            SexLintError::ImpureFunction { name, span, .. } if name == "flush" => Some(*span),
+            _ => None,
+        })
+        .expect("flush should be reported");
+    assert_eq!(&CALL_GRAPH[span.start..span.end], "c_write(fd)");
+}
+
+// ═══════════════════════════════════════════════════════════════════
+// Sex Declaration Placement Tests
+// ═══════════════════════════════════════════════════════════════════
+
+const SEX_DECLARATIONS: &str = r#"
+sex extern "C" {
+    fun read(fd: Int32) -> Int32;
+    fun close(fd: Int32) -> Int32;
+}
+
+sex var OPEN_FILES: Int64 = 0
+
+fun double(x: Int64) -> Int64 {
+    return x * 2
+}
+"#;
+
+#[test]
+fn test_linter_flags_sex_declarations_in_pure_file() {
+    let decls = metadol::parse_file_all(SEX_DECLARATIONS).unwrap();
+    let file = FileContext::new(PathBuf::from("container.dol"));
+
+    let result = SexLinter::new(SexContext::Pure).lint_sex_declarations(&file, &decls);
+    let flagged: Vec<_> = result
+        .errors
+        .iter()
+        .map(|e| match e {
+            SexLintError::SexDeclarationInPureFile {
+                kind, name, span, ..
+            } => (kind.as_str(), name.as_str(), span.line),
+            other => panic!("unexpected error {other}"),
+        })
+        .collect();
+    assert_eq!(
+        flagged,
+        [
+            ("sex extern", "read", 3),
+            ("sex extern", "close", 4),
+            ("sex var", "OPEN_FILES", 7),
+        ]
+    );
+    assert!(result.errors[0].to_string().contains("container.dol"));
+    assert!(result.errors[0].to_string().contains("*.sex.dol"));
+}
+
+#[test]
+fn test_linter_allows_sex_declarations_in_sex_files() {
+    let decls = metadol::parse_file_all(SEX_DECLARATIONS).unwrap();
+    let linter = SexLinter::new(SexContext::Pure);
+
+    for path in ["io.sex.dol", "src/sex/io.dol"] {
+        let file = FileContext::new(PathBuf::from(path));
+        assert!(linter.lint_sex_declarations(&file, &decls).is_clean());
+    }
+}
+
+#[test]
+fn test_linter_ignores_gene_documented_as_extern() {
+    let source = r#"
+gene read {
+    read has fd
+}
+
+exegesis {
+    sex extern read
+}
+"#;
+    let decls = metadol::parse_file_all(source).unwrap();
+    let file = FileContext::new(PathBuf::from("container.dol"));
+    let result = SexLinter::new(SexContext::Pure).lint_sex_declarations(&file, &decls);
+    assert!(result.is_clean());
+}
+
 // ═══════════════════════════════════════════════════════════════════
 // Type Checker Effect Context Tests
 // ═══════════════════════════════════════════════════════════════════
@@ -681,7 +871,7 @@ fn test_codegen_sex_function() {
         }],
         return_type: Some(TypeExpr::Named("Void".to_string())),
         body: vec![],
-        exegesis: String::new(),
+        exegesis: Default::default(),
         span: Span::default(),
     };
 
@@ -704,6 +894,85 @@ fn test_codegen_sex_block() {
     assert!(output.contains("println!(\"{}\", \"hello\".to_string())"));
 }
 
+// ═══════════════════════════════════════════════════════════════════
+// Extern Block Parsing Tests
+// ═══════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_parse_extern_block_inherits_abi() {
+    let source = r#"sex extern "C" {
+    fun getpid() -> Int32;
+    fun kill(pid: Int32, sig: Int32) -> Int32;
+}"#;
+    let mut parser = Parser::new(source);
+    let decls = parser.parse_sex_extern_block().unwrap();
+
+    assert_eq!(decls.len(), 2);
+    assert_eq!(decls[0].name, "getpid");
+    assert_eq!(decls[1].name, "kill");
+    assert_eq!(decls[1].params.len(), 2);
+    assert!(decls.iter().all(|d| d.abi.as_deref() == Some("C")));
+}
+
+#[test]
+fn test_parse_extern_block_abi_override() {
+    let source = r#"sex extern "C" {
+    fun getpid() -> Int32;
+    "Rust" fun helper();
+}"#;
+    let mut parser = Parser::new(source);
+    let decls = parser.parse_sex_extern_block().unwrap();
+
+    assert_eq!(decls[0].abi.as_deref(), Some("C"));
+    assert_eq!(decls[1].abi.as_deref(), Some("Rust"));
+}
+
+#[test]
+fn test_parse_extern_block_empty() {
+    let mut parser = Parser::new(r#"sex extern "C" {}"#);
+    let decls = parser.parse_sex_extern_block().unwrap();
+    assert!(decls.is_empty());
+}
+
+#[test]
+fn test_parse_extern_block_top_level() {
+    let source = r#"sex extern "C" {
+    fun getpid() -> Int32
+    fun fork() -> Int32
+}
+
+gene process.id {
+    process has pid
+}
+
+exegesis {
+    A process has an identifier.
+}"#;
+    let decls = metadol::parse_file_all(source).unwrap();
+    assert_eq!(decls.len(), 2);
+    let Declaration::Extern(block) = &decls[0] else {
+        panic!("expected an extern block, got {:?}", decls[0]);
+    };
+    let names: Vec<&str> = block.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["getpid", "fork"]);
+    assert!(block.functions[0].return_type.is_some());
+    assert!(decls[0].exegesis().is_empty());
+    assert!(matches!(&decls[1], Declaration::Gene(g) if g.name == "process.id"));
+}
+
+#[test]
+fn test_extern_block_round_trips_to_dol() {
+    let source = r#"sex extern "C" {
+    fun getpid() -> Int32
+    "Rust" fun kill(pid: Int32, sig: Int32) -> Int32
+}"#;
+    let decls = metadol::parse_file_all(source).unwrap();
+    let printed = decls[0].to_dol();
+    assert!(printed.contains(r#""Rust" fun kill(pid: Int32, sig: Int32) -> Int32"#));
+    let reparsed = metadol::parse_file_all(&printed).unwrap();
+    assert_eq!(reparsed[0].to_dol(), printed);
+}
+
 // ═══════════════════════════════════════════════════════════════════
 // Integration Tests
 // ═══════════════════════════════════════════════════════════════════
@@ -782,6 +1051,21 @@ fn test_error_codes() {
     };
     assert_eq!(err4.code(), "E004");
 
+    let err5 = SexLintError::ImpureFunction {
+        name: "test".to_string(),
+        cause: "call to extern 'c_write'".to_string(),
+        span: Span::default(),
+    };
+    assert_eq!(err5.code(), "E005");
+
+    let err6 = SexLintError::SexDeclarationInPureFile {
+        kind: "sex var".to_string(),
+        name: "test".to_string(),
+        path: "test.dol".to_string(),
+        span: Span::default(),
+    };
+    assert_eq!(err6.code(), "E006");
+
     let warn1 = SexLintWarning::LargeSexBlock {
         size: 100,
         max_size: 50,
@@ -515,3 +515,89 @@ mod type_errors {
         assert!(result.is_err());
     }
 }
+
+// ============================================================================
+// EXPRESSION SPANS
+// ============================================================================
+
+mod expr_spans {
+    use super::*;
+
+    #[test]
+    fn spans_are_opt_in() {
+        let expr = Parser::new("1 + x").parse_expr(0).unwrap();
+        assert!(expr.span().is_none());
+
+        let expr = Parser::new("1 + x")
+            .with_expr_spans()
+            .parse_expr(0)
+            .unwrap();
+        let span = expr.span().expect("expression should carry a span");
+        assert_eq!((span.start, span.end), (0, 5));
+        assert!(matches!(expr.unspanned(), Expr::Binary { .. }));
+    }
+
+    #[test]
+    fn error_points_at_innermost_expression() {
+        let expr = Parser::new("1 + undefined_var")
+            .with_expr_spans()
+            .parse_expr(0)
+            .unwrap();
+        let mut tc = TypeChecker::new();
+        let err = tc.infer(&expr).unwrap_err();
+        let span = err.span.expect("error should carry a span");
+        assert_eq!((span.start, span.end), (4, 17));
+        assert_eq!(span.column, 5);
+    }
+}
+
+// ============================================================================
+// NUMERIC WIDTHS
+// ============================================================================
+
+mod numeric_widths {
+    use super::*;
+
+    fn check(src: &str) -> (Option<metadol::typechecker::Type>, Vec<String>) {
+        let expr = Parser::new(src).parse_expr(0).unwrap();
+        let mut tc = TypeChecker::new();
+        let ty = tc.infer(&expr).ok();
+        let errors = tc.errors().iter().map(|e| e.message.clone()).collect();
+        (ty, errors)
+    }
+
+    #[test]
+    fn out_of_range_literal_is_rejected() {
+        let (_, errors) = check("{ let x: Int8 = 300; x }");
+        assert_eq!(
+            errors,
+            ["integer literal 300 is out of range for Int8 (-128..=127)"]
+        );
+
+        let (_, errors) = check("{ let x: UInt8 = -1; x }");
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn in_range_literal_takes_declared_width() {
+        let (ty, errors) = check("{ let x: Int8 = -128; x + 1 }");
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(ty, Some(metadol::typechecker::Type::Int8));
+    }
+
+    #[test]
+    fn mismatched_widths_are_rejected() {
+        let (ty, errors) = check("{ let a: Int32 = 1; let b: Int64 = 2; a + b }");
+        assert_eq!(ty, Some(metadol::typechecker::Type::Error));
+        assert_eq!(
+            errors,
+            ["mismatched numeric types Int32 and Int64; convert one explicitly with `as`"]
+        );
+    }
+
+    #[test]
+    fn mismatched_binding_is_rejected() {
+        let (_, errors) = check("{ let a: Int64 = 1; let b: Int32 = a; b }");
+        assert_eq!(errors, ["type mismatch: expected Int32, found Int64"]);
+    }
+}
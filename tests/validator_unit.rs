@@ -1,8 +1,15 @@
 //! Unit tests for semantic validation
 //! Tests validator behavior for various inputs
 
+use metadol::ast::{Declaration, QualifiedName, Statement};
+use metadol::diagnostic::DiagnosticCollector;
+use metadol::error::{ValidationError, ValidationWarning};
 use metadol::parser::Parser;
-use metadol::validator::validate;
+use metadol::repository::Repository;
+use metadol::validator::{
+    validate, validate_file, validate_incremental, validate_orphans, validate_repository,
+    validate_with_options, OrphanOptions, ValidationOptions,
+};
 
 // ============================================================================
 // EXEGESIS REQUIREMENT TESTS
@@ -32,6 +39,74 @@ fn validate_gene_with_exegesis() {
     }
 }
 
+#[test]
+fn validate_empty_exegesis_block_warns() {
+    let file = Parser::new("gene Test { }\nexegesis { }")
+        .parse_file()
+        .unwrap();
+    let result = validate(&file.declarations[0]);
+    assert!(result
+        .warnings
+        .iter()
+        .any(|w| matches!(w, ValidationWarning::EmptyExegesis { .. })));
+    assert!(!result
+        .warnings
+        .iter()
+        .any(|w| matches!(w, ValidationWarning::ShortExegesis { .. })));
+    assert!(!result
+        .warnings
+        .iter()
+        .any(|w| matches!(w, ValidationWarning::MissingExegesis { .. })));
+}
+
+#[test]
+fn validate_missing_exegesis_is_distinct_from_empty() {
+    let file = Parser::new("gene Test { }").parse_file().unwrap();
+    let result = validate(&file.declarations[0]);
+    assert!(result
+        .warnings
+        .iter()
+        .any(|w| matches!(w, ValidationWarning::MissingExegesis { .. })));
+    assert!(!result
+        .warnings
+        .iter()
+        .any(|w| matches!(w, ValidationWarning::EmptyExegesis { .. })));
+    assert!(result
+        .warnings
+        .iter()
+        .any(|w| w.to_string().starts_with("missing exegesis")));
+}
+
+#[test]
+fn validate_one_character_exegesis_is_short() {
+    let file = Parser::new("gene Test { }\nexegesis { x }")
+        .parse_file()
+        .unwrap();
+    let result = validate(&file.declarations[0]);
+    assert!(result
+        .warnings
+        .iter()
+        .any(|w| matches!(w, ValidationWarning::ShortExegesis { length: 1, .. })));
+}
+
+#[test]
+fn validate_exegesis_minimum_length_is_configurable() {
+    let input = "gene Test { }\nexegesis { A test gene. }";
+    let file = Parser::new(input).parse_file().unwrap();
+    let is_short = |min_exegesis_length| {
+        let options = ValidationOptions {
+            min_exegesis_length,
+            ..Default::default()
+        };
+        validate_with_options(&file.declarations[0], &options)
+            .warnings
+            .iter()
+            .any(|w| matches!(w, ValidationWarning::ShortExegesis { .. }))
+    };
+    assert!(is_short(20));
+    assert!(!is_short(5));
+}
+
 // ============================================================================
 // VALIDATION BEHAVIOR TESTS
 // ============================================================================
@@ -294,6 +369,442 @@ fn validate_float64_type() {
     }
 }
 
+// ============================================================================
+// DEPRECATION TESTS
+// ============================================================================
+
+#[test]
+fn validate_uses_of_deprecated_gene_warns() {
+    let input = r#"
+#[deprecated("use container.identity instead")]
+gene container.exists { container has id }
+
+trait container.lifecycle { uses container.exists }
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let results = validate_file(&file);
+
+    let warning = results[1]
+        .warnings
+        .iter()
+        .find(|w| matches!(w, ValidationWarning::DeprecatedReference { .. }))
+        .expect("expected a deprecation warning");
+    assert_eq!(
+        warning.to_string(),
+        "'container.exists' is deprecated: use container.identity instead at line 5, column 29"
+    );
+    assert!(!results[0]
+        .warnings
+        .iter()
+        .any(|w| matches!(w, ValidationWarning::DeprecatedReference { .. })));
+}
+
+#[test]
+fn validate_uses_of_current_gene_does_not_warn() {
+    let input = r#"
+gene container.exists { container has id }
+trait container.lifecycle { uses container.exists }
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    assert!(validate_file(&file)
+        .iter()
+        .flat_map(|r| &r.warnings)
+        .all(|w| !matches!(w, ValidationWarning::DeprecatedReference { .. })));
+}
+
+// ============================================================================
+// RECURSIVE TYPE TESTS
+// ============================================================================
+
+#[test]
+fn validate_file_reports_recursive_gene() {
+    let input = r#"
+gene Left { Left has right: Right }
+gene Right { Right has left: Left }
+gene Tree { Tree has children: List<Tree> }
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let results = validate_file(&file);
+
+    let ValidationError::TypeError { message, .. } = &results[0].errors[0] else {
+        panic!("expected a type error, got {:?}", results[0].errors);
+    };
+    assert!(message.contains("Left -> Right -> Left"));
+    assert!(results[1].errors.is_empty());
+    assert!(results[2].errors.is_empty());
+}
+
+// ============================================================================
+// EVOLUTION LINEAGE TESTS
+// ============================================================================
+
+#[test]
+fn validate_evolution_removing_existing_member() {
+    let input = r#"
+gene container.exists { container has id container has image }
+evolves container.exists @ 0.0.2 > 0.0.1 { removes image }
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let results = validate_file(&file);
+
+    assert!(!results[1]
+        .errors
+        .iter()
+        .any(|e| matches!(e, ValidationError::MissingEvolutionTarget { .. })));
+}
+
+#[test]
+fn validate_evolution_removing_unknown_member_fails() {
+    let input = r#"
+gene container.exists { container has id }
+evolves container.exists @ 0.0.2 > 0.0.1 { adds container has image }
+evolves container.exists @ 0.0.3 > 0.0.2 { removes image }
+evolves container.exists @ 0.0.4 > 0.0.3 { removes image }
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let results = validate_file(&file);
+
+    assert!(results[2].errors.is_empty());
+    let error = results[3]
+        .errors
+        .iter()
+        .find(|e| matches!(e, ValidationError::MissingEvolutionTarget { .. }))
+        .expect("expected a missing evolution target error");
+    assert_eq!(
+        error.to_string(),
+        "evolution of 'container.exists' cannot remove 'image': not present at version 0.0.3"
+    );
+}
+
+// ============================================================================
+// CAPABILITY REQUIREMENT TESTS
+// ============================================================================
+
+#[test]
+fn validate_known_capabilities() {
+    let input = r#"
+gene network { network has address }
+system univrs.node @ 0.1.0 {
+  requires storage >= 0.1.0
+  requires network and storage
+}
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let results = validate_file(&file);
+
+    assert!(!results[1]
+        .errors
+        .iter()
+        .any(|e| matches!(e, ValidationError::UnresolvedReference { .. })));
+}
+
+#[test]
+fn validate_unknown_capability_fails() {
+    let input = r#"
+gene network { network has address }
+system univrs.node @ 0.1.0 {
+  requires (network and storage) or local_disk
+}
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let results = validate_file(&file);
+
+    let unresolved: Vec<String> = results[1]
+        .errors
+        .iter()
+        .filter_map(|e| match e {
+            ValidationError::UnresolvedReference { reference, .. } => Some(reference.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(unresolved, ["storage", "local_disk"]);
+}
+
+// ============================================================================
+// INLINE CONSTRAINT TESTS
+// ============================================================================
+
+#[test]
+fn inline_constraint_body_is_retained() {
+    let input = r#"
+gene Money {
+    has amount: UInt64
+
+    constraint non_negative {
+        this.amount >= 0
+    }
+}
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let Declaration::Gene(gene) = &file.declarations[0] else {
+        panic!("expected gene");
+    };
+
+    let constraint = gene
+        .statements
+        .iter()
+        .find_map(|stmt| match stmt {
+            Statement::Constraint(constraint) => Some(constraint),
+            _ => None,
+        })
+        .expect("inline constraint");
+    assert_eq!(constraint.name, "non_negative");
+    assert!(!constraint.is_reference());
+    let body = constraint.body.as_ref().expect("constraint body");
+    assert_eq!(body.to_dol(), "this.amount >= 0");
+}
+
+#[test]
+fn inline_constraint_reference_resolves() {
+    let input = r#"
+constraint money.bounded {
+    amount never overflows
+}
+
+gene Money {
+    has amount: UInt64
+    constraint money.bounded
+}
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let repo = Repository::new(&file.declarations);
+    let Declaration::Gene(gene) = &file.declarations[1] else {
+        panic!("expected gene");
+    };
+    let Some(Statement::Constraint(reference)) = gene.statements.last() else {
+        panic!("expected inline constraint");
+    };
+
+    assert!(reference.is_reference());
+    let resolved = repo.constraint(&reference.name).unwrap();
+    assert_eq!(resolved.name, "money.bounded");
+    assert!(validate_file(&file).iter().all(|result| !result
+        .errors
+        .iter()
+        .any(|e| matches!(e, ValidationError::UnresolvedReference { .. }))));
+}
+
+#[test]
+fn inline_constraint_unknown_reference_fails() {
+    let input = r#"
+gene Money {
+    has amount: UInt64
+    constraint money.bounded
+}
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let results = validate_file(&file);
+
+    assert!(results[0].errors.iter().any(|e| matches!(
+        e,
+        ValidationError::UnresolvedReference { reference, .. } if reference == "money.bounded"
+    )));
+}
+
+// ============================================================================
+// DUPLICATE DECLARATION TESTS
+// ============================================================================
+
+#[test]
+fn validate_duplicate_gene_reports_both_spans() {
+    let input = r#"
+gene container.exists {
+    container has id
+}
+
+gene container.exists {
+    container has image
+}
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let first_span = file.declarations[0].span();
+    let second_span = file.declarations[1].span();
+
+    let results = validate_file(&file);
+    assert!(!results[0]
+        .errors
+        .iter()
+        .any(|e| matches!(e, ValidationError::DuplicateDeclaration { .. })));
+    let duplicate = results[1]
+        .errors
+        .iter()
+        .find_map(|e| match e {
+            ValidationError::DuplicateDeclaration { name, span, first } => {
+                Some((name.clone(), *span, *first))
+            }
+            _ => None,
+        })
+        .expect("expected a duplicate declaration error");
+    assert_eq!(
+        duplicate,
+        ("container.exists".to_string(), second_span, first_span)
+    );
+}
+
+#[test]
+fn validate_evolution_is_not_a_duplicate() {
+    let input = r#"
+gene container.exists {
+    container has id
+}
+
+evolves container.exists @ 0.0.2 > 0.0.1 {
+    adds container has image
+}
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    assert!(!validate_file(&file)
+        .iter()
+        .flat_map(|r| &r.errors)
+        .any(|e| matches!(e, ValidationError::DuplicateDeclaration { .. })));
+}
+
+#[test]
+fn validate_empty_extern_blocks_are_not_duplicates() {
+    let input = "sex extern { }\nsex extern { }";
+    let file = Parser::new(input).parse_file().unwrap();
+    assert_eq!(file.declarations.len(), 2);
+    assert!(!validate_file(&file)
+        .iter()
+        .flat_map(|r| &r.errors)
+        .any(|e| matches!(e, ValidationError::DuplicateDeclaration { .. })));
+}
+
+// ============================================================================
+// INCREMENTAL VALIDATION TESTS
+// ============================================================================
+
+#[test]
+fn validate_incremental_rechecks_changed_and_dependents() {
+    let input = r#"
+gene container.exists {
+    container has id
+    container has id
+}
+
+trait container.lifecycle {
+    uses container.exists
+    uses container.exists
+}
+
+gene node.exists {
+    node has address
+    node has address
+}
+
+trait node.managed {
+    uses node.exists
+    uses node.exists
+}
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let repo = Repository::new(&file.declarations);
+    let names = |errors: &[ValidationError]| -> Vec<String> {
+        errors
+            .iter()
+            .map(|error| match error {
+                ValidationError::DuplicateProperty { name, .. }
+                | ValidationError::DuplicateDefinition { name, .. } => name.clone(),
+                other => panic!("unexpected error: {other}"),
+            })
+            .collect()
+    };
+
+    let errors = validate_incremental(&repo, &[QualifiedName::new("container.exists")]);
+    assert_eq!(names(&errors), ["id", "container.exists"]);
+
+    let errors = validate_incremental(&repo, &[QualifiedName::new("node.managed")]);
+    assert_eq!(names(&errors), ["node.exists"]);
+}
+
+// ============================================================================
+// FIELD VERSION TESTS
+// ============================================================================
+
+#[test]
+fn validate_field_since_within_module_version() {
+    let input = r#"
+module billing @ 1.2.0
+
+gene Invoice {
+    has id: String
+    #[since("1.2.0")]
+    has currency: String
+}
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let Declaration::Gene(gene) = &file.declarations[0] else {
+        panic!("expected gene");
+    };
+    let Statement::HasField(field) = &gene.statements[1] else {
+        panic!("expected typed field");
+    };
+    assert_eq!(field.since.as_deref(), Some("1.2.0"));
+
+    let results = validate_file(&file);
+    assert!(!results[0]
+        .errors
+        .iter()
+        .any(|e| matches!(e, ValidationError::InvalidVersion { .. })));
+}
+
+#[test]
+fn validate_field_since_after_module_version_fails() {
+    let input = r#"
+module billing @ 1.2.0
+
+gene Invoice {
+    #[since("1.3.0")]
+    has currency: String
+}
+"#;
+    let file = Parser::new(input).parse_file().unwrap();
+    let results = validate_file(&file);
+
+    assert!(results[0].errors.iter().any(|e| matches!(
+        e,
+        ValidationError::InvalidVersion { version, reason }
+            if version == "1.3.0" && reason.contains("module version 1.2.0")
+    )));
+}
+
+#[test]
+fn validate_field_since_release_after_prerelease_module_fails() {
+    let input = r#"
+module billing @ 1.2.0
+
+gene Invoice {
+    #[since("1.2.0-alpha")]
+    has id: String
+    #[since("1.2.0")]
+    has currency: String
+}
+"#;
+    let mut file = Parser::new(input).parse_file().unwrap();
+    // Module headers are written without a suffix; set one on the AST
+    let module_version = file.module.as_mut().unwrap().version.as_mut().unwrap();
+    module_version.suffix = Some("beta".to_string());
+    let results = validate_file(&file);
+
+    let version_errors: Vec<_> = results[0]
+        .errors
+        .iter()
+        .filter_map(|e| match e {
+            ValidationError::InvalidVersion { version, reason } => Some((version, reason)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(version_errors.len(), 1);
+    assert_eq!(version_errors[0].0, "1.2.0");
+    assert!(version_errors[0].1.contains("module version 1.2.0-beta"));
+}
+
+#[test]
+fn parse_since_on_untyped_statement_fails() {
+    let input = r#"gene Invoice { #[since("1.0.0")] invoice has id }"#;
+    assert!(Parser::new(input).parse_file().is_err());
+}
+
 // ============================================================================
 // STRESS TESTS
 // ============================================================================
@@ -384,3 +895,227 @@ fn validate_whitespace_file_no_declarations() {
     let file = Parser::new("   \n\t\n   ").parse_file().unwrap();
     assert!(file.declarations.is_empty());
 }
+
+// ============================================================================
+// ORPHAN DECLARATION TESTS
+// ============================================================================
+
+const ORPHAN_INPUT: &str = r#"
+gene container.exists {
+    container has id
+}
+
+gene image.exists {
+    image has digest
+}
+
+trait container.lifecycle {
+    uses container.exists
+    container derives from image.exists
+}
+
+system container.runtime @ 0.1.0 {
+    requires container.lifecycle >= 0.0.1
+}
+
+gene stale.exists {
+    stale has reason
+}
+
+pub gene api.exists {
+    api has endpoint
+}
+"#;
+
+#[test]
+fn validate_orphans_flags_private_unreferenced_declarations() {
+    let file = Parser::new(ORPHAN_INPUT).parse_file().unwrap();
+
+    let results = validate_orphans(&file, &OrphanOptions::default());
+    let names: Vec<&str> = results
+        .iter()
+        .map(|r| r.declaration_name.as_str())
+        .collect();
+    assert_eq!(names, ["stale.exists"]);
+    assert!(matches!(
+        &results[0].warnings[0],
+        ValidationWarning::OrphanDeclaration { name, span }
+            if name == "stale.exists" && span.line == 19
+    ));
+}
+
+#[test]
+fn validate_orphans_roots_are_configurable() {
+    let file = Parser::new(ORPHAN_INPUT).parse_file().unwrap();
+
+    let options = OrphanOptions {
+        roots: vec!["stale.exists".to_string()],
+        public_roots: false,
+    };
+    let results = validate_orphans(&file, &options);
+    let names: Vec<&str> = results
+        .iter()
+        .map(|r| r.declaration_name.as_str())
+        .collect();
+    assert_eq!(names, ["api.exists"]);
+}
+
+// ============================================================================
+// TRAIT LAW TESTS
+// ============================================================================
+
+fn law_warnings(source: &str) -> Vec<String> {
+    let decl = Parser::new(source).parse().unwrap();
+    validate(&decl)
+        .warnings
+        .into_iter()
+        .filter_map(|w| match w {
+            ValidationWarning::UnboundLawParameter { name, .. } => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn validate_law_with_bound_parameters() {
+    let warnings = law_warnings(
+        "trait Ordered {\n  uses Comparable\n  \
+         law transitive(a: Int64, b: Int64) { forall c: Int64. !(a <= b && b <= c) || a <= c }\n}",
+    );
+    assert!(warnings.is_empty(), "{warnings:?}");
+}
+
+#[test]
+fn validate_law_with_unbound_parameter_warns() {
+    let decl = Parser::new(
+        "trait Ordered {\n  uses Comparable\n  \
+         law reflexive(a: Int64) { compare(a, b) == 0 && b == a }\n}",
+    )
+    .parse()
+    .unwrap();
+    let result = validate(&decl);
+    let unbound: Vec<_> = result
+        .warnings
+        .iter()
+        .filter(|w| matches!(w, ValidationWarning::UnboundLawParameter { .. }))
+        .collect();
+    assert_eq!(unbound.len(), 1);
+    assert!(matches!(
+        unbound[0],
+        ValidationWarning::UnboundLawParameter { law, name, span }
+            if law == "reflexive" && name == "b" && span.line == 3
+    ));
+}
+
+#[test]
+fn validate_law_binders_do_not_leak() {
+    let warnings = law_warnings(
+        "trait Mapped {\n  uses Functor\n  \
+         law identity(xs: List<Int64>) { map(xs, |x| x) == xs && x == 0 }\n}",
+    );
+    assert_eq!(warnings, ["x"]);
+}
+
+// ============================================================================
+// DUPLICATE PROPERTY TESTS
+// ============================================================================
+
+#[test]
+fn validate_duplicate_has_property_fails() {
+    let file = Parser::new(
+        "gene container.exists {\n  container has identity\n  container has identity\n}\n\
+         exegesis { A container has a single identity. }",
+    )
+    .parse_file()
+    .unwrap();
+    let results = validate_file(&file);
+    let result = &results[0];
+    assert!(!result.is_valid());
+    assert!(matches!(
+        &result.errors[..],
+        [ValidationError::DuplicateProperty { name, span, first }]
+            if name == "identity" && span.line == 3 && first.line == 2
+    ));
+
+    let mut collector = DiagnosticCollector::new();
+    collector.add_validation(result);
+    assert!(collector.has_errors());
+}
+
+#[test]
+fn validate_duplicate_typed_field_fails() {
+    let decl = Parser::new(
+        "gene Item {\n  has id: String\n  has id: Int64\n}\nexegesis { An item with an id. }",
+    )
+    .parse()
+    .unwrap();
+    let result = validate(&decl);
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors[0].code(), "E0209");
+}
+
+#[test]
+fn validate_case_insensitive_property_warns() {
+    let decl = Parser::new(
+        "gene Item {\n  has id: String\n  has Id: String\n}\nexegesis { An item with an id. }",
+    )
+    .parse()
+    .unwrap();
+    let result = validate(&decl);
+    assert!(result.is_valid());
+    assert!(result.warnings.iter().any(|w| matches!(
+        w,
+        ValidationWarning::SimilarProperty { name, similar, .. }
+            if name == "Id" && similar == "id"
+    )));
+}
+
+// ============================================================================
+// REPOSITORY REFERENCE TESTS
+// ============================================================================
+
+#[test]
+fn validate_repository_resolves_across_files() {
+    let first = Parser::new(
+        "trait container.lifecycle {\n  uses container.exists\n  uses container.lifecycle\n}",
+    )
+    .parse_file()
+    .unwrap();
+    let second = Parser::new("gene container.exists { container has id }")
+        .parse_file()
+        .unwrap();
+    let decls: Vec<Declaration> = first
+        .declarations
+        .into_iter()
+        .chain(second.declarations)
+        .collect();
+
+    let diagnostics = validate_repository(&Repository::new(&decls));
+    assert!(diagnostics.diagnostics().is_empty());
+}
+
+#[test]
+fn validate_repository_reports_unresolved_uses() {
+    let file = Parser::new(
+        "gene container.exists { container has id }\n\
+         trait container.lifecycle {\n  uses container.exist\n  uses network.stack\n}",
+    )
+    .parse_file()
+    .unwrap();
+
+    let diagnostics = validate_repository(&Repository::new(&file.declarations));
+    assert!(diagnostics.has_errors());
+    let messages: Vec<&str> = diagnostics
+        .diagnostics()
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "unresolved reference to 'container.exist' at line 3, column 3; \
+             did you mean 'container.exists'?",
+            "unresolved reference to 'network.stack' at line 4, column 3",
+        ]
+    );
+}